@@ -12,7 +12,7 @@
 //! pointing into it still lives. So this won't compile:
 //!
 //! ```compile_fail
-//! use core::mem::MaybeUninit;
+//! use core::mem::{ManuallyDrop, MaybeUninit};
 //! use coca::arena::{Arena, Box};
 //!
 //! let bad_array = {
@@ -29,6 +29,19 @@
 //!
 //! [sub]: Arena::make_sub_arena
 //!
+//! Where introducing a new scope for a sub-arena is inconvenient, e.g. when
+//! the arena is rewound once per iteration of a loop, an arena [checkpoint]
+//! can be taken instead. Allocations made through the checkpoint are released
+//! when it is [reset](Checkpoint::reset) or dropped, and the borrow checker
+//! ensures none of them are still alive at that point.
+//!
+//! [checkpoint]: Arena::checkpoint
+//!
+//! Where the arena must also be used for longer-lived allocations in between,
+//! a [`CheckpointToken`] can be recorded with [`Arena::checkpoint_token`]
+//! instead; rewinding to it with the `unsafe` [`Arena::reset_to`] leaves
+//! ensuring that the released allocations are no longer in use to the caller.
+//!
 //! Note that this is legal but **strongly discouraged**:
 //!
 //! ```no_run
@@ -41,6 +54,24 @@
 //! }
 //! ```
 //!
+//! Arenas can also allocate from the back of their region, growing downwards
+//! towards allocations from the front, e.g. with [`Arena::alloc_back`]. This
+//! allows separating long-lived data from temporary scratch data, while both
+//! share the same memory budget.
+//!
+//! When many short-lived values of a single type are needed, a [`TypedSlab`]
+//! can be carved out of an arena with [`Arena::typed_slab`]. Unlike the arena
+//! itself, it reuses the memory of values that have been dropped.
+//!
+//! With the `alloc` feature enabled, a [`ChainedArena`] can be used where an
+//! upper bound on memory usage is not known up front. It allocates additional
+//! memory blocks from the global allocator as needed, and frees them all when
+//! it is dropped.
+//!
+//! With the `std` feature enabled, [`with_scratch`] provides temporary arenas
+//! backed by per-thread scratch buffers, so code needing short-lived working
+//! memory doesn't have to be passed an arena explicitly.
+//!
 //! A `Box` should not outlive the arena it was allocated from. If temporary
 //! allocations are required where an arena allocated value is to be returned,
 //! consider using [`Arena::try_reserve`].
@@ -58,15 +89,17 @@
 //! This does not apply to creating sub-arenas.
 
 use crate::collections::cache::CacheTable;
+use crate::collections::hash_map::{HashMap, HashMapLayout};
 use crate::ArenaString;
 use crate::storage::{ArenaStorage, ArrayLayout, Capacity, LayoutSpec};
 
 use core::alloc::Layout;
+use core::cell::Cell;
 use core::cmp::Ordering;
 use core::fmt::{self, Debug, Display, Formatter, Pointer, Write};
 use core::hash::{BuildHasher, Hash, Hasher};
 use core::marker::PhantomData;
-use core::mem::MaybeUninit;
+use core::mem::{ManuallyDrop, MaybeUninit};
 use core::ops::{Deref, DerefMut, Range};
 use core::ptr::{null_mut, slice_from_raw_parts_mut, NonNull};
 use core::slice::from_raw_parts_mut;
@@ -310,7 +343,7 @@ impl<I: Iterator + ?Sized> Iterator for Box<'_, I> {
 #[derive(Copy, Clone)]
 struct ProfileMetaData {
     initial_cursor_pos: usize,
-    peak_cursor_pos: usize,
+    peak_utilization: usize,
     allocation_count: usize,
     failed_allocations: usize,
 }
@@ -339,6 +372,7 @@ pub struct UtilizationProfile {
 /// See the the [module-level documentation](crate::arena) for more.
 pub struct Arena<'src> {
     cursor: *mut MaybeUninit<u8>,
+    back: *mut MaybeUninit<u8>,
     end: *mut MaybeUninit<u8>,
     src: PhantomData<&'src mut ()>, // Ensures you can't allocate out of the source arena while this one is still alive
 }
@@ -440,7 +474,7 @@ impl Arena<'static> {
 
 impl Debug for Arena<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        core::write!(f, "Arena({:p}..{:p})", self.cursor, self.end)
+        core::write!(f, "Arena({:p}..{:p})", self.cursor, self.back)
     }
 }
 
@@ -468,7 +502,7 @@ impl<'src> Arena<'src> {
             let meta = new_end.cast::<ProfileMetaData>();
             meta.write(ProfileMetaData {
                 initial_cursor_pos: start as usize,
-                peak_cursor_pos: start as usize,
+                peak_utilization: 0,
                 allocation_count: 0,
                 failed_allocations: 0,
             });
@@ -478,12 +512,14 @@ impl<'src> Arena<'src> {
 
         Arena {
             cursor: start,
+            back: end,
             end,
             src: PhantomData,
         }
     }
 
-    /// Calculates the size of the space remaining in the arena in bytes.
+    /// Calculates the size of the space remaining in the arena in bytes,
+    /// i.e. between the allocations from the front and from the back.
     ///
     /// An allocation is not guaranteed to succeed even when the returned value
     /// is greater than or equal to the requested number of bytes, because
@@ -491,7 +527,7 @@ impl<'src> Arena<'src> {
     /// to handle allocation failure.
     #[inline]
     pub fn bytes_remaining(&self) -> usize {
-        (self.end as usize) - (self.cursor as usize)
+        (self.back as usize) - (self.cursor as usize)
     }
 
     /// Constructs a new `Arena` allocating out of the free space remaining in `self`.
@@ -518,11 +554,128 @@ impl<'src> Arena<'src> {
     pub fn make_sub_arena(&mut self) -> Arena<'_> {
         Arena {
             cursor: self.cursor,
+            back: self.back,
             end: self.end,
             src: PhantomData,
         }
     }
 
+    /// Records the current state of `self` in a [`Checkpoint`], through which
+    /// the arena can be used for allocations that are released in aggregate
+    /// when the checkpoint is reset or dropped.
+    ///
+    /// Unlike a sub-arena, the checkpoint can be rewound repeatedly. It hands
+    /// out sub-arenas only for as long as it is borrowed, so no allocations
+    /// made through them can outlive a call to [`reset`](Checkpoint::reset):
+    ///
+    /// ```compile_fail
+    /// # use core::mem::MaybeUninit;
+    /// # use coca::arena::Arena;
+    /// let mut backing_region = [MaybeUninit::uninit(); 1024];
+    /// let mut arena = Arena::from(&mut backing_region[..]);
+    /// let mut frame = arena.checkpoint();
+    ///
+    /// let x = frame.arena().alloc(42);
+    /// frame.reset();
+    /// assert_eq!(*x, 42);
+    /// ```
+    ///
+    /// # Examples
+    /// ```
+    /// use core::mem::MaybeUninit;
+    /// use coca::arena::Arena;
+    ///
+    /// let mut backing_region = [MaybeUninit::uninit(); 1024];
+    /// let mut arena = Arena::from(&mut backing_region[..]);
+    /// let persistent = arena.alloc(0u64);
+    ///
+    /// let mut frame = arena.checkpoint();
+    /// for i in 0..100 {
+    ///     // each iteration may use almost all of the remaining memory...
+    ///     let mut arena = frame.arena();
+    ///     let a = arena.alloc([i; 100]);
+    ///     let b = arena.alloc([i; 100]);
+    ///     assert_eq!(a[99] + b[99], 2 * i);
+    ///     drop((a, b, arena));
+    ///
+    ///     // ...because it's released before the next one:
+    ///     frame.reset();
+    /// }
+    ///
+    /// drop(frame);
+    /// assert!(arena.try_alloc([0u32; 200]).is_some());
+    /// # drop(persistent);
+    /// ```
+    #[inline]
+    pub fn checkpoint(&mut self) -> Checkpoint<'_, 'src> {
+        Checkpoint {
+            cursor: self.cursor,
+            back: self.back,
+            arena: self,
+        }
+    }
+
+    /// Records the current state of `self` in a [`CheckpointToken`], which
+    /// [`reset_to`](Arena::reset_to) can rewind the arena to.
+    ///
+    /// Unlike a [`Checkpoint`], the token does not borrow the arena, so it
+    /// can be used for persistent allocations in between, at the cost of
+    /// having to uphold the safety requirements of `reset_to` manually.
+    ///
+    /// # Examples
+    /// ```
+    /// use core::mem::MaybeUninit;
+    /// use coca::arena::{Arena, Box};
+    /// use coca::collections::ArenaVec;
+    ///
+    /// let mut backing_region = [MaybeUninit::uninit(); 1024];
+    /// let mut arena = Arena::from(&mut backing_region[..]);
+    /// let mut sums: ArenaVec<'_, Box<'_, u32>> = arena.with_capacity(10);
+    ///
+    /// for i in 0..10 {
+    ///     let token = arena.checkpoint_token();
+    ///     let scratch = arena.alloc([i; 100]);
+    ///     let sum = scratch.iter().sum();
+    ///     drop(scratch);
+    ///
+    ///     // Safety: the only allocation since the token was recorded has been dropped
+    ///     unsafe { arena.reset_to(token); }
+    ///
+    ///     // allocations made after rewinding are unaffected by it:
+    ///     sums.push(arena.alloc(sum));
+    /// }
+    ///
+    /// assert_eq!(*sums[9], 900);
+    /// ```
+    #[inline]
+    pub fn checkpoint_token(&self) -> CheckpointToken {
+        CheckpointToken { cursor: self.cursor, back: self.back, end: self.end }
+    }
+
+    /// Rewinds `self` to the state recorded in `token`, releasing all
+    /// allocations made since.
+    ///
+    /// # Panics
+    /// Panics if `token` was not recorded from `self` or a sub-arena sharing
+    /// its region, or if `self` has already been rewound past the recorded
+    /// state.
+    ///
+    /// # Safety
+    /// None of the allocations made from `self` since `token` was recorded
+    /// may be used after this call, as their memory will be handed out again.
+    /// Allocations made before the token was recorded are unaffected.
+    #[track_caller]
+    pub unsafe fn reset_to(&mut self, token: CheckpointToken) {
+        let CheckpointToken { cursor, back, end } = token;
+        assert!(
+            end == self.end && cursor <= self.cursor && cursor <= back && self.back <= back,
+            "checkpoint token does not match the state of the arena"
+        );
+
+        self.cursor = cursor;
+        self.back = back;
+    }
+
     #[inline]
     fn try_alloc_raw(&mut self, alloc_layout: &Layout) -> *mut MaybeUninit<u8> {
         let align_offset = align_offset(self.cursor, alloc_layout);
@@ -542,12 +695,39 @@ impl<'src> Arena<'src> {
                 self.cursor = new_cursor;
 
                 #[cfg(feature = "profile")]
-                {
-                    let meta = self.profile_meta_data_mut();
-                    if meta.peak_cursor_pos < new_cursor as usize {
-                        meta.peak_cursor_pos = new_cursor as usize;
-                    }
-                }
+                self.update_peak_utilization();
+
+                return result;
+            }
+        }
+
+        #[cfg(feature = "profile")]
+        {
+            self.profile_meta_data_mut().failed_allocations += 1;
+        }
+
+        null_mut()
+    }
+
+    #[inline]
+    fn try_alloc_raw_back(&mut self, alloc_layout: &Layout) -> *mut MaybeUninit<u8> {
+        #[cfg(feature = "profile")]
+        {
+            self.profile_meta_data_mut().allocation_count += 1;
+        }
+
+        // as in `try_alloc_raw`, bounds must be checked through usize arithmetic;
+        // the padding is whatever is needed to align the start of the allocation:
+        if alloc_layout.size() <= self.bytes_remaining() {
+            let unaligned = (self.back as usize) - alloc_layout.size();
+            let padding = unaligned & (alloc_layout.align() - 1);
+            let total_bytes = alloc_layout.size() + padding;
+            if self.bytes_remaining() >= total_bytes {
+                let result = unsafe { self.back.sub(total_bytes) };
+                self.back = result;
+
+                #[cfg(feature = "profile")]
+                self.update_peak_utilization();
 
                 return result;
             }
@@ -870,12 +1050,7 @@ impl<'src> Arena<'src> {
     where
         T: Sized,
     {
-        let alloc_layout = match Layout::array::<T>(count) {
-            Ok(layout) => layout,
-            Err(_) => {
-                return None;
-            }
-        };
+        let alloc_layout = Layout::array::<T>(count).ok()?;
 
         let ptr = self.try_alloc_raw(&alloc_layout).cast::<MaybeUninit<T>>();
         if ptr.is_null() {
@@ -915,494 +1090,1984 @@ impl<'src> Arena<'src> {
         self.try_reserve_array(count).map(|b| b.init_with(|_| x))
     }
 
-    /// Constructs a collection `C` with the given capacity, backed by arena-allocated memory.
-    /// 
-    /// # Panics
-    /// Panics if the remaining space is insufficient.
-    /// See [`try_with_capacity`](Arena::try_with_capacity) for a checked version
-    /// that never panics.
-    pub fn with_capacity<S, C>(&mut self, capacity: usize) -> C
-    where
-        C: From<ArenaStorage<'src, S>>,
-        S: LayoutSpec,
-    {
-        self.try_with_capacity(capacity).expect("unexpected allocation failure in `with_capacity`")
-    }
-
-    /// Constructs a collection `C` with the given capacity, backed by arena-allocated memory.
-    /// 
-    /// Returns [`None`] if the remaining space is insufficient.
-    pub fn try_with_capacity<S, C>(&mut self, capacity: usize) -> Option<C>
-    where
-        C: From<ArenaStorage<'src, S>>,
-        S: LayoutSpec,
-    {
-        Some(C::from(self.try_storage_with_capacity(capacity)?))
-    }
-
-    /// Constructs an [`ArenaString`] initialized with the given contents, and no excess capacity.
-    /// 
+    /// Allocates memory in the arena and then copies the contents of `src`
+    /// into it.
+    ///
     /// # Panics
-    /// Panics if the remaining space in the arena is insufficient.
-    /// See [`try_string_from`](Arena::try_string_from) for a checked version that never panics.
+    /// Panics if the remaining space in the arena is insufficient. See
+    /// [`try_alloc_slice_copy`](Arena::try_alloc_slice_copy) for a checked
+    /// version that never panics.
+    #[inline]
     #[track_caller]
-    pub fn string_from<I: Capacity, T: AsRef<str>>(&mut self, value: T) -> ArenaString<'src, I> {
-        self.try_string_from::<I, T>(value).expect("unexpected allocation failure in `string_from`")
+    pub fn alloc_slice_copy<T: Copy>(&mut self, src: &[T]) -> Box<'src, [T]> {
+        self.try_alloc_slice_copy(src)
+            .expect("unexpected allocation failure in `alloc_slice_copy`")
     }
 
-    /// Constructs an [`ArenaString`] initialized with the given contents, and no excess capacity.
-    /// 
+    /// Allocates memory in the arena and then copies the contents of `src`
+    /// into it.
+    ///
     /// Returns [`None`] if the remaining space in the arena is insufficient.
-    /// 
+    ///
     /// # Examples
     /// ```
-    /// use coca::arena::Arena;
     /// use core::mem::MaybeUninit;
+    /// use coca::arena::Arena;
     ///
     /// # fn test() -> Option<()> {
     /// let mut backing_region = [MaybeUninit::uninit(); 1024];
     /// let mut arena = Arena::from(&mut backing_region[..]);
-    /// 
-    /// let mut s: coca::ArenaString<'_, usize> = arena.try_string_from("Hello, World!")?;
-    /// assert_eq!(s, "Hello, World!");
-    /// assert_eq!(s.len(), s.capacity());
-    /// # Some(()) }
+    ///
+    /// let primes = arena.try_alloc_slice_copy(&[2, 3, 5, 7, 11])?;
+    /// assert_eq!(&primes[..], &[2, 3, 5, 7, 11]);
+    /// # Some(())
+    /// # }
     /// # assert!(test().is_some());
     /// ```
-    pub fn try_string_from<I: Capacity, T: AsRef<str>>(&mut self, value: T) -> Option<ArenaString<'src, I>> {
-        let str = value.as_ref();
-        let mut result: ArenaString<'_, _> = self.try_with_capacity(str.len())?;
-        result.push_str(str);
-        Some(result)
+    pub fn try_alloc_slice_copy<T: Copy>(&mut self, src: &[T]) -> Option<Box<'src, [T]>> {
+        let ptr = self.try_array_raw::<T>(src.len())?;
+        unsafe {
+            core::ptr::copy_nonoverlapping(src.as_ptr(), ptr.cast::<T>(), src.len());
+            Some(Box::new_unchecked(slice_from_raw_parts_mut(ptr.cast::<T>(), src.len())))
+        }
     }
 
-    /// Constructs an [`ArenaString`] with the given capacity,
-    /// and initializes it with the given contents.
-    /// 
+    /// Allocates memory in the arena and then places clones of each element
+    /// of `src` into it.
+    ///
     /// # Panics
-    /// Panics if the remaining space in the arena is insufficient.
-    /// See [`try_string_with_capacity_from`](Arena::try_string_with_capacity_from)
-    /// for a checked version that never panics.
+    /// Panics if the remaining space in the arena is insufficient. See
+    /// [`try_alloc_slice_clone`](Arena::try_alloc_slice_clone) for a checked
+    /// version that never panics.
+    #[inline]
     #[track_caller]
-    pub fn string_with_capacity_from<I: Capacity, T: AsRef<str>>(&mut self, capacity: I, value: T) -> ArenaString<'src, I> {
-        self.try_string_with_capacity_from(capacity, value).expect("unexpected allocation failure in `string_with_capacity_from`")
+    pub fn alloc_slice_clone<T: Clone>(&mut self, src: &[T]) -> Box<'src, [T]> {
+        self.try_alloc_slice_clone(src)
+            .expect("unexpected allocation failure in `alloc_slice_clone`")
     }
 
-    /// Constructs an [`ArenaString`] with the given capacity,
-    /// and initializes it with the given contents.
-    /// 
-    /// Returns [`None`] if the remaining space in the arena is insufficient,
-    /// or if `value.as_ref().len()` is larger than `capacity`.
-    /// 
+    /// Allocates memory in the arena and then places clones of each element
+    /// of `src` into it.
+    ///
+    /// Returns [`None`] if the remaining space in the arena is insufficient.
+    ///
     /// # Examples
     /// ```
-    /// use coca::arena::Arena;
     /// use core::mem::MaybeUninit;
+    /// use coca::arena::Arena;
     ///
     /// # fn test() -> Option<()> {
     /// let mut backing_region = [MaybeUninit::uninit(); 1024];
     /// let mut arena = Arena::from(&mut backing_region[..]);
-    /// 
-    /// let mut s = arena.try_string_with_capacity_from(100usize, "Hello, World!")?;
-    /// assert_eq!(s, "Hello, World!");
-    /// assert_eq!(s.capacity(), 100);
-    /// # Some(()) }
+    ///
+    /// let names = [String::from("Alice"), String::from("Bob")];
+    /// let cloned = arena.try_alloc_slice_clone(&names)?;
+    /// assert_eq!(&cloned[..], &names[..]);
+    /// # Some(())
+    /// # }
     /// # assert!(test().is_some());
     /// ```
-    pub fn try_string_with_capacity_from<I: Capacity, T: AsRef<str>>(&mut self, capacity: I, value: T) -> Option<ArenaString<'src, I>> {
-        if value.as_ref().len() > capacity.as_usize() {
-            return None;
+    pub fn try_alloc_slice_clone<T: Clone>(&mut self, src: &[T]) -> Option<Box<'src, [T]>> {
+        // Drops the clones made so far if cloning another element panics.
+        struct Guard<T> {
+            ptr: *mut T,
+            initialized: usize,
         }
 
-        let mut result: ArenaString<'_, _> = self.try_with_capacity(capacity.as_usize())?;
-        result.push_str(value.as_ref());
-        Some(result)
-    }
+        impl<T> Drop for Guard<T> {
+            fn drop(&mut self) {
+                unsafe { core::ptr::drop_in_place(slice_from_raw_parts_mut(self.ptr, self.initialized)); }
+            }
+        }
 
-    /// Constructs a new [`CacheTable`] with the specified hash builder and capacity, rounded up to the next multiple of `L::CAPACITY`.
-    /// 
-    /// Returns `None` if the remaining space in the arena is insufficient.
-    #[allow(clippy::type_complexity)]
-    pub fn try_cache_with_hasher<K: Eq + Hash, V, L: crate::collections::cache::CacheLine<K, V>, H: BuildHasher>(&mut self, capacity: usize, hash_builder: H) -> Option<CacheTable<K, V, ArenaStorage<'src, ArrayLayout<L>>, L, H>> {
-        let capacity = (capacity + L::CAPACITY - 1) / L::CAPACITY;
-        let storage = self.try_storage_with_capacity(capacity)?;
-        Some(CacheTable::from_storage_and_hasher(storage, hash_builder))
-    }
+        let ptr = self.try_array_raw::<T>(src.len())?.cast::<T>();
+        let mut guard = Guard { ptr, initialized: 0 };
+        for x in src {
+            unsafe { ptr.add(guard.initialized).write(x.clone()); }
+            guard.initialized += 1;
+        }
 
-    /// Constructs a new [`CacheTable`] with the specified hash builder and capacity, rounded up to the next multiple of `L::CAPACITY`.
-    /// 
-    /// # Panics
-    /// Panics if the remaining space in the arena is insufficient to exhaust
-    /// the iterator. See [`try_cache_with_hasher`](Arena::try_cache_with_hasher)
-    /// for a checked version that never panics.
-    pub fn cache_with_hasher<K: Eq + Hash, V, L: crate::collections::cache::CacheLine<K, V>, H: BuildHasher>(&mut self, capacity: usize, hash_builder: H) -> CacheTable<K, V, ArenaStorage<'src, ArrayLayout<L>>, L, H> {
-        self.try_cache_with_hasher(capacity, hash_builder)
-            .expect("unexpected allocation failure in cache_with_hasher")
+        core::mem::forget(guard);
+        Some(unsafe { Box::new_unchecked(slice_from_raw_parts_mut(ptr, src.len())) })
     }
 
-    /// Transforms an iterator into a boxed slice in the arena.
+    /// Allocates memory in the arena and then copies the string `src` into it.
     ///
     /// # Panics
-    /// Panics if the remaining space in the arena is insufficient to exhaust
-    /// the iterator. See [`try_collect_slice`](Arena::try_collect_slice)
-    /// for a checked version that never panics.
+    /// Panics if the remaining space in the arena is insufficient. See
+    /// [`try_alloc_str`](Arena::try_alloc_str) for a checked version that
+    /// never panics.
     #[inline]
     #[track_caller]
-    pub fn collect_slice<T, I>(&mut self, iter: I) -> Box<'src, [T]>
-    where
-        T: Sized,
-        I: IntoIterator<Item = T>,
-    {
-        self.try_collect_slice(iter)
-            .expect("unexpected allocation failure in `collect`")
+    pub fn alloc_str(&mut self, src: &str) -> Box<'src, str> {
+        self.try_alloc_str(src)
+            .expect("unexpected allocation failure in `alloc_str`")
     }
 
-    /// Transforms an iterator into a boxed slice in the arena.
+    /// Allocates memory in the arena and then copies the string `src` into it.
     ///
-    /// Returns [`None`] if the remaining space in the arena is insufficient
-    /// to exhaust the iterator.
+    /// Returns [`None`] if the remaining space in the arena is insufficient.
     ///
     /// # Examples
     /// ```
-    /// use core::mem::{MaybeUninit, size_of, size_of_val};
-    /// use coca::arena::Arena;
+    /// use core::mem::MaybeUninit;
+    /// use coca::arena::{Arena, Box};
     ///
     /// # fn test() -> Option<()> {
     /// let mut backing_region = [MaybeUninit::uninit(); 1024];
     /// let mut arena = Arena::from(&mut backing_region[..]);
     ///
-    /// let a = [1, 2, 3];
-    /// let doubled = arena.try_collect_slice(a.iter().map(|&x| x * 2))?;
-    ///
-    /// assert_eq!(&doubled[..], &[2, 4, 6]);
+    /// let greeting: Box<'_, str> = arena.try_alloc_str("Hello, World!")?;
+    /// assert_eq!(&*greeting, "Hello, World!");
     /// # Some(())
     /// # }
     /// # assert!(test().is_some());
     /// ```
-    pub fn try_collect_slice<T, I>(&mut self, iter: I) -> Option<Box<'src, [T]>>
-    where
-        T: Sized,
-        I: IntoIterator<Item = T>,
-    {
-        let alloc_layout = Layout::new::<T>();
-        let align_offset = align_offset(self.cursor, &alloc_layout);
+    pub fn try_alloc_str(&mut self, src: &str) -> Option<Box<'src, str>> {
+        let bytes = self.try_alloc_slice_copy(src.as_bytes())?;
+        let ptr = bytes.ptr.as_ptr() as *mut str;
+        core::mem::forget(bytes);
+        Some(unsafe { Box::new_unchecked(ptr) })
+    }
 
-        #[cfg(feature = "profile")]
-        {
-            self.profile_meta_data_mut().allocation_count += 1;
-        }
+    /// Allocates memory in the arena, places `x` into it, and then coerces the
+    /// pointer to a dynamically sized type `T`, usually a trait object.
+    ///
+    /// # Panics
+    /// Panics if the remaining space in the arena is insufficient. See
+    /// [`try_alloc_unsize`](Arena::try_alloc_unsize) for a checked version
+    /// that never panics.
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(docs_rs, doc(cfg(feature = "unstable")))]
+    #[inline]
+    #[track_caller]
+    pub fn alloc_unsize<T: ?Sized>(&mut self, x: impl core::marker::Unsize<T>) -> Box<'src, T> {
+        self.try_alloc_unsize(x)
+            .expect("unexpected allocation failure in `alloc_unsize`")
+    }
 
-        let bytes_remaining = self.bytes_remaining();
-        if bytes_remaining < align_offset {
-            #[cfg(feature = "profile")]
-            {
-                self.profile_meta_data_mut().failed_allocations += 1;
-            }
-            return None;
-        }
+    /// Allocates memory in the arena, places `x` into it, and then coerces the
+    /// pointer to a dynamically sized type `T`, usually a trait object.
+    ///
+    /// Returns [`None`] if the remaining space in the arena is insufficient.
+    ///
+    /// # Examples
+    /// ```
+    /// use core::fmt::Display;
+    /// use core::mem::MaybeUninit;
+    /// use coca::arena::{Arena, Box};
+    ///
+    /// # fn test() -> Option<()> {
+    /// let mut backing_region = [MaybeUninit::uninit(); 1024];
+    /// let mut arena = Arena::from(&mut backing_region[..]);
+    ///
+    /// let items: [Box<'_, dyn Display>; 2] = [
+    ///     arena.try_alloc_unsize::<dyn Display>(42)?,
+    ///     arena.try_alloc_unsize::<dyn Display>("forty-two")?,
+    /// ];
+    /// assert_eq!(items[0].to_string(), "42");
+    /// assert_eq!(items[1].to_string(), "forty-two");
+    /// # Some(())
+    /// # }
+    /// # assert!(test().is_some());
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(docs_rs, doc(cfg(feature = "unstable")))]
+    pub fn try_alloc_unsize<T: ?Sized>(&mut self, x: impl core::marker::Unsize<T>) -> Option<Box<'src, T>> {
+        let b = self.try_alloc(x)?;
+        let ptr = b.ptr.as_ptr() as *mut T;
+        core::mem::forget(b);
+        Some(unsafe { Box::new_unchecked(ptr) })
+    }
 
-        let iter = iter.into_iter();
-        let (min_items, _) = iter.size_hint();
-        
-        let item_capacity = (bytes_remaining - align_offset) / core::mem::size_of::<T>();
-        if item_capacity < min_items {
-            #[cfg(feature = "profile")]
-            {
-                self.profile_meta_data_mut().failed_allocations += 1;
-            }
-            return None;
-        }
+    /// Constructs a collection `C` with the given capacity, backed by arena-allocated memory.
+    /// 
+    /// # Panics
+    /// Panics if the remaining space is insufficient.
+    /// See [`try_with_capacity`](Arena::try_with_capacity) for a checked version
+    /// that never panics.
+    pub fn with_capacity<S, C>(&mut self, capacity: usize) -> C
+    where
+        C: From<ArenaStorage<'src, S>>,
+        S: LayoutSpec,
+    {
+        self.try_with_capacity(capacity).expect("unexpected allocation failure in `with_capacity`")
+    }
 
-        let base = unsafe { self.cursor.add(align_offset).cast::<T>() };
-        let mut count = 0_usize;
-        let mut cursor = base;
+    /// Constructs a collection `C` with the given capacity, backed by arena-allocated memory.
+    /// 
+    /// Returns [`None`] if the remaining space is insufficient.
+    pub fn try_with_capacity<S, C>(&mut self, capacity: usize) -> Option<C>
+    where
+        C: From<ArenaStorage<'src, S>>,
+        S: LayoutSpec,
+    {
+        Some(C::from(self.try_storage_with_capacity(capacity)?))
+    }
 
-        for val in iter {
-            if count == item_capacity {
-                for i in 0..count {
-                    unsafe {
-                        base.add(i).drop_in_place();
-                    }
-                }
+    /// Allocates enough memory at the back of the arena for `capacity` items
+    /// according to the [`LayoutSpec`], leaving the memory uninitialized.
+    ///
+    /// Memory allocated from the back grows downwards from the end of the
+    /// arena, towards allocations from the front. This allows separating
+    /// allocations with different lifetimes within the same budget.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is large enough to cause a [`LayoutError`](core::alloc::LayoutError),
+    /// or if the remaining space in the arena is insufficient.
+    /// See [`try_storage_with_capacity_back`](Arena::try_storage_with_capacity_back)
+    /// for a checked version that never panics.
+    #[inline]
+    #[track_caller]
+    pub fn storage_with_capacity_back<R: LayoutSpec>(
+        &mut self,
+        capacity: usize,
+    ) -> ArenaStorage<'src, R> {
+        self.try_storage_with_capacity_back(capacity)
+            .expect("unexpected allocation failure in `storage_with_capacity_back`")
+    }
 
-                #[cfg(feature = "profile")]
-                {
-                    self.profile_meta_data_mut().failed_allocations += 1;
-                }
+    /// Allocates enough memory at the back of the arena for `capacity` items
+    /// according to the [`LayoutSpec`], leaving the memory uninitialized.
+    ///
+    /// Returns [`CapacityError`](crate::CapacityError) if `capacity` is large
+    /// enough to cause a [`LayoutError`](core::alloc::LayoutError), or if the
+    /// allocation would overlap the front of the arena.
+    pub fn try_storage_with_capacity_back<R: LayoutSpec>(
+        &mut self,
+        capacity: usize,
+    ) -> crate::Result<ArenaStorage<'src, R>> {
+        let layout = R::layout_with_capacity(capacity).map_err(|_| crate::CapacityError)?;
+        let ptr = self.try_alloc_raw_back(&layout).cast::<u8>();
+        unsafe { ArenaStorage::from_raw_parts(ptr, capacity) }.ok_or(crate::CapacityError)
+    }
 
-                return None;
-            }
+    /// Allocates memory at the back of the arena and then places `x` into it.
+    ///
+    /// # Panics
+    /// Panics if the remaining space in the arena is insufficient. See
+    /// [`try_alloc_back`](Arena::try_alloc_back) for a checked version that
+    /// never panics.
+    #[inline]
+    #[track_caller]
+    pub fn alloc_back<T: Sized>(&mut self, x: T) -> Box<'src, T> {
+        self.try_reserve_back()
+            .expect("unexpected allocation failure in `alloc_back`")
+            .init(x)
+    }
 
-            count += 1;
-            unsafe {
-                cursor.write(val);
-                cursor = cursor.add(1);
-            }
-        }
+    /// Allocates memory at the back of the arena and then places `x` into it.
+    ///
+    /// Returns [`CapacityError`](crate::CapacityError) if the allocation would
+    /// overlap the front of the arena, in which case `x` is dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// use core::mem::MaybeUninit;
+    /// use coca::arena::Arena;
+    ///
+    /// let mut backing_region = [MaybeUninit::uninit(); 1024];
+    /// let mut arena = Arena::from(&mut backing_region[..]);
+    ///
+    /// let level = arena.alloc([1u8; 256]);
+    /// let scratch = arena.alloc_back([2u8; 256]);
+    /// assert!(arena.bytes_remaining() <= 512);
+    ///
+    /// while let Ok(_) = arena.try_alloc_back(0u64) {}
+    /// assert!(arena.try_alloc(0u64).is_none());
+    /// # assert!(level.iter().all(|&x| x == 1) && scratch.iter().all(|&x| x == 2));
+    /// ```
+    #[inline]
+    pub fn try_alloc_back<T: Sized>(&mut self, x: T) -> crate::Result<Box<'src, T>> {
+        self.try_reserve_back().map(|b| b.init(x))
+    }
 
-        self.cursor = cursor.cast::<MaybeUninit<u8>>();
+    /// Allocates memory at the back of the arena, leaving it uninitialized.
+    ///
+    /// # Panics
+    /// Panics if the remaining space in the arena is insufficient. See
+    /// [`try_reserve_back`](Arena::try_reserve_back) for a checked version
+    /// that never panics.
+    #[inline]
+    #[track_caller]
+    pub fn reserve_back<T: Sized>(&mut self) -> Box<'src, MaybeUninit<T>> {
+        self.try_reserve_back()
+            .expect("unexpected allocation failure in `reserve_back`")
+    }
 
-        #[cfg(feature = "profile")]
-        {
-            let meta = self.profile_meta_data_mut();
-            if meta.peak_cursor_pos < cursor as usize {
-                meta.peak_cursor_pos = cursor as usize;
-            }
+    /// Allocates memory at the back of the arena, leaving it uninitialized.
+    ///
+    /// Returns [`CapacityError`](crate::CapacityError) if the allocation would
+    /// overlap the front of the arena.
+    #[inline]
+    pub fn try_reserve_back<T: Sized>(&mut self) -> crate::Result<Box<'src, MaybeUninit<T>>> {
+        let ptr = self.try_alloc_raw_back(&Layout::new::<T>()).cast::<MaybeUninit<T>>();
+        if ptr.is_null() {
+            return crate::CapacityError::new();
         }
 
-        unsafe {
-            let slice = from_raw_parts_mut(base, count);
-            Some(Box::new_unchecked(slice))
-        }
+        Ok(unsafe { Box::new_unchecked(ptr) })
     }
 
-    /// Transforms an iterator into a collection `C` with the given capacity.
-    /// 
-    /// The collection type must be convertible [`From<ArenaStorage>`](core::convert::From),
-    /// i.e. able to use arena-allocated memory, and must be [`Extend`able](core::iter::Extend)
-    /// by including the contents of the given iterator.
-    /// 
+    /// Constructs a collection `C` with the given capacity, backed by memory
+    /// allocated from the back of the arena.
+    ///
     /// # Panics
     /// Panics if the remaining space is insufficient.
+    /// See [`try_with_capacity_back`](Arena::try_with_capacity_back) for a
+    /// checked version that never panics.
     #[track_caller]
-    pub fn collect_with_capacity<I, S, C>(&mut self, iter: I, capacity: usize) -> C
+    pub fn with_capacity_back<S, C>(&mut self, capacity: usize) -> C
     where
+        C: From<ArenaStorage<'src, S>>,
         S: LayoutSpec,
-        C: From<ArenaStorage<'src, S>> + Extend<I::Item>,
-        I: Iterator,
     {
-        self.try_collect_with_capacity(iter, capacity).expect("unexpected allocation failure in `collect_with_capacity`")
+        self.try_with_capacity_back(capacity).expect("unexpected allocation failure in `with_capacity_back`")
     }
 
-    /// Transforms an iterator into a collection `C` with the given capacity.
-    /// 
-    /// The collection type must be convertible [`From<ArenaStorage>`](core::convert::From),
-    /// i.e. able to use arena-allocated memory, and must be [`Extend`able](core::iter::Extend)
-    /// by including the contents of the given iterator.
-    /// 
-    /// Returns [`None`] if the remaining space is insufficient.
-    /// 
+    /// Constructs a collection `C` with the given capacity, backed by memory
+    /// allocated from the back of the arena.
+    ///
+    /// Returns [`CapacityError`](crate::CapacityError) if the remaining space
+    /// is insufficient.
+    ///
     /// # Examples
     /// ```
-    /// use core::mem::{MaybeUninit, size_of, size_of_val};
-    /// use coca::arena::Arena;
+    /// use core::mem::MaybeUninit;
+    /// use coca::{arena::Arena, collections::ArenaVec};
     ///
-    /// # fn test() -> Option<()> {
     /// let mut backing_region = [MaybeUninit::uninit(); 1024];
     /// let mut arena = Arena::from(&mut backing_region[..]);
     ///
-    /// let chars = ['a', 'b', 'c', 'd', 'e'];
-    /// let s: coca::ArenaString<'_, usize> = arena.try_collect_with_capacity(chars.iter(), 8)?;
-    /// 
-    /// assert_eq!(s, "abcde");
-    /// # Some(())
-    /// # }
-    /// # assert!(test().is_some());
+    /// let mut front: ArenaVec<'_, u32> = arena.with_capacity(100);
+    /// let mut back: ArenaVec<'_, u32> = arena.with_capacity_back(100);
+    /// assert!(arena.try_with_capacity_back::<_, ArenaVec<'_, u32>>(100).is_err());
+    ///
+    /// front.push(1);
+    /// back.push(2);
     /// ```
-    pub fn try_collect_with_capacity<I, S, C>(&mut self, iter: I, capacity: usize) -> Option<C>
+    pub fn try_with_capacity_back<S, C>(&mut self, capacity: usize) -> crate::Result<C>
     where
+        C: From<ArenaStorage<'src, S>>,
         S: LayoutSpec,
-        C: From<ArenaStorage<'src, S>> + Extend<I::Item>,
-        I: Iterator,
     {
-        let storage = self.try_storage_with_capacity(capacity)?;
-        let mut result = C::from(storage);
-        result.extend(iter);
-        Some(result)
+        self.try_storage_with_capacity_back(capacity).map(C::from)
     }
 
-    /// Constructs a new [`Writer`] backed by the free space remaining in `self`.
+    /// Allocates a [`TypedSlab`] with room for `capacity` values of type `T`.
     ///
-    /// The arena cannot be used for allocation until the writer is dropped.
+    /// # Panics
+    /// Panics if the remaining space in the arena is insufficient. See
+    /// [`try_typed_slab`](Arena::try_typed_slab) for a checked version that
+    /// never panics.
+    #[inline]
+    #[track_caller]
+    pub fn typed_slab<T>(&mut self, capacity: usize) -> TypedSlab<'src, T> {
+        self.try_typed_slab(capacity)
+            .expect("unexpected allocation failure in `typed_slab`")
+    }
+
+    /// Allocates a [`TypedSlab`] with room for `capacity` values of type `T`.
     ///
-    /// Primarily intended for use in expansions of [`fmt!`]. This should only
-    /// be used explicitly where format strings don't work as well.
+    /// Unlike values allocated directly from the arena, the memory occupied by
+    /// values in the slab is reused once they are dropped.
+    ///
+    /// Returns [`None`] if the remaining space in the arena is insufficient.
     ///
     /// # Examples
     /// ```
-    /// use coca::arena::{Arena, Box};
-    /// use core::{fmt::Write, mem::MaybeUninit};
+    /// use core::mem::MaybeUninit;
+    /// use coca::arena::Arena;
     ///
-    /// # fn main() -> Result<(), core::fmt::Error> {
-    /// let parts = ["Hello", ",", " ", "World", "!"];
+    /// # fn test() -> Option<()> {
     /// let mut backing_region = [MaybeUninit::uninit(); 1024];
     /// let mut arena = Arena::from(&mut backing_region[..]);
+    /// let slab = arena.try_typed_slab::<[u8; 16]>(4)?;
     ///
-    /// let mut writer = arena.make_writer();
-    /// for s in parts.iter() {
-    ///     writer.write_str(s)?;
+    /// for i in 0..100 {
+    ///     let a = slab.alloc([i; 16]);
+    ///     let b = slab.alloc([i + 1; 16]);
+    ///     assert!(*a < *b);
     /// }
     ///
-    /// let combined: Box::<'_, str> = writer.into();
-    /// assert_eq!(combined.as_ref(), "Hello, World!");
-    /// # Ok(())
+    /// assert!(slab.is_empty());
+    /// # Some(())
     /// # }
-    #[inline]
-    pub fn make_writer<'a>(&'a mut self) -> Writer<'a, 'src> {
-        Writer {
-            source: self,
-            len: 0,
-        }
+    /// # assert!(test().is_some());
+    /// ```
+    pub fn try_typed_slab<T>(&mut self, capacity: usize) -> Option<TypedSlab<'src, T>> {
+        let slots = self.try_array_raw::<SlabSlot<T>>(capacity)?;
+        Some(TypedSlab {
+            slots: NonNull::new(slots.cast::<SlabSlot<T>>())?,
+            capacity,
+            initialized: Cell::new(0),
+            free_list: FreeList { head: Cell::new(null_mut()), len: Cell::new(0) },
+            src: PhantomData,
+        })
     }
-}
 
-#[cfg(feature = "profile")]
-impl Arena<'_> {
-    /// Returns a profile of all allocations from the arena and all sub-arenas created from it.
-    ///
+    /// Constructs an [`ArenaString`] initialized with the given contents, and no excess capacity.
+    /// 
+    /// # Panics
+    /// Panics if the remaining space in the arena is insufficient.
+    /// See [`try_string_from`](Arena::try_string_from) for a checked version that never panics.
+    #[track_caller]
+    pub fn string_from<I: Capacity, T: AsRef<str>>(&mut self, value: T) -> ArenaString<'src, I> {
+        self.try_string_from::<I, T>(value).expect("unexpected allocation failure in `string_from`")
+    }
+
+    /// Constructs an [`ArenaString`] initialized with the given contents, and no excess capacity.
+    /// 
+    /// Returns [`None`] if the remaining space in the arena is insufficient.
+    /// 
     /// # Examples
     /// ```
-    /// use core::mem::MaybeUninit;
     /// use coca::arena::Arena;
+    /// use core::mem::MaybeUninit;
     ///
-    /// let mut backing_region = [MaybeUninit::uninit(); 256];
+    /// # fn test() -> Option<()> {
+    /// let mut backing_region = [MaybeUninit::uninit(); 1024];
     /// let mut arena = Arena::from(&mut backing_region[..]);
-    ///
-    /// {
-    ///     let mut tmp = arena.make_sub_arena();
-    ///     let _ = tmp.array_default::<u8>(100);
-    /// }
-    /// {
-    ///     let mut tmp = arena.make_sub_arena();
-    ///     let _ = tmp.array_default::<u8>(50);
-    ///     let _ = tmp.try_array_default::<u8>(200);
-    /// }
-    /// {
-    ///     let mut tmp = arena.make_sub_arena();
-    ///     let _ = tmp.array_default::<u8>(200);
-    /// }
-    ///
-    /// let profile = arena.utilization();
-    /// assert_eq!(profile.peak_utilization, 200);
-    /// assert_eq!(profile.allocation_count, 4);
-    /// assert_eq!(profile.failed_allocations, 1);
+    /// 
+    /// let mut s: coca::ArenaString<'_, usize> = arena.try_string_from("Hello, World!")?;
+    /// assert_eq!(s, "Hello, World!");
+    /// assert_eq!(s.len(), s.capacity());
+    /// # Some(()) }
+    /// # assert!(test().is_some());
     /// ```
-    #[inline]
+    pub fn try_string_from<I: Capacity, T: AsRef<str>>(&mut self, value: T) -> Option<ArenaString<'src, I>> {
+        let str = value.as_ref();
+        let mut result: ArenaString<'_, _> = self.try_with_capacity(str.len())?;
+        result.push_str(str);
+        Some(result)
+    }
+
+    /// Constructs an [`ArenaString`] with the given capacity,
+    /// and initializes it with the given contents.
+    /// 
+    /// # Panics
+    /// Panics if the remaining space in the arena is insufficient.
+    /// See [`try_string_with_capacity_from`](Arena::try_string_with_capacity_from)
+    /// for a checked version that never panics.
+    #[track_caller]
+    pub fn string_with_capacity_from<I: Capacity, T: AsRef<str>>(&mut self, capacity: I, value: T) -> ArenaString<'src, I> {
+        self.try_string_with_capacity_from(capacity, value).expect("unexpected allocation failure in `string_with_capacity_from`")
+    }
+
+    /// Constructs an [`ArenaString`] with the given capacity,
+    /// and initializes it with the given contents.
+    /// 
+    /// Returns [`None`] if the remaining space in the arena is insufficient,
+    /// or if `value.as_ref().len()` is larger than `capacity`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use coca::arena::Arena;
+    /// use core::mem::MaybeUninit;
+    ///
+    /// # fn test() -> Option<()> {
+    /// let mut backing_region = [MaybeUninit::uninit(); 1024];
+    /// let mut arena = Arena::from(&mut backing_region[..]);
+    /// 
+    /// let mut s = arena.try_string_with_capacity_from(100usize, "Hello, World!")?;
+    /// assert_eq!(s, "Hello, World!");
+    /// assert_eq!(s.capacity(), 100);
+    /// # Some(()) }
+    /// # assert!(test().is_some());
+    /// ```
+    pub fn try_string_with_capacity_from<I: Capacity, T: AsRef<str>>(&mut self, capacity: I, value: T) -> Option<ArenaString<'src, I>> {
+        if value.as_ref().len() > capacity.as_usize() {
+            return None;
+        }
+
+        let mut result: ArenaString<'_, _> = self.try_with_capacity(capacity.as_usize())?;
+        result.push_str(value.as_ref());
+        Some(result)
+    }
+
+    /// Constructs a new [`CacheTable`] with the specified hash builder and capacity, rounded up to the next multiple of `L::CAPACITY`.
+    /// 
+    /// Returns `None` if the remaining space in the arena is insufficient.
+    #[allow(clippy::type_complexity)]
+    pub fn try_cache_with_hasher<K: Eq + Hash, V, L: crate::collections::cache::CacheLine<K, V>, H: BuildHasher>(&mut self, capacity: usize, hash_builder: H) -> Option<CacheTable<K, V, ArenaStorage<'src, ArrayLayout<L>>, L, H>> {
+        let capacity = (capacity + L::CAPACITY - 1) / L::CAPACITY;
+        let storage = self.try_storage_with_capacity(capacity)?;
+        Some(CacheTable::from_storage_and_hasher(storage, hash_builder))
+    }
+
+    /// Constructs a new [`CacheTable`] with the specified hash builder and capacity, rounded up to the next multiple of `L::CAPACITY`.
+    /// 
+    /// # Panics
+    /// Panics if the remaining space in the arena is insufficient to exhaust
+    /// the iterator. See [`try_cache_with_hasher`](Arena::try_cache_with_hasher)
+    /// for a checked version that never panics.
+    pub fn cache_with_hasher<K: Eq + Hash, V, L: crate::collections::cache::CacheLine<K, V>, H: BuildHasher>(&mut self, capacity: usize, hash_builder: H) -> CacheTable<K, V, ArenaStorage<'src, ArrayLayout<L>>, L, H> {
+        self.try_cache_with_hasher(capacity, hash_builder)
+            .expect("unexpected allocation failure in cache_with_hasher")
+    }
+
+    /// Constructs a new, empty [`HashMap`] with the specified hash builder and capacity.
+    /// 
+    /// Returns `None` if the remaining space in the arena is insufficient.
+    /// 
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use coca::arena::Arena;
+    /// use core::mem::MaybeUninit;
+    /// type HashBuilder = core::hash::BuildHasherDefault<rustc_hash::FxHasher>;
+    /// 
+    /// # fn test() -> Option<()> {
+    /// let mut backing_region = [MaybeUninit::uninit(); 1024];
+    /// let mut arena = Arena::from(&mut backing_region[..]);
+    /// 
+    /// let mut map = arena.try_hash_map_with_hasher(16, HashBuilder::default())?;
+    /// map.insert("a", 1);
+    /// assert_eq!(map.get("a"), Some(&1));
+    /// # Some(()) }
+    /// # assert!(test().is_some());
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn try_hash_map_with_hasher<K: Eq + Hash, V, H: BuildHasher>(&mut self, capacity: usize, hash_builder: H) -> Option<HashMap<K, V, ArenaStorage<'src, HashMapLayout<K, V>>, H>> {
+        let storage = self.try_storage_with_capacity(capacity)?;
+        Some(HashMap::from_storage_and_hasher(storage, hash_builder))
+    }
+
+    /// Constructs a new, empty [`HashMap`] with the specified hash builder and capacity.
+    /// 
+    /// # Panics
+    /// Panics if the remaining space in the arena is insufficient. See
+    /// [`try_hash_map_with_hasher`](Arena::try_hash_map_with_hasher)
+    /// for a checked version that never panics.
+    #[track_caller]
+    pub fn hash_map_with_hasher<K: Eq + Hash, V, H: BuildHasher>(&mut self, capacity: usize, hash_builder: H) -> HashMap<K, V, ArenaStorage<'src, HashMapLayout<K, V>>, H> {
+        self.try_hash_map_with_hasher(capacity, hash_builder)
+            .expect("unexpected allocation failure in hash_map_with_hasher")
+    }
+
+    /// Transforms an iterator into a boxed slice in the arena.
+    ///
+    /// # Panics
+    /// Panics if the remaining space in the arena is insufficient to exhaust
+    /// the iterator. See [`try_collect_slice`](Arena::try_collect_slice)
+    /// for a checked version that never panics.
+    #[inline]
+    #[track_caller]
+    pub fn collect_slice<T, I>(&mut self, iter: I) -> Box<'src, [T]>
+    where
+        T: Sized,
+        I: IntoIterator<Item = T>,
+    {
+        self.try_collect_slice(iter)
+            .expect("unexpected allocation failure in `collect`")
+    }
+
+    /// Transforms an iterator into a boxed slice in the arena.
+    ///
+    /// Returns [`None`] if the remaining space in the arena is insufficient
+    /// to exhaust the iterator.
+    ///
+    /// # Examples
+    /// ```
+    /// use core::mem::{MaybeUninit, size_of, size_of_val};
+    /// use coca::arena::Arena;
+    ///
+    /// # fn test() -> Option<()> {
+    /// let mut backing_region = [MaybeUninit::uninit(); 1024];
+    /// let mut arena = Arena::from(&mut backing_region[..]);
+    ///
+    /// let a = [1, 2, 3];
+    /// let doubled = arena.try_collect_slice(a.iter().map(|&x| x * 2))?;
+    ///
+    /// assert_eq!(&doubled[..], &[2, 4, 6]);
+    /// # Some(())
+    /// # }
+    /// # assert!(test().is_some());
+    /// ```
+    pub fn try_collect_slice<T, I>(&mut self, iter: I) -> Option<Box<'src, [T]>>
+    where
+        T: Sized,
+        I: IntoIterator<Item = T>,
+    {
+        let alloc_layout = Layout::new::<T>();
+        let align_offset = align_offset(self.cursor, &alloc_layout);
+
+        #[cfg(feature = "profile")]
+        {
+            self.profile_meta_data_mut().allocation_count += 1;
+        }
+
+        let bytes_remaining = self.bytes_remaining();
+        if bytes_remaining < align_offset {
+            #[cfg(feature = "profile")]
+            {
+                self.profile_meta_data_mut().failed_allocations += 1;
+            }
+            return None;
+        }
+
+        let iter = iter.into_iter();
+        let (min_items, _) = iter.size_hint();
+        
+        let item_capacity = (bytes_remaining - align_offset) / core::mem::size_of::<T>();
+        if item_capacity < min_items {
+            #[cfg(feature = "profile")]
+            {
+                self.profile_meta_data_mut().failed_allocations += 1;
+            }
+            return None;
+        }
+
+        let base = unsafe { self.cursor.add(align_offset).cast::<T>() };
+        let mut count = 0_usize;
+        let mut cursor = base;
+
+        for val in iter {
+            if count == item_capacity {
+                for i in 0..count {
+                    unsafe {
+                        base.add(i).drop_in_place();
+                    }
+                }
+
+                #[cfg(feature = "profile")]
+                {
+                    self.profile_meta_data_mut().failed_allocations += 1;
+                }
+
+                return None;
+            }
+
+            count += 1;
+            unsafe {
+                cursor.write(val);
+                cursor = cursor.add(1);
+            }
+        }
+
+        self.cursor = cursor.cast::<MaybeUninit<u8>>();
+
+        #[cfg(feature = "profile")]
+        self.update_peak_utilization();
+
+        unsafe {
+            let slice = from_raw_parts_mut(base, count);
+            Some(Box::new_unchecked(slice))
+        }
+    }
+
+    /// Transforms an iterator into a collection `C` with the given capacity.
+    /// 
+    /// The collection type must be convertible [`From<ArenaStorage>`](core::convert::From),
+    /// i.e. able to use arena-allocated memory, and must be [`Extend`able](core::iter::Extend)
+    /// by including the contents of the given iterator.
+    /// 
+    /// # Panics
+    /// Panics if the remaining space is insufficient.
+    #[track_caller]
+    pub fn collect_with_capacity<I, S, C>(&mut self, iter: I, capacity: usize) -> C
+    where
+        S: LayoutSpec,
+        C: From<ArenaStorage<'src, S>> + Extend<I::Item>,
+        I: Iterator,
+    {
+        self.try_collect_with_capacity(iter, capacity).expect("unexpected allocation failure in `collect_with_capacity`")
+    }
+
+    /// Transforms an iterator into a collection `C` with the given capacity.
+    /// 
+    /// The collection type must be convertible [`From<ArenaStorage>`](core::convert::From),
+    /// i.e. able to use arena-allocated memory, and must be [`Extend`able](core::iter::Extend)
+    /// by including the contents of the given iterator.
+    /// 
+    /// Returns [`None`] if the remaining space is insufficient.
+    /// 
+    /// # Examples
+    /// ```
+    /// use core::mem::{MaybeUninit, size_of, size_of_val};
+    /// use coca::arena::Arena;
+    ///
+    /// # fn test() -> Option<()> {
+    /// let mut backing_region = [MaybeUninit::uninit(); 1024];
+    /// let mut arena = Arena::from(&mut backing_region[..]);
+    ///
+    /// let chars = ['a', 'b', 'c', 'd', 'e'];
+    /// let s: coca::ArenaString<'_, usize> = arena.try_collect_with_capacity(chars.iter(), 8)?;
+    /// 
+    /// assert_eq!(s, "abcde");
+    /// # Some(())
+    /// # }
+    /// # assert!(test().is_some());
+    /// ```
+    pub fn try_collect_with_capacity<I, S, C>(&mut self, iter: I, capacity: usize) -> Option<C>
+    where
+        S: LayoutSpec,
+        C: From<ArenaStorage<'src, S>> + Extend<I::Item>,
+        I: Iterator,
+    {
+        let storage = self.try_storage_with_capacity(capacity)?;
+        let mut result = C::from(storage);
+        result.extend(iter);
+        Some(result)
+    }
+
+    /// Constructs a new [`Writer`] backed by the free space remaining in `self`.
+    ///
+    /// The arena cannot be used for allocation until the writer is dropped.
+    ///
+    /// Primarily intended for use in expansions of [`fmt!`]. This should only
+    /// be used explicitly where format strings don't work as well.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::arena::{Arena, Box};
+    /// use core::{fmt::Write, mem::MaybeUninit};
+    ///
+    /// # fn main() -> Result<(), core::fmt::Error> {
+    /// let parts = ["Hello", ",", " ", "World", "!"];
+    /// let mut backing_region = [MaybeUninit::uninit(); 1024];
+    /// let mut arena = Arena::from(&mut backing_region[..]);
+    ///
+    /// let mut writer = arena.make_writer();
+    /// for s in parts.iter() {
+    ///     writer.write_str(s)?;
+    /// }
+    ///
+    /// let combined: Box::<'_, str> = writer.into();
+    /// assert_eq!(combined.as_ref(), "Hello, World!");
+    /// # Ok(())
+    /// # }
+    #[inline]
+    pub fn make_writer<'a>(&'a mut self) -> Writer<'a, 'src> {
+        Writer {
+            source: self,
+            len: 0,
+        }
+    }
+}
+
+#[cfg(feature = "profile")]
+impl Arena<'_> {
+    /// Returns a profile of all allocations from the arena and all sub-arenas created from it.
+    ///
+    /// # Examples
+    /// ```
+    /// use core::mem::MaybeUninit;
+    /// use coca::arena::Arena;
+    ///
+    /// let mut backing_region = [MaybeUninit::uninit(); 256];
+    /// let mut arena = Arena::from(&mut backing_region[..]);
+    ///
+    /// {
+    ///     let mut tmp = arena.make_sub_arena();
+    ///     let _ = tmp.array_default::<u8>(100);
+    /// }
+    /// {
+    ///     let mut tmp = arena.make_sub_arena();
+    ///     let _ = tmp.array_default::<u8>(50);
+    ///     let _ = tmp.try_array_default::<u8>(200);
+    /// }
+    /// {
+    ///     let mut tmp = arena.make_sub_arena();
+    ///     let _ = tmp.array_default::<u8>(200);
+    /// }
+    ///
+    /// let profile = arena.utilization();
+    /// assert_eq!(profile.peak_utilization, 200);
+    /// assert_eq!(profile.allocation_count, 4);
+    /// assert_eq!(profile.failed_allocations, 1);
+    /// ```
+    #[inline]
+    #[allow(clippy::cast_ptr_alignment)]
+    pub fn utilization(&self) -> UtilizationProfile {
+        let layout = Layout::new::<ProfileMetaData>();
+        debug_assert_eq!(align_offset(self.end, &layout), 0);
+        let &ProfileMetaData {
+            peak_utilization,
+            allocation_count,
+            failed_allocations,
+            ..
+        } = unsafe { &*self.end.cast::<ProfileMetaData>() };
+        UtilizationProfile {
+            peak_utilization,
+            allocation_count,
+            failed_allocations,
+        }
+    }
+
+    #[inline]
     #[allow(clippy::cast_ptr_alignment)]
+    fn profile_meta_data_mut(&mut self) -> &mut ProfileMetaData {
+        let layout = Layout::new::<ProfileMetaData>();
+        debug_assert_eq!(align_offset(self.end, &layout), 0);
+        unsafe { &mut *self.end.cast::<ProfileMetaData>() }
+    }
+
+    #[inline]
+    fn update_peak_utilization(&mut self) {
+        let back_bytes = (self.end as usize) - (self.back as usize);
+        let cursor = self.cursor as usize;
+        let meta = self.profile_meta_data_mut();
+        let utilization = cursor - meta.initial_cursor_pos + back_bytes;
+        if meta.peak_utilization < utilization {
+            meta.peak_utilization = utilization;
+        }
+    }
+}
+
+/// A recorded state of an [`Arena`] that can be returned to.
+///
+/// The arena can be borrowed from the checkpoint for allocations, which are
+/// released when the checkpoint is [reset](Checkpoint::reset) or dropped.
+///
+/// This `struct` is created by [`Arena::checkpoint`], see its documentation
+/// for more.
+pub struct Checkpoint<'a, 'src> {
+    arena: &'a mut Arena<'src>,
+    cursor: *mut MaybeUninit<u8>,
+    back: *mut MaybeUninit<u8>,
+}
+
+impl Checkpoint<'_, '_> {
+    /// Returns a sub-arena for allocations that do not outlive the borrow of
+    /// `self`.
+    ///
+    /// Allocations made through the sub-arena are recorded in the checkpoint
+    /// when it is dropped, and retained until the checkpoint is reset. If it
+    /// is leaked, or replaced with an arena allocating from a different region,
+    /// the memory it handed out is made available to the next call.
+    ///
+    /// Since the sub-arena must be dropped before the checkpoint can be reset,
+    /// it cannot be swapped with an arena allocating from a shorter-lived
+    /// region that is then handed back to the original arena:
+    ///
+    /// ```compile_fail
+    /// # use core::mem::MaybeUninit;
+    /// # use coca::arena::Arena;
+    /// let mut backing_region = [MaybeUninit::uninit(); 1024];
+    /// let mut arena = Arena::from(&mut backing_region[..]);
+    /// let mut checkpoint = arena.checkpoint();
+    /// let mut sub_arena = checkpoint.arena();
+    /// {
+    ///     let mut short_region = [MaybeUninit::uninit(); 64];
+    ///     core::mem::swap(&mut *sub_arena, &mut Arena::from(&mut short_region[..]));
+    /// }
+    /// drop(sub_arena);
+    /// core::mem::forget(checkpoint);
+    /// let x = arena.alloc(42);
+    /// ```
+    #[inline]
+    pub fn arena(&mut self) -> CheckpointArena<'_> {
+        let Arena { cursor, back, end, .. } = &mut *self.arena;
+        CheckpointArena {
+            arena: Arena {
+                cursor: *cursor,
+                back: *back,
+                end: *end,
+                src: PhantomData,
+            },
+            end: *end,
+            cursor,
+            back,
+        }
+    }
+
+    /// Calculates the number of bytes allocated through `self` since the
+    /// checkpoint was created or last reset, including padding.
+    #[inline]
+    pub fn bytes_allocated(&self) -> usize {
+        let front = (self.arena.cursor as usize) - (self.cursor as usize);
+        let back = (self.back as usize) - (self.arena.back as usize);
+        front + back
+    }
+
+    /// Rewinds the arena to the state it was in when the checkpoint was
+    /// created, releasing all allocations made through `self` since.
+    ///
+    /// # Examples
+    /// ```
+    /// # use core::mem::MaybeUninit;
+    /// # use coca::arena::Arena;
+    /// let mut backing_region = [MaybeUninit::uninit(); 1024];
+    /// let mut arena = Arena::from(&mut backing_region[..]);
+    /// let mut checkpoint = arena.checkpoint();
+    ///
+    /// let _ = checkpoint.arena().alloc(0u64);
+    /// assert!(checkpoint.bytes_allocated() >= 8);
+    ///
+    /// checkpoint.reset();
+    /// assert_eq!(checkpoint.bytes_allocated(), 0);
+    /// ```
+    #[inline]
+    pub fn reset(&mut self) {
+        self.arena.cursor = self.cursor;
+        self.arena.back = self.back;
+    }
+}
+
+impl Drop for Checkpoint<'_, '_> {
+    fn drop(&mut self) {
+        self.reset();
+    }
+}
+
+impl Debug for Checkpoint<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        core::write!(f, "Checkpoint({:p}..{:p})", self.cursor, self.back)
+    }
+}
+
+/// A sub-arena borrowed from a [`Checkpoint`], which records its allocations
+/// in the checkpoint when dropped.
+///
+/// This `struct` is created by [`Checkpoint::arena`], see its documentation
+/// for more.
+pub struct CheckpointArena<'a> {
+    arena: Arena<'a>,
+    cursor: &'a mut *mut MaybeUninit<u8>,
+    back: &'a mut *mut MaybeUninit<u8>,
+    end: *mut MaybeUninit<u8>,
+}
+
+impl<'a> Deref for CheckpointArena<'a> {
+    type Target = Arena<'a>;
+    fn deref(&self) -> &Arena<'a> {
+        &self.arena
+    }
+}
+
+impl<'a> DerefMut for CheckpointArena<'a> {
+    fn deref_mut(&mut self) -> &mut Arena<'a> {
+        &mut self.arena
+    }
+}
+
+impl Drop for CheckpointArena<'_> {
+    fn drop(&mut self) {
+        // The sub-arena may have been replaced with one allocating from a
+        // different region, whose bounds must not leak into the checkpoint.
+        let Arena { cursor, back, end, .. } = self.arena;
+        if end == self.end && *self.cursor <= cursor && cursor <= back && back <= *self.back {
+            *self.cursor = cursor;
+            *self.back = back;
+        }
+    }
+}
+
+impl Debug for CheckpointArena<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CheckpointArena").field(&self.arena).finish()
+    }
+}
+
+/// A recorded state of an [`Arena`] that does not borrow it.
+///
+/// This `struct` is created by [`Arena::checkpoint_token`], see its
+/// documentation for more.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct CheckpointToken {
+    cursor: *mut MaybeUninit<u8>,
+    back: *mut MaybeUninit<u8>,
+    end: *mut MaybeUninit<u8>,
+}
+
+impl Debug for CheckpointToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        core::write!(f, "CheckpointToken({:p}..{:p})", self.cursor, self.back)
+    }
+}
+
+/// Implementor of [`core::fmt::Write`] backed by an [`Arena`].
+/// Primarily intended for use in expansions of [`fmt!`].
+///
+/// See [`Arena::make_writer`] for example usage.
+pub struct Writer<'src, 'buf> {
+    source: &'src mut Arena<'buf>,
+    len: usize,
+}
+
+impl Write for Writer<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let bytes_remaining = self.source.bytes_remaining();
+        if s.len() > bytes_remaining {
+            #[cfg(feature = "profile")]
+            {
+                self.source.profile_meta_data_mut().allocation_count += 1;
+                self.source.profile_meta_data_mut().failed_allocations += 1;
+            }
+            return fmt::Result::Err(fmt::Error);
+        }
+
+        unsafe {
+            s.as_ptr()
+                .copy_to_nonoverlapping(self.source.cursor.cast::<u8>(), s.len());
+        }
+
+        self.source.cursor = unsafe { self.source.cursor.add(s.len()) };
+        self.len += s.len();
+
+        #[cfg(feature = "profile")]
+        {
+            self.source.profile_meta_data_mut().allocation_count += 1;
+            self.source.update_peak_utilization();
+        }
+
+        Ok(())
+    }
+}
+
+impl<'buf> From<Writer<'_, 'buf>> for Box<'buf, str> {
+    fn from(writer: Writer<'_, 'buf>) -> Self {
+        unsafe {
+            let ptr = writer.source.cursor.sub(writer.len).cast::<u8>();
+            let slice = from_raw_parts_mut(ptr, writer.len);
+            let str_ptr = NonNull::new_unchecked(slice).as_ptr() as *mut str;
+
+            Box::new_unchecked(str_ptr)
+        }
+    }
+}
+
+/// Creates a `Option<Box<'_, str>>` using interpolation of run-time expressions.
+///
+/// The first argument `fmt!` receives is an [`Arena`] from which the string
+/// will be allocated.
+///
+/// The second argument is a format string. This must be a string literal.
+/// Additional parameters passed to `fmt!` replace the `{}`s contained within
+/// the formatting string in the order given unless named or positional
+/// parameters are used; see [`core::fmt`] for more information.
+///
+/// Evaluates to `None` if the arena does not have enough space remaining to
+/// contain the formatted string.
+///
+/// # Examples
+/// ```
+/// use coca::{arena::Arena, fmt};
+/// use core::mem::MaybeUninit;
+///
+/// # fn test() -> Option<()> {
+/// let mut backing_region = [MaybeUninit::uninit(); 256];
+/// let mut arena = Arena::from(&mut backing_region[..]);
+/// let output = fmt!(arena, "test")?;
+/// let output = fmt!(arena, "hello {}", "world!")?;
+/// # Some(())
+/// # }
+/// # assert!(test().is_some());
+/// ```
+#[macro_export]
+macro_rules! fmt {
+    ($arena:expr, $($arg:tt)*) => {{
+        #[allow(unused_imports)]
+        use core::fmt::Write;
+        let mut writer = $arena.make_writer();
+        core::write!(writer, $($arg)*)
+            .ok()
+            .map(|_| $crate::arena::Box::<'_, str>::from(writer))
+    }}
+}
+
+/// The rule by which a [`ChainedArena`] determines the size of newly
+/// allocated chunks.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+#[derive(Copy, Clone, Debug)]
+pub enum GrowthPolicy {
+    /// All chunks are the same size as the first one.
+    Constant,
+    /// Each chunk is twice as large as the previous one.
+    Doubling,
+    /// The size of each chunk is computed from that of the previous one.
+    Custom(fn(usize) -> usize),
+}
+
+#[cfg(feature = "alloc")]
+impl GrowthPolicy {
+    fn next_chunk_size(self, previous: usize) -> usize {
+        match self {
+            GrowthPolicy::Constant => previous,
+            GrowthPolicy::Doubling => previous.saturating_mul(2),
+            GrowthPolicy::Custom(f) => f(previous),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+struct ChainState {
+    current: Arena<'static>,
+    chunk_size: usize,
+    chunks: alloc::vec::Vec<(NonNull<u8>, Layout)>,
+}
+
+/// An arena that allocates additional memory blocks from the global allocator
+/// when it runs out of space.
+///
+/// Unlike [`Arena`], allocation only requires a shared reference, and the
+/// returned [`Box`]es and [`ArenaStorage`]s borrow the chained arena, which
+/// frees all its memory blocks when dropped. Collections constructed from it
+/// still have a fixed capacity, and will never reallocate.
+///
+/// # Examples
+/// ```
+/// use coca::arena::{ChainedArena, GrowthPolicy};
+/// use coca::collections::ArenaVec;
+///
+/// let arena = ChainedArena::with_growth_policy(256, GrowthPolicy::Doubling);
+/// let a = arena.alloc([1u8; 200]);
+/// let b = arena.alloc([2u8; 200]); // doesn't fit in the first chunk
+/// assert_eq!(arena.chunk_count(), 2);
+///
+/// let mut v: ArenaVec<'_, u64> = arena.with_capacity(100);
+/// v.push(a[0] as u64 + b[0] as u64);
+/// assert_eq!(v.capacity(), 100);
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+pub struct ChainedArena {
+    state: core::cell::UnsafeCell<ChainState>,
+    policy: GrowthPolicy,
+}
+
+#[cfg(feature = "alloc")]
+impl ChainedArena {
+    const CHUNK_ALIGNMENT: usize = 8;
+
+    /// Constructs a new `ChainedArena` with a first chunk of `chunk_size`
+    /// bytes, doubling the size of each subsequently allocated chunk.
+    ///
+    /// # Panics
+    /// Panics if the allocation of the first chunk fails.
+    #[track_caller]
+    pub fn new(chunk_size: usize) -> Self {
+        Self::with_growth_policy(chunk_size, GrowthPolicy::Doubling)
+    }
+
+    /// Constructs a new `ChainedArena` with a first chunk of `chunk_size`
+    /// bytes, determining the size of subsequent chunks by `policy`.
+    ///
+    /// # Panics
+    /// Panics if the allocation of the first chunk fails.
+    #[track_caller]
+    pub fn with_growth_policy(chunk_size: usize, policy: GrowthPolicy) -> Self {
+        let mut chunks = alloc::vec::Vec::new();
+        let current = Self::alloc_chunk(&mut chunks, chunk_size)
+            .expect("unexpected allocation failure in `ChainedArena::with_growth_policy`");
+
+        ChainedArena {
+            state: core::cell::UnsafeCell::new(ChainState { current, chunk_size, chunks }),
+            policy,
+        }
+    }
+
+    fn alloc_chunk(chunks: &mut alloc::vec::Vec<(NonNull<u8>, Layout)>, size: usize) -> Option<Arena<'static>> {
+        #[cfg(feature = "profile")]
+        let size = size.checked_add(core::mem::size_of::<ProfileMetaData>() + 2 * core::mem::size_of::<usize>())?;
+
+        let layout = Layout::from_size_align(size.max(1), Self::CHUNK_ALIGNMENT).ok()?;
+        let ptr = NonNull::new(unsafe { alloc::alloc::alloc(layout) })?;
+        chunks.push((ptr, layout));
+
+        let start = ptr.as_ptr().cast::<MaybeUninit<u8>>();
+        Some(unsafe { Arena::from_raw_pointers(start, start.add(layout.size())) })
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    fn state(&self) -> &mut ChainState {
+        // SAFETY: ChainedArena is !Sync, and no reference to the state
+        // outlives any of the methods calling this.
+        unsafe { &mut *self.state.get() }
+    }
+
+    fn try_alloc_raw(&self, layout: &Layout) -> *mut MaybeUninit<u8> {
+        let state = self.state();
+        let ptr = state.current.try_alloc_raw(layout);
+        if !ptr.is_null() {
+            return ptr;
+        }
+
+        let next_chunk = layout.size().checked_add(layout.align())
+            .map(|min_size| self.policy.next_chunk_size(state.chunk_size).max(min_size))
+            .and_then(|size| Some((Self::alloc_chunk(&mut state.chunks, size)?, size)));
+
+        match next_chunk {
+            Some((arena, chunk_size)) => {
+                state.current = arena;
+                state.chunk_size = chunk_size;
+                state.current.try_alloc_raw(layout)
+            }
+            None => null_mut(),
+        }
+    }
+
+    /// Returns the number of memory blocks allocated so far.
+    pub fn chunk_count(&self) -> usize {
+        self.state().chunks.len()
+    }
+
+    /// Calculates the size of the space remaining in the current memory block
+    /// in bytes.
+    ///
+    /// Allocations exceeding this will cause a new block to be allocated.
+    pub fn bytes_remaining(&self) -> usize {
+        self.state().current.bytes_remaining()
+    }
+
+    /// Allocates enough memory in the arena for `capacity` items according to
+    /// the [`LayoutSpec`], leaving the memory uninitialized.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is large enough to cause a [`LayoutError`](core::alloc::LayoutError),
+    /// or if allocating a new memory block fails. See
+    /// [`try_storage_with_capacity`](ChainedArena::try_storage_with_capacity)
+    /// for a checked version that never panics.
+    #[track_caller]
+    pub fn storage_with_capacity<R: LayoutSpec>(&self, capacity: usize) -> ArenaStorage<'_, R> {
+        self.try_storage_with_capacity(capacity)
+            .expect("unexpected allocation failure in `storage_with_capacity`")
+    }
+
+    /// Allocates enough memory in the arena for `capacity` items according to
+    /// the [`LayoutSpec`], leaving the memory uninitialized.
+    ///
+    /// Returns [`None`] if `capacity` is large enough to cause a [`LayoutError`](core::alloc::LayoutError),
+    /// or if allocating a new memory block fails.
+    pub fn try_storage_with_capacity<R: LayoutSpec>(&self, capacity: usize) -> Option<ArenaStorage<'_, R>> {
+        let layout = R::layout_with_capacity(capacity).ok()?;
+        let ptr = self.try_alloc_raw(&layout).cast::<u8>();
+        unsafe { ArenaStorage::from_raw_parts(ptr, capacity) }
+    }
+
+    /// Constructs a collection `C` with the given capacity, backed by arena-allocated memory.
+    ///
+    /// # Panics
+    /// Panics if allocating a new memory block fails. See
+    /// [`try_with_capacity`](ChainedArena::try_with_capacity) for a checked
+    /// version that never panics.
+    #[track_caller]
+    pub fn with_capacity<'a, S, C>(&'a self, capacity: usize) -> C
+    where
+        C: From<ArenaStorage<'a, S>>,
+        S: LayoutSpec,
+    {
+        self.try_with_capacity(capacity).expect("unexpected allocation failure in `with_capacity`")
+    }
+
+    /// Constructs a collection `C` with the given capacity, backed by arena-allocated memory.
+    ///
+    /// Returns [`None`] if allocating a new memory block fails.
+    pub fn try_with_capacity<'a, S, C>(&'a self, capacity: usize) -> Option<C>
+    where
+        C: From<ArenaStorage<'a, S>>,
+        S: LayoutSpec,
+    {
+        Some(C::from(self.try_storage_with_capacity(capacity)?))
+    }
+
+    /// Allocates memory in the arena and then places `x` into it.
+    ///
+    /// # Panics
+    /// Panics if allocating a new memory block fails. See
+    /// [`try_alloc`](ChainedArena::try_alloc) for a checked version that
+    /// never panics.
+    #[track_caller]
+    pub fn alloc<T: Sized>(&self, x: T) -> Box<'_, T> {
+        self.try_reserve()
+            .expect("unexpected allocation failure in `alloc`")
+            .init(x)
+    }
+
+    /// Allocates memory in the arena and then places `x` into it.
+    ///
+    /// Returns [`None`] if allocating a new memory block fails.
+    pub fn try_alloc<T: Sized>(&self, x: T) -> Option<Box<'_, T>> {
+        self.try_reserve().map(|b| b.init(x))
+    }
+
+    /// Allocates memory in the arena, leaving it uninitialized.
+    ///
+    /// # Panics
+    /// Panics if allocating a new memory block fails. See
+    /// [`try_reserve`](ChainedArena::try_reserve) for a checked version that
+    /// never panics.
+    #[track_caller]
+    pub fn reserve<T: Sized>(&self) -> Box<'_, MaybeUninit<T>> {
+        self.try_reserve()
+            .expect("unexpected allocation failure in `reserve`")
+    }
+
+    /// Allocates memory in the arena, leaving it uninitialized.
+    ///
+    /// Returns [`None`] if allocating a new memory block fails.
+    pub fn try_reserve<T: Sized>(&self) -> Option<Box<'_, MaybeUninit<T>>> {
+        let ptr = self.try_alloc_raw(&Layout::new::<T>()).cast::<MaybeUninit<T>>();
+        if ptr.is_null() {
+            return None;
+        }
+
+        Some(unsafe { Box::new_unchecked(ptr) })
+    }
+
+    /// Allocates memory in the arena for an array of `count` elements,
+    /// leaving it uninitialized.
+    ///
+    /// # Panics
+    /// Panics if allocating a new memory block fails. See
+    /// [`try_reserve_array`](ChainedArena::try_reserve_array) for a checked
+    /// version that never panics.
+    #[track_caller]
+    pub fn reserve_array<T: Sized>(&self, count: usize) -> Box<'_, [MaybeUninit<T>]> {
+        self.try_reserve_array(count)
+            .expect("unexpected allocation failure in `reserve_array`")
+    }
+
+    /// Allocates memory in the arena for an array of `count` elements,
+    /// leaving it uninitialized.
+    ///
+    /// Returns [`None`] if allocating a new memory block fails.
+    pub fn try_reserve_array<T: Sized>(&self, count: usize) -> Option<Box<'_, [MaybeUninit<T>]>> {
+        let layout = Layout::array::<T>(count).ok()?;
+        let ptr = self.try_alloc_raw(&layout).cast::<MaybeUninit<T>>();
+        if ptr.is_null() {
+            return None;
+        }
+
+        Some(unsafe { Box::new_unchecked(slice_from_raw_parts_mut(ptr, count)) })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Drop for ChainedArena {
+    fn drop(&mut self) {
+        for &(ptr, layout) in &self.state.get_mut().chunks {
+            unsafe { alloc::alloc::dealloc(ptr.as_ptr(), layout) };
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Debug for ChainedArena {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChainedArena")
+            .field("chunk_count", &self.chunk_count())
+            .field("bytes_remaining", &self.bytes_remaining())
+            .field("policy", &self.policy)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The size in bytes of each thread's scratch buffers, unless changed with
+/// [`set_scratch_capacity`].
+#[cfg(feature = "std")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "std")))]
+pub const DEFAULT_SCRATCH_CAPACITY: usize = 64 * 1024;
+
+#[cfg(feature = "std")]
+struct ScratchBuffer {
+    arena: Arena<'static>,
+    capacity: usize,
+    ptr: NonNull<u8>,
+    layout: Layout,
+}
+
+#[cfg(feature = "std")]
+impl ScratchBuffer {
+    #[track_caller]
+    fn new(capacity: usize) -> Self {
+        let layout = Layout::from_size_align(capacity.max(1), 8)
+            .expect("invalid scratch buffer capacity");
+        let ptr = NonNull::new(unsafe { alloc::alloc::alloc(layout) })
+            .unwrap_or_else(|| alloc::alloc::handle_alloc_error(layout));
+
+        let start = ptr.as_ptr().cast::<MaybeUninit<u8>>();
+        let arena = unsafe { Arena::from_raw_pointers(start, start.add(capacity)) };
+        ScratchBuffer { arena, capacity, ptr, layout }
+    }
+
+    fn sub_arena(&mut self) -> Arena<'static> {
+        let sub = self.arena.make_sub_arena();
+        Arena { cursor: sub.cursor, back: sub.back, end: sub.end, src: PhantomData }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for ScratchBuffer {
+    fn drop(&mut self) {
+        unsafe { alloc::alloc::dealloc(self.ptr.as_ptr(), self.layout) };
+    }
+}
+
+#[cfg(feature = "std")]
+struct ScratchState {
+    capacity: Cell<usize>,
+    depth: Cell<usize>,
+    buffers: core::cell::RefCell<alloc::vec::Vec<ScratchBuffer>>,
+}
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    static SCRATCH: ScratchState = const { ScratchState {
+        capacity: Cell::new(DEFAULT_SCRATCH_CAPACITY),
+        depth: Cell::new(0),
+        buffers: core::cell::RefCell::new(alloc::vec::Vec::new()),
+    } };
+}
+
+#[cfg(feature = "std")]
+struct ScratchDepthGuard<'a> {
+    depth: &'a Cell<usize>,
+    restore: usize,
+}
+
+#[cfg(feature = "std")]
+impl Drop for ScratchDepthGuard<'_> {
+    fn drop(&mut self) {
+        self.depth.set(self.restore);
+    }
+}
+
+/// Sets the size in bytes of the current thread's scratch buffers.
+///
+/// Buffers that were allocated with a different size are replaced the next
+/// time they are needed; buffers in use by an enclosing call to [`with_scratch`]
+/// are not affected until it returns.
+///
+/// # Panics
+/// With the `profile` feature enabled, later calls to [`with_scratch`] panic
+/// if `capacity` is too small to fit the profiling meta data, see the
+/// [module-level documentation](crate::arena#memory-profiling).
+#[cfg(feature = "std")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "std")))]
+pub fn set_scratch_capacity(capacity: usize) {
+    SCRATCH.with(|scratch| scratch.capacity.set(capacity));
+}
+
+/// Calls `f` with a temporary arena allocating out of a per-thread scratch
+/// buffer, releasing all of its allocations when `f` returns.
+///
+/// This avoids threading an `&mut Arena` through layers of code that only
+/// need some short-lived working memory. Calls may be nested: each nesting
+/// level has a buffer of its own, which is allocated from the global allocator
+/// on first use and then kept for the lifetime of the thread, and each call
+/// receives a [sub-arena](Arena::make_sub_arena) of its level's buffer. This
+/// means the arena passed to an enclosing call may still be used from inside
+/// the nested one.
+///
+/// The size of the buffers can be configured with [`set_scratch_capacity`],
+/// and defaults to [`DEFAULT_SCRATCH_CAPACITY`].
+///
+/// # Panics
+/// Panics if allocating a scratch buffer fails.
+///
+/// # Examples
+/// ```
+/// use coca::arena::with_scratch;
+///
+/// fn sum_of_squares(n: u64) -> u64 {
+///     with_scratch(|arena| {
+///         let squares = arena.collect_slice((1..=n).map(|i| i * i));
+///         squares.iter().sum()
+///     })
+/// }
+///
+/// let total = with_scratch(|arena| {
+///     let mut terms = arena.array_default::<u64>(4);
+///     for (i, t) in terms.iter_mut().enumerate() {
+///         *t = sum_of_squares(i as u64 + 1);
+///     }
+///     terms.iter().sum::<u64>()
+/// });
+///
+/// assert_eq!(total, 1 + 5 + 14 + 30);
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "std")))]
+#[track_caller]
+pub fn with_scratch<R, F: FnOnce(&mut Arena<'_>) -> R>(f: F) -> R {
+    SCRATCH.with(|scratch| {
+        let depth = scratch.depth.get();
+        let mut arena = {
+            let mut buffers = scratch.buffers.borrow_mut();
+            let capacity = scratch.capacity.get();
+            if buffers.len() == depth {
+                buffers.push(ScratchBuffer::new(capacity));
+            } else if buffers[depth].capacity != capacity {
+                buffers[depth] = ScratchBuffer::new(capacity);
+            }
+
+            // The buffer at this depth is not touched again until we return,
+            // and its memory does not move when the vector reallocates.
+            buffers[depth].sub_arena()
+        };
+
+        scratch.depth.set(depth + 1);
+        let _guard = ScratchDepthGuard { depth: &scratch.depth, restore: depth };
+        f(&mut arena)
+    })
+}
+
+/// An adapter implementing the unstable [`Allocator`](core::alloc::Allocator)
+/// trait for shared references to it, allowing an [`Arena`] to serve as the
+/// backing memory of standard library containers.
+///
+/// Deallocation is a no-op, except when freeing the most recent allocation,
+/// which rewinds the arena. Likewise, the most recent allocation can be grown
+/// or shrunk in place.
+///
+/// # Examples
+/// ```
+/// #![feature(allocator_api)]
+/// # extern crate alloc;
+/// use core::mem::MaybeUninit;
+/// use coca::arena::{Arena, ArenaAllocator};
+///
+/// let mut backing_region = [MaybeUninit::uninit(); 1024];
+/// let allocator = ArenaAllocator::from(Arena::from(&mut backing_region[..]));
+///
+/// let mut v = alloc::vec::Vec::new_in(&allocator);
+/// for i in 0..100u32 {
+///     v.push(i); // grows in place
+/// }
+///
+/// let total = allocator.bytes_remaining();
+/// drop(v); // rewinds the arena
+/// assert!(allocator.bytes_remaining() >= total + 400);
+/// ```
+#[cfg(feature = "unstable")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "unstable")))]
+pub struct ArenaAllocator<'src> {
+    arena: core::cell::UnsafeCell<Arena<'src>>,
+}
+
+#[cfg(feature = "unstable")]
+impl<'src> From<Arena<'src>> for ArenaAllocator<'src> {
+    fn from(arena: Arena<'src>) -> Self {
+        ArenaAllocator { arena: core::cell::UnsafeCell::new(arena) }
+    }
+}
+
+#[cfg(feature = "unstable")]
+impl<'src> ArenaAllocator<'src> {
+    #[allow(clippy::mut_from_ref)]
+    fn arena(&self) -> &mut Arena<'src> {
+        // SAFETY: ArenaAllocator is !Sync, and no reference to the arena
+        // outlives any of the methods calling this.
+        unsafe { &mut *self.arena.get() }
+    }
+
+    /// Calculates the size of the space remaining in the underlying arena in bytes.
+    pub fn bytes_remaining(&self) -> usize {
+        self.arena().bytes_remaining()
+    }
+
+    /// Returns a profile of all allocations from the underlying arena.
+    ///
+    /// See [`Arena::utilization`] for details.
+    #[cfg(feature = "profile")]
+    #[cfg_attr(docs_rs, doc(cfg(feature = "profile")))]
     pub fn utilization(&self) -> UtilizationProfile {
-        let layout = Layout::new::<ProfileMetaData>();
-        debug_assert_eq!(align_offset(self.end, &layout), 0);
-        let &ProfileMetaData {
-            initial_cursor_pos,
-            peak_cursor_pos,
-            allocation_count,
-            failed_allocations,
-        } = unsafe { &*self.end.cast::<ProfileMetaData>() };
-        UtilizationProfile {
-            peak_utilization: peak_cursor_pos - initial_cursor_pos,
-            allocation_count,
-            failed_allocations,
+        self.arena().utilization()
+    }
+
+    /// Consumes the adapter, returning the underlying arena.
+    pub fn into_inner(self) -> Arena<'src> {
+        self.arena.into_inner()
+    }
+
+    /// Returns `true` if the block at `ptr` with the given `size` ends at the
+    /// arena's cursor, i.e. if it is the most recent allocation.
+    fn is_top(&self, ptr: NonNull<u8>, size: usize) -> bool {
+        (ptr.as_ptr() as usize).wrapping_add(size) == self.arena().cursor as usize
+    }
+
+    /// Attempts to resize the block at `ptr` in place, which is only possible
+    /// for the most recent allocation.
+    fn try_resize_in_place(&self, ptr: NonNull<u8>, old_layout: Layout, new_layout: Layout) -> bool {
+        if !self.is_top(ptr, old_layout.size()) || (ptr.as_ptr() as usize) % new_layout.align() != 0 {
+            return false;
+        }
+
+        let arena = self.arena();
+        let available = (arena.back as usize) - (ptr.as_ptr() as usize);
+        if new_layout.size() > available {
+            return false;
+        }
+
+        arena.cursor = unsafe { ptr.as_ptr().add(new_layout.size()) }.cast();
+
+        #[cfg(feature = "profile")]
+        arena.update_peak_utilization();
+
+        true
+    }
+}
+
+#[cfg(feature = "unstable")]
+unsafe impl core::alloc::Allocator for &ArenaAllocator<'_> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, core::alloc::AllocError> {
+        let ptr = self.arena().try_alloc_raw(&layout).cast::<u8>();
+        let ptr = NonNull::new(ptr).ok_or(core::alloc::AllocError)?;
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if self.is_top(ptr, layout.size()) {
+            self.arena().cursor = ptr.as_ptr().cast();
+        }
+    }
+
+    unsafe fn grow(&self, ptr: NonNull<u8>, old_layout: Layout, new_layout: Layout) -> Result<NonNull<[u8]>, core::alloc::AllocError> {
+        debug_assert!(new_layout.size() >= old_layout.size());
+        if self.try_resize_in_place(ptr, old_layout, new_layout) {
+            return Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()));
+        }
+
+        let new_ptr = self.allocate(new_layout)?;
+        core::ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr().cast::<u8>(), old_layout.size());
+        Ok(new_ptr)
+    }
+
+    unsafe fn shrink(&self, ptr: NonNull<u8>, old_layout: Layout, new_layout: Layout) -> Result<NonNull<[u8]>, core::alloc::AllocError> {
+        debug_assert!(new_layout.size() <= old_layout.size());
+        if (ptr.as_ptr() as usize) % new_layout.align() != 0 {
+            let new_ptr = self.allocate(new_layout)?;
+            core::ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr().cast::<u8>(), new_layout.size());
+            return Ok(new_ptr);
+        }
+
+        if self.is_top(ptr, old_layout.size()) {
+            self.arena().cursor = ptr.as_ptr().add(new_layout.size()).cast();
+            return Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()));
+        }
+
+        Ok(NonNull::slice_from_raw_parts(ptr, old_layout.size()))
+    }
+}
+
+#[cfg(feature = "unstable")]
+impl Debug for ArenaAllocator<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ArenaAllocator").field(self.arena()).finish()
+    }
+}
+
+union SlabSlot<T> {
+    value: ManuallyDrop<T>,
+    next: *mut SlabSlot<T>,
+}
+
+struct FreeList<T> {
+    head: Cell<*mut SlabSlot<T>>,
+    len: Cell<usize>,
+}
+
+impl<T> FreeList<T> {
+    unsafe fn release(&self, slot: *mut SlabSlot<T>) {
+        (*slot).next = self.head.get();
+        self.head.set(slot);
+        self.len.set(self.len.get() - 1);
+    }
+}
+
+/// A fixed-capacity block of slots for values of type `T` carved out of an
+/// [`Arena`], which recycles the slots of dropped values.
+///
+/// This `struct` is created by [`Arena::typed_slab`], see its documentation
+/// for more.
+pub struct TypedSlab<'src, T> {
+    slots: NonNull<SlabSlot<T>>,
+    capacity: usize,
+    initialized: Cell<usize>,
+    free_list: FreeList<T>,
+    src: PhantomData<&'src ()>,
+}
+
+impl<T> TypedSlab<'_, T> {
+    /// Returns the number of values the slab can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of values currently in the slab.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.free_list.len.get()
+    }
+
+    /// Returns `true` if the slab contains no values.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the slab contains the maximum number of values.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity
+    }
+
+    /// Places `x` into a vacant slot, reusing the slot of a previously dropped
+    /// value if possible.
+    ///
+    /// # Panics
+    /// Panics if the slab is already full. See [`try_alloc`](TypedSlab::try_alloc)
+    /// for a checked version that never panics.
+    #[track_caller]
+    pub fn alloc(&self, x: T) -> SlabBox<'_, T> {
+        #[cold]
+        #[inline(never)]
+        #[track_caller]
+        fn assert_failed() -> ! {
+            panic!("slab is already at capacity")
+        }
+
+        match self.try_alloc(x) {
+            Ok(b) => b,
+            Err(_) => assert_failed(),
+        }
+    }
+
+    /// Places `x` into a vacant slot, reusing the slot of a previously dropped
+    /// value if possible.
+    ///
+    /// Returns `Err(x)` if the slab is already full.
+    ///
+    /// # Examples
+    /// ```
+    /// use core::mem::MaybeUninit;
+    /// use coca::arena::Arena;
+    ///
+    /// let mut backing_region = [MaybeUninit::uninit(); 1024];
+    /// let mut arena = Arena::from(&mut backing_region[..]);
+    /// let slab = arena.typed_slab::<u64>(2);
+    ///
+    /// let a = slab.try_alloc(1).unwrap();
+    /// let b = slab.try_alloc(2).unwrap();
+    /// assert_eq!(slab.try_alloc(3), Err(3));
+    ///
+    /// drop(a);
+    /// let c = slab.try_alloc(3).unwrap();
+    /// assert_eq!(*b + *c, 5);
+    /// ```
+    pub fn try_alloc(&self, x: T) -> Result<SlabBox<'_, T>, T> {
+        let head = self.free_list.head.get();
+        let slot = if !head.is_null() {
+            self.free_list.head.set(unsafe { (*head).next });
+            head
+        } else if self.initialized.get() < self.capacity {
+            let idx = self.initialized.get();
+            self.initialized.set(idx + 1);
+            unsafe { self.slots.as_ptr().add(idx) }
+        } else {
+            return Err(x);
+        };
+
+        self.free_list.len.set(self.len() + 1);
+        let ptr = slot.cast::<T>();
+        unsafe {
+            ptr.write(x);
+            Ok(SlabBox { ptr: NonNull::new_unchecked(ptr), free_list: &self.free_list })
         }
     }
+}
 
-    #[inline]
-    #[allow(clippy::cast_ptr_alignment)]
-    fn profile_meta_data_mut(&mut self) -> &mut ProfileMetaData {
-        let layout = Layout::new::<ProfileMetaData>();
-        debug_assert_eq!(align_offset(self.end, &layout), 0);
-        unsafe { &mut *self.end.cast::<ProfileMetaData>() }
+impl<T> Debug for TypedSlab<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedSlab")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity)
+            .finish()
     }
 }
 
-/// Implementor of [`core::fmt::Write`] backed by an [`Arena`].
-/// Primarily intended for use in expansions of [`fmt!`].
+/// A pointer type providing ownership of a value stored in a [`TypedSlab`].
 ///
-/// See [`Arena::make_writer`] for example usage.
-pub struct Writer<'src, 'buf> {
-    source: &'src mut Arena<'buf>,
-    len: usize,
+/// The slot occupied by the value is returned to the slab when it is dropped.
+pub struct SlabBox<'a, T> {
+    ptr: NonNull<T>,
+    free_list: &'a FreeList<T>,
 }
 
-impl Write for Writer<'_, '_> {
-    fn write_str(&mut self, s: &str) -> fmt::Result {
-        let bytes_remaining = self.source.bytes_remaining();
-        if s.len() > bytes_remaining {
-            #[cfg(feature = "profile")]
-            {
-                self.source.profile_meta_data_mut().allocation_count += 1;
-                self.source.profile_meta_data_mut().failed_allocations += 1;
-            }
-            return fmt::Result::Err(fmt::Error);
+impl<T> SlabBox<'_, T> {
+    /// Moves the value out of the slab, returning its slot.
+    pub fn into_inner(self) -> T {
+        let this = ManuallyDrop::new(self);
+        unsafe {
+            let slot = this.ptr.as_ptr().cast::<SlabSlot<T>>();
+            let value = ManuallyDrop::take(&mut (*slot).value);
+            this.free_list.release(slot);
+            value
         }
+    }
+}
+
+impl<T> Deref for SlabBox<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T> DerefMut for SlabBox<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<T> AsRef<T> for SlabBox<'_, T> {
+    fn as_ref(&self) -> &T {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T> AsMut<T> for SlabBox<'_, T> {
+    fn as_mut(&mut self) -> &mut T {
+        unsafe { self.ptr.as_mut() }
+    }
+}
 
+impl<T> Drop for SlabBox<'_, T> {
+    fn drop(&mut self) {
         unsafe {
-            s.as_ptr()
-                .copy_to_nonoverlapping(self.source.cursor.cast::<u8>(), s.len());
+            self.ptr.as_ptr().drop_in_place();
+            self.free_list.release(self.ptr.as_ptr().cast());
         }
+    }
+}
 
-        self.source.cursor = unsafe { self.source.cursor.add(s.len()) };
-        self.len += s.len();
+impl<T: Debug> Debug for SlabBox<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
 
-        #[cfg(feature = "profile")]
-        {
-            let cursor = self.source.cursor as usize;
-            let meta = self.source.profile_meta_data_mut();
+impl<T: Display> Display for SlabBox<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
 
-            meta.allocation_count += 1;
-            if meta.peak_cursor_pos < cursor {
-                meta.peak_cursor_pos = cursor;
-            }
-        }
+impl<T> Pointer for SlabBox<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Pointer::fmt(&self.ptr, f)
+    }
+}
 
-        Ok(())
+impl<T: PartialEq> PartialEq for SlabBox<'_, T> {
+    #[inline]
+    fn eq(&self, other: &SlabBox<'_, T>) -> bool {
+        PartialEq::eq(&**self, &**other)
     }
 }
 
-impl<'buf> From<Writer<'_, 'buf>> for Box<'buf, str> {
-    fn from(writer: Writer<'_, 'buf>) -> Self {
-        unsafe {
-            let ptr = writer.source.cursor.sub(writer.len).cast::<u8>();
-            let slice = from_raw_parts_mut(ptr, writer.len);
-            let str_ptr = NonNull::new_unchecked(slice).as_ptr() as *mut str;
+impl<T: PartialOrd> PartialOrd for SlabBox<'_, T> {
+    #[inline]
+    fn partial_cmp(&self, other: &SlabBox<'_, T>) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+    #[inline]
+    fn lt(&self, other: &SlabBox<'_, T>) -> bool {
+        PartialOrd::lt(&**self, &**other)
+    }
+    #[inline]
+    fn le(&self, other: &SlabBox<'_, T>) -> bool {
+        PartialOrd::le(&**self, &**other)
+    }
+    #[inline]
+    fn ge(&self, other: &SlabBox<'_, T>) -> bool {
+        PartialOrd::ge(&**self, &**other)
+    }
+    #[inline]
+    fn gt(&self, other: &SlabBox<'_, T>) -> bool {
+        PartialOrd::gt(&**self, &**other)
+    }
+}
 
-            Box::new_unchecked(str_ptr)
-        }
+impl<T: Ord> Ord for SlabBox<'_, T> {
+    #[inline]
+    fn cmp(&self, other: &SlabBox<'_, T>) -> Ordering {
+        Ord::cmp(&**self, &**other)
     }
 }
 
-/// Creates a `Option<Box<'_, str>>` using interpolation of run-time expressions.
-///
-/// The first argument `fmt!` receives is an [`Arena`] from which the string
-/// will be allocated.
-///
-/// The second argument is a format string. This must be a string literal.
-/// Additional parameters passed to `fmt!` replace the `{}`s contained within
-/// the formatting string in the order given unless named or positional
-/// parameters are used; see [`core::fmt`] for more information.
-///
-/// Evaluates to `None` if the arena does not have enough space remaining to
-/// contain the formatted string.
-///
-/// # Examples
-/// ```
-/// use coca::{arena::Arena, fmt};
-/// use core::mem::MaybeUninit;
-///
-/// # fn test() -> Option<()> {
-/// let mut backing_region = [MaybeUninit::uninit(); 256];
-/// let mut arena = Arena::from(&mut backing_region[..]);
-/// let output = fmt!(arena, "test")?;
-/// let output = fmt!(arena, "hello {}", "world!")?;
-/// # Some(())
-/// # }
-/// # assert!(test().is_some());
-/// ```
-#[macro_export]
-macro_rules! fmt {
-    ($arena:expr, $($arg:tt)*) => {{
-        #[allow(unused_imports)]
-        use core::fmt::Write;
-        let mut writer = $arena.make_writer();
-        core::write!(writer, $($arg)*)
-            .ok()
-            .map(|_| $crate::arena::Box::<'_, str>::from(writer))
-    }}
+impl<T: Eq> Eq for SlabBox<'_, T> {}
+
+impl<T: Hash> Hash for SlabBox<'_, T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    #[cfg(feature = "std")]
+    fn nested_scratch_arenas_are_disjoint() {
+        let outer = with_scratch(|a| {
+            let x = a.alloc([1u8; 64]);
+            let inner = with_scratch(|b| {
+                let y = b.alloc([2u8; 64]);
+                // the enclosing arena remains usable:
+                let z = a.alloc([3u8; 64]);
+                assert_eq!(*x, [1; 64]);
+                assert_eq!(*z, [3; 64]);
+                y.iter().map(|&v| v as usize).sum::<usize>()
+            });
+            let remaining = a.bytes_remaining();
+            inner + remaining
+        });
+
+        // all allocations were released, so we get the same results again:
+        let again = with_scratch(|a| {
+            let _x = a.alloc([1u8; 64]);
+            let inner = with_scratch(|b| b.alloc([2u8; 64]).len() * 2);
+            let _z = a.alloc([3u8; 64]);
+            inner + a.bytes_remaining()
+        });
+        assert_eq!(outer, again);
+
+        set_scratch_capacity(4096);
+        assert!(with_scratch(|a| a.try_array_default::<u8>(4097).is_none()));
+        assert!(with_scratch(|a| with_scratch(|_| a.try_array_default::<u8>(1024).is_some())));
+        set_scratch_capacity(DEFAULT_SCRATCH_CAPACITY);
+    }
+
+    #[test]
+    fn typed_slab_recycles_slots() {
+        use crate::test_utils::*;
+
+        let mut backing_region = [MaybeUninit::uninit(); 1024];
+        let mut arena = Arena::from(&mut backing_region[..]);
+        let slab = arena.typed_slab::<Droppable>(3);
+        let drop_count = DropCounter::new();
+
+        let a = slab.alloc(drop_count.new_droppable(()));
+        let b = slab.alloc(drop_count.new_droppable(()));
+        let c = slab.alloc(drop_count.new_droppable(()));
+        assert!(slab.is_full());
+        assert!(slab.try_alloc(drop_count.new_droppable(())).is_err());
+        assert_eq!(drop_count.dropped(), 1);
+
+        let b_addr = &*b as *const Droppable;
+        drop(b);
+        assert_eq!(drop_count.dropped(), 2);
+        assert_eq!(slab.len(), 2);
+
+        let d = slab.alloc(drop_count.new_droppable(()));
+        assert_eq!(&*d as *const Droppable, b_addr);
+
+        let inner = a.into_inner();
+        assert_eq!(drop_count.dropped(), 2);
+        assert_eq!(slab.len(), 2);
+        drop(inner);
+
+        drop((c, d));
+        assert_eq!(drop_count.dropped(), 5);
+        assert!(slab.is_empty());
+    }
+
     #[test]
     fn failed_collect_drops_taken_items() {
         use crate::test_utils::*;
@@ -1460,6 +3125,324 @@ mod tests {
         );
     }
 
+    #[test]
+    fn checkpoint_reset_and_recovery() {
+        let mut backing_region = [MaybeUninit::uninit(); 256];
+        let mut arena = Arena::from(&mut backing_region[..]);
+        let _ = arena.alloc(0u8);
+        let remaining = arena.bytes_remaining();
+
+        let mut checkpoint = arena.checkpoint();
+        for _ in 0..10 {
+            let _ = checkpoint.arena().alloc([0u8; 128]);
+            let _ = checkpoint.arena().alloc_back([0u8; 64]);
+            assert_eq!(checkpoint.bytes_allocated(), 128 + 64);
+            assert!(checkpoint.arena().try_alloc([0u8; 128]).is_none());
+            checkpoint.reset();
+        }
+
+        let _ = checkpoint.arena().alloc([0u8; 32]);
+        core::mem::forget(checkpoint.arena().alloc([0u8; 32]));
+        assert_eq!(checkpoint.bytes_allocated(), 64);
+        core::mem::forget(checkpoint.arena());
+        assert_eq!(checkpoint.bytes_allocated(), 64);
+
+        let mut other_region = [MaybeUninit::uninit(); 64];
+        {
+            let mut other = Arena::from(&mut other_region[..]);
+            let mut sub_arena = checkpoint.arena();
+            let _ = sub_arena.alloc([0u8; 32]);
+            core::mem::swap(&mut *sub_arena, &mut other);
+        }
+        assert_eq!(checkpoint.bytes_allocated(), 64);
+        assert_eq!(checkpoint.arena().bytes_remaining(), remaining - 64);
+        drop(checkpoint);
+
+        assert_eq!(arena.bytes_remaining(), remaining);
+
+        #[cfg(feature = "profile")]
+        {
+            let profile = arena.utilization();
+            assert_eq!(profile.peak_utilization, 1 + 128 + 64);
+            assert_eq!(profile.allocation_count, 34);
+            assert_eq!(profile.failed_allocations, 10);
+        }
+    }
+
+    #[test]
+    fn checkpoint_token_reset() {
+        let mut backing_region = [MaybeUninit::uninit(); 256];
+        let mut arena = Arena::from(&mut backing_region[..]);
+        let first = arena.alloc(1u8);
+        let remaining = arena.bytes_remaining();
+
+        let token = arena.checkpoint_token();
+        for i in 0..10u8 {
+            let _ = arena.alloc([i; 128]);
+            let _ = arena.alloc_back([i; 64]);
+            assert!(arena.try_alloc([0u8; 128]).is_none());
+            unsafe { arena.reset_to(token); }
+            assert_eq!(arena.bytes_remaining(), remaining);
+        }
+
+        // persistent allocations can be made between checkpoints...
+        let persistent = arena.alloc(2u8);
+        let later = arena.checkpoint_token();
+        let _ = arena.alloc([0u8; 64]);
+        unsafe { arena.reset_to(later); }
+        assert_eq!(arena.bytes_remaining(), remaining - 1);
+        assert_eq!((*first, *persistent), (1, 2));
+        drop(persistent);
+
+        // ...but once released, the arena can't be moved forward again:
+        unsafe { arena.reset_to(token); }
+        let result = {
+            extern crate std;
+            use std::panic::{catch_unwind, AssertUnwindSafe};
+            catch_unwind(AssertUnwindSafe(|| unsafe { arena.reset_to(later) }))
+        };
+        assert!(result.is_err());
+        assert_eq!(arena.bytes_remaining(), remaining);
+
+        let mut other_region = [MaybeUninit::uninit(); 256];
+        let other = Arena::from(&mut other_region[..]).checkpoint_token();
+        let result = {
+            extern crate std;
+            use std::panic::{catch_unwind, AssertUnwindSafe};
+            catch_unwind(AssertUnwindSafe(|| unsafe { arena.reset_to(other) }))
+        };
+        assert!(result.is_err());
+        assert_eq!(arena.bytes_remaining(), remaining);
+        assert_eq!(*first, 1);
+    }
+
+    #[test]
+    fn slice_and_str_allocation_edge_cases() {
+        extern crate std;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::string::String;
+
+        let mut backing_region = [MaybeUninit::uninit(); 256];
+        let mut arena = Arena::from(&mut backing_region[..]);
+        let remaining = arena.bytes_remaining();
+
+        assert!(arena.alloc_slice_copy::<u64>(&[]).is_empty());
+        assert!(arena.alloc_slice_clone::<String>(&[]).is_empty());
+        assert_eq!(&*arena.alloc_str(""), "");
+        assert_eq!(arena.bytes_remaining(), remaining);
+
+        assert!(arena.try_alloc_slice_copy(&[0u8; 512]).is_none());
+        assert!(arena.try_alloc_slice_clone(&[0u8; 512]).is_none());
+        assert!(arena.try_alloc_str(core::str::from_utf8(&[b'a'; 512]).unwrap()).is_none());
+        assert_eq!(arena.bytes_remaining(), remaining);
+
+        assert!(catch_unwind(AssertUnwindSafe(|| arena.alloc_slice_copy(&[0u8; 512]))).is_err());
+        assert!(catch_unwind(AssertUnwindSafe(|| arena.alloc_slice_clone(&[0u8; 512]))).is_err());
+        assert!(catch_unwind(AssertUnwindSafe(|| arena.alloc_str(core::str::from_utf8(&[b'a'; 512]).unwrap()))).is_err());
+        assert_eq!(arena.bytes_remaining(), remaining);
+
+        // zero-length allocations succeed even once the arena is exhausted:
+        let _ = arena.alloc_slice_copy(&[0u8; 256][..remaining]);
+        assert_eq!(arena.bytes_remaining(), 0);
+        assert!(arena.try_alloc_slice_copy::<u8>(&[]).is_some());
+        assert!(arena.try_alloc_slice_clone::<u8>(&[]).is_some());
+        assert!(arena.try_alloc_str("").is_some());
+        assert!(arena.try_alloc_str("a").is_none());
+    }
+
+    #[test]
+    fn slice_clone_drops_clones_on_panic() {
+        extern crate std;
+        use core::cell::Cell;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        struct PanicOnClone<'a> {
+            drops: &'a Cell<usize>,
+            panics: bool,
+        }
+
+        impl Clone for PanicOnClone<'_> {
+            fn clone(&self) -> Self {
+                assert!(!self.panics, "cloning failed");
+                PanicOnClone { drops: self.drops, panics: false }
+            }
+        }
+
+        impl Drop for PanicOnClone<'_> {
+            fn drop(&mut self) {
+                self.drops.set(self.drops.get() + 1);
+            }
+        }
+
+        let drops = Cell::new(0);
+        let src = [false, false, false, true, false].map(|panics| PanicOnClone { drops: &drops, panics });
+
+        let mut backing_region = [MaybeUninit::uninit(); 256];
+        let mut arena = Arena::from(&mut backing_region[..]);
+        let result = catch_unwind(AssertUnwindSafe(|| arena.alloc_slice_clone(&src)));
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 3);
+
+        let clones = arena.alloc_slice_clone(&src[..3]);
+        drop(clones);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    #[cfg(feature = "unstable")]
+    fn unsize_allocation_edge_cases() {
+        extern crate std;
+        use core::fmt::Debug;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use crate::test_utils::*;
+
+        let drop_count = DropCounter::new();
+        let mut backing_region = [MaybeUninit::uninit(); 64];
+        let mut arena = Arena::from(&mut backing_region[..]);
+        let remaining = arena.bytes_remaining();
+
+        let empty: Box<'_, [u8]> = arena.alloc_unsize([0u8; 0]);
+        assert!(empty.is_empty());
+        let unit: Box<'_, dyn Debug> = arena.alloc_unsize(());
+        assert_eq!(std::format!("{:?}", unit), "()");
+        assert_eq!(arena.bytes_remaining(), remaining);
+
+        let none: [Droppable<'_, u8>; 0] = [];
+        assert!(arena.try_alloc_unsize::<[Droppable<'_, u8>]>(none).is_some());
+        let too_large = [(); 128].map(|_| drop_count.new_droppable(0u8));
+        assert!(arena.try_alloc_unsize::<[Droppable<'_, u8>]>(too_large).is_none());
+        assert_eq!(drop_count.dropped(), 128);
+        assert_eq!(arena.bytes_remaining(), remaining);
+
+        let too_large = [0u8; 128];
+        assert!(catch_unwind(AssertUnwindSafe(|| arena.alloc_unsize::<[u8]>(too_large))).is_err());
+        assert_eq!(arena.bytes_remaining(), remaining);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn chained_arena_grows() {
+        use crate::test_utils::DropCounter;
+
+        let drop_count = DropCounter::new();
+        {
+            let arena = ChainedArena::with_growth_policy(64, GrowthPolicy::Constant);
+            let boxes: alloc::vec::Vec<_> = (0..100u32)
+                .map(|i| arena.alloc((i, drop_count.new_droppable(()))))
+                .collect();
+            assert!(arena.chunk_count() > 1);
+
+            let big = arena.reserve_array::<u64>(1000);
+            assert_eq!(big.len(), 1000);
+
+            for (i, b) in boxes.iter().enumerate() {
+                assert_eq!(b.0 as usize, i);
+            }
+        }
+        assert_eq!(drop_count.dropped(), 100);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn chained_arena_edge_cases() {
+        extern crate std;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let arena = ChainedArena::new(0);
+        assert_eq!(arena.alloc([0u8; 0]).len(), 0);
+        assert_eq!(arena.reserve_array::<u32>(0).len(), 0);
+        assert_eq!(arena.chunk_count(), 1);
+
+        // a chunk larger than the requested size is allocated when necessary:
+        let arena = ChainedArena::with_growth_policy(16, GrowthPolicy::Constant);
+        assert_eq!(arena.alloc([7u8; 100])[99], 7);
+        assert_eq!(arena.chunk_count(), 2);
+
+        // growth fails when the next chunk can't be allocated:
+        let arena = ChainedArena::with_growth_policy(64, GrowthPolicy::Custom(|_| usize::MAX));
+        let remaining = arena.bytes_remaining();
+        let _ = arena.reserve_array::<u8>(remaining);
+        assert_eq!(arena.bytes_remaining(), 0);
+        assert!(arena.try_alloc(()).is_some());
+        assert!(arena.try_reserve_array::<u8>(0).is_some());
+
+        assert!(arena.try_alloc(0u8).is_none());
+        assert!(arena.try_reserve_array::<u8>(1).is_none());
+        assert!(catch_unwind(AssertUnwindSafe(|| arena.alloc(0u8))).is_err());
+        assert_eq!(arena.chunk_count(), 1);
+
+        let arena = ChainedArena::new(64);
+        assert!(arena.try_reserve_array::<u8>(isize::MAX as usize).is_none());
+        assert!(arena.try_reserve_array::<u64>(usize::MAX).is_none());
+        assert_eq!(arena.chunk_count(), 1);
+        assert!(arena.try_alloc(0u64).is_some());
+    }
+
+    #[test]
+    #[cfg(all(feature = "unstable", feature = "alloc"))]
+    fn arena_allocator_resizes_in_place() {
+        let mut backing_region = [MaybeUninit::uninit(); 1024];
+        let allocator = ArenaAllocator::from(Arena::from(&mut backing_region[..]));
+        let initial = allocator.bytes_remaining();
+
+        let mut v = alloc::vec::Vec::<u64, _>::with_capacity_in(4, &allocator);
+        v.extend(0..4);
+        let ptr = v.as_ptr();
+        v.extend(4..32);
+        assert_eq!(v.as_ptr(), ptr);
+
+        let b = alloc::boxed::Box::new_in(0u64, &allocator);
+        v.push(32);
+        assert_ne!(v.as_ptr(), ptr);
+
+        v.shrink_to_fit();
+        drop(v);
+        drop(b);
+        assert!(allocator.bytes_remaining() < initial);
+
+        let a = alloc::boxed::Box::new_in([0u8; 32], &allocator);
+        let remaining = allocator.bytes_remaining();
+        drop(a);
+        assert_eq!(allocator.bytes_remaining(), remaining + 32);
+    }
+
+    #[test]
+    #[cfg(all(feature = "unstable", feature = "alloc"))]
+    fn arena_allocator_edge_cases() {
+        use core::alloc::{AllocError, Allocator};
+
+        let mut backing_region = [MaybeUninit::uninit(); 256];
+        let allocator = ArenaAllocator::from(Arena::from(&mut backing_region[..]));
+        let initial = allocator.bytes_remaining();
+
+        let empty = (&allocator).allocate(Layout::new::<[u64; 0]>()).unwrap();
+        assert_eq!(empty.len(), 0);
+        assert_eq!(allocator.bytes_remaining(), initial);
+        unsafe { (&allocator).deallocate(empty.cast(), Layout::new::<[u64; 0]>()) };
+
+        let too_large = Layout::array::<u8>(initial + 1).unwrap();
+        assert_eq!((&allocator).allocate(too_large), Err(AllocError));
+        assert_eq!(allocator.bytes_remaining(), initial);
+
+        let mut v = alloc::vec::Vec::<u8, _>::new_in(&allocator);
+        assert!(v.try_reserve_exact(initial + 1).is_err());
+        v.extend(0..16);
+        let ptr = v.as_ptr();
+        assert!(v.try_reserve_exact(initial).is_err());
+        assert_eq!(v.as_ptr(), ptr);
+        assert!(v.iter().copied().eq(0..16));
+        drop(v);
+
+        let all = Layout::array::<u8>(allocator.bytes_remaining()).unwrap();
+        let block = (&allocator).allocate(all).unwrap();
+        assert_eq!(allocator.bytes_remaining(), 0);
+        assert_eq!((&allocator).allocate(Layout::new::<u8>()), Err(AllocError));
+        assert!((&allocator).allocate(Layout::new::<()>()).is_ok());
+
+        unsafe { (&allocator).deallocate(block.cast(), all) };
+        assert_eq!(allocator.bytes_remaining(), initial);
+    }
+
     #[test]
     fn debug_impl() {
         let mut backing_region_a = [MaybeUninit::uninit(); 256];
@@ -0,0 +1,602 @@
+//! A fixed-capacity set of small integers, implemented as a bit array.
+
+use core::fmt::Debug;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+
+use crate::arena::Arena;
+use crate::collections::SetLike;
+use crate::storage::{buffer_too_large_for_index_type, ArrayLayout, Capacity, Storage};
+
+const WORD_BITS: usize = core::mem::size_of::<usize>() * 8;
+
+/// Returns the number of `usize` words required to store the specified number of bits.
+///
+/// This can be used to specify the capacity of an
+/// [`InlineBitSet`](crate::collections::InlineBitSet) in bits:
+///
+/// ```
+/// use coca::collections::{InlineBitSet, bit_set::words_required};
+///
+/// let set = InlineBitSet::<{ words_required(1000) }>::new();
+/// assert!(set.capacity() >= 1000);
+/// ```
+#[allow(clippy::manual_is_multiple_of)] // usize::is_multiple_of requires Rust 1.87
+pub const fn words_required(bits: usize) -> usize {
+    bits / WORD_BITS + (bits % WORD_BITS != 0) as usize
+}
+
+/// A set of integers in the range `0..capacity`, stored as an array of bits.
+///
+/// Each potential element occupies a single bit, so a set with a capacity of
+/// *n* requires *n* / 8 bytes of storage, rounded up to a multiple of the
+/// size of a `usize`. Insertion, removal and lookup take constant time;
+/// set operations such as [`union_with`](BitSet::union_with) operate on whole
+/// words at a time, as do [`count_ones`](BitSet::count_ones) and iteration.
+///
+/// Elements are represented by the index type `I`, which must be able to
+/// represent all values up to the capacity of the provided storage.
+pub struct BitSet<S: Storage<ArrayLayout<usize>>, I: Capacity = usize> {
+    buf: S,
+    elem: PhantomData<I>,
+}
+
+impl<S: Storage<ArrayLayout<usize>>, I: Capacity> From<S> for BitSet<S, I> {
+    /// Converts a contiguous block of memory into an empty set.
+    ///
+    /// # Panics
+    /// Panics if the index type `I` cannot represent all elements up to the
+    /// capacity of `buf`.
+    fn from(mut buf: S) -> Self {
+        let max_element = buf.capacity().checked_mul(WORD_BITS).map(|bits| bits.saturating_sub(1));
+        if !matches!(max_element, Some(max) if max <= I::MAX_REPRESENTABLE) {
+            buffer_too_large_for_index_type::<I>();
+        }
+
+        let bytes = buf.capacity() * core::mem::size_of::<usize>();
+        unsafe { core::ptr::write_bytes(buf.get_mut_ptr(), 0, bytes); }
+
+        BitSet { buf, elem: PhantomData }
+    }
+}
+
+impl<S: Storage<ArrayLayout<usize>>, I: Capacity> BitSet<S, I> {
+    #[inline(always)]
+    fn locate(index: usize) -> (usize, usize) {
+        (index / WORD_BITS, 1 << (index % WORD_BITS))
+    }
+
+    /// Returns the bits of the set as a slice of words.
+    ///
+    /// The element `i` is represented by the bit `i % usize::BITS` of the word
+    /// at index `i / usize::BITS`, with bit 0 being the least significant.
+    #[inline]
+    pub fn as_words(&self) -> &[usize] {
+        unsafe { core::slice::from_raw_parts(self.buf.get_ptr().cast(), self.buf.capacity()) }
+    }
+
+    #[inline]
+    fn as_words_mut(&mut self) -> &mut [usize] {
+        unsafe { core::slice::from_raw_parts_mut(self.buf.get_mut_ptr().cast(), self.buf.capacity()) }
+    }
+
+    /// Returns the number of elements the set can hold, i.e. one more than
+    /// the largest element that can be inserted.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity() * WORD_BITS
+    }
+
+    /// Returns the number of elements in the set.
+    ///
+    /// This takes time linear in the capacity of the set.
+    ///
+    /// # Examples
+    /// ```
+    /// let mut set = coca::collections::InlineBitSet::<2>::new();
+    /// set.insert(1);
+    /// set.insert(100);
+    /// assert_eq!(set.count_ones(), 2);
+    /// ```
+    pub fn count_ones(&self) -> usize {
+        self.as_words().iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Returns `true` if the set contains no elements, or `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.as_words().iter().all(|&w| w == 0)
+    }
+
+    /// Removes all elements from the set.
+    pub fn clear(&mut self) {
+        self.as_words_mut().iter_mut().for_each(|w| *w = 0);
+    }
+
+    /// Returns `true` if the set contains the specified element, or `false` otherwise.
+    ///
+    /// Elements outside the set's capacity are never contained in it.
+    #[inline]
+    pub fn contains(&self, element: I) -> bool {
+        let (word, mask) = Self::locate(element.as_usize());
+        matches!(self.as_words().get(word), Some(w) if w & mask != 0)
+    }
+
+    /// Adds an element to the set.
+    ///
+    /// Returns `true` if the set did not previously contain the element, or
+    /// `false` otherwise.
+    ///
+    /// # Panics
+    /// Panics if `element` is outside the set's capacity.
+    ///
+    /// # Examples
+    /// ```
+    /// let mut set = coca::collections::InlineBitSet::<1>::new();
+    /// assert!(set.insert(3));
+    /// assert!(!set.insert(3));
+    /// assert!(set.contains(3));
+    /// ```
+    #[track_caller]
+    pub fn insert(&mut self, element: I) -> bool {
+        let index = element.as_usize();
+        let capacity = self.capacity();
+        assert!(index < capacity, "element {} is out of bounds for bit set of capacity {}", index, capacity);
+
+        let (word, mask) = Self::locate(index);
+        let w = &mut self.as_words_mut()[word];
+        let inserted = *w & mask == 0;
+        *w |= mask;
+        inserted
+    }
+
+    /// Removes an element from the set.
+    ///
+    /// Returns `true` if the set previously contained the element, or `false` otherwise.
+    pub fn remove(&mut self, element: I) -> bool {
+        let (word, mask) = Self::locate(element.as_usize());
+        match self.as_words_mut().get_mut(word) {
+            Some(w) if *w & mask != 0 => {
+                *w &= !mask;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Adds all elements of `other` to `self`.
+    ///
+    /// Returns [`CapacityError`](crate::CapacityError) without modifying
+    /// `self` if `other` contains any elements outside of `self`'s capacity.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBitSet;
+    ///
+    /// let mut small = InlineBitSet::<1>::new();
+    /// let mut large = InlineBitSet::<4>::new();
+    /// small.insert(1);
+    /// large.insert(2);
+    ///
+    /// assert!(small.try_union_with(&large).is_ok());
+    /// assert!(small.iter().eq([1, 2]));
+    ///
+    /// large.insert(200);
+    /// assert!(small.try_union_with(&large).is_err());
+    /// assert!(small.iter().eq([1, 2]));
+    /// ```
+    pub fn try_union_with<S2: Storage<ArrayLayout<usize>>, I2: Capacity>(&mut self, other: &BitSet<S2, I2>) -> crate::Result<()> {
+        let words = self.as_words_mut();
+        let other = other.as_words();
+        if other.len() > words.len() && other[words.len()..].iter().any(|&w| w != 0) {
+            return crate::CapacityError::new();
+        }
+
+        words.iter_mut().zip(other).for_each(|(w, o)| *w |= o);
+        Ok(())
+    }
+
+    /// Adds all elements of `other` to `self`.
+    ///
+    /// # Panics
+    /// Panics if `other` contains any elements outside of `self`'s capacity.
+    /// See [`try_union_with`](BitSet::try_union_with) for a checked version.
+    #[track_caller]
+    pub fn union_with<S2: Storage<ArrayLayout<usize>>, I2: Capacity>(&mut self, other: &BitSet<S2, I2>) {
+        self.try_union_with(other).expect("insufficient capacity");
+    }
+
+    /// Removes all elements from `self` that are not contained in `other`.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBitSet;
+    ///
+    /// let mut a = InlineBitSet::<2>::new();
+    /// let mut b = InlineBitSet::<1>::new();
+    /// a.extend([1, 2, 3, 100]);
+    /// b.extend([2, 3, 4]);
+    ///
+    /// a.intersect_with(&b);
+    /// assert!(a.iter().eq([2, 3]));
+    /// ```
+    pub fn intersect_with<S2: Storage<ArrayLayout<usize>>, I2: Capacity>(&mut self, other: &BitSet<S2, I2>) {
+        let other = other.as_words();
+        for (i, w) in self.as_words_mut().iter_mut().enumerate() {
+            *w &= other.get(i).copied().unwrap_or(0);
+        }
+    }
+
+    /// Removes all elements from `self` that are contained in `other`.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBitSet;
+    ///
+    /// let mut a = InlineBitSet::<1>::new();
+    /// let mut b = InlineBitSet::<2>::new();
+    /// a.extend([1, 2, 3]);
+    /// b.extend([2, 3, 4, 100]);
+    ///
+    /// a.difference_with(&b);
+    /// assert!(a.iter().eq([1]));
+    /// ```
+    pub fn difference_with<S2: Storage<ArrayLayout<usize>>, I2: Capacity>(&mut self, other: &BitSet<S2, I2>) {
+        self.as_words_mut().iter_mut().zip(other.as_words()).for_each(|(w, o)| *w &= !o);
+    }
+
+    /// Returns `true` if `self` has no elements in common with `other`.
+    /// This is equivalent to checking for an empty intersection.
+    pub fn is_disjoint<O: SetLike<I>>(&self, other: &O) -> bool {
+        self.iter().all(|x| !other.contains(&x))
+    }
+
+    /// Returns `true` if `self` is a subset of `other`, i.e. `other` contains
+    /// at least all the values in `self`.
+    pub fn is_subset_of<O: SetLike<I>>(&self, other: &O) -> bool {
+        self.iter().all(|x| other.contains(&x))
+    }
+
+    /// Returns `true` if `self` is a superset of `other`, i.e. `self` contains
+    /// at least all the values in `other`.
+    pub fn is_superset_of<O: SetLike<I>>(&self, other: &O) -> bool {
+        other.all(|&x| self.contains(x))
+    }
+
+    /// Returns the smallest element in the set, or [`None`] if the set is empty.
+    pub fn first_set(&self) -> Option<I> {
+        self.find_from(0, !0)
+    }
+
+    /// Returns the largest element in the set, or [`None`] if the set is empty.
+    pub fn last_set(&self) -> Option<I> {
+        let words = self.as_words();
+        let (i, w) = words.iter().enumerate().rev().find(|(_, &w)| w != 0)?;
+        Some(I::from_usize(i * WORD_BITS + (WORD_BITS - 1 - w.leading_zeros() as usize)))
+    }
+
+    /// Returns the smallest element in the set that is strictly greater than
+    /// `element`, or [`None`] if there is no such element.
+    ///
+    /// # Examples
+    /// ```
+    /// let mut set = coca::collections::InlineBitSet::<4>::new();
+    /// set.extend([3, 64, 200]);
+    ///
+    /// assert_eq!(set.first_set(), Some(3));
+    /// assert_eq!(set.next_set_after(3), Some(64));
+    /// assert_eq!(set.next_set_after(64), Some(200));
+    /// assert_eq!(set.next_set_after(200), None);
+    /// ```
+    pub fn next_set_after(&self, element: I) -> Option<I> {
+        let start = element.as_usize().checked_add(1)?;
+        let (word, mask) = Self::locate(start);
+        self.find_from(word, !(mask - 1))
+    }
+
+    /// Returns the smallest element at or after the start of word `word`,
+    /// ignoring bits of that word not included in `mask`.
+    fn find_from(&self, word: usize, mask: usize) -> Option<I> {
+        let words = self.as_words();
+        let first = words.get(word)? & mask;
+        if first != 0 {
+            return Some(I::from_usize(word * WORD_BITS + first.trailing_zeros() as usize));
+        }
+
+        let (i, w) = words.iter().enumerate().skip(word + 1).find(|(_, &w)| w != 0)?;
+        Some(I::from_usize(i * WORD_BITS + w.trailing_zeros() as usize))
+    }
+
+    /// Returns an iterator visiting all elements in ascending order.
+    ///
+    /// # Examples
+    /// ```
+    /// let mut set = coca::collections::InlineBitSet::<2>::new();
+    /// set.extend([100, 7, 42]);
+    /// assert!(set.iter().eq([7, 42, 100]));
+    /// ```
+    pub fn iter(&self) -> Iter<'_, I> {
+        let words = self.as_words();
+        Iter { current: words.first().copied().unwrap_or(0), base: 0, words, elem: PhantomData }
+    }
+}
+
+impl<S: Storage<ArrayLayout<usize>>, I: Capacity> SetLike<I> for BitSet<S, I> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.count_ones()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        BitSet::is_empty(self)
+    }
+
+    #[inline]
+    fn contains(&self, value: &I) -> bool {
+        BitSet::contains(self, *value)
+    }
+
+    fn all<F: FnMut(&I) -> bool>(&self, mut pred: F) -> bool {
+        self.iter().all(|x| pred(&x))
+    }
+}
+
+impl<S1, S2, I1, I2> core::ops::BitAndAssign<&'_ BitSet<S2, I2>> for BitSet<S1, I1>
+where
+    S1: Storage<ArrayLayout<usize>>,
+    S2: Storage<ArrayLayout<usize>>,
+    I1: Capacity,
+    I2: Capacity,
+{
+    fn bitand_assign(&mut self, rhs: &BitSet<S2, I2>) {
+        self.intersect_with(rhs);
+    }
+}
+
+impl<S1, S2, I1, I2> core::ops::BitOrAssign<&'_ BitSet<S2, I2>> for BitSet<S1, I1>
+where
+    S1: Storage<ArrayLayout<usize>>,
+    S2: Storage<ArrayLayout<usize>>,
+    I1: Capacity,
+    I2: Capacity,
+{
+    fn bitor_assign(&mut self, rhs: &BitSet<S2, I2>) {
+        self.union_with(rhs);
+    }
+}
+
+impl<S1, S2, I1, I2> core::ops::SubAssign<&'_ BitSet<S2, I2>> for BitSet<S1, I1>
+where
+    S1: Storage<ArrayLayout<usize>>,
+    S2: Storage<ArrayLayout<usize>>,
+    I1: Capacity,
+    I2: Capacity,
+{
+    fn sub_assign(&mut self, rhs: &BitSet<S2, I2>) {
+        self.difference_with(rhs);
+    }
+}
+
+impl<S: Storage<ArrayLayout<usize>>, I: Capacity> Debug for BitSet<S, I> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<S: Storage<ArrayLayout<usize>>, I: Capacity> Extend<I> for BitSet<S, I> {
+    fn extend<It: IntoIterator<Item = I>>(&mut self, iter: It) {
+        iter.into_iter().for_each(|x| { self.insert(x); });
+    }
+}
+
+impl<'a, S: Storage<ArrayLayout<usize>>, I: Capacity> Extend<&'a I> for BitSet<S, I> {
+    fn extend<It: IntoIterator<Item = &'a I>>(&mut self, iter: It) {
+        iter.into_iter().for_each(|&x| { self.insert(x); });
+    }
+}
+
+impl<'a, S: Storage<ArrayLayout<usize>>, I: Capacity> IntoIterator for &'a BitSet<S, I> {
+    type Item = I;
+    type IntoIter = Iter<'a, I>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<S1, S2, I1, I2> PartialEq<BitSet<S2, I2>> for BitSet<S1, I1>
+where
+    S1: Storage<ArrayLayout<usize>>,
+    S2: Storage<ArrayLayout<usize>>,
+    I1: Capacity,
+    I2: Capacity,
+{
+    /// Tests for `self` and `other` to contain the same elements, regardless
+    /// of their capacities.
+    fn eq(&self, other: &BitSet<S2, I2>) -> bool {
+        let (a, b) = (self.as_words(), other.as_words());
+        let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+        short == &long[..short.len()] && long[short.len()..].iter().all(|&w| w == 0)
+    }
+}
+
+impl<S: Storage<ArrayLayout<usize>>, I: Capacity> Eq for BitSet<S, I> {}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+impl<I: Capacity> crate::collections::AllocBitSet<I> {
+    /// Constructs a new, empty [`AllocBitSet`](crate::collections::AllocBitSet)
+    /// that can hold at least the specified number of elements.
+    ///
+    /// # Panics
+    /// Panics if the index type `I` cannot represent all elements up to the
+    /// specified capacity, rounded up to a multiple of `usize::BITS`.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from(crate::storage::AllocStorage::with_capacity(words_required(capacity)))
+    }
+}
+
+impl<'a, I: Capacity> crate::collections::ArenaBitSet<'a, I> {
+    /// Constructs a new, empty [`ArenaBitSet`](crate::collections::ArenaBitSet)
+    /// in the given arena that can hold at least the specified number of elements.
+    ///
+    /// # Panics
+    /// Panics if the remaining space in the arena is insufficient, or if the
+    /// index type `I` cannot represent all elements up to the specified
+    /// capacity, rounded up to a multiple of `usize::BITS`. See
+    /// [`try_with_capacity`](crate::collections::ArenaBitSet::try_with_capacity)
+    /// for a version that returns [`None`] if the arena is exhausted.
+    #[track_caller]
+    pub fn with_capacity(arena: &mut Arena<'a>, capacity: usize) -> Self {
+        Self::from(arena.storage_with_capacity(words_required(capacity)))
+    }
+
+    /// Constructs a new, empty [`ArenaBitSet`](crate::collections::ArenaBitSet)
+    /// in the given arena that can hold at least the specified number of elements.
+    ///
+    /// Returns [`None`] if the remaining space in the arena is insufficient.
+    ///
+    /// # Panics
+    /// Panics if the index type `I` cannot represent all elements up to the
+    /// specified capacity, rounded up to a multiple of `usize::BITS`.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::arena::Arena;
+    /// use coca::collections::ArenaBitSet;
+    /// use core::mem::MaybeUninit;
+    ///
+    /// let mut backing_region = [MaybeUninit::uninit(); 512];
+    /// let mut arena = Arena::from(&mut backing_region[..]);
+    ///
+    /// let set = ArenaBitSet::<u16>::try_with_capacity(&mut arena, 3000).unwrap();
+    /// assert!(set.capacity() >= 3000);
+    /// assert!(ArenaBitSet::<u16>::try_with_capacity(&mut arena, 3000).is_none());
+    /// ```
+    pub fn try_with_capacity(arena: &mut Arena<'a>, capacity: usize) -> Option<Self> {
+        Some(Self::from(arena.try_storage_with_capacity(words_required(capacity))?))
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+impl<I: Capacity> Clone for crate::collections::AllocBitSet<I> {
+    fn clone(&self) -> Self {
+        let mut result = Self::with_capacity(self.capacity());
+        result.as_words_mut().copy_from_slice(self.as_words());
+        result
+    }
+}
+
+impl<I: Capacity, const W: usize> crate::collections::InlineBitSet<W, I> {
+    /// Constructs a new, empty [`InlineBitSet`](crate::collections::InlineBitSet).
+    ///
+    /// # Panics
+    /// Panics if the index type `I` cannot represent all elements up to the
+    /// set's capacity.
+    pub fn new() -> Self {
+        Self::from([core::mem::MaybeUninit::uninit(); W])
+    }
+}
+
+impl<I: Capacity, const W: usize> Default for crate::collections::InlineBitSet<W, I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I: Capacity, const W: usize> Clone for crate::collections::InlineBitSet<W, I> {
+    fn clone(&self) -> Self {
+        let mut result = Self::new();
+        result.as_words_mut().copy_from_slice(self.as_words());
+        result
+    }
+}
+
+/// An iterator over the elements of a [`BitSet`], in ascending order.
+///
+/// This `struct` is created by the [`iter`](BitSet::iter) method on `BitSet`.
+/// See its documentation for more.
+pub struct Iter<'a, I> {
+    words: &'a [usize],
+    base: usize,
+    current: usize,
+    elem: PhantomData<I>,
+}
+
+impl<I: Capacity> Iterator for Iter<'_, I> {
+    type Item = I;
+
+    fn next(&mut self) -> Option<I> {
+        while self.current == 0 {
+            self.base += 1;
+            self.current = *self.words.get(self.base)?;
+        }
+
+        let bit = self.current.trailing_zeros() as usize;
+        self.current &= self.current - 1;
+        Some(I::from_usize(self.base * WORD_BITS + bit))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.words.get(self.base + 1..).map_or(0, |ws| ws.iter().map(|w| w.count_ones() as usize).sum::<usize>());
+        let len = remaining + self.current.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl<I: Capacity> ExactSizeIterator for Iter<'_, I> {}
+impl<I: Capacity> FusedIterator for Iter<'_, I> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::{InlineBitSet, InlineSortedMap};
+
+    #[test]
+    fn randomized_operations() {
+        use rand::{rngs::SmallRng, RngCore, SeedableRng};
+        let mut rng = SmallRng::from_seed(crate::test_utils::RNG_SEED);
+
+        let mut set = InlineBitSet::<8, u16>::new();
+        let mut reference = InlineSortedMap::<u16, (), 512>::new();
+        let capacity = set.capacity() as u32;
+
+        for _ in 0..10_000 {
+            let x = (rng.next_u32() % capacity) as u16;
+            match rng.next_u32() % 4 {
+                0 | 1 => assert_eq!(set.insert(x), reference.insert(x, ()).is_none()),
+                2 => assert_eq!(set.remove(x), reference.remove(&x).is_some()),
+                _ => {
+                    let expected = reference.range(x + 1..).next().map(|(k, _)| *k);
+                    assert_eq!(set.next_set_after(x), expected);
+                }
+            }
+        }
+
+        assert_eq!(set.count_ones(), reference.len());
+        assert_eq!(set.iter().len(), reference.len());
+        assert!(set.iter().eq(reference.keys().iter().copied()));
+        assert_eq!(set.first_set(), reference.first_key_value().map(|(k, _)| *k));
+        assert_eq!(set.last_set(), reference.last_key_value().map(|(k, _)| *k));
+    }
+
+    #[test]
+    fn constructors_take_capacity_in_bits() {
+        use crate::arena::Arena;
+        use crate::collections::ArenaBitSet;
+        use core::mem::MaybeUninit;
+
+        let mut backing_region = [MaybeUninit::uninit(); 1024];
+        let mut arena = Arena::from(&mut backing_region[..]);
+
+        for bits in [0, 1, 63, 64, 65, 1000] {
+            let set = ArenaBitSet::<u16>::with_capacity(&mut arena, bits);
+            assert_eq!(set.capacity(), super::words_required(bits) * super::WORD_BITS);
+
+            #[cfg(feature = "alloc")]
+            assert_eq!(crate::collections::AllocBitSet::<u16>::with_capacity(bits).capacity(), set.capacity());
+        }
+
+        assert!(ArenaBitSet::<u16>::try_with_capacity(&mut arena, 8 * 1024).is_none());
+    }
+}
@@ -0,0 +1,743 @@
+//! A contiguous growable array of bits, with optional rank and select support.
+//!
+//! A [`BitVec`] packs its elements into `usize` words, using one bit per
+//! element. To answer rank and select queries efficiently, it can be frozen
+//! into a [`RankSelect`] structure, which stores a table of cumulative
+//! population counts in a second storage block:
+//!
+//! ```
+//! use coca::collections::{InlineBitVec, bit_vec::rank_table_len};
+//! use core::mem::MaybeUninit;
+//!
+//! let mut bits = InlineBitVec::<4>::new();
+//! bits.extend([true, false, false, true, true, false, true]);
+//!
+//! let table = [MaybeUninit::uninit(); rank_table_len(7)];
+//! let index = bits.into_rank_select(table);
+//!
+//! assert_eq!(index.rank1(4), 2);
+//! assert_eq!(index.rank0(4), 2);
+//! assert_eq!(index.select1(2), Some(4));
+//! assert_eq!(index.select0(2), Some(5));
+//! assert_eq!(index.select1(4), None);
+//! ```
+
+use core::fmt::Debug;
+use core::iter::FusedIterator;
+use core::ops::{Range, RangeBounds};
+
+use crate::collections::bit_set::words_required;
+use crate::storage::{buffer_too_large_for_index_type, normalize_range, ArrayLayout, Capacity, Storage};
+
+const WORD_BITS: usize = core::mem::size_of::<usize>() * 8;
+
+/// Returns the number of entries a rank table needs to index the specified number of bits.
+///
+/// See [`BitVec::into_rank_select`] for details.
+pub const fn rank_table_len(bits: usize) -> usize {
+    words_required(bits) + 1
+}
+
+#[inline(always)]
+fn get_bit(words: &[usize], index: usize) -> bool {
+    words[index / WORD_BITS] & (1 << (index % WORD_BITS)) != 0
+}
+
+/// Returns the position of the `k`-th set bit of `word`, counting from zero.
+#[inline]
+fn select_in_word(mut word: usize, k: usize) -> usize {
+    for _ in 0..k {
+        word &= word - 1;
+    }
+
+    word.trailing_zeros() as usize
+}
+
+/// A contiguous growable array of bits with constant capacity.
+///
+/// Bits are stored in `usize` words, with the element at index `i` stored in
+/// bit `i % usize::BITS` of word `i / usize::BITS`, bit 0 being the least
+/// significant. Unused bits past the end of the vector are always zero.
+///
+/// The index type `I` must be able to represent the capacity of the vector
+/// in bits.
+pub struct BitVec<S: Storage<ArrayLayout<usize>>, I: Capacity = usize> {
+    buf: S,
+    len: I,
+}
+
+impl<S: Storage<ArrayLayout<usize>>, I: Capacity> From<S> for BitVec<S, I> {
+    /// Converts a contiguous block of memory into an empty bit vector.
+    ///
+    /// # Panics
+    /// Panics if the index type `I` cannot represent the capacity of `buf` in bits.
+    fn from(mut buf: S) -> Self {
+        let capacity = buf.capacity().checked_mul(WORD_BITS);
+        if !matches!(capacity, Some(cap) if cap <= I::MAX_REPRESENTABLE) {
+            buffer_too_large_for_index_type::<I>();
+        }
+
+        let bytes = buf.capacity() * core::mem::size_of::<usize>();
+        unsafe { core::ptr::write_bytes(buf.get_mut_ptr(), 0, bytes); }
+
+        BitVec { buf, len: I::from_usize(0) }
+    }
+}
+
+impl<S: Storage<ArrayLayout<usize>>, I: Capacity> BitVec<S, I> {
+    /// Returns the words containing the vector's bits.
+    ///
+    /// This includes all words of the underlying storage; bits past the end
+    /// of the vector are zero.
+    #[inline]
+    pub fn as_words(&self) -> &[usize] {
+        unsafe { core::slice::from_raw_parts(self.buf.get_ptr().cast(), self.buf.capacity()) }
+    }
+
+    #[inline]
+    fn as_words_mut(&mut self) -> &mut [usize] {
+        unsafe { core::slice::from_raw_parts_mut(self.buf.get_mut_ptr().cast(), self.buf.capacity()) }
+    }
+
+    /// Returns the number of bits the vector can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity() * WORD_BITS
+    }
+
+    /// Returns the number of bits in the vector.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len.as_usize()
+    }
+
+    /// Returns `true` if the vector contains no bits, or `false` otherwise.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the vector contains the maximum number of bits it can hold, or `false` otherwise.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+
+    /// Returns the bit at the specified index, or [`None`] if the index is out of bounds.
+    ///
+    /// # Examples
+    /// ```
+    /// let mut bits = coca::collections::InlineBitVec::<1>::new();
+    /// bits.push(true);
+    /// assert_eq!(bits.get(0), Some(true));
+    /// assert_eq!(bits.get(1), None);
+    /// ```
+    #[inline]
+    pub fn get(&self, index: I) -> Option<bool> {
+        let index = index.as_usize();
+        if index >= self.len() { return None; }
+        Some(get_bit(self.as_words(), index))
+    }
+
+    /// Sets the bit at the specified index to `value`, returning its previous value.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    #[track_caller]
+    pub fn set(&mut self, index: I, value: bool) -> bool {
+        let index = index.as_usize();
+        let len = self.len();
+        assert!(index < len, "index out of bounds: the len is {} but the index is {}", len, index);
+
+        let mask = 1 << (index % WORD_BITS);
+        let word = &mut self.as_words_mut()[index / WORD_BITS];
+        let previous = *word & mask != 0;
+        if value { *word |= mask; } else { *word &= !mask; }
+        previous
+    }
+
+    /// Appends a bit to the back of the vector.
+    ///
+    /// Returns [`Err`] containing the bit if the vector is already full.
+    pub fn try_push(&mut self, value: bool) -> Result<(), bool> {
+        if self.is_full() {
+            return Err(value);
+        }
+
+        let index = self.len();
+        self.len = I::from_usize(index + 1);
+        if value {
+            self.as_words_mut()[index / WORD_BITS] |= 1 << (index % WORD_BITS);
+        }
+
+        Ok(())
+    }
+
+    /// Appends a bit to the back of the vector.
+    ///
+    /// # Panics
+    /// Panics if the vector is already full. See [`try_push`](BitVec::try_push)
+    /// for a checked version that never panics.
+    #[track_caller]
+    pub fn push(&mut self, value: bool) {
+        #[cold]
+        #[inline(never)]
+        fn assert_failed() -> ! {
+            panic!("vector is already at capacity")
+        }
+
+        if self.try_push(value).is_err() {
+            assert_failed();
+        }
+    }
+
+    /// Removes the last bit from the vector and returns it, or [`None`] if the vector is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// let mut bits = coca::collections::InlineBitVec::<1>::new();
+    /// bits.push(true);
+    /// bits.push(false);
+    /// assert_eq!(bits.pop(), Some(false));
+    /// assert_eq!(bits.pop(), Some(true));
+    /// assert_eq!(bits.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<bool> {
+        let index = self.len().checked_sub(1)?;
+        self.len = I::from_usize(index);
+
+        let mask = 1 << (index % WORD_BITS);
+        let word = &mut self.as_words_mut()[index / WORD_BITS];
+        let value = *word & mask != 0;
+        *word &= !mask;
+        Some(value)
+    }
+
+    /// Shortens the vector, keeping the first `len` bits.
+    ///
+    /// If `len` is greater than the vector's current length, this has no effect.
+    pub fn truncate(&mut self, len: I) {
+        let new_len = len.as_usize();
+        let old_len = self.len();
+        if new_len >= old_len { return; }
+
+        let first_word = new_len / WORD_BITS;
+        let words = self.as_words_mut();
+        words[first_word] &= (1 << (new_len % WORD_BITS)) - 1;
+        words[first_word + 1..words_required(old_len)].iter_mut().for_each(|w| *w = 0);
+        self.len = len;
+    }
+
+    /// Clears the vector, removing all bits.
+    pub fn clear(&mut self) {
+        self.as_words_mut().iter_mut().for_each(|w| *w = 0);
+        self.len = I::from_usize(0);
+    }
+
+    /// Returns the number of set bits in the vector.
+    pub fn count_ones(&self) -> usize {
+        self.as_words().iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Returns the number of unset bits in the vector.
+    pub fn count_zeros(&self) -> usize {
+        self.len() - self.count_ones()
+    }
+
+    /// Returns a view of the entire vector.
+    pub fn as_bit_slice(&self) -> BitSlice<'_> {
+        BitSlice { words: self.as_words(), start: 0, len: self.len() }
+    }
+
+    /// Returns a view of the specified range of the vector.
+    ///
+    /// # Panics
+    /// Panics if the starting point is greater than the end point or if the
+    /// end point is greater than the length of the vector.
+    ///
+    /// # Examples
+    /// ```
+    /// let mut bits = coca::collections::InlineBitVec::<2>::new();
+    /// bits.extend((0..100).map(|i| i % 3 == 0));
+    ///
+    /// let slice = bits.slice(60..70);
+    /// assert_eq!(slice.len(), 10);
+    /// assert_eq!(slice.count_ones(), 4);
+    /// assert!(slice.iter().eq((60..70).map(|i| i % 3 == 0)));
+    /// ```
+    pub fn slice<R: RangeBounds<I>>(&self, range: R) -> BitSlice<'_> {
+        let Range { start, end } = normalize_range(range, self.len());
+        BitSlice { words: self.as_words(), start, len: end - start }
+    }
+
+    /// Returns an iterator over the bits of the vector.
+    pub fn iter(&self) -> Iter<'_> {
+        self.as_bit_slice().iter()
+    }
+
+    /// Freezes the vector, building a table of cumulative population counts
+    /// in the provided storage to support efficient rank and select queries.
+    ///
+    /// The table requires [`rank_table_len(self.len())`](rank_table_len)
+    /// entries. If `table` is too small, the vector and the storage are
+    /// returned unchanged.
+    pub fn try_into_rank_select<R: Storage<ArrayLayout<usize>>>(self, mut table: R) -> Result<RankSelect<S, R, I>, (Self, R)> {
+        let entries = rank_table_len(self.len());
+        if table.capacity() < entries {
+            return Err((self, table));
+        }
+
+        let ptr = table.get_mut_ptr();
+        unsafe { core::ptr::write_bytes(ptr, 0, entries * core::mem::size_of::<usize>()); }
+        let counts: &mut [usize] = unsafe { core::slice::from_raw_parts_mut(ptr.cast(), entries) };
+
+        let mut total = 0;
+        for (count, word) in counts.iter_mut().zip(&self.as_words()[..entries - 1]) {
+            *count = total;
+            total += word.count_ones() as usize;
+        }
+
+        counts[entries - 1] = total;
+        Ok(RankSelect { bits: self, table })
+    }
+
+    /// Freezes the vector, building a table of cumulative population counts
+    /// in the provided storage to support efficient rank and select queries.
+    ///
+    /// # Panics
+    /// Panics if `table` has fewer than [`rank_table_len(self.len())`](rank_table_len)
+    /// entries. See [`try_into_rank_select`](BitVec::try_into_rank_select)
+    /// for a checked version that never panics.
+    #[track_caller]
+    pub fn into_rank_select<R: Storage<ArrayLayout<usize>>>(self, table: R) -> RankSelect<S, R, I> {
+        self.try_into_rank_select(table).ok().expect("insufficient capacity")
+    }
+}
+
+impl<S: Storage<ArrayLayout<usize>>, I: Capacity> Debug for BitVec<S, I> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.as_bit_slice().fmt(f)
+    }
+}
+
+impl<S: Storage<ArrayLayout<usize>>, I: Capacity> Extend<bool> for BitVec<S, I> {
+    fn extend<It: IntoIterator<Item = bool>>(&mut self, iter: It) {
+        iter.into_iter().for_each(|b| self.push(b));
+    }
+}
+
+impl<'a, S: Storage<ArrayLayout<usize>>, I: Capacity> Extend<&'a bool> for BitVec<S, I> {
+    fn extend<It: IntoIterator<Item = &'a bool>>(&mut self, iter: It) {
+        iter.into_iter().for_each(|&b| self.push(b));
+    }
+}
+
+impl<'a, S: Storage<ArrayLayout<usize>>, I: Capacity> IntoIterator for &'a BitVec<S, I> {
+    type Item = bool;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<S1, S2, I1, I2> PartialEq<BitVec<S2, I2>> for BitVec<S1, I1>
+where
+    S1: Storage<ArrayLayout<usize>>,
+    S2: Storage<ArrayLayout<usize>>,
+    I1: Capacity,
+    I2: Capacity,
+{
+    fn eq(&self, other: &BitVec<S2, I2>) -> bool {
+        let words = words_required(self.len());
+        self.len() == other.len() && self.as_words()[..words] == other.as_words()[..words]
+    }
+}
+
+impl<S: Storage<ArrayLayout<usize>>, I: Capacity> Eq for BitVec<S, I> {}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+impl<I: Capacity> crate::collections::AllocBitVec<I> {
+    /// Constructs a new, empty [`AllocBitVec`](crate::collections::AllocBitVec)
+    /// that can hold at least the specified number of bits.
+    ///
+    /// # Panics
+    /// Panics if the index type `I` cannot represent the specified capacity,
+    /// rounded up to a multiple of `usize::BITS`.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from(crate::storage::AllocStorage::with_capacity(words_required(capacity)))
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+impl<I: Capacity> Clone for crate::collections::AllocBitVec<I> {
+    fn clone(&self) -> Self {
+        let mut result = Self::with_capacity(self.capacity());
+        result.as_words_mut().copy_from_slice(self.as_words());
+        result.len = self.len;
+        result
+    }
+}
+
+impl<I: Capacity, const W: usize> crate::collections::InlineBitVec<W, I> {
+    /// Constructs a new, empty [`InlineBitVec`](crate::collections::InlineBitVec).
+    ///
+    /// # Panics
+    /// Panics if the index type `I` cannot represent the vector's capacity in bits.
+    pub fn new() -> Self {
+        Self::from([core::mem::MaybeUninit::uninit(); W])
+    }
+}
+
+impl<I: Capacity, const W: usize> Default for crate::collections::InlineBitVec<W, I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I: Capacity, const W: usize> Clone for crate::collections::InlineBitVec<W, I> {
+    fn clone(&self) -> Self {
+        let mut result = Self::new();
+        result.as_words_mut().copy_from_slice(self.as_words());
+        result.len = self.len;
+        result
+    }
+}
+
+/// A read-only view into a range of bits of a [`BitVec`].
+///
+/// This `struct` is created by the [`slice`](BitVec::slice) and
+/// [`as_bit_slice`](BitVec::as_bit_slice) methods on `BitVec`.
+#[derive(Clone, Copy)]
+pub struct BitSlice<'a> {
+    words: &'a [usize],
+    start: usize,
+    len: usize,
+}
+
+impl<'a> BitSlice<'a> {
+    /// Returns the number of bits in the slice.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the slice contains no bits, or `false` otherwise.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the bit at the specified index, or [`None`] if the index is out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<bool> {
+        if index >= self.len { return None; }
+        Some(get_bit(self.words, self.start + index))
+    }
+
+    /// Returns the number of set bits in the slice.
+    pub fn count_ones(&self) -> usize {
+        if self.len == 0 { return 0; }
+
+        let end = self.start + self.len;
+        let (first, last) = (self.start / WORD_BITS, (end - 1) / WORD_BITS);
+        let head_mask = !0 << (self.start % WORD_BITS);
+        let tail_mask = !0 >> (WORD_BITS - 1 - (end - 1) % WORD_BITS);
+
+        if first == last {
+            return (self.words[first] & head_mask & tail_mask).count_ones() as usize;
+        }
+
+        let inner: usize = self.words[first + 1..last].iter().map(|w| w.count_ones() as usize).sum();
+        inner + (self.words[first] & head_mask).count_ones() as usize + (self.words[last] & tail_mask).count_ones() as usize
+    }
+
+    /// Returns the number of unset bits in the slice.
+    pub fn count_zeros(&self) -> usize {
+        self.len - self.count_ones()
+    }
+
+    /// Returns a view of the specified range of the slice.
+    ///
+    /// # Panics
+    /// Panics if the starting point is greater than the end point or if the
+    /// end point is greater than the length of the slice.
+    #[must_use]
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> BitSlice<'a> {
+        let Range { start, end } = normalize_range(range, self.len);
+        BitSlice { words: self.words, start: self.start + start, len: end - start }
+    }
+
+    /// Returns an iterator over the bits of the slice.
+    pub fn iter(&self) -> Iter<'a> {
+        Iter { words: self.words, front: self.start, back: self.start + self.len }
+    }
+}
+
+impl Debug for BitSlice<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("[")?;
+        for bit in self {
+            f.write_str(if bit { "1" } else { "0" })?;
+        }
+        f.write_str("]")
+    }
+}
+
+impl PartialEq for BitSlice<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for BitSlice<'_> {}
+
+impl<'a> IntoIterator for BitSlice<'a> {
+    type Item = bool;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &BitSlice<'a> {
+    type Item = bool;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the bits of a [`BitVec`] or [`BitSlice`].
+///
+/// This `struct` is created by the `iter` methods on [`BitVec`](BitVec::iter)
+/// and [`BitSlice`](BitSlice::iter).
+#[derive(Clone)]
+pub struct Iter<'a> {
+    words: &'a [usize],
+    front: usize,
+    back: usize,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<bool> {
+        if self.front == self.back { return None; }
+        let bit = get_bit(self.words, self.front);
+        self.front += 1;
+        Some(bit)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<bool> {
+        if self.front == self.back { return None; }
+        self.back -= 1;
+        Some(get_bit(self.words, self.back))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+impl FusedIterator for Iter<'_> {}
+
+/// A frozen [`BitVec`] with a table of cumulative population counts, which
+/// supports rank queries in constant time and select queries in logarithmic time.
+///
+/// This `struct` is created by the [`into_rank_select`](BitVec::into_rank_select)
+/// method on `BitVec`. The table contains one entry per word of the vector,
+/// plus one entry for the total, requiring an additional `usize` per
+/// `usize::BITS` bits.
+pub struct RankSelect<S: Storage<ArrayLayout<usize>>, R: Storage<ArrayLayout<usize>>, I: Capacity = usize> {
+    bits: BitVec<S, I>,
+    table: R,
+}
+
+impl<S: Storage<ArrayLayout<usize>>, R: Storage<ArrayLayout<usize>>, I: Capacity> RankSelect<S, R, I> {
+    #[inline(always)]
+    fn table(&self) -> &[usize] {
+        let entries = rank_table_len(self.bits.len());
+        unsafe { core::slice::from_raw_parts(self.table.get_ptr().cast(), entries) }
+    }
+
+    /// Returns a reference to the indexed bit vector.
+    #[inline]
+    pub fn bits(&self) -> &BitVec<S, I> {
+        &self.bits
+    }
+
+    /// Returns the number of bits in the indexed vector.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    /// Returns `true` if the indexed vector contains no bits, or `false` otherwise.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    /// Returns the bit at the specified index, or [`None`] if the index is out of bounds.
+    #[inline]
+    pub fn get(&self, index: I) -> Option<bool> {
+        self.bits.get(index)
+    }
+
+    /// Returns the number of set bits in the vector.
+    ///
+    /// Unlike [`BitVec::count_ones`], this takes constant time.
+    #[inline]
+    pub fn count_ones(&self) -> usize {
+        self.table()[words_required(self.len())]
+    }
+
+    /// Returns the number of unset bits in the vector.
+    #[inline]
+    pub fn count_zeros(&self) -> usize {
+        self.len() - self.count_ones()
+    }
+
+    /// Returns the number of set bits in the range `0..index`.
+    ///
+    /// # Panics
+    /// Panics if `index` is greater than the length of the vector.
+    #[track_caller]
+    pub fn rank1(&self, index: I) -> usize {
+        let index = index.as_usize();
+        let len = self.len();
+        assert!(index <= len, "index out of bounds: the len is {} but the index is {}", len, index);
+
+        let (word, bit) = (index / WORD_BITS, index % WORD_BITS);
+        let partial = if bit == 0 { 0 } else { self.bits.as_words()[word] & ((1 << bit) - 1) };
+        self.table()[word] + partial.count_ones() as usize
+    }
+
+    /// Returns the number of unset bits in the range `0..index`.
+    ///
+    /// # Panics
+    /// Panics if `index` is greater than the length of the vector.
+    #[track_caller]
+    pub fn rank0(&self, index: I) -> usize {
+        index.as_usize() - self.rank1(index)
+    }
+
+    /// Returns the index of the set bit preceded by exactly `k` other set
+    /// bits, or [`None`] if the vector contains no more than `k` set bits.
+    pub fn select1(&self, k: usize) -> Option<I> {
+        if k >= self.count_ones() { return None; }
+
+        let table = self.table();
+        let word = table.partition_point(|&ones| ones <= k) - 1;
+        let bit = select_in_word(self.bits.as_words()[word], k - table[word]);
+        Some(I::from_usize(word * WORD_BITS + bit))
+    }
+
+    /// Returns the index of the unset bit preceded by exactly `k` other unset
+    /// bits, or [`None`] if the vector contains no more than `k` unset bits.
+    pub fn select0(&self, k: usize) -> Option<I> {
+        if k >= self.count_zeros() { return None; }
+
+        let table = self.table();
+        let zeros_before = |w: usize| w * WORD_BITS - table[w];
+        let words = words_required(self.len());
+
+        let (mut lo, mut hi) = (0, words);
+        while hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            if zeros_before(mid) <= k { lo = mid; } else { hi = mid; }
+        }
+
+        let bit = select_in_word(!self.bits.as_words()[lo], k - zeros_before(lo));
+        Some(I::from_usize(lo * WORD_BITS + bit))
+    }
+
+    /// Consumes the index, returning the bit vector and the table storage.
+    pub fn into_inner(self) -> (BitVec<S, I>, R) {
+        (self.bits, self.table)
+    }
+}
+
+impl<S: Storage<ArrayLayout<usize>>, R: Storage<ArrayLayout<usize>>, I: Capacity> Debug for RankSelect<S, R, I> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RankSelect")
+            .field("bits", &self.bits)
+            .field("count_ones", &self.count_ones())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::{InlineBitVec, InlineVec};
+    use core::mem::MaybeUninit;
+
+    #[test]
+    fn randomized_rank_select() {
+        use rand::{rngs::SmallRng, RngCore, SeedableRng};
+        let mut rng = SmallRng::from_seed(crate::test_utils::RNG_SEED);
+
+        for _ in 0..50 {
+            let mut bits = InlineBitVec::<8, u16>::new();
+            let mut reference = InlineVec::<bool, 512>::new();
+            let len = rng.next_u32() as usize % (bits.capacity() + 1);
+            let density = rng.next_u32() % 8;
+
+            for _ in 0..len {
+                let bit = rng.next_u32() % 8 < density;
+                bits.push(bit);
+                reference.push(bit);
+            }
+
+            assert!(bits.iter().eq(reference.iter().copied()));
+            assert_eq!(bits.count_ones(), reference.iter().filter(|&&b| b).count());
+
+            let (a, b) = (rng.next_u32() as usize % (len + 1), rng.next_u32() as usize % (len + 1));
+            let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+            let slice = bits.slice(lo as u16..hi as u16);
+            assert!(slice.iter().eq(reference[lo..hi].iter().copied()));
+            assert_eq!(slice.count_ones(), reference[lo..hi].iter().filter(|&&b| b).count());
+
+            let index = bits.into_rank_select([MaybeUninit::uninit(); rank_table_len(512)]);
+            let (mut ones, mut zeros) = (0, 0);
+            for (i, &bit) in reference.iter().enumerate() {
+                assert_eq!(index.rank1(i as u16), ones);
+                assert_eq!(index.rank0(i as u16), zeros);
+
+                if bit {
+                    assert_eq!(index.select1(ones), Some(i as u16));
+                    ones += 1;
+                } else {
+                    assert_eq!(index.select0(zeros), Some(i as u16));
+                    zeros += 1;
+                }
+            }
+
+            assert_eq!(index.rank1(len as u16), ones);
+            assert_eq!(index.select1(ones), None);
+            assert_eq!(index.select0(zeros), None);
+        }
+    }
+
+    #[test]
+    fn truncate_clears_unused_bits() {
+        let mut bits = InlineBitVec::<2>::new();
+        bits.extend(core::iter::repeat(true).take(100));
+        bits.truncate(10);
+        assert_eq!(bits.count_ones(), 10);
+
+        bits.extend(core::iter::repeat(false).take(90));
+        assert_eq!(bits.len(), 100);
+        assert_eq!(bits.count_ones(), 10);
+    }
+}
@@ -0,0 +1,2221 @@
+//! An ordered map based on a [B-Tree](https://en.wikipedia.org/wiki/B-tree)
+//! whose nodes are allocated from a [`DirectPool`].
+
+use core::borrow::Borrow;
+use core::fmt::Debug;
+use core::iter::FusedIterator;
+use core::mem::MaybeUninit;
+use core::ops::{Bound, RangeBounds};
+
+use crate::collections::pool::direct::{DirectPool, DirectPoolLayout};
+use crate::collections::pool::{DefaultHandle, Handle};
+use crate::storage::Storage;
+
+use self::Entry::{Occupied, Vacant};
+
+const B: usize = 6;
+const CAPACITY: usize = 2 * B - 1;
+const MIN_LEN: usize = B - 1;
+
+/// A fixed-size node of a [`BTreeMap`], holding up to eleven key-value pairs.
+///
+/// Leaf and internal nodes share the same representation, so the storage
+/// required for each node does not depend on its position in the tree.
+pub struct Node<K, V, H> {
+    parent: Option<H>,
+    parent_idx: usize,
+    len: usize,
+    keys: [MaybeUninit<K>; CAPACITY],
+    values: [MaybeUninit<V>; CAPACITY],
+    edges: [Option<H>; CAPACITY + 1],
+}
+
+impl<K, V, H: Handle> Node<K, V, H> {
+    fn new() -> Self {
+        Node {
+            parent: None,
+            parent_idx: 0,
+            len: 0,
+            keys: unsafe { MaybeUninit::uninit().assume_init() },
+            values: unsafe { MaybeUninit::uninit().assume_init() },
+            edges: [None; CAPACITY + 1],
+        }
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline(always)]
+    fn is_leaf(&self) -> bool {
+        self.edges[0].is_none()
+    }
+
+    #[inline(always)]
+    fn keys(&self) -> &[K] {
+        unsafe { core::slice::from_raw_parts(self.keys.as_ptr().cast(), self.len()) }
+    }
+
+    #[inline(always)]
+    fn edge(&self, idx: usize) -> H {
+        self.edges[idx].expect("internal node is missing an edge")
+    }
+
+    #[inline(always)]
+    fn keys_ptr(&mut self) -> *mut K {
+        self.keys.as_mut_ptr().cast()
+    }
+
+    #[inline(always)]
+    fn values_ptr(&mut self) -> *mut V {
+        self.values.as_mut_ptr().cast()
+    }
+}
+
+/// The [`LayoutSpec`](crate::storage::LayoutSpec) for a [`BTreeMap`].
+pub type BTreeMapLayout<K, V, H = DefaultHandle> = DirectPoolLayout<Node<K, V, H>, H>;
+
+type Position<H> = (H, usize);
+
+enum SearchResult<H> {
+    Found(H, usize),
+    GoDown(H, usize),
+    Empty,
+}
+
+/// An ordered map based on a [B-Tree](https://en.wikipedia.org/wiki/B-tree).
+///
+/// The tree's nodes are allocated from a [`DirectPool`] using the provided
+/// storage, so the capacity of a `BTreeMap` is measured in nodes rather than
+/// entries. Each node holds between five and eleven key-value pairs, except
+/// for the root, which may hold fewer. An insertion is only attempted if the
+/// pool has a free node for every full node it may have to split, plus one
+/// for a new root if the root is full. Each of those full nodes holds at
+/// least six more entries than the minimum, so a map with a node capacity
+/// of *n* can always hold at least 5 × *n* entries, and at most 11 × *n*
+/// entries.
+///
+/// Looking up, inserting and removing a key all take *O*(log *n*) time, with
+/// insertion and removal only moving a bounded number of entries, as opposed
+/// to the *O*(*n*) shifting required by a
+/// [`SortedMap`](crate::collections::sorted_map::SortedMap). In return, each
+/// node reserves space for the maximum number of entries and child edges.
+///
+/// Insertion only fails if the node pool is exhausted, in which case the map
+/// is left unchanged; see [`try_insert`](BTreeMap::try_insert).
+///
+/// It is required that the keys implement the [`Ord`] trait, although this can
+/// frequently be achieved using `#[derive(PartialEq, Eq, PartialOrd, Ord)]`.
+///
+/// It is a logic error for a key to be modified in such a way that its ordering
+/// relative to any other key, as determined by the `Ord` trait, changes while
+/// it is in the map. This is normally only possible through [`Cell`](core::cell::Cell),
+/// [`RefCell`](core::cell::RefCell), global state, I/O, or unsafe code. The
+/// behavior resulting from such a logic error is not specified, but will not
+/// result in undefined behavior. This could include panics, incorrect results,
+/// aborts, memory leaks, and non-termination.
+pub struct BTreeMap<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle = DefaultHandle> {
+    nodes: DirectPool<Node<K, V, H>, S, H>,
+    root: Option<H>,
+    len: usize,
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> From<S> for BTreeMap<K, V, S, H> {
+    /// Constructs a new, empty `BTreeMap`, allocating nodes from the given storage.
+    ///
+    /// # Panics
+    /// Panics if the storage's capacity cannot be indexed by the handle type `H`.
+    fn from(buf: S) -> Self {
+        BTreeMap { nodes: DirectPool::from(buf), root: None, len: 0 }
+    }
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> BTreeMap<K, V, S, H> {
+    #[inline(always)]
+    fn node(&self, handle: H) -> &Node<K, V, H> {
+        self.nodes.get(handle).expect("invalid node handle")
+    }
+
+    #[inline(always)]
+    fn node_ptr(&mut self, handle: H) -> *mut Node<K, V, H> {
+        self.nodes.get_mut(handle).expect("invalid node handle")
+    }
+
+    /// Returns the maximum number of nodes the map can allocate.
+    #[inline]
+    pub fn node_capacity(&self) -> usize {
+        self.nodes.capacity()
+    }
+
+    /// Returns the number of nodes currently allocated by the map.
+    #[inline]
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the number of entries in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map contains no entries, or `false` otherwise.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Clears the map, removing all key-value pairs.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    ///
+    /// let mut map = InlineBTreeMap::<u32, &'static str, 4>::new();
+    /// map.insert(1, "a");
+    /// map.clear();
+    /// assert!(map.is_empty());
+    /// assert_eq!(map.node_count(), 0);
+    /// ```
+    pub fn clear(&mut self) {
+        self.root = None;
+        self.len = 0;
+
+        for node in self.nodes.values_mut() {
+            let len = node.len();
+            node.len = 0;
+
+            unsafe {
+                let keys = node.keys_ptr();
+                let values = node.values_ptr();
+                for i in 0..len {
+                    keys.add(i).drop_in_place();
+                    values.add(i).drop_in_place();
+                }
+            }
+        }
+
+        self.nodes.clear();
+    }
+
+    fn first_leaf_position(&self, mut node: H) -> Position<H> {
+        loop {
+            let n = self.node(node);
+            if n.is_leaf() { return (node, 0); }
+            node = n.edge(0);
+        }
+    }
+
+    fn last_leaf_position(&self, mut node: H) -> Position<H> {
+        loop {
+            let n = self.node(node);
+            if n.is_leaf() { return (node, n.len() - 1); }
+            node = n.edge(n.len());
+        }
+    }
+
+    fn first_position(&self) -> Option<Position<H>> {
+        self.root.map(|root| self.first_leaf_position(root))
+    }
+
+    fn last_position(&self) -> Option<Position<H>> {
+        self.root.map(|root| self.last_leaf_position(root))
+    }
+
+    fn next_position(&self, (node, idx): Position<H>) -> Option<Position<H>> {
+        let n = self.node(node);
+        if !n.is_leaf() {
+            return Some(self.first_leaf_position(n.edge(idx + 1)));
+        }
+
+        let (mut node, mut idx) = (node, idx + 1);
+        loop {
+            let n = self.node(node);
+            if idx < n.len() { return Some((node, idx)); }
+            idx = n.parent_idx;
+            node = n.parent?;
+        }
+    }
+
+    fn prev_position(&self, (node, idx): Position<H>) -> Option<Position<H>> {
+        let n = self.node(node);
+        if !n.is_leaf() {
+            return Some(self.last_leaf_position(n.edge(idx)));
+        }
+
+        let (mut node, mut idx) = (node, idx);
+        loop {
+            if idx > 0 { return Some((node, idx - 1)); }
+            let n = self.node(node);
+            idx = n.parent_idx;
+            node = n.parent?;
+        }
+    }
+
+    #[inline(always)]
+    fn key_at(&self, (node, idx): Position<H>) -> &K {
+        &self.node(node).keys()[idx]
+    }
+
+    #[inline(always)]
+    fn entry_at(&self, (node, idx): Position<H>) -> (&K, &V) {
+        let n = self.node(node);
+        unsafe { (&*n.keys[idx].as_ptr(), &*n.values[idx].as_ptr()) }
+    }
+
+    /// Returns a pointer to the value at the given position.
+    ///
+    /// # Safety
+    /// Callers must ensure that the position refers to an initialized entry.
+    #[inline(always)]
+    unsafe fn entry_at_mut(&mut self, (node, idx): Position<H>) -> (*mut K, *mut V) {
+        let n = self.node_ptr(node);
+        ((*n).keys_ptr().add(idx), (*n).values_ptr().add(idx))
+    }
+
+    /// Returns the number of nodes that must be allocated to insert a new
+    /// entry into the given leaf.
+    fn nodes_required_for_insertion(&self, leaf: Option<H>) -> usize {
+        let mut required = 0;
+        let mut next = leaf;
+        while let Some(node) = next {
+            let n = self.node(node);
+            if n.len() < CAPACITY { return required; }
+            required += 1;
+            next = n.parent;
+        }
+
+        // Either the map is empty, or the root must be split:
+        required + 1
+    }
+
+    #[inline(always)]
+    fn can_allocate(&self, required: usize) -> bool {
+        self.nodes.capacity() - self.nodes.len() >= required
+    }
+
+    /// Sets the parent pointers of the given node's children in the range `from..=to`.
+    unsafe fn correct_children(&mut self, node: H, from: usize, to: usize) {
+        let n = self.node_ptr(node);
+        for i in from..=to {
+            if let Some(child) = (*n).edges[i] {
+                let c = self.node_ptr(child);
+                (*c).parent = Some(node);
+                (*c).parent_idx = i;
+            }
+        }
+    }
+
+    /// Inserts a key-value pair and its right edge into a node that is not full.
+    unsafe fn insert_fit(&mut self, node: H, idx: usize, key: K, value: V, edge: Option<H>) -> *mut V {
+        let n = self.node_ptr(node);
+        let len = (*n).len();
+        debug_assert!(len < CAPACITY);
+
+        let keys = (*n).keys_ptr();
+        let values = (*n).values_ptr();
+        core::ptr::copy(keys.add(idx), keys.add(idx + 1), len - idx);
+        core::ptr::copy(values.add(idx), values.add(idx + 1), len - idx);
+        keys.add(idx).write(key);
+        values.add(idx).write(value);
+
+        if edge.is_some() {
+            let edges = (*n).edges.as_mut_ptr();
+            core::ptr::copy(edges.add(idx + 1), edges.add(idx + 2), len - idx);
+            edges.add(idx + 1).write(edge);
+        }
+
+        (*n).len += 1;
+
+        if edge.is_some() {
+            self.correct_children(node, idx + 1, len + 1);
+        }
+
+        values.add(idx)
+    }
+
+    /// Inserts a key-value pair into a leaf, splitting nodes as necessary.
+    /// Returns a pointer to the inserted value.
+    ///
+    /// # Safety
+    /// Callers must ensure that the node pool has sufficient capacity, as
+    /// determined by [`nodes_required_for_insertion`](BTreeMap::nodes_required_for_insertion),
+    /// and that the map remains sorted after the insertion.
+    unsafe fn insert_recursing(&mut self, leaf: H, idx: usize, key: K, value: V) -> *mut V {
+        let (mut node, mut idx, mut key, mut value) = (leaf, idx, key, value);
+        let mut edge = None;
+        let mut result: *mut V = core::ptr::null_mut();
+
+        self.len += 1;
+        loop {
+            if self.node(node).len() < CAPACITY {
+                let ptr = self.insert_fit(node, idx, key, value, edge);
+                return if result.is_null() { ptr } else { result };
+            }
+
+            let right = self.nodes.insert(Node::new());
+            let n = self.node_ptr(node);
+            let r = self.node_ptr(right);
+
+            let right_len = CAPACITY - B;
+            core::ptr::copy_nonoverlapping((*n).keys_ptr().add(B), (*r).keys_ptr(), right_len);
+            core::ptr::copy_nonoverlapping((*n).values_ptr().add(B), (*r).values_ptr(), right_len);
+            let median_key = (*n).keys_ptr().add(B - 1).read();
+            let median_value = (*n).values_ptr().add(B - 1).read();
+
+            if !(*n).is_leaf() {
+                for i in 0..=right_len {
+                    (*r).edges[i] = (*n).edges[B + i].take();
+                }
+            }
+
+            (*n).len = B - 1;
+            (*r).len = right_len;
+            self.correct_children(right, 0, right_len);
+
+            let ptr = if idx < B {
+                self.insert_fit(node, idx, key, value, edge)
+            } else {
+                self.insert_fit(right, idx - B, key, value, edge)
+            };
+
+            if result.is_null() { result = ptr; }
+
+            let n = self.node_ptr(node);
+            if let Some(parent) = (*n).parent {
+                idx = (*n).parent_idx;
+                node = parent;
+                key = median_key;
+                value = median_value;
+                edge = Some(right);
+            } else {
+                let mut root = Node::new();
+                root.keys[0] = MaybeUninit::new(median_key);
+                root.values[0] = MaybeUninit::new(median_value);
+                root.edges[0] = Some(node);
+                root.edges[1] = Some(right);
+                root.len = 1;
+
+                let root = self.nodes.insert(root);
+                self.correct_children(root, 0, 1);
+                self.root = Some(root);
+                return result;
+            }
+        }
+    }
+
+    /// Inserts a key-value pair as the only entry of a new root node.
+    ///
+    /// # Safety
+    /// Callers must ensure that the map is empty and the node pool has space for one node.
+    unsafe fn insert_root(&mut self, key: K, value: V) -> *mut V {
+        debug_assert!(self.root.is_none());
+        let root = self.nodes.insert(Node::new());
+        self.root = Some(root);
+        self.len += 1;
+        self.insert_fit(root, 0, key, value, None)
+    }
+
+    /// Removes and returns the key-value pair at the given position, rebalancing the tree.
+    unsafe fn remove_at(&mut self, (node, idx): Position<H>) -> (K, V) {
+        self.len -= 1;
+
+        let n = self.node_ptr(node);
+        if (*n).is_leaf() {
+            let kv = self.remove_from_leaf(node, idx);
+            self.rebalance(node);
+            return kv;
+        }
+
+        let (leaf, leaf_idx) = self.last_leaf_position((*n).edge(idx));
+        let (pred_key, pred_value) = self.remove_from_leaf(leaf, leaf_idx);
+
+        let n = self.node_ptr(node);
+        let k = (*n).keys_ptr().add(idx).replace(pred_key);
+        let v = (*n).values_ptr().add(idx).replace(pred_value);
+
+        self.rebalance(leaf);
+        (k, v)
+    }
+
+    unsafe fn remove_from_leaf(&mut self, node: H, idx: usize) -> (K, V) {
+        let n = self.node_ptr(node);
+        let new_len = (*n).len() - 1;
+
+        let keys = (*n).keys_ptr();
+        let values = (*n).values_ptr();
+        let k = keys.add(idx).read();
+        let v = values.add(idx).read();
+        core::ptr::copy(keys.add(idx + 1), keys.add(idx), new_len - idx);
+        core::ptr::copy(values.add(idx + 1), values.add(idx), new_len - idx);
+
+        (*n).len = new_len;
+        (k, v)
+    }
+
+    /// Restores the minimum occupancy invariant, starting at the given node
+    /// and working up towards the root.
+    unsafe fn rebalance(&mut self, mut node: H) {
+        loop {
+            let n = self.node_ptr(node);
+            let parent = match (*n).parent {
+                Some(_) if (*n).len() >= MIN_LEN => return,
+                Some(parent) => parent,
+                None => {
+                    if (*n).len == 0 {
+                        let child = (*n).edges[0];
+                        if let Some(child) = child {
+                            (*self.node_ptr(child)).parent = None;
+                        }
+
+                        self.root = child;
+                        self.nodes.remove(node);
+                    }
+                    return;
+                }
+            };
+
+            let idx = (*n).parent_idx;
+            let p = self.node_ptr(parent);
+            let left = if idx > 0 { (*p).edges[idx - 1] } else { None };
+            let right = if idx < (*p).len() { (*p).edges[idx + 1] } else { None };
+
+            if let Some(left) = left {
+                if (*self.node_ptr(left)).len() > MIN_LEN {
+                    self.steal_left(parent, idx - 1, left, node);
+                    return;
+                }
+            }
+
+            if let Some(right) = right {
+                if (*self.node_ptr(right)).len() > MIN_LEN {
+                    self.steal_right(parent, idx, node, right);
+                    return;
+                }
+            }
+
+            match (left, right) {
+                (Some(left), _) => self.merge(parent, idx - 1, left, node),
+                (None, Some(right)) => self.merge(parent, idx, node, right),
+                (None, None) => unreachable!("non-root node without siblings"),
+            }
+
+            node = parent;
+        }
+    }
+
+    /// Moves the last entry of `left` into the parent, and the parent's entry at `kv_idx` into `right`.
+    unsafe fn steal_left(&mut self, parent: H, kv_idx: usize, left: H, right: H) {
+        let p = self.node_ptr(parent);
+        let l = self.node_ptr(left);
+        let r = self.node_ptr(right);
+
+        let left_len = (*l).len() - 1;
+        let right_len = (*r).len();
+
+        let key = (*l).keys_ptr().add(left_len).read();
+        let value = (*l).values_ptr().add(left_len).read();
+        let key = (*p).keys_ptr().add(kv_idx).replace(key);
+        let value = (*p).values_ptr().add(kv_idx).replace(value);
+
+        let keys = (*r).keys_ptr();
+        let values = (*r).values_ptr();
+        core::ptr::copy(keys, keys.add(1), right_len);
+        core::ptr::copy(values, values.add(1), right_len);
+        keys.write(key);
+        values.write(value);
+
+        if !(*r).is_leaf() {
+            let edges = (*r).edges.as_mut_ptr();
+            core::ptr::copy(edges, edges.add(1), right_len + 1);
+            edges.write((*l).edges[left_len + 1].take());
+        }
+
+        (*l).len = left_len;
+        (*r).len = right_len + 1;
+        self.correct_children(right, 0, right_len + 1);
+    }
+
+    /// Moves the first entry of `right` into the parent, and the parent's entry at `kv_idx` into `left`.
+    unsafe fn steal_right(&mut self, parent: H, kv_idx: usize, left: H, right: H) {
+        let p = self.node_ptr(parent);
+        let l = self.node_ptr(left);
+        let r = self.node_ptr(right);
+
+        let left_len = (*l).len();
+        let right_len = (*r).len() - 1;
+
+        let keys = (*r).keys_ptr();
+        let values = (*r).values_ptr();
+        let key = keys.read();
+        let value = values.read();
+        core::ptr::copy(keys.add(1), keys, right_len);
+        core::ptr::copy(values.add(1), values, right_len);
+
+        let key = (*p).keys_ptr().add(kv_idx).replace(key);
+        let value = (*p).values_ptr().add(kv_idx).replace(value);
+        (*l).keys_ptr().add(left_len).write(key);
+        (*l).values_ptr().add(left_len).write(value);
+
+        if !(*r).is_leaf() {
+            let edges = (*r).edges.as_mut_ptr();
+            (*l).edges[left_len + 1] = edges.read();
+            core::ptr::copy(edges.add(1), edges, right_len + 1);
+            (*r).edges[right_len + 1] = None;
+        }
+
+        (*l).len = left_len + 1;
+        (*r).len = right_len;
+        self.correct_children(left, left_len + 1, left_len + 1);
+        self.correct_children(right, 0, right_len);
+    }
+
+    /// Merges the parent's entry at `kv_idx` and all entries of `right` into `left`,
+    /// and deallocates `right`.
+    unsafe fn merge(&mut self, parent: H, kv_idx: usize, left: H, right: H) {
+        let p = self.node_ptr(parent);
+        let l = self.node_ptr(left);
+        let r = self.node_ptr(right);
+
+        let parent_len = (*p).len() - 1;
+        let left_len = (*l).len();
+        let right_len = (*r).len();
+
+        let pkeys = (*p).keys_ptr();
+        let pvalues = (*p).values_ptr();
+        (*l).keys_ptr().add(left_len).write(pkeys.add(kv_idx).read());
+        (*l).values_ptr().add(left_len).write(pvalues.add(kv_idx).read());
+        core::ptr::copy(pkeys.add(kv_idx + 1), pkeys.add(kv_idx), parent_len - kv_idx);
+        core::ptr::copy(pvalues.add(kv_idx + 1), pvalues.add(kv_idx), parent_len - kv_idx);
+
+        let pedges = (*p).edges.as_mut_ptr();
+        core::ptr::copy(pedges.add(kv_idx + 2), pedges.add(kv_idx + 1), parent_len - kv_idx);
+        (*p).edges[parent_len + 1] = None;
+        (*p).len = parent_len;
+
+        core::ptr::copy_nonoverlapping((*r).keys_ptr(), (*l).keys_ptr().add(left_len + 1), right_len);
+        core::ptr::copy_nonoverlapping((*r).values_ptr(), (*l).values_ptr().add(left_len + 1), right_len);
+
+        if !(*r).is_leaf() {
+            for i in 0..=right_len {
+                (*l).edges[left_len + 1 + i] = (*r).edges[i];
+            }
+        }
+
+        (*l).len = left_len + 1 + right_len;
+        self.correct_children(parent, kv_idx + 1, parent_len);
+        self.correct_children(left, left_len + 1, left_len + 1 + right_len);
+        self.nodes.remove(right);
+    }
+
+    /// An iterator visiting all key-value pairs in ascending order of their keys.
+    /// The iterator element type is `(&'a K, &'a V)`.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    ///
+    /// let mut map = InlineBTreeMap::<u32, &'static str, 4>::new();
+    /// map.insert(3, "c");
+    /// map.insert(1, "a");
+    /// map.insert(2, "b");
+    ///
+    /// let mut iter = map.iter();
+    /// assert_eq!(iter.next(), Some((&1, &"a")));
+    /// assert_eq!(iter.next_back(), Some((&3, &"c")));
+    /// assert_eq!(iter.next(), Some((&2, &"b")));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, K, V, S, H> {
+        Iter { base: Range { map: self, front: self.first_position(), back: self.last_position() }, len: self.len }
+    }
+
+    /// An iterator visiting all key-value pairs in ascending order of their
+    /// keys, with mutable references to the values. The iterator element type
+    /// is `(&'a K, &'a mut V)`.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    ///
+    /// let mut map = InlineBTreeMap::<&'static str, u32, 4>::new();
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    /// map.insert("c", 3);
+    ///
+    /// for (_, val) in map.iter_mut() {
+    ///     *val *= 2;
+    /// }
+    ///
+    /// assert_eq!(map.get("a"), Some(&2));
+    /// assert_eq!(map.get("b"), Some(&4));
+    /// assert_eq!(map.get("c"), Some(&6));
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V, S, H> {
+        let (front, back, len) = (self.first_position(), self.last_position(), self.len);
+        IterMut { base: RangeMut { map: self, front, back }, len }
+    }
+
+    /// An iterator visiting all keys in ascending order.
+    /// The iterator element type is `&'a K`.
+    pub fn keys(&self) -> Keys<'_, K, V, S, H> {
+        Keys { base: self.iter() }
+    }
+
+    /// An iterator visiting all values in ascending order of their keys.
+    /// The iterator element type is `&'a V`.
+    pub fn values(&self) -> Values<'_, K, V, S, H> {
+        Values { base: self.iter() }
+    }
+
+    /// An iterator visiting all values mutably, in ascending order of their keys.
+    /// The iterator element type is `&'a mut V`.
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V, S, H> {
+        ValuesMut { base: self.iter_mut() }
+    }
+
+    /// Returns the key-value pair with the smallest key in the map, or [`None`] if the map is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    ///
+    /// let mut map = InlineBTreeMap::<u32, &'static str, 4>::new();
+    /// assert_eq!(map.first_key_value(), None);
+    ///
+    /// map.insert(2, "b");
+    /// map.insert(1, "a");
+    /// assert_eq!(map.first_key_value(), Some((&1, &"a")));
+    /// ```
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.first_position().map(|pos| self.entry_at(pos))
+    }
+
+    /// Returns the key-value pair with the greatest key in the map, or [`None`] if the map is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    ///
+    /// let mut map = InlineBTreeMap::<u32, &'static str, 4>::new();
+    /// assert_eq!(map.last_key_value(), None);
+    ///
+    /// map.insert(2, "b");
+    /// map.insert(1, "a");
+    /// assert_eq!(map.last_key_value(), Some((&2, &"b")));
+    /// ```
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.last_position().map(|pos| self.entry_at(pos))
+    }
+
+    /// Removes and returns the key-value pair with the smallest key in the map,
+    /// or [`None`] if the map is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    ///
+    /// let mut map = InlineBTreeMap::<u32, &'static str, 4>::new();
+    /// map.insert(1, "a");
+    /// map.insert(2, "b");
+    ///
+    /// assert_eq!(map.pop_first(), Some((1, "a")));
+    /// assert_eq!(map.pop_first(), Some((2, "b")));
+    /// assert_eq!(map.pop_first(), None);
+    /// ```
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        let pos = self.first_position()?;
+        unsafe { Some(self.remove_at(pos)) }
+    }
+
+    /// Removes and returns the key-value pair with the greatest key in the map,
+    /// or [`None`] if the map is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    ///
+    /// let mut map = InlineBTreeMap::<u32, &'static str, 4>::new();
+    /// map.insert(1, "a");
+    /// map.insert(2, "b");
+    ///
+    /// assert_eq!(map.pop_last(), Some((2, "b")));
+    /// assert_eq!(map.pop_last(), Some((1, "a")));
+    /// assert_eq!(map.pop_last(), None);
+    /// ```
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        let pos = self.last_position()?;
+        unsafe { Some(self.remove_at(pos)) }
+    }
+
+    /// Creates a consuming iterator visiting all keys in ascending order.
+    /// The map cannot be used after calling this. The iterator element type is `K`.
+    pub fn into_keys(self) -> IntoKeys<K, V, S, H> {
+        IntoKeys { base: self.into_iter() }
+    }
+
+    /// Creates a consuming iterator visiting all values in ascending order of
+    /// their keys. The map cannot be used after calling this. The iterator
+    /// element type is `V`.
+    pub fn into_values(self) -> IntoValues<K, V, S, H> {
+        IntoValues { base: self.into_iter() }
+    }
+}
+
+impl<K: Ord, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> BTreeMap<K, V, S, H> {
+    fn search<Q>(&self, key: &Q) -> SearchResult<H>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut next = self.root;
+        while let Some(node) = next {
+            let n = self.node(node);
+            match n.keys().binary_search_by(|k| k.borrow().cmp(key)) {
+                Ok(idx) => return SearchResult::Found(node, idx),
+                Err(idx) if n.is_leaf() => return SearchResult::GoDown(node, idx),
+                Err(idx) => next = Some(n.edge(idx)),
+            }
+        }
+
+        SearchResult::Empty
+    }
+
+    fn find<Q>(&self, key: &Q) -> Option<Position<H>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.search(key) {
+            SearchResult::Found(node, idx) => Some((node, idx)),
+            _ => None,
+        }
+    }
+
+    /// Returns the position of the first entry whose key lies within the given bound.
+    fn lower_bound<Q>(&self, bound: Bound<&Q>) -> Option<Position<H>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut node = self.root?;
+        let mut candidate = None;
+
+        loop {
+            let n = self.node(node);
+            let idx = match bound {
+                Bound::Included(q) => n.keys().partition_point(|k| k.borrow() < q),
+                Bound::Excluded(q) => n.keys().partition_point(|k| k.borrow() <= q),
+                Bound::Unbounded => 0,
+            };
+
+            if idx < n.len() { candidate = Some((node, idx)); }
+            if n.is_leaf() { return candidate; }
+            node = n.edge(idx);
+        }
+    }
+
+    /// Returns the position of the last entry whose key lies within the given bound.
+    fn upper_bound<Q>(&self, bound: Bound<&Q>) -> Option<Position<H>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut node = self.root?;
+        let mut candidate = None;
+
+        loop {
+            let n = self.node(node);
+            let idx = match bound {
+                Bound::Included(q) => n.keys().partition_point(|k| k.borrow() <= q),
+                Bound::Excluded(q) => n.keys().partition_point(|k| k.borrow() < q),
+                Bound::Unbounded => n.len(),
+            };
+
+            if idx > 0 { candidate = Some((node, idx - 1)); }
+            if n.is_leaf() { return candidate; }
+            node = n.edge(idx);
+        }
+    }
+
+    fn range_positions<Q, R>(&self, range: &R) -> (Option<Position<H>>, Option<Position<H>>)
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        match (range.start_bound(), range.end_bound()) {
+            (Bound::Excluded(s), Bound::Excluded(e)) if s == e => {
+                panic!("range start and end are equal and excluded in BTreeMap")
+            }
+            (Bound::Included(s) | Bound::Excluded(s), Bound::Included(e) | Bound::Excluded(e)) if s > e => {
+                panic!("range start is greater than range end in BTreeMap")
+            }
+            _ => {}
+        }
+
+        let front = self.lower_bound(range.start_bound());
+        let back = self.upper_bound(range.end_bound());
+        match (front, back) {
+            (Some(f), Some(b)) if self.key_at(f) <= self.key_at(b) => (front, back),
+            _ => (None, None),
+        }
+    }
+
+    /// Gets the given key's corresponding [`Entry`] in the map for in-place manipulation.
+    ///
+    /// # Panics
+    /// Panics if the map does not contain the given key, and inserting it
+    /// could require allocating more nodes than are available.
+    /// See [`try_entry`](BTreeMap::try_entry) for a checked version that never panics.
+    #[track_caller]
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, S, H> {
+        self.try_entry(key).ok().expect("node pool is exhausted")
+    }
+
+    /// Gets the given key's corresponding [`Entry`] in the map for in-place manipulation.
+    ///
+    /// Returns [`Err(key)`] if the map does not contain the given key, and
+    /// inserting it could require allocating more nodes than are available.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    ///
+    /// let mut letters = InlineBTreeMap::<char, u32, 8>::new();
+    ///
+    /// for ch in "i am, therefore i'm coded".chars() {
+    ///     let counter = letters.try_entry(ch).unwrap().or_insert(0);
+    ///     *counter += 1;
+    /// }
+    ///
+    /// assert_eq!(letters.get(&'a'), Some(&1));
+    /// assert_eq!(letters.get(&'e'), Some(&4));
+    /// assert_eq!(letters.get(&'i'), Some(&2));
+    /// assert_eq!(letters.get(&'o'), Some(&2));
+    /// assert_eq!(letters.get(&'u'), None);
+    /// ```
+    pub fn try_entry(&mut self, key: K) -> Result<Entry<'_, K, V, S, H>, K> {
+        let (leaf, idx) = match self.search(&key) {
+            SearchResult::Found(node, idx) => {
+                return Ok(Occupied(OccupiedEntry { key, position: (node, idx), map: self }));
+            }
+            SearchResult::GoDown(leaf, idx) => (Some(leaf), idx),
+            SearchResult::Empty => (None, 0),
+        };
+
+        if !self.can_allocate(self.nodes_required_for_insertion(leaf)) {
+            return Err(key);
+        }
+
+        Ok(Vacant(VacantEntry { key, leaf, idx, map: self }))
+    }
+
+    /// Returns a reference to the value associated with the given key.
+    ///
+    /// The key may be any borrowed form of the map's key type,
+    /// but `Ord` on the borrowed form *must* match that for the key type.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    ///
+    /// let mut map = InlineBTreeMap::<u32, &'static str, 4>::new();
+    /// map.insert(1, "a");
+    ///
+    /// assert_eq!(map.get(&1), Some(&"a"));
+    /// assert_eq!(map.get(&2), None);
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key).map(|pos| self.entry_at(pos).1)
+    }
+
+    /// Returns the key-value pair corresponding to the given key.
+    ///
+    /// The key may be any borrowed form of the map's key type,
+    /// but `Ord` on the borrowed form *must* match that for the key type.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    ///
+    /// let mut map = InlineBTreeMap::<u32, &'static str, 4>::new();
+    /// map.insert(1, "a");
+    ///
+    /// assert_eq!(map.get_key_value(&1), Some((&1, &"a")));
+    /// assert_eq!(map.get_key_value(&2), None);
+    /// ```
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key).map(|pos| self.entry_at(pos))
+    }
+
+    /// Returns `true` if the map contains a value for the given key, or `false` otherwise.
+    ///
+    /// The key may be any borrowed form of the map's key type,
+    /// but `Ord` on the borrowed form *must* match that for the key type.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    ///
+    /// let mut map = InlineBTreeMap::<u32, &'static str, 4>::new();
+    /// map.insert(1, "a");
+    ///
+    /// assert_eq!(map.contains_key(&1), true);
+    /// assert_eq!(map.contains_key(&2), false);
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Returns a mutable reference to the value associated with the given key.
+    ///
+    /// The key may be any borrowed form of the map's key type,
+    /// but `Ord` on the borrowed form *must* match that for the key type.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    ///
+    /// let mut map = InlineBTreeMap::<u32, &'static str, 4>::new();
+    /// map.insert(1, "a");
+    ///
+    /// if let Some(x) = map.get_mut(&1) {
+    ///     *x = "b";
+    /// }
+    ///
+    /// assert_eq!(map[&1], "b");
+    /// ```
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let pos = self.find(key)?;
+        unsafe { Some(&mut *self.entry_at_mut(pos).1) }
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the map did not have this key present, [`None`] is returned.
+    ///
+    /// If the map did have this key present, the value is updated, and the
+    /// old value is returned. The key is not updated though; this matters for
+    /// types that can be `==` without being identical.
+    ///
+    /// # Panics
+    /// Panics if the key is not present and the node pool is exhausted. See
+    /// [`try_insert`](BTreeMap::try_insert) for a checked version that never panics.
+    #[inline]
+    #[track_caller]
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.try_insert(key, value).expect("node pool is exhausted")
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the map did not have this key present, `Ok(None)` is returned if the
+    /// key-value pair is inserted, or [`CapacityError`](crate::CapacityError)
+    /// if inserting it could require allocating more nodes than are available,
+    /// in which case both the key and the value are dropped, and the map is
+    /// left unchanged. Use the [`Entry` API](BTreeMap::try_entry) to retain
+    /// ownership in this case.
+    ///
+    /// If the map did have this key present, the value is updated, and the
+    /// old value is returned. The key is not updated though; this matters for
+    /// types that can be `==` without being identical.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    ///
+    /// let mut map = InlineBTreeMap::<u32, u32, 1>::new();
+    /// assert_eq!(map.try_insert(0, 37).ok(), Some(None));
+    /// assert_eq!(map.try_insert(0, 42).ok(), Some(Some(37)));
+    ///
+    /// // A single node holds up to eleven entries:
+    /// for i in 1..11 {
+    ///     assert!(map.try_insert(i, i).is_ok());
+    /// }
+    ///
+    /// assert!(map.try_insert(11, 11).is_err());
+    /// assert_eq!(map.len(), 11);
+    /// ```
+    pub fn try_insert(&mut self, key: K, value: V) -> crate::Result<Option<V>> {
+        match self.try_entry(key) {
+            Ok(Occupied(mut entry)) => Ok(Some(entry.insert(value))),
+            Ok(Vacant(entry)) => {
+                entry.insert(value);
+                Ok(None)
+            }
+            Err(_) => crate::CapacityError::new(),
+        }
+    }
+
+    /// Removes a key from the map, returning the value associated with the key
+    /// if it was previously in the map.
+    ///
+    /// The key may be any borrowed form of the map's key type,
+    /// but [`Ord`] on the borrowed form *must* match that for the key type.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    ///
+    /// let mut map = InlineBTreeMap::<u32, &'static str, 4>::new();
+    /// map.insert(1, "a");
+    ///
+    /// assert_eq!(map.remove(&1), Some("a"));
+    /// assert_eq!(map.remove(&1), None);
+    /// ```
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// Removes a key from the map, returning the stored key and associated
+    /// value if the key was previously in the map.
+    ///
+    /// The key may be any borrowed form of the map's key type,
+    /// but [`Ord`] on the borrowed form *must* match that for the key type.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    ///
+    /// let mut map = InlineBTreeMap::<u32, &'static str, 4>::new();
+    /// map.insert(1, "a");
+    ///
+    /// assert_eq!(map.remove_entry(&1), Some((1, "a")));
+    /// assert_eq!(map.remove_entry(&1), None);
+    /// ```
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let pos = self.find(key)?;
+        unsafe { Some(self.remove_at(pos)) }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all key-value pairs `(k, v)` such that `pred(&k, &mut v)`
+    /// returns `false`. The elements are visited in ascending order of their keys.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    ///
+    /// let mut map = InlineBTreeMap::<u32, u32, 8>::new();
+    /// (0..32).for_each(|x| { map.insert(x, x * 10); });
+    /// assert_eq!(map.len(), 32);
+    ///
+    /// map.retain(|&k, _| k % 2 == 0);
+    /// assert_eq!(map.len(), 16);
+    /// assert!(map.keys().all(|k| k % 2 == 0));
+    /// ```
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut pred: F) {
+        let mut pos = self.first_position();
+        while let Some(p) = pos {
+            let keep = unsafe {
+                let (k, v) = self.entry_at_mut(p);
+                pred(&*k, &mut *v)
+            };
+
+            if keep {
+                pos = self.next_position(p);
+            } else {
+                let (k, _) = unsafe { self.remove_at(p) };
+                pos = self.lower_bound(Bound::Excluded(&k));
+            }
+        }
+    }
+
+    /// Constructs a double-ended iterator over a sub-range of entries in the
+    /// map, in ascending order of their keys.
+    ///
+    /// The simplest way is to use the range syntax `min..max`, thus
+    /// `range(min..max)` will yield elements from `min` (inclusive) to
+    /// `max` (exclusive). The range may also be entered as
+    /// `(Bound<T>, Bound<T>)`, so for example `range((Excluded(4), Included(10)))`
+    /// will yield a left-exclusive, right-inclusive range from 4 to 10.
+    ///
+    /// # Panics
+    /// Panics if range `start > end`, or if range `start == end` and both
+    /// bounds are `Excluded`.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    /// use core::ops::Bound::Included;
+    ///
+    /// let mut map = InlineBTreeMap::<u32, &'static str, 4>::new();
+    /// map.insert(3, "a");
+    /// map.insert(5, "b");
+    /// map.insert(8, "c");
+    ///
+    /// for (&key, &value) in map.range((Included(&4), Included(&8))) {
+    ///     println!("{}: {}", key, value);
+    /// }
+    ///
+    /// assert_eq!(Some((&5, &"b")), map.range(4..).next());
+    /// assert_eq!(Some((&5, &"b")), map.range(..8).next_back());
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, K, V, S, H>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let (front, back) = self.range_positions(&range);
+        Range { map: self, front, back }
+    }
+
+    /// Constructs a mutable double-ended iterator over a sub-range of entries
+    /// in the map, in ascending order of their keys.
+    ///
+    /// See [`range`](BTreeMap::range) for details on how the range is specified.
+    ///
+    /// # Panics
+    /// Panics if range `start > end`, or if range `start == end` and both
+    /// bounds are `Excluded`.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    ///
+    /// let mut map = InlineBTreeMap::<&'static str, u32, 4>::new();
+    /// map.insert("Alice", 0);
+    /// map.insert("Bob", 0);
+    /// map.insert("Carol", 0);
+    /// map.insert("Cheryl", 0);
+    ///
+    /// for (_, balance) in map.range_mut("B".."Cheryl") {
+    ///     *balance += 100;
+    /// }
+    ///
+    /// assert_eq!(map["Alice"], 0);
+    /// assert_eq!(map["Bob"], 100);
+    /// assert_eq!(map["Carol"], 100);
+    /// assert_eq!(map["Cheryl"], 0);
+    /// ```
+    pub fn range_mut<Q, R>(&mut self, range: R) -> RangeMut<'_, K, V, S, H>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let (front, back) = self.range_positions(&range);
+        RangeMut { map: self, front, back }
+    }
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> Drop for BTreeMap<K, V, S, H> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<K: Debug, V: Debug, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> Debug for BTreeMap<K, V, S, H> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<Q, K, V, S, H> core::ops::Index<&'_ Q> for BTreeMap<K, V, S, H>
+where
+    Q: Ord + ?Sized,
+    K: Ord + Borrow<Q>,
+    S: Storage<BTreeMapLayout<K, V, H>>,
+    H: Handle,
+{
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+impl<K, V, S, H> Extend<(K, V)> for BTreeMap<K, V, S, H>
+where
+    K: Ord,
+    S: Storage<BTreeMapLayout<K, V, H>>,
+    H: Handle,
+{
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        let iter = iter.into_iter();
+        iter.for_each(move |(k, v)| { self.insert(k, v); });
+    }
+}
+
+impl<'a, K, V, S, H> Extend<(&'a K, &'a V)> for BTreeMap<K, V, S, H>
+where
+    K: Clone + Ord,
+    V: Clone,
+    S: Storage<BTreeMapLayout<K, V, H>>,
+    H: Handle,
+{
+    fn extend<T: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: T) {
+        let iter = iter.into_iter();
+        iter.for_each(|(k, v)| {
+            self.insert(k.clone(), v.clone());
+        });
+    }
+}
+
+impl<K, V, S1, H1, S2, H2> PartialEq<BTreeMap<K, V, S2, H2>> for BTreeMap<K, V, S1, H1>
+where
+    K: PartialEq,
+    V: PartialEq,
+    S1: Storage<BTreeMapLayout<K, V, H1>>,
+    S2: Storage<BTreeMapLayout<K, V, H2>>,
+    H1: Handle,
+    H2: Handle,
+{
+    fn eq(&self, other: &BTreeMap<K, V, S2, H2>) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<K: Eq, V: Eq, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> Eq for BTreeMap<K, V, S, H> {}
+
+impl<K: Clone, V: Clone, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> BTreeMap<K, V, S, H> {
+    /// Clones the subtree rooted at `src` into `dst`, preserving its shape,
+    /// and returns the handle of the cloned root.
+    fn clone_subtree_into<S2: Storage<BTreeMapLayout<K, V, H>>>(&self, src: H, dst: &mut BTreeMap<K, V, S2, H>) -> H {
+        let src_node = self.node(src);
+        let handle = dst.nodes.insert(Node::new());
+
+        for i in 0..src_node.len() {
+            let (k, v) = self.entry_at((src, i));
+            let d = dst.node_ptr(handle);
+            unsafe {
+                (*d).keys_ptr().add(i).write(k.clone());
+                (*d).values_ptr().add(i).write(v.clone());
+                (*d).len += 1;
+            }
+            dst.len += 1;
+        }
+
+        if !src_node.is_leaf() {
+            for i in 0..=src_node.len() {
+                let child = self.clone_subtree_into(src_node.edge(i), dst);
+                let d = dst.node_ptr(handle);
+                unsafe { (*d).edges[i] = Some(child); }
+            }
+
+            unsafe { dst.correct_children(handle, 0, src_node.len()); }
+        }
+
+        handle
+    }
+
+    fn clone_into<S2: Storage<BTreeMapLayout<K, V, H>>>(&self, dst: &mut BTreeMap<K, V, S2, H>) {
+        debug_assert!(dst.is_empty() && dst.node_capacity() >= self.node_count());
+        if let Some(root) = self.root {
+            let root = self.clone_subtree_into(root, dst);
+            dst.root = Some(root);
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+impl<K, V, H: Handle> crate::collections::AllocBTreeMap<K, V, H> {
+    /// Constructs a new, empty [`AllocBTreeMap`](crate::collections::AllocBTreeMap)
+    /// with space for the specified number of nodes.
+    ///
+    /// # Panics
+    /// Panics if the specified capacity cannot be indexed by the handle type `H`.
+    pub fn with_node_capacity(node_capacity: usize) -> Self {
+        Self::from(crate::storage::AllocStorage::with_capacity(node_capacity))
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+impl<K: Clone, V: Clone, H: Handle> Clone for crate::collections::AllocBTreeMap<K, V, H> {
+    fn clone(&self) -> Self {
+        let mut result = Self::with_node_capacity(self.node_capacity());
+        self.clone_into(&mut result);
+        result
+    }
+}
+
+impl<K, V, H: Handle, const N: usize> crate::collections::InlineBTreeMap<K, V, N, H> {
+    /// Constructs a new, empty [`InlineBTreeMap`](crate::collections::InlineBTreeMap).
+    pub fn new() -> Self {
+        BTreeMap { nodes: DirectPool::new(), root: None, len: 0 }
+    }
+}
+
+impl<K, V, H: Handle, const N: usize> Default for crate::collections::InlineBTreeMap<K, V, N, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clone, V: Clone, H: Handle, const N: usize> Clone for crate::collections::InlineBTreeMap<K, V, N, H> {
+    fn clone(&self) -> Self {
+        let mut result = Self::new();
+        self.clone_into(&mut result);
+        result
+    }
+}
+
+/// A view into an occupied entry in a [`BTreeMap`]. It is part of the [`Entry`] enum.
+pub struct OccupiedEntry<'a, K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> {
+    key: K,
+    position: Position<H>,
+    map: &'a mut BTreeMap<K, V, S, H>,
+}
+
+impl<'a, K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> OccupiedEntry<'a, K, V, S, H> {
+    /// Gets a reference to the key used to create the entry.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Take the ownership of the key and value from the map.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    /// use coca::collections::btree_map::Entry;
+    ///
+    /// let mut map = InlineBTreeMap::<&'static str, u32, 4>::new();
+    ///
+    /// map.entry("foobar").or_insert(12);
+    /// assert_eq!(map.get("foobar"), Some(&12));
+    ///
+    /// if let Entry::Occupied(o) = map.entry("foobar") {
+    ///     o.remove_entry();
+    /// }
+    ///
+    /// assert_eq!(map.contains_key("foobar"), false);
+    /// ```
+    pub fn remove_entry(self) -> (K, V) {
+        unsafe { self.map.remove_at(self.position) }
+    }
+
+    /// Gets a reference to the value in the entry.
+    pub fn get(&self) -> &V {
+        self.map.entry_at(self.position).1
+    }
+
+    /// Gets a mutable reference to the value in the entry.
+    ///
+    /// If you need a reference to the value which may outlive the `Entry`,
+    /// see [`into_mut`](OccupiedEntry::into_mut).
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    /// use coca::collections::btree_map::Entry;
+    ///
+    /// let mut map = InlineBTreeMap::<&'static str, u32, 4>::new();
+    ///
+    /// map.entry("foobar").or_insert(12);
+    /// assert_eq!(map.get("foobar"), Some(&12));
+    ///
+    /// if let Entry::Occupied(mut o) = map.entry("foobar") {
+    ///     *o.get_mut() += 10;
+    ///     assert_eq!(*o.get(), 22);
+    ///
+    ///     // You can use the same Entry multiple times:
+    ///     *o.get_mut() += 2;
+    /// }
+    ///
+    /// assert_eq!(map.get("foobar"), Some(&24));
+    /// ```
+    pub fn get_mut(&mut self) -> &mut V {
+        unsafe { &mut *self.map.entry_at_mut(self.position).1 }
+    }
+
+    /// Converts the `OccupiedEntry` into a mutable reference to the value
+    /// in the entry with a lifetime bound to the map itself.
+    ///
+    /// If you need multiple references to the `OccupiedEntry`,
+    /// see [`get_mut`](OccupiedEntry::get_mut).
+    pub fn into_mut(self) -> &'a mut V {
+        unsafe { &mut *self.map.entry_at_mut(self.position).1 }
+    }
+
+    /// Sets the value of the entry, and returns the entry's old value.
+    pub fn insert(&mut self, value: V) -> V {
+        core::mem::replace(self.get_mut(), value)
+    }
+
+    /// Takes the value out of the entry, and returns it.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    /// use coca::collections::btree_map::Entry;
+    ///
+    /// let mut map = InlineBTreeMap::<&'static str, u32, 4>::new();
+    ///
+    /// map.entry("foobar").or_insert(12);
+    /// assert_eq!(map.get("foobar"), Some(&12));
+    ///
+    /// if let Entry::Occupied(o) = map.entry("foobar") {
+    ///     assert_eq!(o.remove(), 12);
+    /// }
+    ///
+    /// assert_eq!(map.contains_key("foobar"), false);
+    /// ```
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Replaces the entry, returning the old key-value pair. The new key in the map will be the key used to create the entry.
+    pub fn replace_entry(self, value: V) -> (K, V) {
+        unsafe {
+            let (k, v) = self.map.entry_at_mut(self.position);
+            let k = k.replace(self.key);
+            let v = v.replace(value);
+            (k, v)
+        }
+    }
+
+    /// Replaces the key in the map with the one used to create the entry.
+    ///
+    /// This matters for key types that can be `==` without being identical.
+    pub fn replace_key(self) -> K {
+        unsafe {
+            let (k, _) = self.map.entry_at_mut(self.position);
+            k.replace(self.key)
+        }
+    }
+}
+
+impl<K: Debug, V: Debug, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> Debug for OccupiedEntry<'_, K, V, S, H> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("OccupiedEntry")
+            .field("key", &self.key)
+            .field("value", self.get())
+            .finish()
+    }
+}
+
+/// A view into a vacant entry in a [`BTreeMap`]. It is part of the [`Entry`] enum.
+pub struct VacantEntry<'a, K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> {
+    key: K,
+    leaf: Option<H>,
+    idx: usize,
+    map: &'a mut BTreeMap<K, V, S, H>,
+}
+
+impl<'a, K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> VacantEntry<'a, K, V, S, H> {
+    /// Gets a reference to the key that would be used when inserting through the `VacantEntry`.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Take ownership of the key.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Sets the value of the entry with the `VacantEntry`'s key, and returns a mutable reference to it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        unsafe {
+            let ptr = match self.leaf {
+                Some(leaf) => self.map.insert_recursing(leaf, self.idx, self.key, value),
+                None => self.map.insert_root(self.key, value),
+            };
+
+            &mut *ptr
+        }
+    }
+}
+
+impl<K: Debug, V: Debug, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> Debug for VacantEntry<'_, K, V, S, H> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("VacantEntry").field(&self.key).finish()
+    }
+}
+
+/// A view into a single entry in a map, which may be either vacant or occupied.
+///
+/// This `enum` is constructed from the [`try_entry`](BTreeMap::try_entry) method on [`BTreeMap`].
+pub enum Entry<'a, K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> {
+    /// An occupied entry.
+    Occupied(OccupiedEntry<'a, K, V, S, H>),
+    /// A vacant entry.
+    Vacant(VacantEntry<'a, K, V, S, H>),
+}
+
+impl<'a, K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> Entry<'a, K, V, S, H> {
+    /// Ensures a value is in the entry by inserting the `default` if empty,
+    /// and returns a mutable reference to the value in the entry.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    ///
+    /// let mut map = InlineBTreeMap::<&'static str, u32, 4>::new();
+    ///
+    /// map.entry("foobar").or_insert(3);
+    /// assert_eq!(map.get("foobar"), Some(&3));
+    ///
+    /// *map.entry("foobar").or_insert(5) *= 2;
+    /// assert_eq!(map.get("foobar"), Some(&6));
+    /// ```
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Occupied(entry) => entry.into_mut(),
+            Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the `default`
+    /// function if empty, and returns a mutable reference to the value in the entry.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Occupied(entry) => entry.into_mut(),
+            Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting, if empty, the result of the default function.
+    /// This method allows for generating key-derived values for insertion by providing the default
+    /// function a reference to the key that was moved during the `.entry(key)` method call.
+    ///
+    /// The reference to the moved key is provided so that cloning or copying the key is
+    /// unnecessary, unlike with `.or_insert_with(|| ... )`.
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Occupied(entry) => entry.into_mut(),
+            Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+        }
+    }
+
+    /// Returns a reference to the key used to create the entry.
+    pub fn key(&self) -> &K {
+        match *self {
+            Occupied(ref entry) => entry.key(),
+            Vacant(ref entry) => entry.key(),
+        }
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any
+    /// potential inserts into the map.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineBTreeMap;
+    ///
+    /// let mut map = InlineBTreeMap::<&'static str, u32, 4>::new();
+    ///
+    /// map.entry("foobar")
+    ///     .and_modify(|v| { *v += 1 })
+    ///     .or_insert(37);
+    /// assert_eq!(map.get("foobar"), Some(&37));
+    ///
+    /// map.entry("foobar")
+    ///     .and_modify(|v| { *v += 1 })
+    ///     .or_insert(42);
+    /// assert_eq!(map.get("foobar"), Some(&38));
+    /// ```
+    #[must_use]
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        match self {
+            Occupied(mut entry) => {
+                f(entry.get_mut());
+                Occupied(entry)
+            },
+            Vacant(entry) => Vacant(entry),
+        }
+    }
+}
+
+impl<'a, K, V: Default, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> Entry<'a, K, V, S, H> {
+    /// Ensures a value is in the entry by inserting the default value if empty,
+    /// and returns a mutable reference to the value in the entry.
+    pub fn or_default(self) -> &'a mut V {
+        match self {
+            Occupied(entry) => entry.into_mut(),
+            Vacant(entry) => entry.insert(V::default())
+        }
+    }
+}
+
+impl<K: Debug, V: Debug, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> Debug for Entry<'_, K, V, S, H> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Vacant(ref v) => f.debug_tuple("Entry").field(v).finish(),
+            Occupied(ref o) => f.debug_tuple("Entry").field(o).finish(),
+        }
+    }
+}
+
+/// An iterator over a sub-range of entries in a [`BTreeMap`].
+///
+/// This `struct` is created by the [`range`](BTreeMap::range) method on `BTreeMap`.
+/// See its documentation for more.
+pub struct Range<'a, K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> {
+    map: &'a BTreeMap<K, V, S, H>,
+    front: Option<Position<H>>,
+    back: Option<Position<H>>,
+}
+
+impl<'a, K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> Iterator for Range<'a, K, V, S, H> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pos = self.front?;
+        if self.front == self.back {
+            self.front = None;
+            self.back = None;
+        } else {
+            self.front = self.map.next_position(pos);
+        }
+
+        Some(self.map.entry_at(pos))
+    }
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> DoubleEndedIterator for Range<'_, K, V, S, H> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let pos = self.back?;
+        if self.front == self.back {
+            self.front = None;
+            self.back = None;
+        } else {
+            self.back = self.map.prev_position(pos);
+        }
+
+        Some(self.map.entry_at(pos))
+    }
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> FusedIterator for Range<'_, K, V, S, H> {}
+
+/// A mutable iterator over a sub-range of entries in a [`BTreeMap`].
+///
+/// This `struct` is created by the [`range_mut`](BTreeMap::range_mut) method on `BTreeMap`.
+/// See its documentation for more.
+pub struct RangeMut<'a, K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> {
+    map: &'a mut BTreeMap<K, V, S, H>,
+    front: Option<Position<H>>,
+    back: Option<Position<H>>,
+}
+
+impl<'a, K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> Iterator for RangeMut<'a, K, V, S, H> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pos = self.front?;
+        if self.front == self.back {
+            self.front = None;
+            self.back = None;
+        } else {
+            self.front = self.map.next_position(pos);
+        }
+
+        unsafe {
+            let (k, v) = self.map.entry_at_mut(pos);
+            Some((&*k, &mut *v))
+        }
+    }
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> DoubleEndedIterator for RangeMut<'_, K, V, S, H> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let pos = self.back?;
+        if self.front == self.back {
+            self.front = None;
+            self.back = None;
+        } else {
+            self.back = self.map.prev_position(pos);
+        }
+
+        unsafe {
+            let (k, v) = self.map.entry_at_mut(pos);
+            Some((&*k, &mut *v))
+        }
+    }
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> FusedIterator for RangeMut<'_, K, V, S, H> {}
+
+/// An iterator over the entries of a [`BTreeMap`].
+///
+/// This `struct` is created by the [`iter`](BTreeMap::iter) method on `BTreeMap`.
+/// See its documentation for more.
+pub struct Iter<'a, K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> {
+    base: Range<'a, K, V, S, H>,
+    len: usize,
+}
+
+impl<'a, K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> Iterator for Iter<'a, K, V, S, H> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.base.next()?;
+        self.len -= 1;
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> DoubleEndedIterator for Iter<'_, K, V, S, H> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let result = self.base.next_back()?;
+        self.len -= 1;
+        Some(result)
+    }
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> ExactSizeIterator for Iter<'_, K, V, S, H> {}
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> FusedIterator for Iter<'_, K, V, S, H> {}
+
+impl<'a, K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> IntoIterator for &'a BTreeMap<K, V, S, H> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V, S, H>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A mutable iterator over the entries of a [`BTreeMap`].
+///
+/// This `struct` is created by the [`iter_mut`](BTreeMap::iter_mut) method on `BTreeMap`.
+/// See its documentation for more.
+pub struct IterMut<'a, K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> {
+    base: RangeMut<'a, K, V, S, H>,
+    len: usize,
+}
+
+impl<'a, K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> Iterator for IterMut<'a, K, V, S, H> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.base.next()?;
+        self.len -= 1;
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> DoubleEndedIterator for IterMut<'_, K, V, S, H> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let result = self.base.next_back()?;
+        self.len -= 1;
+        Some(result)
+    }
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> ExactSizeIterator for IterMut<'_, K, V, S, H> {}
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> FusedIterator for IterMut<'_, K, V, S, H> {}
+
+impl<'a, K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> IntoIterator for &'a mut BTreeMap<K, V, S, H> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V, S, H>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// An iterator over the keys of a [`BTreeMap`].
+///
+/// This `struct` is created by the [`keys`](BTreeMap::keys) method on `BTreeMap`.
+/// See its documentation for more.
+pub struct Keys<'a, K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> {
+    base: Iter<'a, K, V, S, H>,
+}
+
+impl<'a, K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> Iterator for Keys<'a, K, V, S, H> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.base.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.base.size_hint()
+    }
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> DoubleEndedIterator for Keys<'_, K, V, S, H> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.base.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> ExactSizeIterator for Keys<'_, K, V, S, H> {}
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> FusedIterator for Keys<'_, K, V, S, H> {}
+
+/// An iterator over the values of a [`BTreeMap`].
+///
+/// This `struct` is created by the [`values`](BTreeMap::values) method on `BTreeMap`.
+/// See its documentation for more.
+pub struct Values<'a, K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> {
+    base: Iter<'a, K, V, S, H>,
+}
+
+impl<'a, K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> Iterator for Values<'a, K, V, S, H> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.base.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.base.size_hint()
+    }
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> DoubleEndedIterator for Values<'_, K, V, S, H> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.base.next_back().map(|(_, v)| v)
+    }
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> ExactSizeIterator for Values<'_, K, V, S, H> {}
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> FusedIterator for Values<'_, K, V, S, H> {}
+
+/// A mutable iterator over the values of a [`BTreeMap`].
+///
+/// This `struct` is created by the [`values_mut`](BTreeMap::values_mut) method on `BTreeMap`.
+/// See its documentation for more.
+pub struct ValuesMut<'a, K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> {
+    base: IterMut<'a, K, V, S, H>,
+}
+
+impl<'a, K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> Iterator for ValuesMut<'a, K, V, S, H> {
+    type Item = &'a mut V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.base.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.base.size_hint()
+    }
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> DoubleEndedIterator for ValuesMut<'_, K, V, S, H> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.base.next_back().map(|(_, v)| v)
+    }
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> ExactSizeIterator for ValuesMut<'_, K, V, S, H> {}
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> FusedIterator for ValuesMut<'_, K, V, S, H> {}
+
+/// An owning iterator over the entries of a [`BTreeMap`].
+///
+/// This `struct` is created by the [`into_iter`](IntoIterator::into_iter)
+/// method on `BTreeMap` (provided by the [`IntoIterator`] trait). See its
+/// documentation for more.
+pub struct IntoIter<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> {
+    map: BTreeMap<K, V, S, H>,
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> Iterator for IntoIter<K, V, S, H> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.map.pop_first()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.map.len();
+        (len, Some(len))
+    }
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> DoubleEndedIterator for IntoIter<K, V, S, H> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.map.pop_last()
+    }
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> ExactSizeIterator for IntoIter<K, V, S, H> {}
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> FusedIterator for IntoIter<K, V, S, H> {}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> IntoIterator for BTreeMap<K, V, S, H> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V, S, H>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { map: self }
+    }
+}
+
+/// An owning iterator over the keys of a [`BTreeMap`].
+///
+/// This `struct` is created by the [`into_keys`](BTreeMap::into_keys) method on `BTreeMap`.
+/// See its documentation for more.
+pub struct IntoKeys<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> {
+    base: IntoIter<K, V, S, H>,
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> Iterator for IntoKeys<K, V, S, H> {
+    type Item = K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.base.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.base.size_hint()
+    }
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> DoubleEndedIterator for IntoKeys<K, V, S, H> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.base.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> ExactSizeIterator for IntoKeys<K, V, S, H> {}
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> FusedIterator for IntoKeys<K, V, S, H> {}
+
+/// An owning iterator over the values of a [`BTreeMap`].
+///
+/// This `struct` is created by the [`into_values`](BTreeMap::into_values) method on `BTreeMap`.
+/// See its documentation for more.
+pub struct IntoValues<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> {
+    base: IntoIter<K, V, S, H>,
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> Iterator for IntoValues<K, V, S, H> {
+    type Item = V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.base.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.base.size_hint()
+    }
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> DoubleEndedIterator for IntoValues<K, V, S, H> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.base.next_back().map(|(_, v)| v)
+    }
+}
+
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> ExactSizeIterator for IntoValues<K, V, S, H> {}
+impl<K, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle> FusedIterator for IntoValues<K, V, S, H> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::{InlineBTreeMap, InlineSortedMap};
+
+    /// Checks the structural invariants of the tree, returning its height.
+    fn check_invariants<K: Ord, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle>(map: &BTreeMap<K, V, S, H>) {
+        fn check_node<K: Ord, V, S: Storage<BTreeMapLayout<K, V, H>>, H: Handle>(map: &BTreeMap<K, V, S, H>, handle: H, is_root: bool) -> (usize, usize) {
+            let node = map.node(handle);
+            assert!(node.len() <= CAPACITY);
+            assert!(is_root || node.len() >= MIN_LEN);
+            assert!(node.keys().windows(2).all(|w| w[0] < w[1]));
+
+            if node.is_leaf() {
+                assert!(node.edges.iter().all(Option::is_none));
+                return (1, node.len());
+            }
+
+            let mut height = None;
+            let mut count = node.len();
+            for i in 0..=node.len() {
+                let child = node.edge(i);
+                assert_eq!(map.node(child).parent, Some(handle));
+                assert_eq!(map.node(child).parent_idx, i);
+
+                let (h, c) = check_node(map, child, false);
+                assert!(height.map_or(true, |height| height == h));
+                height = Some(h);
+                count += c;
+            }
+
+            assert!(node.edges[node.len() + 1..].iter().all(Option::is_none));
+            (height.unwrap() + 1, count)
+        }
+
+        match map.root {
+            Some(root) => {
+                assert!(map.node(root).parent.is_none());
+                let (_, count) = check_node(map, root, true);
+                assert_eq!(count, map.len());
+            }
+            None => {
+                assert_eq!(map.len(), 0);
+                assert_eq!(map.node_count(), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn randomized_operations() {
+        use crate::test_utils::{DropCounter, Droppable};
+        use rand::{rngs::SmallRng, RngCore, SeedableRng};
+        let mut rng = SmallRng::from_seed(crate::test_utils::RNG_SEED);
+
+        let drop_count = DropCounter::new();
+        let mut inserted = 0;
+
+        let mut map = InlineBTreeMap::<u32, Droppable<u32>, 64>::new();
+        let mut reference = InlineSortedMap::<u32, (), 512>::new();
+
+        for i in 0..20_000 {
+            let key = rng.next_u32() % 512;
+            match rng.next_u32() % 5 {
+                0 | 1 | 2 => {
+                    match map.try_insert(key, drop_count.new_droppable(key)) {
+                        Ok(None) => assert_eq!(reference.insert(key, ()), None),
+                        Ok(Some(old)) => {
+                            assert!(reference.contains_key(&key));
+                            assert_eq!(old.value, key);
+                        }
+                        Err(_) => assert!(!reference.contains_key(&key)),
+                    }
+                    inserted += 1;
+                }
+                3 => {
+                    assert_eq!(map.remove(&key).map(|d| d.value), reference.remove(&key).map(|_| key));
+                }
+                _ => {
+                    let (a, b) = (key, rng.next_u32() % 512);
+                    let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+                    assert!(map.range(lo..hi).map(|(k, _)| *k).eq(reference.range(lo..hi).map(|(k, _)| *k)));
+                    assert!(map.range(lo..=hi).rev().map(|(k, _)| *k).eq(reference.range(lo..=hi).rev().map(|(k, _)| *k)));
+                }
+            }
+
+            if i % 97 == 0 {
+                check_invariants(&map);
+                assert!(map.keys().eq(reference.keys().iter()));
+            }
+        }
+
+        check_invariants(&map);
+        assert_eq!(map.len(), reference.len());
+        assert!(map.iter().all(|(k, v)| *k == v.value));
+
+        let before = map.len();
+        map.retain(|k, _| k % 3 != 0);
+        check_invariants(&map);
+        assert_eq!(map.len(), before - reference.keys().iter().filter(|&&k| k % 3 == 0).count());
+
+        while let Some((k, v)) = if rng.next_u32() % 2 == 0 { map.pop_first() } else { map.pop_last() } {
+            assert_eq!(k, v.value);
+            if map.len() % 13 == 0 { check_invariants(&map); }
+        }
+
+        check_invariants(&map);
+        assert_eq!(drop_count.dropped(), inserted);
+    }
+
+    #[test]
+    fn insertion_fails_atomically_when_pool_is_exhausted() {
+        let mut map = InlineBTreeMap::<u32, u32, 3>::new();
+        let mut count = 0;
+        while map.try_insert(count, count).is_ok() {
+            count += 1;
+        }
+
+        check_invariants(&map);
+        assert_eq!(map.len(), count as usize);
+        assert!(map.keys().copied().eq(0..count));
+
+        // Keys that fit into a non-full leaf can still be inserted:
+        map.remove(&0);
+        assert!(map.try_insert(0, 0).is_ok());
+        check_invariants(&map);
+    }
+
+    #[test]
+    fn documented_capacity_bounds_hold() {
+        use rand::{rngs::SmallRng, RngCore, SeedableRng};
+
+        fn check_bounds<const N: usize>(map: &InlineBTreeMap<u32, u32, N>) {
+            check_invariants(map);
+            assert!(map.len() >= 5 * N, "pool of {} nodes ran out at {} entries", N, map.len());
+            assert!(map.len() <= 11 * N);
+        }
+
+        fn fill<I: Iterator<Item = u32>, const N: usize>(keys: I) {
+            let mut map = InlineBTreeMap::<u32, u32, N>::new();
+            for key in keys {
+                if map.try_insert(key, key).is_err() { break; }
+            }
+            check_bounds(&map);
+        }
+
+        fn churn<const N: usize>(rng: &mut SmallRng) {
+            // Repeatedly filling the map and then thinning it out leaves
+            // many nodes at minimum occupancy when the pool runs out:
+            let mut map = InlineBTreeMap::<u32, u32, N>::new();
+            for _ in 0..50 {
+                while map.try_insert(rng.next_u32(), 0).is_ok() {}
+                check_bounds(&map);
+
+                let keep = 1 + rng.next_u32() % 4;
+                map.retain(|_, _| rng.next_u32() % 5 < keep);
+            }
+        }
+
+        fn check_all<const N: usize>() {
+            let mut rng = SmallRng::from_seed(crate::test_utils::RNG_SEED);
+            fill::<_, N>(0..);
+            fill::<_, N>((0..u32::MAX).rev());
+            fill::<_, N>((0..).map(|i: u32| i.wrapping_mul(0x9E37_79B9)));
+            fill::<_, N>(core::iter::repeat_with(|| rng.next_u32()));
+            churn::<N>(&mut rng);
+        }
+
+        check_all::<1>();
+        check_all::<2>();
+        check_all::<3>();
+        check_all::<13>();
+        check_all::<16>();
+        check_all::<64>();
+        check_all::<200>();
+    }
+
+    #[test]
+    fn clone_preserves_shape() {
+        let mut map = InlineBTreeMap::<u32, u32, 32>::new();
+        map.extend((0..100).map(|x| (x, x * x)));
+        map.retain(|k, _| k % 4 != 0);
+
+        let copy = map.clone();
+        check_invariants(&copy);
+        assert_eq!(copy.node_count(), map.node_count());
+        assert_eq!(copy, map);
+    }
+}
@@ -7,13 +7,14 @@
 
 use core::{borrow::Borrow, cell::Cell};
 use core::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
+use core::iter::FusedIterator;
 use core::marker::PhantomData;
 use core::mem::MaybeUninit;
+#[cfg(feature = "profile")]
+use core::sync::atomic::{AtomicUsize, Ordering};
 
 use crate::storage::{ArrayLayout, InlineStorage, Storage};
 
-// TODO: wider cache line types!
-
 /// Types that can be used as the cache line type `L` of a [`CacheTable`].
 pub trait CacheLine<K: Eq, V> {
     /// The maximum number of entries that can be cached in a line.
@@ -24,6 +25,17 @@ pub trait CacheLine<K: Eq, V> {
     /// Implementors may assume the provided pointer to be valid and non-null;
     /// they may *not* assume the referenced memory to be initialized.
     unsafe fn init(this: *mut Self);
+    /// Initialize the pointed to cache line to be empty, using `seed` to
+    /// initialize any random state of the eviction policy.
+    /// 
+    /// The default implementation ignores the seed and calls [`init`](CacheLine::init).
+    /// 
+    /// # Safety
+    /// The same requirements as for [`init`](CacheLine::init) apply.
+    unsafe fn init_seeded(this: *mut Self, seed: u32) {
+        let _ = seed;
+        Self::init(this);
+    }
     /// Returns a reference to the value corresponding to the key.
     /// 
     /// The key may be any borrowed form of the cache's key type, but [`Eq`] on
@@ -42,7 +54,50 @@ pub trait CacheLine<K: Eq, V> {
     /// Ensures a value corresponding to the provided key is cached by inserting
     /// the result of the default function if none is found, and returns a reference
     /// to the cached value.
-    fn get_or_insert_with<F: FnOnce(&K) -> V>(&mut self, k: K, default: F) -> &V;
+    fn get_or_insert_with<F: FnOnce(&K) -> V>(&mut self, k: K, default: F) -> &V {
+        self.get_or_insert_with_eviction(k, default, |_, _| {})
+    }
+    /// Ensures a value corresponding to the provided key is cached by inserting
+    /// the result of the default function if none is found, and returns a reference
+    /// to the cached value.
+    /// 
+    /// If the cache line is already full, another key-value pair must be
+    /// evicted from the cache line and passed to `on_evict`.
+    fn get_or_insert_with_eviction<F: FnOnce(&K) -> V, E: FnOnce(K, V)>(&mut self, k: K, default: F, on_evict: E) -> &V;
+    /// Removes a key from the cache line, returning the stored key and value
+    /// if the key was previously cached.
+    /// 
+    /// The key may be any borrowed form of the cache's key type, but [`Eq`] on
+    /// the borrowed form *must* match that of the key type.
+    fn remove<Q: Borrow<K>>(&mut self, k: &Q) -> Option<(K, V)>;
+    /// Removes and returns an arbitrary key-value pair from the cache line,
+    /// or [`None`] if it is empty.
+    fn pop(&mut self) -> Option<(K, V)>;
+    /// Retains only the key-value pairs specified by the predicate.
+    /// 
+    /// In other words, removes all pairs `(k, v)` such that `f(&k, &mut v)`
+    /// returns `false`.
+    fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, f: F);
+    /// Returns references to the key-value pair stored in the slot at the
+    /// specified index, or [`None`] if the slot is unoccupied.
+    /// 
+    /// Every cached key-value pair must be reachable through exactly one index
+    /// less than [`CAPACITY`](CacheLine::CAPACITY). Unlike [`get`](CacheLine::get),
+    /// this must not affect the eviction policy.
+    fn get_index(&self, index: usize) -> Option<(&K, &V)>;
+    /// Returns pointers to the key and value stored in the slot at the
+    /// specified index of the pointed to cache line, or [`None`] if the slot
+    /// is unoccupied.
+    /// 
+    /// See [`get_index`](CacheLine::get_index) for details.
+    /// 
+    /// # Safety
+    /// Callers must ensure `this` is valid for reads and writes.
+    /// 
+    /// Implementors must not create references to the cache line as a whole,
+    /// or to the values in any other slot, so that references previously
+    /// derived from pointers returned for other indices remain valid.
+    unsafe fn get_index_raw(this: *mut Self, index: usize) -> Option<(*const K, *mut V)>;
     /// Clears the cache line, removing all key-value pairs and resetting any
     /// additional state.
     fn clear(&mut self);
@@ -126,7 +181,7 @@ impl<K: Eq, V> CacheLine<K, V> for UnitCache<K, V> {
         evicted
     }
 
-    fn get_or_insert_with<F: FnOnce(&K) -> V>(&mut self, k: K, default: F) -> &V {
+    fn get_or_insert_with_eviction<F: FnOnce(&K) -> V, E: FnOnce(K, V)>(&mut self, k: K, default: F, on_evict: E) -> &V {
         if !self.occupied {
             self.value = MaybeUninit::new(default(&k));
             self.key = MaybeUninit::new(k);
@@ -138,20 +193,56 @@ impl<K: Eq, V> CacheLine<K, V> for UnitCache<K, V> {
             return unsafe { &*self.value.as_ptr() };
         }
 
+        let value = default(&k);
         let key_ptr = self.key.as_mut_ptr();
         let value_ptr = self.value.as_mut_ptr();
 
         unsafe {
-            value_ptr.drop_in_place();
-            value_ptr.write(default(&k));
+            let evicted_key = key_ptr.read();
+            let evicted_value = value_ptr.read();
 
-            key_ptr.drop_in_place();
             key_ptr.write(k);
+            value_ptr.write(value);
 
+            on_evict(evicted_key, evicted_value);
             &*value_ptr
         }
     }
 
+    fn remove<Q: Borrow<K>>(&mut self, k: &Q) -> Option<(K, V)> {
+        if !self.occupied { return None; }
+        let my_key = unsafe { &*self.key.as_ptr() };
+        if my_key == k.borrow() {
+            self.pop()
+        } else {
+            None
+        }
+    }
+
+    fn pop(&mut self) -> Option<(K, V)> {
+        if !self.occupied { return None; }
+        self.occupied = false;
+        unsafe { Some((self.key.as_ptr().read(), self.value.as_ptr().read())) }
+    }
+
+    fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut f: F) {
+        if !self.occupied { return; }
+        let (key, value) = unsafe { (&*self.key.as_ptr(), &mut *self.value.as_mut_ptr()) };
+        if !f(key, value) {
+            self.clear();
+        }
+    }
+
+    fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        if index != 0 || !self.occupied { return None; }
+        unsafe { Some((&*self.key.as_ptr(), &*self.value.as_ptr())) }
+    }
+
+    unsafe fn get_index_raw(this: *mut Self, index: usize) -> Option<(*const K, *mut V)> {
+        if index != 0 || !(*this).occupied { return None; }
+        Some(((*this).key.as_ptr(), (*this).value.as_mut_ptr()))
+    }
+
     fn clear(&mut self) {
         if !self.occupied {
             return;
@@ -207,6 +298,73 @@ macro_rules! get_methods {
     }
 }
 
+macro_rules! removal_methods {
+    () => {
+        fn remove<Q: Borrow<K>>(&mut self, k: &Q) -> Option<(K, V)> {
+            for i in 0..self.len() {
+                let my_key = unsafe { &*self.keys[i].as_ptr() };
+                if my_key == k.borrow() {
+                    return Some(self.swap_remove_slot(i));
+                }
+            }
+
+            None
+        }
+
+        fn pop(&mut self) -> Option<(K, V)> {
+            let len = self.len();
+            (len > 0).then(|| self.swap_remove_slot(len - 1))
+        }
+
+        fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut f: F) {
+            let mut i = 0;
+            while i < self.len() {
+                let (key, value) = unsafe { (&*self.keys[i].as_ptr(), &mut *self.values[i].as_mut_ptr()) };
+                if f(key, value) {
+                    i += 1;
+                } else {
+                    drop(self.swap_remove_slot(i));
+                }
+            }
+        }
+
+        fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+            if index >= self.len() { return None; }
+            unsafe { Some((&*self.keys[index].as_ptr(), &*self.values[index].as_ptr())) }
+        }
+
+        unsafe fn get_index_raw(this: *mut Self, index: usize) -> Option<(*const K, *mut V)> {
+            if index >= Self::len_raw(this) { return None; }
+            Some(((*this).keys[index].as_ptr(), (*this).values[index].as_mut_ptr()))
+        }
+    }
+}
+
+macro_rules! swap_remove_slot {
+    () => {
+        // Moves the last occupied slot into the vacated one, so occupied
+        // slots always form a prefix; `relabel` updates the eviction policy.
+        fn swap_remove_slot(&mut self, i: usize) -> (K, V) {
+            let last = self.len() - 1;
+            let removed = unsafe {(
+                self.keys[i].as_ptr().read(),
+                self.values[i].as_ptr().read()
+            )};
+
+            if i != last {
+                unsafe {
+                    self.keys[i] = MaybeUninit::new(self.keys[last].as_ptr().read());
+                    self.values[i] = MaybeUninit::new(self.values[last].as_ptr().read());
+                }
+                self.relabel(last, i);
+            }
+
+            self.len = last as u8;
+            removed
+        }
+    }
+}
+
 /// A cache storing the two most recently accessed key-value pairs.
 /// 
 /// Intended primarily for use as the [`CacheLine`] type `L` of a [`CacheTable`].
@@ -251,6 +409,11 @@ impl<K, V> LruCache2<K, V> {
         (self.state.get() & 0b11) as usize
     }
 
+    #[inline(always)]
+    unsafe fn len_raw(this: *const Self) -> usize {
+        ((*this).state.get() & 0b11) as usize
+    }
+
     #[inline(always)]
     fn least_recently_used(&self) -> usize {
         1 ^ (self.state.get() >> 2) as usize
@@ -262,6 +425,25 @@ impl<K, V> LruCache2<K, V> {
         debug_assert!(i < len);
         self.state.set(len as u8 | (i << 2) as u8);
     }
+
+    fn swap_remove_slot(&mut self, i: usize) -> (K, V) {
+        let last = self.len() - 1;
+        let removed = unsafe {(
+            self.keys[i].as_ptr().read(),
+            self.values[i].as_ptr().read()
+        )};
+
+        if i != last {
+            unsafe {
+                self.keys[i] = MaybeUninit::new(self.keys[last].as_ptr().read());
+                self.values[i] = MaybeUninit::new(self.values[last].as_ptr().read());
+            }
+        }
+
+        // At most one entry remains, which is trivially the most recently used.
+        self.state.set(last as u8);
+        removed
+    }
 }
 
 impl<K: Eq, V> CacheLine<K, V> for LruCache2<K, V> {
@@ -272,6 +454,7 @@ impl<K: Eq, V> CacheLine<K, V> for LruCache2<K, V> {
     }
 
     get_methods!();
+    removal_methods!();
 
     fn insert(&mut self, k: K, v: V) -> Option<(K, V)> {
         let len = self.len();
@@ -314,7 +497,7 @@ impl<K: Eq, V> CacheLine<K, V> for LruCache2<K, V> {
         }
     }
 
-    fn get_or_insert_with<F: FnOnce(&K) -> V>(&mut self, k: K, default: F) -> &V {
+    fn get_or_insert_with_eviction<F: FnOnce(&K) -> V, E: FnOnce(K, V)>(&mut self, k: K, default: F, on_evict: E) -> &V {
         let len = self.len();
         for i in 0..len {
             let my_key = unsafe { &*self.keys[i].as_ptr() };
@@ -334,14 +517,15 @@ impl<K: Eq, V> CacheLine<K, V> for LruCache2<K, V> {
             let lru = self.least_recently_used();
             self.mark_used(lru);
 
-            unsafe {
-                self.keys[lru].as_mut_ptr().drop_in_place();
-                self.values[lru].as_mut_ptr().drop_in_place();
-            }
+            let (evicted_key, evicted_value) = unsafe {(
+                self.keys[lru].as_ptr().read(),
+                self.values[lru].as_ptr().read()
+            )};
 
             self.keys[lru] = MaybeUninit::new(k);
             self.values[lru] = MaybeUninit::new(value);
 
+            on_evict(evicted_key, evicted_value);
             unsafe { &*self.values[lru].as_ptr() }
         }
     }
@@ -369,137 +553,910 @@ impl<K, V> Drop for LruCache2<K, V> {
     }
 }
 
-/// A map implemented with an array of [`CacheLine`]s indexed by the keys' [`Hash`].
-/// 
-/// The choice of cache line type has several implications for runtime performance,
-/// memory overhead, and caching behavior:
-/// 
-/// * Using [`UnitCache`] requires an additional [`bool`] per cache slot for tracking
-///   occupancy, and results in a direct-mapped cache.
-/// * Using [`LruCache2`] results in a 2-way set-associative cache with a least
-///   recently used eviction policy tracked per cache line with a single [`u8`].
-/// 
-/// Note that the cache's capacity is always an integer multiple of the cache line's
-/// capacity.
-/// 
-/// For `no_std` compatibility, no default hash builder is provided, but when using
-/// [`Hasher`] types implementing [`Default`], the constructors [`new`](CacheTable::new),
-/// and [`with_capacity`](CacheTable::with_capacity)
-/// are provided. Otherwise, use [`with_hasher`](CacheTable::with_hasher),
-/// [`with_capacity_and_hasher`](CacheTable::with_capacity_and_hasher) or
-/// [`Arena::try_cache_with_hasher`](crate::arena::Arena::try_cache_with_hasher).
-pub struct CacheTable<K: Eq, V, S: Storage<ArrayLayout<L>>, L: CacheLine<K, V>, H> {
-    buf: S,
-    hash_builder: H,
-    lines: PhantomData<L>,
-    keys: PhantomData<K>,
-    values: PhantomData<V>,
+macro_rules! associative_line_methods {
+    () => {
+        fn insert(&mut self, k: K, v: V) -> Option<(K, V)> {
+            let len = self.len();
+            for i in 0..len {
+                let my_key = unsafe { &*self.keys[i].as_ptr() };
+                if my_key == k.borrow() {
+                    self.mark_used(i);
+
+                    let evicted = unsafe {(
+                        self.keys[i].as_ptr().read(),
+                        self.values[i].as_ptr().read()
+                    )};
+
+                    self.keys[i] = MaybeUninit::new(k);
+                    self.values[i] = MaybeUninit::new(v);
+
+                    return Some(evicted);
+                }
+            }
+
+            if len < N {
+                self.keys[len] = MaybeUninit::new(k);
+                self.values[len] = MaybeUninit::new(v);
+                self.len = (len + 1) as u8;
+                self.mark_inserted(len);
+                None
+            } else {
+                let victim = self.select_victim();
+
+                let evicted = unsafe {(
+                    self.keys[victim].as_ptr().read(),
+                    self.values[victim].as_ptr().read()
+                )};
+
+                self.keys[victim] = MaybeUninit::new(k);
+                self.values[victim] = MaybeUninit::new(v);
+                self.mark_inserted(victim);
+
+                Some(evicted)
+            }
+        }
+
+        fn get_or_insert_with_eviction<F: FnOnce(&K) -> V, E: FnOnce(K, V)>(&mut self, k: K, default: F, on_evict: E) -> &V {
+            let len = self.len();
+            for i in 0..len {
+                let my_key = unsafe { &*self.keys[i].as_ptr() };
+                if my_key == k.borrow() {
+                    self.mark_used(i);
+                    return unsafe { &*self.values[i].as_ptr() };
+                }
+            }
+
+            let value = default(&k);
+            if len < N {
+                self.keys[len] = MaybeUninit::new(k);
+                self.values[len] = MaybeUninit::new(value);
+                self.len = (len + 1) as u8;
+                self.mark_inserted(len);
+                return unsafe { &*self.values[len].as_ptr() };
+            }
+
+            let victim = self.select_victim();
+            let (evicted_key, evicted_value) = unsafe {(
+                self.keys[victim].as_ptr().read(),
+                self.values[victim].as_ptr().read()
+            )};
+
+            self.keys[victim] = MaybeUninit::new(k);
+            self.values[victim] = MaybeUninit::new(value);
+            self.mark_inserted(victim);
+
+            on_evict(evicted_key, evicted_value);
+            unsafe { &*self.values[victim].as_ptr() }
+        }
+
+        fn clear(&mut self) {
+            for i in 0..self.len() {
+                unsafe {
+                    self.keys[i].as_mut_ptr().drop_in_place();
+                    self.values[i].as_mut_ptr().drop_in_place();
+                }
+            }
+
+            self.len = 0;
+            self.reset_policy();
+        }
+    }
 }
 
-impl<K: Eq, V, S: Storage<ArrayLayout<L>>, L: CacheLine<K, V>, H: Default> From<S> for CacheTable<K, V, S, L, H> {
-    fn from(buf: S) -> Self {
-        let mut result = CacheTable {
-            buf, hash_builder: H::default(), lines: PhantomData, keys: PhantomData, values: PhantomData,
-        };
-        result.init_cache_lines();
-        result
+/// An `N`-way set-associative cache line with a least recently used eviction policy.
+///
+/// The recency order is tracked with one additional byte per entry, so `N`
+/// must be between 1 and 255. Lookups and insertions take *O*(`N`) time,
+/// so this is best suited to small values of `N`; [`LruCache4`] and
+/// [`LruCache8`] are provided for convenience.
+///
+/// Intended primarily for use as the [`CacheLine`] type `L` of a [`CacheTable`].
+///
+/// # Examples
+/// ```
+/// use coca::collections::cache::{LruCacheN, CacheLine};
+/// let mut cache = LruCacheN::<i32, &'static str, 3>::default();
+///
+/// assert!(cache.insert(1, "A").is_none());
+/// assert!(cache.insert(2, "B").is_none());
+/// assert!(cache.insert(3, "C").is_none());
+/// assert_eq!(cache.get(&1), Some(&"A")); // Entry 2 is now least recently used...
+///
+/// assert_eq!(cache.insert(4, "D"), Some((2, "B"))); // ...so it will be evicted first.
+/// assert_eq!(cache.insert(5, "E"), Some((3, "C")));
+/// assert_eq!(cache.get(&1), Some(&"A"));
+/// assert!(cache.get(&2).is_none());
+/// ```
+pub struct LruCacheN<K, V, const N: usize> {
+    keys: [MaybeUninit<K>; N],
+    values: [MaybeUninit<V>; N],
+    // A permutation of all slot indices, from most to least recently used.
+    order: [Cell<u8>; N],
+    len: u8,
+}
+
+/// A 4-way set-associative cache line with a least recently used eviction policy.
+pub type LruCache4<K, V> = LruCacheN<K, V, 4>;
+/// An 8-way set-associative cache line with a least recently used eviction policy.
+pub type LruCache8<K, V> = LruCacheN<K, V, 8>;
+
+impl<K: Eq, V, const N: usize> Default for LruCacheN<K, V, N> {
+    fn default() -> Self {
+        let mut result = MaybeUninit::uninit();
+        unsafe {
+            Self::init(result.as_mut_ptr());
+            result.assume_init()
+        }
     }
 }
 
-impl<K: Eq, V, S: Storage<ArrayLayout<L>>, L: CacheLine<K, V>, H> CacheTable<K, V, S, L, H> {
-    fn init_cache_lines(&mut self) {
-        let line_ptr = self.buf.get_mut_ptr().cast::<L>();
-        for i in 0..self.buf.capacity() {
-            unsafe { L::init(line_ptr.add(i)); }
+impl<K, V, const N: usize> LruCacheN<K, V, N> {
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    #[inline(always)]
+    unsafe fn len_raw(this: *const Self) -> usize {
+        (*this).len as usize
+    }
+
+    fn mark_used(&self, i: usize) {
+        let mut prev = i as u8;
+        for slot in &self.order {
+            let current = slot.replace(prev);
+            if current == i as u8 { break; }
+            prev = current;
         }
     }
 
-    /// Returns the number of key-value pairs the cache can hold.
     #[inline(always)]
-    pub fn capacity(&self) -> usize {
-        self.buf.capacity() * L::CAPACITY
+    fn mark_inserted(&self, i: usize) {
+        self.mark_used(i);
     }
 
-    fn get_cache_line_for_hash(&self, hash: u64) -> &L {
-        let line_index = hash as usize % self.buf.capacity();
-        let line_ptr = self.buf.get_ptr().cast::<L>();
-        unsafe { &*line_ptr.add(line_index) }
+    #[inline(always)]
+    fn select_victim(&self) -> usize {
+        self.order[N - 1].get() as usize
     }
 
-    fn get_cache_line_for_hash_mut(&mut self, hash: u64) -> &mut L {
-        let line_index = hash as usize % self.buf.capacity();
-        let line_ptr = self.buf.get_mut_ptr().cast::<L>();
-        unsafe { &mut *line_ptr.add(line_index) }
+    fn reset_policy(&mut self) {
+        for (i, slot) in self.order.iter().enumerate() {
+            slot.set(i as u8);
+        }
     }
 
-    /// Clears the cache, removing all key-value pairs.
-    /// 
-    /// # Examples
-    /// ```
-    /// # extern crate rustc_hash;
-    /// use rustc_hash::FxHasher;
-    /// use coca::collections::InlineDirectMappedCache;
-    /// use core::hash::BuildHasherDefault;
-    /// 
-    /// let mut cache = InlineDirectMappedCache::<i32, &'static str, BuildHasherDefault<FxHasher>, 4>::new();
-    /// cache.insert(1, "A");
-    /// cache.clear();
-    /// assert!(cache.get(&1).is_none());
-    /// ```
-    pub fn clear(&mut self) {
-        let num_lines = self.buf.capacity();
-        let line_ptr = self.buf.get_mut_ptr().cast::<L>();
-        for i in 0..num_lines {
-            let line = unsafe { &mut *line_ptr.add(i) };
-            line.clear();
+    fn relabel(&mut self, from: usize, to: usize) {
+        for slot in &mut self.order {
+            let label = slot.get_mut();
+            if *label == from as u8 {
+                *label = to as u8;
+            } else if *label == to as u8 {
+                *label = from as u8;
+            }
         }
     }
+
+    swap_remove_slot!();
 }
 
-impl<K: Eq + Hash, V, S: Storage<ArrayLayout<L>>, L: CacheLine<K, V>, H: BuildHasher> CacheTable<K, V, S, L, H> {
-    /// Constructs a new cache table using the specified storage and hash builder.
-    pub fn from_storage_and_hasher(buf: S, hash_builder: H) -> Self {
-        let mut result = CacheTable {
-            buf, hash_builder, lines: PhantomData, keys: PhantomData, values: PhantomData,
-        };
-        result.init_cache_lines();
-        result
-    }
+impl<K: Eq, V, const N: usize> CacheLine<K, V> for LruCacheN<K, V, N> {
+    const CAPACITY: usize = {
+        assert!(N > 0 && N <= u8::MAX as usize, "LruCacheN requires 0 < N < 256");
+        N
+    };
 
-    fn make_hash(&self, val: &K) -> u64 {
-        let mut state = self.hash_builder.build_hasher();
-        val.hash(&mut state);
-        state.finish()
+    unsafe fn init(this: *mut Self) {
+        let _ = Self::CAPACITY;
+        (*this).len = 0;
+        let order = core::ptr::addr_of_mut!((*this).order).cast::<Cell<u8>>();
+        for i in 0..N {
+            order.add(i).write(Cell::new(i as u8));
+        }
     }
 
-    /// Returns a reference to the value corresponding to the key.
-    /// 
-    /// The key may be any borrowed form of the map's key type, but [`Hash`]
-    /// and [`Eq`] on the borrowed form *must* match those for the key type.
-    /// 
-    /// # Examples
-    /// ```
-    /// # extern crate rustc_hash;
-    /// use rustc_hash::FxHasher;
-    /// use coca::collections::InlineDirectMappedCache;
-    /// use core::hash::BuildHasherDefault;
-    /// 
-    /// let mut cache = InlineDirectMappedCache::<i32, &'static str, BuildHasherDefault<FxHasher>, 4>::new();
-    /// cache.insert(1, "A");
-    /// assert_eq!(cache.get(&1), Some(&"A"));
-    /// assert_eq!(cache.get(&2), None);
-    /// ```
-    pub fn get<Q: Borrow<K>>(&self, k: &Q) -> Option<&V> {
-        let key = k.borrow();
-        let hash = self.make_hash(key);
-        let cache_line = self.get_cache_line_for_hash(hash);
-        cache_line.get(key)
-    }
+    get_methods!();
+    removal_methods!();
+    associative_line_methods!();
+}
 
-    /// Returns a mutable reference to the value corresponding to the key.
-    /// 
-    /// # Examples
-    /// ```
+impl<K, V, const N: usize> Drop for LruCacheN<K, V, N> {
+    fn drop(&mut self) {
+        for i in 0..self.len() {
+            unsafe {
+                self.keys[i].as_mut_ptr().drop_in_place();
+                self.values[i].as_mut_ptr().drop_in_place();
+            }
+        }
+    }
+}
+
+/// An `N`-way set-associative cache line using the CLOCK (or second chance)
+/// eviction policy, an approximation of least recently used eviction.
+///
+/// Each entry has a reference bit that is set whenever it is accessed. When
+/// an entry must be evicted, a clock hand sweeps over the entries, clearing
+/// reference bits until it finds an entry whose bit is already clear. The
+/// reference bits are packed into a single [`u64`], so `N` must be between
+/// 1 and 64.
+///
+/// Intended primarily for use as the [`CacheLine`] type `L` of a [`CacheTable`].
+///
+/// # Examples
+/// ```
+/// use coca::collections::cache::{ClockCache, CacheLine};
+/// let mut cache = ClockCache::<i32, &'static str, 3>::default();
+///
+/// assert!(cache.insert(1, "A").is_none());
+/// assert!(cache.insert(2, "B").is_none());
+/// assert!(cache.insert(3, "C").is_none());
+/// assert_eq!(cache.get(&1), Some(&"A")); // Entry 1 gets a second chance...
+///
+/// assert_eq!(cache.insert(4, "D"), Some((2, "B"))); // ...so entry 2 is evicted instead.
+/// assert_eq!(cache.get(&1), Some(&"A"));
+/// assert_eq!(cache.get(&4), Some(&"D"));
+/// ```
+pub struct ClockCache<K, V, const N: usize> {
+    keys: [MaybeUninit<K>; N],
+    values: [MaybeUninit<V>; N],
+    referenced: Cell<u64>,
+    len: u8,
+    hand: u8,
+}
+
+impl<K: Eq, V, const N: usize> Default for ClockCache<K, V, N> {
+    fn default() -> Self {
+        let mut result = MaybeUninit::uninit();
+        unsafe {
+            Self::init(result.as_mut_ptr());
+            result.assume_init()
+        }
+    }
+}
+
+impl<K, V, const N: usize> ClockCache<K, V, N> {
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    #[inline(always)]
+    unsafe fn len_raw(this: *const Self) -> usize {
+        (*this).len as usize
+    }
+
+    #[inline(always)]
+    fn mark_used(&self, i: usize) {
+        self.referenced.set(self.referenced.get() | 1 << i);
+    }
+
+    #[inline(always)]
+    fn mark_inserted(&self, i: usize) {
+        self.referenced.set(self.referenced.get() & !(1 << i));
+    }
+
+    fn select_victim(&mut self) -> usize {
+        let mut referenced = self.referenced.get();
+        let mut hand = self.hand as usize;
+        while referenced & 1 << hand != 0 {
+            referenced &= !(1 << hand);
+            hand = (hand + 1) % N;
+        }
+
+        self.referenced.set(referenced);
+        self.hand = ((hand + 1) % N) as u8;
+        hand
+    }
+
+    fn reset_policy(&mut self) {
+        self.referenced.set(0);
+        self.hand = 0;
+    }
+
+    fn relabel(&mut self, from: usize, to: usize) {
+        let referenced = self.referenced.get_mut();
+        let from_bit = (*referenced >> from) & 1;
+        *referenced &= !(1 << from | 1 << to);
+        *referenced |= from_bit << to;
+    }
+
+    swap_remove_slot!();
+}
+
+impl<K: Eq, V, const N: usize> CacheLine<K, V> for ClockCache<K, V, N> {
+    const CAPACITY: usize = {
+        assert!(N > 0 && N <= 64, "ClockCache requires 0 < N <= 64");
+        N
+    };
+
+    unsafe fn init(this: *mut Self) {
+        let _ = Self::CAPACITY;
+        (*this).len = 0;
+        (*this).referenced = Cell::new(0);
+        (*this).hand = 0;
+    }
+
+    get_methods!();
+    removal_methods!();
+    associative_line_methods!();
+}
+
+impl<K, V, const N: usize> Drop for ClockCache<K, V, N> {
+    fn drop(&mut self) {
+        for i in 0..self.len() {
+            unsafe {
+                self.keys[i].as_mut_ptr().drop_in_place();
+                self.values[i].as_mut_ptr().drop_in_place();
+            }
+        }
+    }
+}
+
+/// Sources of randomness for the eviction policy of a [`RandomCache`].
+///
+/// Each cache line owns its own generator state. A standalone line initializes
+/// it with [`Default`], while the lines of a [`CacheTable`] are each seeded
+/// with a distinct value using [`from_seed`](CacheRng::from_seed).
+/// Implementing this trait for a wrapper type allows using any random number
+/// generator, including seeded or hardware generators.
+pub trait CacheRng: Default {
+    /// Constructs a new generator from the specified seed.
+    fn from_seed(seed: u32) -> Self;
+    /// Returns the next random number in the sequence.
+    fn next_u32(&mut self) -> u32;
+}
+
+/// A minimal 32-bit xorshift generator, the default [`CacheRng`] of [`RandomCache`].
+///
+/// This is fast and small, but *not* suitable for anything requiring
+/// unpredictable output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct XorShiftRng(u32);
+
+impl XorShiftRng {
+    /// Constructs a new generator from the specified seed.
+    ///
+    /// Because the xorshift algorithm gets stuck at zero, a `seed` of zero
+    /// is replaced with the default seed.
+    pub const fn new(seed: u32) -> Self {
+        XorShiftRng(if seed == 0 { 0x9E37_79B9 } else { seed })
+    }
+}
+
+impl Default for XorShiftRng {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl CacheRng for XorShiftRng {
+    fn from_seed(seed: u32) -> Self {
+        Self::new(seed)
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.0 = x;
+        x
+    }
+}
+
+/// An `N`-way set-associative cache line that evicts a random entry when full.
+///
+/// Random replacement requires no bookkeeping on access, and avoids the
+/// pathological behavior of least recently used eviction on cyclic access
+/// patterns slightly larger than the cache line. The random numbers are taken
+/// from the generator `R`, which defaults to [`XorShiftRng`]. `N` must be
+/// between 1 and 255.
+///
+/// Intended primarily for use as the [`CacheLine`] type `L` of a [`CacheTable`].
+///
+/// # Examples
+/// ```
+/// use coca::collections::cache::{RandomCache, CacheLine, XorShiftRng};
+/// let mut cache = RandomCache::<i32, &'static str, 2>::with_rng(XorShiftRng::new(42));
+///
+/// assert!(cache.insert(1, "A").is_none());
+/// assert!(cache.insert(2, "B").is_none());
+///
+/// let (k, _) = cache.insert(3, "C").unwrap();
+/// assert!(k == 1 || k == 2);
+/// assert!(cache.get(&k).is_none());
+/// assert_eq!(cache.get(&3), Some(&"C"));
+/// ```
+pub struct RandomCache<K, V, const N: usize, R = XorShiftRng> {
+    keys: [MaybeUninit<K>; N],
+    values: [MaybeUninit<V>; N],
+    rng: R,
+    len: u8,
+}
+
+impl<K: Eq, V, const N: usize, R: CacheRng> Default for RandomCache<K, V, N, R> {
+    fn default() -> Self {
+        Self::with_rng(R::default())
+    }
+}
+
+impl<K: Eq, V, const N: usize, R: CacheRng> RandomCache<K, V, N, R> {
+    /// Constructs a new, empty cache line using the specified random number generator.
+    pub fn with_rng(rng: R) -> Self {
+        let mut result = MaybeUninit::<Self>::uninit();
+        unsafe {
+            Self::init(result.as_mut_ptr());
+            (*result.as_mut_ptr()).rng = rng;
+            result.assume_init()
+        }
+    }
+}
+
+#[allow(clippy::unused_self)]
+impl<K, V, const N: usize, R: CacheRng> RandomCache<K, V, N, R> {
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    #[inline(always)]
+    unsafe fn len_raw(this: *const Self) -> usize {
+        (*this).len as usize
+    }
+
+    #[inline(always)]
+    fn mark_used(&self, _: usize) {}
+
+    #[inline(always)]
+    fn mark_inserted(&self, _: usize) {}
+
+    fn select_victim(&mut self) -> usize {
+        self.rng.next_u32() as usize % N
+    }
+
+    #[inline(always)]
+    fn reset_policy(&mut self) {}
+
+    #[inline(always)]
+    fn relabel(&mut self, _: usize, _: usize) {}
+
+    swap_remove_slot!();
+}
+
+impl<K: Eq, V, const N: usize, R: CacheRng> CacheLine<K, V> for RandomCache<K, V, N, R> {
+    const CAPACITY: usize = {
+        assert!(N > 0 && N <= u8::MAX as usize, "RandomCache requires 0 < N < 256");
+        N
+    };
+
+    unsafe fn init(this: *mut Self) {
+        let _ = Self::CAPACITY;
+        (*this).len = 0;
+        core::ptr::addr_of_mut!((*this).rng).write(R::default());
+    }
+
+    unsafe fn init_seeded(this: *mut Self, seed: u32) {
+        let _ = Self::CAPACITY;
+        (*this).len = 0;
+        core::ptr::addr_of_mut!((*this).rng).write(R::from_seed(seed));
+    }
+
+    get_methods!();
+    removal_methods!();
+    associative_line_methods!();
+}
+
+impl<K, V, const N: usize, R> Drop for RandomCache<K, V, N, R> {
+    fn drop(&mut self) {
+        for i in 0..self.len as usize {
+            unsafe {
+                self.keys[i].as_mut_ptr().drop_in_place();
+                self.values[i].as_mut_ptr().drop_in_place();
+            }
+        }
+    }
+}
+
+/// Counters describing the effectiveness of a [`CacheTable`], useful for
+/// choosing its capacity and cache line type.
+/// 
+/// See [`CacheTable::statistics`] for details.
+#[cfg(feature = "profile")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "profile")))]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CacheStatistics {
+    /// The number of lookups that found the requested key.
+    pub hits: usize,
+    /// The number of lookups that did not find the requested key.
+    pub misses: usize,
+    /// The number of key-value pairs inserted, including those replacing
+    /// a previous value for the same key.
+    pub insertions: usize,
+    /// The number of key-value pairs evicted to make room for another.
+    pub evictions: usize,
+}
+
+/// The counters backing [`CacheStatistics`], which are atomic so that enabling
+/// the `profile` feature does not make otherwise `Sync` tables `!Sync`.
+#[cfg(feature = "profile")]
+#[derive(Default)]
+struct StatisticsCounters {
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+    insertions: AtomicUsize,
+    evictions: AtomicUsize,
+}
+
+#[cfg(feature = "profile")]
+impl StatisticsCounters {
+    fn load(&self) -> CacheStatistics {
+        CacheStatistics {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            insertions: self.insertions.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(feature = "profile")]
+#[inline(always)]
+fn update_statistics<F: FnOnce(&mut CacheStatistics)>(stats: &StatisticsCounters, f: F) {
+    let mut delta = CacheStatistics::default();
+    f(&mut delta);
+
+    let counters = [
+        (&stats.hits, delta.hits),
+        (&stats.misses, delta.misses),
+        (&stats.insertions, delta.insertions),
+        (&stats.evictions, delta.evictions),
+    ];
+
+    for (counter, increment) in counters {
+        if increment != 0 {
+            counter.fetch_add(increment, Ordering::Relaxed);
+        }
+    }
+}
+
+/// A map implemented with an array of [`CacheLine`]s indexed by the keys' [`Hash`].
+/// 
+/// The choice of cache line type has several implications for runtime performance,
+/// memory overhead, and caching behavior:
+/// 
+/// * Using [`UnitCache`] requires an additional [`bool`] per cache slot for tracking
+///   occupancy, and results in a direct-mapped cache.
+/// * Using [`LruCache2`] results in a 2-way set-associative cache with a least
+///   recently used eviction policy tracked per cache line with a single [`u8`].
+/// * Using [`LruCacheN`] results in an `N`-way set-associative cache with a least
+///   recently used eviction policy, tracked with an additional [`u8`] per slot.
+/// * Using [`ClockCache`] results in an `N`-way set-associative cache with the
+///   CLOCK eviction policy, which approximates least recently used eviction with
+///   a single reference bit per slot.
+/// * Using [`RandomCache`] results in an `N`-way set-associative cache evicting
+///   entries at random, which requires no bookkeeping on access.
+/// 
+/// Note that the cache's capacity is always an integer multiple of the cache line's
+/// capacity.
+/// 
+/// For `no_std` compatibility, no default hash builder is provided, but when using
+/// [`Hasher`] types implementing [`Default`], the constructors [`new`](CacheTable::new),
+/// and [`with_capacity`](CacheTable::with_capacity)
+/// are provided. Otherwise, use [`with_hasher`](CacheTable::with_hasher),
+/// [`with_capacity_and_hasher`](CacheTable::with_capacity_and_hasher) or
+/// [`Arena::try_cache_with_hasher`](crate::arena::Arena::try_cache_with_hasher).
+pub struct CacheTable<K: Eq, V, S: Storage<ArrayLayout<L>>, L: CacheLine<K, V>, H> {
+    buf: S,
+    hash_builder: H,
+    lines: PhantomData<L>,
+    keys: PhantomData<K>,
+    values: PhantomData<V>,
+    #[cfg(feature = "profile")]
+    stats: StatisticsCounters,
+}
+
+impl<K: Eq, V, S: Storage<ArrayLayout<L>>, L: CacheLine<K, V>, H: Default> From<S> for CacheTable<K, V, S, L, H> {
+    fn from(buf: S) -> Self {
+        let mut result = CacheTable {
+            buf, hash_builder: H::default(), lines: PhantomData, keys: PhantomData, values: PhantomData,
+            #[cfg(feature = "profile")]
+            stats: StatisticsCounters::default(),
+        };
+        result.init_cache_lines(0);
+        result
+    }
+}
+
+/// Derives a distinct seed for each line of a [`CacheTable`] from the table's seed.
+fn line_seed(seed: u32, line: usize) -> u32 {
+    // the line index is spread out by the golden ratio, then mixed with
+    // the finalizer of MurmurHash3; both steps are bijective, so distinct
+    // lines always receive distinct seeds
+    let mut x = (line as u32).wrapping_add(1).wrapping_mul(0x9E37_79B9) ^ seed;
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x85EB_CA6B);
+    x ^= x >> 13;
+    x = x.wrapping_mul(0xC2B2_AE35);
+    x ^ (x >> 16)
+}
+
+impl<K: Eq, V, S: Storage<ArrayLayout<L>>, L: CacheLine<K, V>, H> CacheTable<K, V, S, L, H> {
+    fn init_cache_lines(&mut self, seed: u32) {
+        let line_ptr = self.buf.get_mut_ptr().cast::<L>();
+        for i in 0..self.buf.capacity() {
+            unsafe { L::init_seeded(line_ptr.add(i), line_seed(seed, i)); }
+        }
+    }
+
+    /// Returns the number of key-value pairs the cache can hold.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity() * L::CAPACITY
+    }
+
+    fn get_cache_line_for_hash(&self, hash: u64) -> &L {
+        let line_index = hash as usize % self.buf.capacity();
+        let line_ptr = self.buf.get_ptr().cast::<L>();
+        unsafe { &*line_ptr.add(line_index) }
+    }
+
+    fn get_cache_line_for_hash_mut(buf: &mut S, hash: u64) -> &mut L {
+        let line_index = hash as usize % buf.capacity();
+        let line_ptr = buf.get_mut_ptr().cast::<L>();
+        unsafe { &mut *line_ptr.add(line_index) }
+    }
+
+    /// Clears the cache, removing all key-value pairs.
+    /// 
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineDirectMappedCache;
+    /// use core::hash::BuildHasherDefault;
+    /// 
+    /// let mut cache = InlineDirectMappedCache::<i32, &'static str, BuildHasherDefault<FxHasher>, 4>::new();
+    /// cache.insert(1, "A");
+    /// cache.clear();
+    /// assert!(cache.get(&1).is_none());
+    /// ```
+    pub fn clear(&mut self) {
+        let num_lines = self.buf.capacity();
+        let line_ptr = self.buf.get_mut_ptr().cast::<L>();
+        for i in 0..num_lines {
+            let line = unsafe { &mut *line_ptr.add(i) };
+            line.clear();
+        }
+    }
+
+    fn lines(&self) -> &[L] {
+        unsafe { core::slice::from_raw_parts(self.buf.get_ptr().cast(), self.buf.capacity()) }
+    }
+
+    fn lines_mut(&mut self) -> &mut [L] {
+        unsafe { core::slice::from_raw_parts_mut(self.buf.get_mut_ptr().cast(), self.buf.capacity()) }
+    }
+
+    /// Retains only the key-value pairs specified by the predicate.
+    /// 
+    /// In other words, removes all pairs `(k, v)` such that `f(&k, &mut v)`
+    /// returns `false`.
+    /// 
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::Inline2WayLruCache;
+    /// use core::hash::BuildHasherDefault;
+    /// 
+    /// let mut cache = Inline2WayLruCache::<i32, i32, BuildHasherDefault<FxHasher>, 8>::new();
+    /// for i in 0..8 {
+    ///     cache.insert(i, i * 10);
+    /// }
+    /// 
+    /// cache.retain(|k, _| k % 2 == 0);
+    /// assert!(cache.iter().all(|(k, _)| k % 2 == 0));
+    /// assert!(cache.get(&1).is_none());
+    /// ```
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut f: F) {
+        for line in self.lines_mut() {
+            line.retain(&mut f);
+        }
+    }
+
+    /// Returns an iterator over all cached key-value pairs, in arbitrary order.
+    /// 
+    /// Unlike [`get`](CacheTable::get), this does not count as an access for
+    /// the purposes of the eviction policy.
+    /// 
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::Inline2WayLruCache;
+    /// use core::hash::BuildHasherDefault;
+    /// 
+    /// let mut cache = Inline2WayLruCache::<i32, i32, BuildHasherDefault<FxHasher>, 8>::new();
+    /// cache.insert(1, 10);
+    /// cache.insert(2, 20);
+    /// 
+    /// let mut sum = 0;
+    /// for (k, v) in cache.iter() {
+    ///     assert_eq!(k * 10, *v);
+    ///     sum += v;
+    /// }
+    /// assert_eq!(sum, 30);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, K, V, L> {
+        Iter { lines: self.lines(), line: 0, slot: 0, entries: PhantomData }
+    }
+
+    /// Returns an iterator over all cached key-value pairs, in arbitrary order,
+    /// with mutable references to the values.
+    /// 
+    /// Unlike [`get_mut`](CacheTable::get_mut), this does not count as an access
+    /// for the purposes of the eviction policy.
+    /// 
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::Inline2WayLruCache;
+    /// use core::hash::BuildHasherDefault;
+    /// 
+    /// let mut cache = Inline2WayLruCache::<i32, i32, BuildHasherDefault<FxHasher>, 8>::new();
+    /// cache.insert(1, 10);
+    /// cache.insert(2, 20);
+    /// 
+    /// for (_, v) in cache.iter_mut() {
+    ///     *v += 1;
+    /// }
+    /// assert_eq!(cache.get(&1), Some(&11));
+    /// assert_eq!(cache.get(&2), Some(&21));
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V, L> {
+        let lines = self.lines_mut();
+        IterMut { lines: lines.as_mut_ptr(), len: lines.len(), line: 0, slot: 0, entries: PhantomData }
+    }
+
+    /// Clears the cache, returning all key-value pairs as an iterator.
+    /// 
+    /// If the returned iterator is dropped before being fully consumed, it
+    /// drops the remaining key-value pairs.
+    /// 
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::Inline2WayLruCache;
+    /// use core::hash::BuildHasherDefault;
+    /// 
+    /// let mut cache = Inline2WayLruCache::<i32, i32, BuildHasherDefault<FxHasher>, 8>::new();
+    /// cache.insert(1, 10);
+    /// cache.insert(2, 20);
+    /// 
+    /// let mut drained: Vec<(i32, i32)> = cache.drain().collect();
+    /// drained.sort();
+    /// assert_eq!(drained, [(1, 10), (2, 20)]);
+    /// assert!(cache.iter().next().is_none());
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, K, V, L> {
+        Drain { lines: self.lines_mut(), line: 0, entries: PhantomData }
+    }
+
+    /// Returns the number of hits, misses, insertions and evictions since the
+    /// cache was created or [`reset_statistics`](CacheTable::reset_statistics)
+    /// was last called.
+    /// 
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineDirectMappedCache;
+    /// use core::hash::BuildHasherDefault;
+    /// 
+    /// let mut cache = InlineDirectMappedCache::<i32, &'static str, BuildHasherDefault<FxHasher>, 1>::new();
+    /// cache.insert(1, "A");
+    /// assert_eq!(cache.get(&1), Some(&"A"));
+    /// assert_eq!(cache.get(&2), None);
+    /// cache.get_or_insert_with(2, |_| "B");
+    /// 
+    /// let stats = cache.statistics();
+    /// assert_eq!(stats.hits, 1);
+    /// assert_eq!(stats.misses, 2);
+    /// assert_eq!(stats.insertions, 2);
+    /// assert_eq!(stats.evictions, 1);
+    /// ```
+    #[cfg(feature = "profile")]
+    #[cfg_attr(docs_rs, doc(cfg(feature = "profile")))]
+    pub fn statistics(&self) -> CacheStatistics {
+        self.stats.load()
+    }
+
+    /// Resets all counters returned by [`statistics`](CacheTable::statistics) to zero.
+    #[cfg(feature = "profile")]
+    #[cfg_attr(docs_rs, doc(cfg(feature = "profile")))]
+    pub fn reset_statistics(&mut self) {
+        self.stats = StatisticsCounters::default();
+    }
+}
+
+impl<K: Eq + Hash, V, S: Storage<ArrayLayout<L>>, L: CacheLine<K, V>, H: BuildHasher> CacheTable<K, V, S, L, H> {
+    /// Constructs a new cache table using the specified storage and hash builder.
+    pub fn from_storage_and_hasher(buf: S, hash_builder: H) -> Self {
+        Self::from_storage_hasher_and_seed(buf, hash_builder, 0)
+    }
+
+    /// Constructs a new cache table using the specified storage and hash builder,
+    /// deriving a distinct seed for the eviction policy of each cache line from
+    /// the specified `seed`.
+    /// 
+    /// This only affects cache lines with a randomized eviction policy, such as
+    /// [`RandomCache`]; other constructors behave as if `seed` was zero.
+    /// 
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use coca::collections::cache::{CacheTable, RandomCache};
+    /// use core::{hash::BuildHasherDefault, mem::MaybeUninit};
+    /// use rustc_hash::FxHasher;
+    ///
+    /// type Line = RandomCache<u32, u32, 4>;
+    /// let lines: [MaybeUninit<Line>; 8] = unsafe { MaybeUninit::uninit().assume_init() };
+    /// let mut cache = CacheTable::<u32, u32, _, Line, _>::from_storage_hasher_and_seed(
+    ///     lines, BuildHasherDefault::<FxHasher>::default(), 0x5EED);
+    ///
+    /// for i in 0..100 { cache.insert(i, i); }
+    /// assert_eq!(cache.iter().count(), 32);
+    /// ```
+    pub fn from_storage_hasher_and_seed(buf: S, hash_builder: H, seed: u32) -> Self {
+        let mut result = CacheTable {
+            buf, hash_builder, lines: PhantomData, keys: PhantomData, values: PhantomData,
+            #[cfg(feature = "profile")]
+            stats: StatisticsCounters::default(),
+        };
+        result.init_cache_lines(seed);
+        result
+    }
+
+    #[allow(clippy::manual_hash_one)] // BuildHasher::hash_one requires Rust 1.71
+    fn make_hash(&self, val: &K) -> u64 {
+        let mut state = self.hash_builder.build_hasher();
+        val.hash(&mut state);
+        state.finish()
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    /// 
+    /// The key may be any borrowed form of the map's key type, but [`Hash`]
+    /// and [`Eq`] on the borrowed form *must* match those for the key type.
+    /// 
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineDirectMappedCache;
+    /// use core::hash::BuildHasherDefault;
+    /// 
+    /// let mut cache = InlineDirectMappedCache::<i32, &'static str, BuildHasherDefault<FxHasher>, 4>::new();
+    /// cache.insert(1, "A");
+    /// assert_eq!(cache.get(&1), Some(&"A"));
+    /// assert_eq!(cache.get(&2), None);
+    /// ```
+    pub fn get<Q: Borrow<K>>(&self, k: &Q) -> Option<&V> {
+        let key = k.borrow();
+        let hash = self.make_hash(key);
+        let cache_line = self.get_cache_line_for_hash(hash);
+        let result = cache_line.get(key);
+        #[cfg(feature = "profile")]
+        update_statistics(&self.stats, |s| if result.is_some() { s.hits += 1; } else { s.misses += 1; });
+        result
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    /// 
+    /// # Examples
+    /// ```
     /// # extern crate rustc_hash;
     /// use rustc_hash::FxHasher;
     /// use coca::collections::InlineDirectMappedCache;
@@ -514,8 +1471,11 @@ impl<K: Eq + Hash, V, S: Storage<ArrayLayout<L>>, L: CacheLine<K, V>, H: BuildHa
     pub fn get_mut<Q: Borrow<K>>(&mut self, k: &Q) -> Option<&mut V> {
         let key = k.borrow();
         let hash = self.make_hash(key);
-        let cache_line = self.get_cache_line_for_hash_mut(hash);
-        cache_line.get_mut(key)
+        let cache_line = Self::get_cache_line_for_hash_mut(&mut self.buf, hash);
+        let result = cache_line.get_mut(key);
+        #[cfg(feature = "profile")]
+        update_statistics(&self.stats, |s| if result.is_some() { s.hits += 1; } else { s.misses += 1; });
+        result
     }
 
     /// Inserts a value computed from `f` into the cache if the given key is
@@ -536,9 +1496,85 @@ impl<K: Eq + Hash, V, S: Storage<ArrayLayout<L>>, L: CacheLine<K, V>, H: BuildHa
     /// # assert_eq!(cache.get(&2), Some(&"B"));
     /// ```
     pub fn get_or_insert_with<F: FnOnce(&K) -> V>(&mut self, k: K, f: F) -> &V {
+        self.get_or_insert_with_eviction(k, f, |_, _| {})
+    }
+
+    /// Inserts a value computed from `f` into the cache if the given key is
+    /// not present, then returns a reference to the value in the cache.
+    /// 
+    /// If the cache line corresponding to the key is already full, the evicted
+    /// key-value pair is passed to `on_evict` instead of being dropped, e.g.
+    /// so that modified values can be written back to their source.
+    /// 
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineDirectMappedCache;
+    /// use core::hash::BuildHasherDefault;
+    /// 
+    /// let mut cache = InlineDirectMappedCache::<i32, &'static str, BuildHasherDefault<FxHasher>, 1>::new();
+    /// let mut flushed = None;
+    /// cache.get_or_insert_with_eviction(1, |_| "A", |k, v| flushed = Some((k, v)));
+    /// assert_eq!(flushed, None);
+    /// 
+    /// cache.get_or_insert_with_eviction(2, |_| "B", |k, v| flushed = Some((k, v)));
+    /// assert_eq!(flushed, Some((1, "A")));
+    /// ```
+    pub fn get_or_insert_with_eviction<F: FnOnce(&K) -> V, E: FnOnce(K, V)>(&mut self, k: K, f: F, on_evict: E) -> &V {
         let hash = self.make_hash(&k);
-        let cache_line = self.get_cache_line_for_hash_mut(hash);
-        cache_line.get_or_insert_with(k, f)
+        let cache_line = Self::get_cache_line_for_hash_mut(&mut self.buf, hash);
+
+        #[cfg(feature = "profile")]
+        {
+            let (mut missed, mut evicted) = (false, false);
+            let result = cache_line.get_or_insert_with_eviction(
+                k,
+                |k| { missed = true; f(k) },
+                |k, v| { evicted = true; on_evict(k, v); },
+            );
+
+            update_statistics(&self.stats, |s| {
+                if missed {
+                    s.misses += 1;
+                    s.insertions += 1;
+                } else {
+                    s.hits += 1;
+                }
+
+                if evicted { s.evictions += 1; }
+            });
+
+            result
+        }
+
+        #[cfg(not(feature = "profile"))]
+        cache_line.get_or_insert_with_eviction(k, f, on_evict)
+    }
+
+    /// Removes a key from the cache, returning the value if the key was
+    /// previously cached.
+    /// 
+    /// The key may be any borrowed form of the map's key type, but [`Hash`]
+    /// and [`Eq`] on the borrowed form *must* match those for the key type.
+    /// 
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineDirectMappedCache;
+    /// use core::hash::BuildHasherDefault;
+    /// 
+    /// let mut cache = InlineDirectMappedCache::<i32, &'static str, BuildHasherDefault<FxHasher>, 4>::new();
+    /// cache.insert(1, "A");
+    /// assert_eq!(cache.remove(&1), Some("A"));
+    /// assert_eq!(cache.remove(&1), None);
+    /// ```
+    pub fn remove<Q: Borrow<K>>(&mut self, k: &Q) -> Option<V> {
+        let key = k.borrow();
+        let hash = self.make_hash(key);
+        let cache_line = Self::get_cache_line_for_hash_mut(&mut self.buf, hash);
+        cache_line.remove(key).map(|(_, v)| v)
     }
 
     /// Inserts a key-value pair into the cache.
@@ -559,11 +1595,174 @@ impl<K: Eq + Hash, V, S: Storage<ArrayLayout<L>>, L: CacheLine<K, V>, H: BuildHa
     /// ```
     pub fn insert(&mut self, k: K, v: V) -> Option<(K, V)> {
         let hash = self.make_hash(&k);
-        let cache_line = self.get_cache_line_for_hash_mut(hash);
+        let cache_line = Self::get_cache_line_for_hash_mut(&mut self.buf, hash);
+
+        #[cfg(feature = "profile")]
+        {
+            let result = cache_line.insert(k, v);
+
+            // A replaced entry's key is equal to the newly inserted key, so it
+            // is still present in the cache line, unlike an evicted one's.
+            // Unlike `get`, `get_index` does not affect the eviction policy.
+            let evicted = match &result {
+                Some((evicted, _)) => (0..L::CAPACITY)
+                    .all(|i| !matches!(cache_line.get_index(i), Some((key, _)) if key == evicted)),
+                None => false,
+            };
+
+            update_statistics(&self.stats, |s| {
+                s.insertions += 1;
+                if evicted { s.evictions += 1; }
+            });
+
+            result
+        }
+
+        #[cfg(not(feature = "profile"))]
         cache_line.insert(k, v)
     }
 }
 
+/// An iterator over the entries of a [`CacheTable`].
+/// 
+/// This `struct` is created by the [`iter`](CacheTable::iter) method on
+/// `CacheTable`. See its documentation for more.
+pub struct Iter<'a, K: Eq, V, L: CacheLine<K, V>> {
+    lines: &'a [L],
+    line: usize,
+    slot: usize,
+    entries: PhantomData<(&'a K, &'a V)>,
+}
+
+impl<'a, K: Eq, V, L: CacheLine<K, V>> Iterator for Iter<'a, K, V, L> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.line < self.lines.len() {
+            let line = &self.lines[self.line];
+            while self.slot < L::CAPACITY {
+                self.slot += 1;
+                if let Some(entry) = line.get_index(self.slot - 1) {
+                    return Some(entry);
+                }
+            }
+
+            self.line += 1;
+            self.slot = 0;
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.lines.len() - self.line) * L::CAPACITY - self.slot;
+        (0, Some(remaining))
+    }
+}
+
+impl<K: Eq, V, L: CacheLine<K, V>> FusedIterator for Iter<'_, K, V, L> {}
+
+/// An iterator over the entries of a [`CacheTable`], with mutable references
+/// to the values.
+/// 
+/// This `struct` is created by the [`iter_mut`](CacheTable::iter_mut) method on
+/// `CacheTable`. See its documentation for more.
+pub struct IterMut<'a, K: Eq, V, L: CacheLine<K, V>> {
+    lines: *mut L,
+    len: usize,
+    line: usize,
+    slot: usize,
+    entries: PhantomData<(&'a mut L, &'a K, &'a mut V)>,
+}
+
+impl<'a, K: Eq, V, L: CacheLine<K, V>> Iterator for IterMut<'a, K, V, L> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.line < self.len {
+            while self.slot < L::CAPACITY {
+                self.slot += 1;
+                // SAFETY: each (line, slot) pair is visited only once, and
+                // `get_index_raw` leaves entries in other slots untouched.
+                let entry = unsafe { L::get_index_raw(self.lines.add(self.line), self.slot - 1) };
+                if let Some((key, value)) = entry {
+                    return Some(unsafe { (&*key, &mut *value) });
+                }
+            }
+
+            self.line += 1;
+            self.slot = 0;
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.len - self.line) * L::CAPACITY - self.slot;
+        (0, Some(remaining))
+    }
+}
+
+impl<K: Eq, V, L: CacheLine<K, V>> FusedIterator for IterMut<'_, K, V, L> {}
+
+/// A draining iterator over the entries of a [`CacheTable`].
+/// 
+/// This `struct` is created by the [`drain`](CacheTable::drain) method on
+/// `CacheTable`. See its documentation for more.
+pub struct Drain<'a, K: Eq, V, L: CacheLine<K, V>> {
+    lines: &'a mut [L],
+    line: usize,
+    entries: PhantomData<(K, V)>,
+}
+
+impl<K: Eq, V, L: CacheLine<K, V>> Iterator for Drain<'_, K, V, L> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.line < self.lines.len() {
+            if let Some(entry) = self.lines[self.line].pop() {
+                return Some(entry);
+            }
+
+            self.line += 1;
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some((self.lines.len() - self.line) * L::CAPACITY))
+    }
+}
+
+impl<K: Eq, V, L: CacheLine<K, V>> FusedIterator for Drain<'_, K, V, L> {}
+
+impl<K: Eq, V, L: CacheLine<K, V>> Drop for Drain<'_, K, V, L> {
+    fn drop(&mut self) {
+        for line in &mut self.lines[self.line..] {
+            line.clear();
+        }
+    }
+}
+
+impl<'a, K: Eq, V, S: Storage<ArrayLayout<L>>, L: CacheLine<K, V>, H> IntoIterator for &'a CacheTable<K, V, S, L, H> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V, L>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K: Eq, V, S: Storage<ArrayLayout<L>>, L: CacheLine<K, V>, H> IntoIterator for &'a mut CacheTable<K, V, S, L, H> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V, L>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
 impl<K: Eq, V, S: Storage<ArrayLayout<L>>, L: CacheLine<K, V>, H> Drop for CacheTable<K, V, S, L, H> {
     fn drop(&mut self) {
         self.clear();
@@ -589,9 +1788,11 @@ impl<K: Eq + Hash, V, L: CacheLine<K, V>, H: BuildHasher, const N: usize> CacheT
             hash_builder,
             lines: PhantomData,
             keys: PhantomData,
-            values: PhantomData
+            values: PhantomData,
+            #[cfg(feature = "profile")]
+            stats: StatisticsCounters::default(),
         };
-        result.init_cache_lines();
+        result.init_cache_lines(0);
         result
     }
 }
@@ -613,9 +1814,11 @@ impl<K: Eq + Hash, V, L: CacheLine<K, V>, H: Hasher + Default, const N: usize> C
             hash_builder: BuildHasherDefault::default(),
             lines: PhantomData,
             keys: PhantomData,
-            values: PhantomData
+            values: PhantomData,
+            #[cfg(feature = "profile")]
+            stats: StatisticsCounters::default(),
         };
-        result.init_cache_lines();
+        result.init_cache_lines(0);
         result
     }
 }
@@ -669,4 +1872,247 @@ impl<K: Eq + Hash, V, L: CacheLine<K, V>, H: Hasher + Default> CacheTable<K, V,
         let hash_builder = BuildHasherDefault::default();
         Self::from_storage_and_hasher(buf, hash_builder)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::DropCounter;
+
+    #[test]
+    fn lru_cache_n_matches_reference_model() {
+        use rand::{rngs::SmallRng, RngCore, SeedableRng};
+        let mut rng = SmallRng::from_seed(crate::test_utils::RNG_SEED);
+
+        let mut cache = LruCache8::<u32, u32>::default();
+        let mut model = crate::collections::InlineVec::<u32, 8>::new();
+
+        for _ in 0..1000 {
+            let key = rng.next_u32() % 12;
+            let op = rng.next_u32() % 5;
+            if op == 0 {
+                let expected = model.iter().position(|&k| k == key);
+                assert_eq!(cache.remove(&key), expected.map(|_| (key, key * 2)));
+                if let Some(idx) = expected {
+                    model.remove(idx);
+                }
+            } else if op % 2 == 0 {
+                let expected = model.iter().position(|&k| k == key);
+                assert_eq!(cache.get(&key).copied(), expected.map(|_| key * 2));
+                if let Some(idx) = expected {
+                    model.remove(idx);
+                    model.insert(0, key);
+                }
+            } else {
+                let evicted = cache.insert(key, key * 2);
+                if let Some(idx) = model.iter().position(|&k| k == key) {
+                    assert_eq!(evicted, Some((key, key * 2)));
+                    model.remove(idx);
+                } else if model.is_full() {
+                    let lru = model.pop().unwrap();
+                    assert_eq!(evicted, Some((lru, lru * 2)));
+                } else {
+                    assert_eq!(evicted, None);
+                }
+                model.insert(0, key);
+            }
+        }
+    }
+
+    fn check_line_against_model<L: CacheLine<u32, u32> + Default>() {
+        use rand::{rngs::SmallRng, RngCore, SeedableRng};
+        let mut rng = SmallRng::from_seed(crate::test_utils::RNG_SEED);
+
+        let mut line = L::default();
+        let mut model = crate::collections::InlineVec::<u32, 64>::new();
+
+        for _ in 0..2000 {
+            let key = rng.next_u32() % 16;
+            match rng.next_u32() % 4 {
+                0 | 1 => {
+                    if let Some((evicted, _)) = line.insert(key, key + 1) {
+                        let idx = model.iter().position(|&k| k == evicted).unwrap();
+                        model.swap_remove(idx);
+                    }
+                    model.push(key);
+                }
+                2 => {
+                    let expected = model.iter().position(|&k| k == key);
+                    assert_eq!(line.remove(&key), expected.map(|_| (key, key + 1)));
+                    if let Some(idx) = expected { model.swap_remove(idx); }
+                }
+                _ => {
+                    let threshold = rng.next_u32() % 16;
+                    line.retain(|&k, v| { *v += 1; k < threshold });
+                    line.retain(|_, v| { *v -= 1; true });
+                    model.retain(|&k| k < threshold);
+                }
+            }
+
+            let mut cached = crate::collections::InlineVec::<u32, 64>::new();
+            for i in 0..L::CAPACITY {
+                if let Some((&k, &v)) = line.get_index(i) {
+                    assert_eq!(v, k + 1);
+                    cached.push(k);
+                }
+            }
+
+            cached.sort_unstable();
+            model.sort_unstable();
+            assert_eq!(cached, model);
+        }
+
+        while let Some((k, _)) = line.pop() {
+            let idx = model.iter().position(|&m| m == k).unwrap();
+            model.swap_remove(idx);
+        }
+        assert!(model.is_empty());
+    }
+
+    #[test]
+    fn cache_lines_match_reference_model() {
+        check_line_against_model::<UnitCache<u32, u32>>();
+        check_line_against_model::<LruCache2<u32, u32>>();
+        check_line_against_model::<LruCache8<u32, u32>>();
+        check_line_against_model::<ClockCache<u32, u32, 5>>();
+        check_line_against_model::<RandomCache<u32, u32, 6>>();
+    }
+
+    #[test]
+    fn associative_lines_drop_all_entries() {
+        let counter = DropCounter::new();
+        {
+            let mut lru = LruCache4::<u32, _>::default();
+            let mut clock = ClockCache::<u32, _, 4>::default();
+            let mut random = RandomCache::<u32, _, 4>::default();
+
+            for i in 0..10 {
+                drop(lru.insert(i, counter.new_droppable(())));
+                drop(clock.insert(i, counter.new_droppable(())));
+                drop(random.insert(i, counter.new_droppable(())));
+            }
+            assert_eq!(counter.dropped(), 18);
+
+            lru.get_or_insert_with(10, |_| counter.new_droppable(()));
+            clock.get_or_insert_with(10, |_| counter.new_droppable(()));
+            random.get_or_insert_with(10, |_| counter.new_droppable(()));
+            assert_eq!(counter.dropped(), 21);
+
+            clock.clear();
+            assert_eq!(counter.dropped(), 25);
+            assert!(clock.get(&10).is_none());
+        }
+        assert_eq!(counter.dropped(), 33);
+    }
+
+    #[test]
+    fn random_cache_lines_are_seeded_independently() {
+        #[derive(Default)]
+        struct IdentityHasher(u64);
+        impl Hasher for IdentityHasher {
+            fn finish(&self) -> u64 { self.0 }
+            fn write(&mut self, _: &[u8]) { unimplemented!() }
+            fn write_u32(&mut self, i: u32) { self.0 = u64::from(i); }
+        }
+
+        type Line = RandomCache<u32, u32, 4>;
+        type Table = CacheTable<u32, u32, InlineStorage<Line, 2>, Line, BuildHasherDefault<IdentityHasher>>;
+
+        // Even keys map to the first line and odd keys to the second, so
+        // both lines see the same sequence of insertions:
+        fn evictions(mut cache: Table) -> [[u32; 32]; 2] {
+            let mut evicted = [[0; 32]; 2];
+            for i in 0..36 {
+                for line in 0..2 {
+                    if let Some((k, _)) = cache.insert(2 * i + line, i) {
+                        evicted[line as usize][i as usize - 4] = k / 2;
+                    }
+                }
+            }
+            evicted
+        }
+
+        let [first, second] = evictions(Table::new());
+        assert_ne!(first, second);
+
+        let seeded = |seed| Table::from_storage_hasher_and_seed(
+            unsafe { MaybeUninit::uninit().assume_init() }, BuildHasherDefault::default(), seed);
+        let [first, second] = evictions(seeded(42));
+        assert_ne!(first, second);
+        assert_eq!(evictions(seeded(42)), [first, second]);
+        assert_ne!(evictions(seeded(43)), [first, second]);
+    }
+
+    #[test]
+    #[cfg(feature = "profile")]
+    fn profiling_does_not_affect_insertion() {
+        use rand::{rngs::SmallRng, RngCore, SeedableRng};
+
+        // With a single line, the table must behave exactly like a
+        // standalone line receiving the same sequence of insertions:
+        fn check<L: CacheLine<u32, u32> + Default>() {
+            type Hasher = BuildHasherDefault<rustc_hash::FxHasher>;
+            let mut table = CacheTable::<u32, u32, InlineStorage<L, 1>, L, Hasher>::new();
+            let mut line = L::default();
+            let mut rng = SmallRng::from_seed(crate::test_utils::RNG_SEED);
+
+            let mut evictions = 0;
+            for i in 0..1000 {
+                let k = rng.next_u32() % (L::CAPACITY as u32 * 3);
+                let expected = line.insert(k, i);
+                if matches!(&expected, Some((evicted, _)) if *evicted != k) { evictions += 1; }
+                assert_eq!(table.insert(k, i), expected);
+            }
+
+            let stats = table.statistics();
+            assert_eq!(stats.insertions, 1000);
+            assert_eq!(stats.evictions, evictions);
+            assert_eq!(stats.hits + stats.misses, 0);
+        }
+
+        check::<UnitCache<u32, u32>>();
+        check::<LruCache2<u32, u32>>();
+        check::<LruCache4<u32, u32>>();
+        check::<ClockCache<u32, u32, 4>>();
+
+        // Lookups may affect the eviction policy, so inserting must not
+        // perform any in addition to those done by the line itself:
+        static LOOKUPS: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(Default)]
+        struct CountingLine(LruCache2<u32, u32>);
+        impl CacheLine<u32, u32> for CountingLine {
+            const CAPACITY: usize = 2;
+            unsafe fn init(this: *mut Self) { LruCache2::init(core::ptr::addr_of_mut!((*this).0)); }
+            fn get<Q: Borrow<u32>>(&self, k: &Q) -> Option<&u32> {
+                LOOKUPS.fetch_add(1, Ordering::Relaxed);
+                self.0.get(k)
+            }
+            fn get_mut<Q: Borrow<u32>>(&mut self, k: &Q) -> Option<&mut u32> {
+                LOOKUPS.fetch_add(1, Ordering::Relaxed);
+                self.0.get_mut(k)
+            }
+            fn insert(&mut self, k: u32, v: u32) -> Option<(u32, u32)> { self.0.insert(k, v) }
+            fn get_or_insert_with_eviction<F: FnOnce(&u32) -> u32, E: FnOnce(u32, u32)>(&mut self, k: u32, default: F, on_evict: E) -> &u32 {
+                self.0.get_or_insert_with_eviction(k, default, on_evict)
+            }
+            fn remove<Q: Borrow<u32>>(&mut self, k: &Q) -> Option<(u32, u32)> { self.0.remove(k) }
+            fn pop(&mut self) -> Option<(u32, u32)> { self.0.pop() }
+            fn retain<F: FnMut(&u32, &mut u32) -> bool>(&mut self, f: F) { self.0.retain(f); }
+            fn get_index(&self, index: usize) -> Option<(&u32, &u32)> { self.0.get_index(index) }
+            unsafe fn get_index_raw(this: *mut Self, index: usize) -> Option<(*const u32, *mut u32)> {
+                LruCache2::get_index_raw(core::ptr::addr_of_mut!((*this).0), index)
+            }
+            fn clear(&mut self) { self.0.clear(); }
+        }
+
+        check::<CountingLine>();
+        assert_eq!(LOOKUPS.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn profiling_preserves_sync() {
+        fn assert_sync<T: Sync>() {}
+        type Hasher = core::hash::BuildHasherDefault<rustc_hash::FxHasher>;
+        assert_sync::<crate::collections::InlineDirectMappedCache<u32, u32, Hasher, 4>>();
+    }
+}
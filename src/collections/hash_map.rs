@@ -0,0 +1,2219 @@
+//! A hash map with constant capacity, using open addressing with linear probing.
+#![allow(clippy::cast_possible_truncation)]
+
+use core::alloc::{Layout, LayoutError};
+use core::borrow::Borrow;
+use core::fmt::Debug;
+use core::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::mem::{size_of, MaybeUninit};
+use core::ptr::{addr_of, addr_of_mut, NonNull};
+
+use crate::storage::{LayoutSpec, Storage};
+use crate::CapacityError;
+
+use self::Entry::{Occupied, Vacant};
+
+/// Control byte marking a slot that has never been occupied.
+const EMPTY: u8 = 0b1000_0000;
+/// Control byte marking a slot whose value was removed.
+const DELETED: u8 = 0b1111_1111;
+
+#[inline(always)]
+fn is_occupied(ctrl: u8) -> bool {
+    ctrl & 0b1000_0000 == 0
+}
+
+#[inline(always)]
+fn tag_of(hash: u64) -> u8 {
+    (hash >> 57) as u8
+}
+
+/// The [`LayoutSpec`] for a [`HashMap`].
+pub struct HashMapLayout<K, V>(PhantomData<(K, V)>);
+impl<K, V> LayoutSpec for HashMapLayout<K, V> {
+    fn layout_with_capacity(items: usize) -> Result<Layout, LayoutError> {
+        let ctrl_array = Layout::array::<u8>(items)?;
+        let keys_array = Layout::array::<K>(items)?;
+        let values_array = Layout::array::<V>(items)?;
+        let (extended, _) = ctrl_array.extend(keys_array)?;
+        let (extended, _) = extended.extend(values_array)?;
+        Ok(extended.pad_to_align())
+    }
+}
+
+/// A hash map with constant capacity, using open addressing with linear probing.
+///
+/// Unlike [`CacheTable`](crate::collections::cache::CacheTable), this never
+/// evicts any entries; insertions into a full map fail instead. Key search
+/// takes expected constant time, as long as the map isn't close to full.
+/// Performance degrades as the load factor approaches 1, so consider choosing
+/// a capacity somewhat larger than the number of entries you intend to store.
+///
+/// Each slot in the storage block has one control byte associated with it,
+/// which holds 7 bits of the key's hash while the slot is occupied. This way,
+/// most unsuccessful key comparisons can be skipped without fetching the key
+/// into the cache.
+///
+/// For `no_std` compatibility, no default hash builder is provided, but when
+/// using [`Hasher`] types implementing [`Default`], the constructors
+/// [`new`](HashMap::new), [`with_capacity`](HashMap::with_capacity) and
+/// [`Default::default`] are provided. Otherwise, use [`with_hasher`](HashMap::with_hasher),
+/// [`with_capacity_and_hasher`](HashMap::with_capacity_and_hasher),
+/// [`from_storage_and_hasher`](HashMap::from_storage_and_hasher) or
+/// [`Arena::try_hash_map_with_hasher`](crate::arena::Arena::try_hash_map_with_hasher).
+///
+/// It is a logic error for a key to be modified in such a way that its hash,
+/// as determined by the [`Hash`] trait, or its equality, as determined by the
+/// [`Eq`] trait, changes while it is in the map. This is normally only possible
+/// through [`Cell`](core::cell::Cell), [`RefCell`](core::cell::RefCell), global
+/// state, I/O, or unsafe code. The behavior resulting from such a logic error
+/// is not specified, but will not result in undefined behavior. This could
+/// include panics, incorrect results, aborts, memory leaks, and non-termination.
+pub struct HashMap<K, V, S: Storage<HashMapLayout<K, V>>, H> {
+    buf: S,
+    len: usize,
+    deleted: usize,
+    hash_builder: H,
+    pairs: PhantomData<(K, V)>,
+}
+
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H: Default> From<S> for HashMap<K, V, S, H> {
+    fn from(buf: S) -> Self {
+        Self::from_storage_and_hasher(buf, H::default())
+    }
+}
+
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> HashMap<K, V, S, H> {
+    /// Constructs a new, empty hash map using the specified storage and hash builder.
+    pub fn from_storage_and_hasher(buf: S, hash_builder: H) -> Self {
+        let mut result = HashMap { buf, len: 0, deleted: 0, hash_builder, pairs: PhantomData };
+        result.reset_control_bytes();
+        result
+    }
+
+    /// Returns a reference to the map's [`BuildHasher`].
+    #[inline]
+    pub fn hasher(&self) -> &H {
+        &self.hash_builder
+    }
+
+    /// Returns the number of entries the map can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    /// Returns the number of entries in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map contains no entries, or `false` otherwise.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the map contains the maximum number of entries it can hold, or `false` otherwise.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len == self.buf.capacity()
+    }
+
+    #[inline(always)]
+    fn offsets(&self) -> (usize, usize) {
+        let cap = self.capacity();
+        let ctrl_array = Layout::array::<u8>(cap).unwrap();
+        let keys_array = Layout::array::<K>(cap).unwrap();
+        let values_array = Layout::array::<V>(cap).unwrap();
+        let (extended, keys_offset) = ctrl_array.extend(keys_array).unwrap();
+        let (_, values_offset) = extended.extend(values_array).unwrap();
+        (keys_offset, values_offset)
+    }
+
+    #[inline(always)]
+    fn control_bytes(&self) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(self.buf.get_ptr(), self.capacity()) }
+    }
+
+    #[inline(always)]
+    fn control_bytes_mut(&mut self) -> &mut [u8] {
+        let cap = self.capacity();
+        unsafe { core::slice::from_raw_parts_mut(self.buf.get_mut_ptr(), cap) }
+    }
+
+    fn reset_control_bytes(&mut self) {
+        self.control_bytes_mut().fill(EMPTY);
+        self.deleted = 0;
+    }
+
+    #[inline(always)]
+    fn key_ptr(&self, idx: usize) -> *const K {
+        debug_assert!(idx < self.capacity());
+        let (keys_offset, _) = self.offsets();
+        unsafe { self.buf.get_ptr().add(keys_offset).cast::<K>().add(idx) }
+    }
+
+    #[inline(always)]
+    fn value_ptr(&self, idx: usize) -> *const V {
+        debug_assert!(idx < self.capacity());
+        let (_, values_offset) = self.offsets();
+        unsafe { self.buf.get_ptr().add(values_offset).cast::<V>().add(idx) }
+    }
+
+    #[inline(always)]
+    fn key_mut_ptr(&mut self, idx: usize) -> *mut K {
+        debug_assert!(idx < self.capacity());
+        let (keys_offset, _) = self.offsets();
+        unsafe { self.buf.get_mut_ptr().add(keys_offset).cast::<K>().add(idx) }
+    }
+
+    #[inline(always)]
+    fn value_mut_ptr(&mut self, idx: usize) -> *mut V {
+        debug_assert!(idx < self.capacity());
+        let (_, values_offset) = self.offsets();
+        unsafe { self.buf.get_mut_ptr().add(values_offset).cast::<V>().add(idx) }
+    }
+
+    /// Returns the index of the next occupied slot at or after `idx`.
+    #[inline]
+    fn next_occupied_slot(&self, idx: usize) -> Option<usize> {
+        self.control_bytes()[idx..].iter().position(|&c| is_occupied(c)).map(|offset| idx + offset)
+    }
+
+    /// Writes the key-value pair into the vacant slot at `idx`, returning a pointer to the value.
+    ///
+    /// # Safety
+    /// The slot at `idx` must not be occupied.
+    unsafe fn write_at(&mut self, idx: usize, tag: u8, key: K, value: V) -> *mut V {
+        debug_assert!(!is_occupied(self.control_bytes()[idx]));
+        if self.control_bytes()[idx] == DELETED {
+            self.deleted -= 1;
+        }
+        self.key_mut_ptr(idx).write(key);
+        let value_ptr = self.value_mut_ptr(idx);
+        value_ptr.write(value);
+        self.control_bytes_mut()[idx] = tag;
+        self.len += 1;
+        value_ptr
+    }
+
+    /// Moves the key-value pair out of the occupied slot at `idx`.
+    ///
+    /// # Safety
+    /// The slot at `idx` must be occupied.
+    unsafe fn remove_at(&mut self, idx: usize) -> (K, V) {
+        debug_assert!(is_occupied(self.control_bytes()[idx]));
+        let k = self.key_ptr(idx).read();
+        let v = self.value_ptr(idx).read();
+
+        // If the following slot was never occupied, no probe sequence can
+        // continue past this slot, so it can be marked as empty again, along
+        // with any tombstones directly preceding it. Otherwise, a tombstone
+        // must be left behind, to be cleared when the map is rehashed.
+        let cap = self.capacity();
+        let next = if idx + 1 == cap { 0 } else { idx + 1 };
+        if self.control_bytes()[next] == EMPTY {
+            let mut cleared = 0;
+            let ctrl = self.control_bytes_mut();
+            ctrl[idx] = EMPTY;
+
+            let mut prev = idx;
+            loop {
+                prev = if prev == 0 { cap - 1 } else { prev - 1 };
+                if ctrl[prev] != DELETED { break; }
+                ctrl[prev] = EMPTY;
+                cleared += 1;
+            }
+
+            self.deleted -= cleared;
+        } else {
+            self.control_bytes_mut()[idx] = DELETED;
+            self.deleted += 1;
+        }
+
+        self.len -= 1;
+        (k, v)
+    }
+
+    /// An iterator visiting all key-value pairs in arbitrary order.
+    /// The iterator element type is `(&'a K, &'a V)`.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    /// map.insert("c", 3);
+    ///
+    /// for (key, val) in map.iter() {
+    ///     println!("{} -> {}", key, val);
+    /// }
+    /// ```
+    pub fn iter(&self) -> Iter<'_, K, V, S, H> {
+        Iter { map: self, front: 0, remaining: self.len }
+    }
+
+    /// An iterator visiting all key-value pairs in arbitrary order, with
+    /// mutable references to the values. The iterator element type is
+    /// `(&'a K, &'a mut V)`.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    /// map.insert("c", 3);
+    ///
+    /// for (_, val) in map.iter_mut() {
+    ///     *val *= 2;
+    /// }
+    ///
+    /// assert_eq!(map.get("a"), Some(&2));
+    /// assert_eq!(map.get("b"), Some(&4));
+    /// assert_eq!(map.get("c"), Some(&6));
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V, S, H> {
+        let remaining = self.len;
+        IterMut { map: self, front: 0, remaining }
+    }
+
+    /// An iterator visiting all keys in arbitrary order.
+    /// The iterator element type is `&'a K`.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    /// map.insert("c", 3);
+    ///
+    /// for key in map.keys() {
+    ///     println!("{}", key);
+    /// }
+    /// ```
+    pub fn keys(&self) -> Keys<'_, K, V, S, H> {
+        Keys { base: self.iter() }
+    }
+
+    /// An iterator visiting all values in arbitrary order.
+    /// The iterator element type is `&'a V`.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    /// map.insert("c", 3);
+    ///
+    /// assert_eq!(map.values().sum::<u32>(), 6);
+    /// ```
+    pub fn values(&self) -> Values<'_, K, V, S, H> {
+        Values { base: self.iter() }
+    }
+
+    /// An iterator visiting all values mutably in arbitrary order.
+    /// The iterator element type is `&'a mut V`.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    /// map.insert("c", 3);
+    ///
+    /// for val in map.values_mut() {
+    ///     *val += 10;
+    /// }
+    ///
+    /// assert_eq!(map.get("a"), Some(&11));
+    /// assert_eq!(map.get("b"), Some(&12));
+    /// assert_eq!(map.get("c"), Some(&13));
+    /// ```
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V, S, H> {
+        ValuesMut { base: self.iter_mut() }
+    }
+
+    /// Clears the map without taking ownership, and returns all key-value pairs as an iterator.
+    ///
+    /// If the iterator is only partially consumed, or not consumed at all,
+    /// all remaining key-value pairs will still be removed.
+    ///
+    /// It is unspecified how many pairs will be removed if a panic occurs while
+    /// dropping an element, or if the [`Drain`] value is leaked.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    ///
+    /// for (k, v) in map.drain().take(1) {
+    ///     let a = k == "a" && v == 1;
+    ///     let b = k == "b" && v == 2;
+    ///     assert!(a || b);
+    /// }
+    ///
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, K, V, S, H> {
+        Drain { map: self, front: 0 }
+    }
+
+    /// Creates an iterator which uses a closure to determine if an element should be removed.
+    ///
+    /// If the closure returns `true`, the element is removed from the map and
+    /// yielded. If the closure returns `false`, or panics, the element remains
+    /// in the map and will not be yielded.
+    ///
+    /// Note that `drain_filter` lets you mutate every value in the filter
+    /// closure, regardless of whether you choose to keep or remove it.
+    ///
+    /// If the iterator is only partially consumed, or not consumed at all,
+    /// all remaining key-value pairs will still be subjected to the closure
+    /// and removed and dropped if it returns true.
+    ///
+    /// It is unspecified how many pairs will be subjected to the closure if a
+    /// panic occurs in the closure, or a panic occurs while dropping an element,
+    /// or if the [`DrainFilter`] value is leaked.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::{InlineHashMap, InlineVec};
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<u32, u32, BuildHasherDefault<FxHasher>, 8>::new();
+    /// (0..8).for_each(|x| { map.insert(x, x); });
+    /// let drained = map.drain_filter(|k, v| { *v = *v * *v; k % 2 == 0 });
+    ///
+    /// let mut evens = InlineVec::<u32, 4>::new();
+    /// let mut odds = InlineVec::<u32, 4>::new();
+    ///
+    /// evens.extend(drained.map(|(_x, x_squared)| x_squared));
+    /// evens.sort_unstable();
+    /// assert_eq!(evens, [0, 4, 16, 36]);
+    ///
+    /// odds.extend(map.into_values());
+    /// odds.sort_unstable();
+    /// assert_eq!(odds, [1, 9, 25, 49]);
+    /// ```
+    pub fn drain_filter<F: FnMut(&K, &mut V) -> bool>(&mut self, pred: F) -> DrainFilter<'_, K, V, S, H, F> {
+        DrainFilter { map: self, should_remove: pred, front: 0 }
+    }
+
+    /// Clears the map, removing all key-value pairs.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    ///
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    /// map.insert("c", 3);
+    /// map.insert("d", 4);
+    /// assert!(map.is_full());
+    ///
+    /// map.clear();
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        let mut idx = 0;
+        while let Some(occupied) = self.next_occupied_slot(idx) {
+            unsafe {
+                self.key_mut_ptr(occupied).drop_in_place();
+                self.value_mut_ptr(occupied).drop_in_place();
+            }
+            idx = occupied + 1;
+        }
+
+        self.reset_control_bytes();
+        self.len = 0;
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all key-value pairs `(k, v)` such that `pred(&k, &mut v)`
+    /// returns `false`. The elements are visited in arbitrary (and unspecified) order.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<u32, u32, BuildHasherDefault<FxHasher>, 8>::new();
+    /// (0..8).for_each(|x| { map.insert(x, x*10); });
+    /// assert_eq!(map.len(), 8);
+    ///
+    /// map.retain(|&k, _| k % 2 == 0);
+    /// assert_eq!(map.len(), 4);
+    /// ```
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut pred: F) {
+        self.drain_filter(|k, v| !(pred)(k, v)).for_each(drop);
+    }
+
+    /// Creates a consuming iterator visiting all keys in arbitrary order.
+    /// The map cannot be used after calling this. The iterator element type is `K`.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::{InlineHashMap, InlineVec};
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    /// map.insert("c", 3);
+    ///
+    /// let mut vec = InlineVec::<&'static str, 4>::new();
+    /// vec.extend(map.into_keys());
+    /// // The keys are visited in arbitrary order,
+    /// // so they must be sorted for this test.
+    /// vec.sort_unstable();
+    /// assert_eq!(vec, ["a", "b", "c"]);
+    /// ```
+    pub fn into_keys(self) -> IntoKeys<K, V, S, H> {
+        IntoKeys { base: self.into_iter() }
+    }
+
+    /// Creates a consuming iterator visiting all values in arbitrary order.
+    /// The map cannot be used after calling this. The iterator element type is `V`.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::{InlineHashMap, InlineVec};
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    /// map.insert("c", 3);
+    ///
+    /// let mut vec = InlineVec::<u32, 4>::new();
+    /// vec.extend(map.into_values());
+    /// // The values are visited in arbitrary order,
+    /// // so they must be sorted for this test.
+    /// vec.sort_unstable();
+    /// assert_eq!(vec, [1, 2, 3]);
+    /// ```
+    pub fn into_values(self) -> IntoValues<K, V, S, H> {
+        IntoValues { base: self.into_iter() }
+    }
+}
+
+impl<K: Hash + Eq, V, S: Storage<HashMapLayout<K, V>>, H: BuildHasher> HashMap<K, V, S, H> {
+    #[inline]
+    #[allow(clippy::manual_hash_one)] // BuildHasher::hash_one requires Rust 1.71
+    fn make_hash<Q: Hash + ?Sized>(&self, val: &Q) -> u64 {
+        let mut state = self.hash_builder.build_hasher();
+        val.hash(&mut state);
+        state.finish()
+    }
+
+    /// Returns `true` if tombstones make up enough of the map's vacant slots
+    /// that it should be rehashed before inserting another key.
+    #[inline]
+    fn has_excess_tombstones(&self) -> bool {
+        let cap = self.capacity();
+        self.deleted > cap / 8 || (self.deleted > 0 && self.len + self.deleted == cap)
+    }
+
+    /// Clears all tombstones by moving every entry as close to the start of
+    /// its probe sequence as possible, without requiring additional storage.
+    fn rehash_in_place(&mut self) {
+        // Entries still waiting to be moved are marked as `DELETED`; if
+        // hashing one of them panics, they can't be found again, so they
+        // are dropped instead of being leaked.
+        struct Guard<'a, K, V, S: Storage<HashMapLayout<K, V>>, H>(&'a mut HashMap<K, V, S, H>);
+        impl<K, V, S: Storage<HashMapLayout<K, V>>, H> Drop for Guard<'_, K, V, S, H> {
+            fn drop(&mut self) {
+                let map = &mut *self.0;
+                for idx in 0..map.capacity() {
+                    if map.control_bytes()[idx] != DELETED { continue; }
+                    map.control_bytes_mut()[idx] = EMPTY;
+                    map.len -= 1;
+                    unsafe {
+                        map.key_mut_ptr(idx).drop_in_place();
+                        map.value_mut_ptr(idx).drop_in_place();
+                    }
+                }
+            }
+        }
+
+        let cap = self.capacity();
+        for c in self.control_bytes_mut() {
+            *c = if is_occupied(*c) { DELETED } else { EMPTY };
+        }
+        self.deleted = 0;
+
+        let guard = Guard(self);
+        let map = &mut *guard.0;
+        for idx in 0..cap {
+            while map.control_bytes()[idx] == DELETED {
+                let hash = map.make_hash(unsafe { &*map.key_ptr(idx) });
+                let tag = tag_of(hash);
+
+                let mut target = hash as usize % cap;
+                while is_occupied(map.control_bytes()[target]) {
+                    target += 1;
+                    if target == cap { target = 0; }
+                }
+
+                if target == idx {
+                    map.control_bytes_mut()[idx] = tag;
+                } else if map.control_bytes()[target] == EMPTY {
+                    unsafe {
+                        map.key_mut_ptr(target).write(map.key_ptr(idx).read());
+                        map.value_mut_ptr(target).write(map.value_ptr(idx).read());
+                    }
+                    map.control_bytes_mut()[target] = tag;
+                    map.control_bytes_mut()[idx] = EMPTY;
+                } else {
+                    // the target slot holds another entry yet to be moved,
+                    // which takes this entry's place in the next iteration
+                    unsafe {
+                        core::ptr::swap(map.key_mut_ptr(idx), map.key_mut_ptr(target));
+                        core::ptr::swap(map.value_mut_ptr(idx), map.value_mut_ptr(target));
+                    }
+                    map.control_bytes_mut()[target] = tag;
+                }
+            }
+        }
+
+        core::mem::forget(guard);
+    }
+
+    /// Like [`lookup_or_vacant_slot`](HashMap::lookup_or_vacant_slot), but
+    /// rehashes the map first if the key is absent and the map contains too
+    /// many tombstones.
+    fn lookup_or_prepare_vacant_slot(&mut self, hash: u64, key: &K) -> Result<usize, Option<usize>> {
+        match self.lookup_or_vacant_slot(hash, key) {
+            Err(_) if self.has_excess_tombstones() => {
+                self.rehash_in_place();
+                self.lookup_or_vacant_slot(hash, key)
+            }
+            result => result,
+        }
+    }
+
+    /// Returns the index of the slot containing the given key, if any.
+    fn lookup<Q>(&self, hash: u64, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        let cap = self.capacity();
+        if cap == 0 { return None; }
+
+        let tag = tag_of(hash);
+        let ctrl = self.control_bytes();
+        let mut idx = hash as usize % cap;
+
+        for _ in 0..cap {
+            let c = ctrl[idx];
+            if c == EMPTY { return None; }
+            if c == tag && unsafe { (*self.key_ptr(idx)).borrow() == key } {
+                return Some(idx);
+            }
+
+            idx += 1;
+            if idx == cap { idx = 0; }
+        }
+
+        None
+    }
+
+    /// Returns `Ok(idx)` if the slot at `idx` contains the given key, or
+    /// `Err(Some(idx))` if the slot at `idx` is the first vacant slot in the
+    /// key's probe sequence, or `Err(None)` if the map is full.
+    fn lookup_or_vacant_slot(&self, hash: u64, key: &K) -> Result<usize, Option<usize>> {
+        let cap = self.capacity();
+        if cap == 0 { return Err(None); }
+
+        let tag = tag_of(hash);
+        let ctrl = self.control_bytes();
+        let mut idx = hash as usize % cap;
+        let mut first_vacant = None;
+
+        for _ in 0..cap {
+            let c = ctrl[idx];
+            if c == EMPTY {
+                return Err(first_vacant.or(Some(idx)));
+            } else if c == DELETED {
+                first_vacant = first_vacant.or(Some(idx));
+            } else if c == tag && unsafe { *self.key_ptr(idx) == *key } {
+                return Ok(idx);
+            }
+
+            idx += 1;
+            if idx == cap { idx = 0; }
+        }
+
+        Err(first_vacant)
+    }
+
+    /// Inserts a key-value pair into the first vacant slot in the key's
+    /// probe sequence, without checking whether the key is already present.
+    ///
+    /// Returns a reference to the inserted key, or the key-value pair if the map is full.
+    pub(crate) fn try_insert_unique_unchecked(&mut self, key: K, value: V) -> Result<&K, (K, V)> {
+        let cap = self.capacity();
+        if self.len == cap { return Err((key, value)); }
+        if self.has_excess_tombstones() { self.rehash_in_place(); }
+
+        let hash = self.make_hash(&key);
+        let ctrl = self.control_bytes();
+        let mut idx = hash as usize % cap;
+        while is_occupied(ctrl[idx]) {
+            idx += 1;
+            if idx == cap { idx = 0; }
+        }
+
+        unsafe {
+            self.write_at(idx, tag_of(hash), key, value);
+            Ok(&*self.key_ptr(idx))
+        }
+    }
+
+    /// Gets the given key's corresponding [`Entry`] in the map for in-place manipulation.
+    ///
+    /// # Panics
+    /// Panics if the map is full and does not contain the given key.
+    /// See [`try_entry`](HashMap::try_entry) for a checked version that never panics.
+    #[track_caller]
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, S, H> {
+        self.try_entry(key).ok().expect("map is already at capacity")
+    }
+
+    /// Gets the given key's corresponding [`Entry`] in the map for in-place manipulation.
+    ///
+    /// Returns [`Err(key)`] if the map is full and does not contain the given key.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut letters = InlineHashMap::<char, u32, BuildHasherDefault<FxHasher>, 32>::new();
+    ///
+    /// for ch in "i am, therefore i'm coded".chars() {
+    ///     let counter = letters.try_entry(ch).unwrap().or_insert(0);
+    ///     *counter += 1;
+    /// }
+    ///
+    /// assert_eq!(letters.get(&'a'), Some(&1));
+    /// assert_eq!(letters.get(&'e'), Some(&4));
+    /// assert_eq!(letters.get(&'i'), Some(&2));
+    /// assert_eq!(letters.get(&'o'), Some(&2));
+    /// assert_eq!(letters.get(&'u'), None);
+    /// ```
+    pub fn try_entry(&mut self, key: K) -> Result<Entry<'_, K, V, S, H>, K> {
+        let hash = self.make_hash(&key);
+        match self.lookup_or_prepare_vacant_slot(hash, &key) {
+            Ok(idx) => Ok(Occupied(OccupiedEntry { key, idx, map: self })),
+            Err(Some(idx)) => Ok(Vacant(VacantEntry { key, idx, tag: tag_of(hash), map: self })),
+            Err(None) => Err(key),
+        }
+    }
+
+    /// Returns a reference to the value associated with the given key.
+    ///
+    /// The key may be any borrowed form of the map's key type, but [`Hash`]
+    /// and [`Eq`] on the borrowed form *must* match those for the key type.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// map.insert("a", 1);
+    ///
+    /// assert_eq!(map.get("a"), Some(&1));
+    /// assert_eq!(map.get("b"), None);
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let idx = self.lookup(self.make_hash(key), key)?;
+        Some(unsafe { &*self.value_ptr(idx) })
+    }
+
+    /// Returns the key-value pair corresponding to the given key.
+    ///
+    /// The key may be any borrowed form of the map's key type, but [`Hash`]
+    /// and [`Eq`] on the borrowed form *must* match those for the key type.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// map.insert("a", 1);
+    ///
+    /// assert_eq!(map.get_key_value("a"), Some((&"a", &1)));
+    /// assert_eq!(map.get_key_value("b"), None);
+    /// ```
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let idx = self.lookup(self.make_hash(key), key)?;
+        Some(unsafe { (&*self.key_ptr(idx), &*self.value_ptr(idx)) })
+    }
+
+    /// Returns `true` if the map contains a value for the given key, or `false` otherwise.
+    ///
+    /// The key may be any borrowed form of the map's key type, but [`Hash`]
+    /// and [`Eq`] on the borrowed form *must* match those for the key type.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// map.insert("a", 1);
+    ///
+    /// assert_eq!(map.contains_key("a"), true);
+    /// assert_eq!(map.contains_key("b"), false);
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.lookup(self.make_hash(key), key).is_some()
+    }
+
+    /// Returns a mutable reference to the value associated with the given key.
+    ///
+    /// The key may be any borrowed form of the map's key type, but [`Hash`]
+    /// and [`Eq`] on the borrowed form *must* match those for the key type.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// map.insert("a", 1);
+    ///
+    /// if let Some(x) = map.get_mut(&"a") {
+    ///     *x = *x + 2;
+    /// }
+    ///
+    /// assert_eq!(map.get("a"), Some(&3));
+    /// ```
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let idx = self.lookup(self.make_hash(key), key)?;
+        Some(unsafe { &mut *self.value_mut_ptr(idx) })
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the map did not have this key present, [`None`] is returned.
+    ///
+    /// If the map did have this key present, the value is updated, and the
+    /// old value is returned. The key is not updated though; this matters for
+    /// types that can be `==` without being identical.
+    ///
+    /// # Panics
+    /// Panics if the map is full and the given key is not present. See
+    /// [`try_insert`](HashMap::try_insert) for a checked version that never panics.
+    #[inline]
+    #[track_caller]
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.try_insert(key, value).expect("map is already at capacity")
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the map did not have this key present, `Ok(None)` is returned if the
+    /// key-value pair is inserted, or [`CapacityError`] if the map is full, in
+    /// which case both the key and the value are dropped. Use the
+    /// [`Entry` API](HashMap::try_entry) to retain ownership in this case.
+    ///
+    /// If the map did have this key present, the value is updated, and the
+    /// old value is returned. The key is not updated though; this matters for
+    /// types that can be `==` without being identical.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// assert_eq!(map.try_insert("a", 37).unwrap(), None);
+    /// assert_eq!(map.try_insert("a", 42).unwrap(), Some(37));
+    ///
+    /// map.insert("b", 23);
+    /// map.insert("c", 19);
+    /// map.insert("d", 8);
+    /// assert_eq!(map.is_full(), true);
+    /// assert!(map.try_insert("e", 0).is_err());
+    /// ```
+    pub fn try_insert(&mut self, key: K, value: V) -> crate::Result<Option<V>> {
+        let hash = self.make_hash(&key);
+        match self.lookup_or_prepare_vacant_slot(hash, &key) {
+            Ok(idx) => Ok(Some(unsafe { self.value_mut_ptr(idx).replace(value) })),
+            Err(Some(idx)) => {
+                unsafe { self.write_at(idx, tag_of(hash), key, value) };
+                Ok(None)
+            }
+            Err(None) => CapacityError::new(),
+        }
+    }
+
+    /// Removes a key from the map, returning the value associated with the key
+    /// if it was previously in the map.
+    ///
+    /// The key may be any borrowed form of the map's key type, but [`Hash`]
+    /// and [`Eq`] on the borrowed form *must* match those for the key type.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// map.insert("a", 1);
+    ///
+    /// assert_eq!(map.remove("a"), Some(1));
+    /// assert_eq!(map.remove("a"), None);
+    /// ```
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// Removes a key from the map, returning the stored key and associated
+    /// value if the key was previously in the map.
+    ///
+    /// The key may be any borrowed form of the map's key type, but [`Hash`]
+    /// and [`Eq`] on the borrowed form *must* match those for the key type.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// map.insert("a", 1);
+    ///
+    /// assert_eq!(map.remove_entry("a"), Some(("a", 1)));
+    /// assert_eq!(map.remove_entry("a"), None);
+    /// ```
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let idx = self.lookup(self.make_hash(key), key)?;
+        Some(unsafe { self.remove_at(idx) })
+    }
+}
+
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> Drop for HashMap<K, V, S, H> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<K: Debug, V: Debug, S: Storage<HashMapLayout<K, V>>, H> Debug for HashMap<K, V, S, H> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<Q, K, V, S, H> core::ops::Index<&'_ Q> for HashMap<K, V, S, H>
+where
+    Q: Hash + Eq + ?Sized,
+    K: Hash + Eq + Borrow<Q>,
+    S: Storage<HashMapLayout<K, V>>,
+    H: BuildHasher,
+{
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+impl<K, V, S, H> Extend<(K, V)> for HashMap<K, V, S, H>
+where
+    K: Hash + Eq,
+    S: Storage<HashMapLayout<K, V>>,
+    H: BuildHasher,
+{
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        let iter = iter.into_iter();
+        iter.for_each(move |(k, v)| { self.insert(k, v); });
+    }
+}
+
+impl<'a, K, V, S, H> Extend<(&'a K, &'a V)> for HashMap<K, V, S, H>
+where
+    K: Clone + Hash + Eq,
+    V: Clone,
+    S: Storage<HashMapLayout<K, V>>,
+    H: BuildHasher,
+{
+    fn extend<T: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: T) {
+        let iter = iter.into_iter();
+        iter.for_each(|(k, v)| {
+            self.insert(k.clone(), v.clone());
+        });
+    }
+}
+
+impl<K, V, S1, H1, S2, H2> PartialEq<HashMap<K, V, S2, H2>> for HashMap<K, V, S1, H1>
+where
+    K: Hash + Eq,
+    V: PartialEq,
+    S1: Storage<HashMapLayout<K, V>>,
+    S2: Storage<HashMapLayout<K, V>>,
+    H2: BuildHasher,
+{
+    /// Tests for `self` and `other` to be equal, and is used by `==`.
+    fn eq(&self, other: &HashMap<K, V, S2, H2>) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+
+        self.iter().all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl<K: Hash + Eq, V: Eq, S: Storage<HashMapLayout<K, V>>, H: BuildHasher> Eq for HashMap<K, V, S, H> {}
+
+impl<K: Clone, V: Clone, S: Storage<HashMapLayout<K, V>>, H> HashMap<K, V, S, H> {
+    /// Clones all entries into `target`, which must be empty and of equal capacity.
+    ///
+    /// Entries are stored at the same indices as in `self`, so no rehashing is required.
+    fn clone_entries_into<S2: Storage<HashMapLayout<K, V>>, H2>(&self, target: &mut HashMap<K, V, S2, H2>) {
+        debug_assert!(target.is_empty());
+        debug_assert_eq!(self.capacity(), target.capacity());
+
+        let mut idx = 0;
+        while let Some(occupied) = self.next_occupied_slot(idx) {
+            let (k, v) = unsafe { (&*self.key_ptr(occupied), &*self.value_ptr(occupied)) };
+            let tag = self.control_bytes()[occupied];
+            unsafe { target.write_at(occupied, tag, k.clone(), v.clone()) };
+            idx = occupied + 1;
+        }
+
+        target.control_bytes_mut().copy_from_slice(self.control_bytes());
+        target.deleted = self.deleted;
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+impl<K: Hash + Eq, V, H: BuildHasher> HashMap<K, V, crate::storage::AllocStorage<HashMapLayout<K, V>>, H> {
+    /// Constructs a new, empty [`AllocHashMap`](crate::collections::AllocHashMap)
+    /// with the specified capacity and hash builder.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use coca::collections::AllocHashMap;
+    /// type HashBuilder = core::hash::BuildHasherDefault<rustc_hash::FxHasher>;
+    ///
+    /// let mut map = AllocHashMap::<i32, &'static str, _>::with_capacity_and_hasher(63, HashBuilder::default());
+    /// assert_eq!(map.capacity(), 63);
+    /// ```
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: H) -> Self {
+        let buf = crate::storage::AllocStorage::with_capacity(capacity);
+        Self::from_storage_and_hasher(buf, hash_builder)
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+impl<K: Hash + Eq, V, H: Hasher + Default> HashMap<K, V, crate::storage::AllocStorage<HashMapLayout<K, V>>, BuildHasherDefault<H>> {
+    /// Constructs a new, empty [`AllocHashMap`](crate::collections::AllocHashMap)
+    /// with the specified capacity.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use coca::collections::AllocHashMap;
+    /// type HashBuilder = core::hash::BuildHasherDefault<rustc_hash::FxHasher>;
+    ///
+    /// let mut map = AllocHashMap::<i32, &'static str, HashBuilder>::with_capacity(63);
+    /// assert_eq!(map.capacity(), 63);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        let buf = crate::storage::AllocStorage::with_capacity(capacity);
+        Self::from_storage_and_hasher(buf, BuildHasherDefault::default())
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+impl<K: Clone, V: Clone, H: Clone> Clone for HashMap<K, V, crate::storage::AllocStorage<HashMapLayout<K, V>>, H> {
+    fn clone(&self) -> Self {
+        let buf = crate::storage::AllocStorage::with_capacity(self.capacity());
+        let mut result = HashMap::from_storage_and_hasher(buf, self.hash_builder.clone());
+        self.clone_entries_into(&mut result);
+        result
+    }
+}
+
+/// A storage block for a [`HashMap`] carved out of a mutable byte slice.
+///
+/// The capacity is the largest number of entries that fit into the slice
+/// after accounting for alignment requirements.
+///
+/// # Examples
+/// ```
+/// use coca::collections::hash_map::SliceStorage;
+/// use coca::storage::Storage;
+/// use core::mem::MaybeUninit;
+///
+/// let mut backing_region = [MaybeUninit::<u8>::uninit(); 256];
+/// let storage = SliceStorage::<u32, u32>::from(&mut backing_region[..]);
+/// assert!(storage.capacity() >= 27);
+/// ```
+pub struct SliceStorage<'a, K, V> {
+    ptr: NonNull<u8>,
+    cap: usize,
+    pairs: PhantomData<(K, V)>,
+    src: PhantomData<&'a mut [MaybeUninit<u8>]>,
+}
+
+impl<'a, K, V> From<&'a mut [MaybeUninit<u8>]> for SliceStorage<'a, K, V> {
+    fn from(buf: &'a mut [MaybeUninit<u8>]) -> Self {
+        let align = HashMapLayout::<K, V>::layout_with_capacity(0)
+            .expect("zero-capacity layout must be valid")
+            .align();
+
+        let base = buf.as_mut_ptr().cast::<u8>();
+        let padding = base.align_offset(align).min(buf.len());
+        let available = buf.len() - padding;
+
+        let fits = |cap: usize| matches!(
+            HashMapLayout::<K, V>::layout_with_capacity(cap),
+            Ok(layout) if layout.size() <= available
+        );
+        let mut cap = available / (1 + size_of::<K>() + size_of::<V>());
+        while cap > 0 && !fits(cap) {
+            cap -= 1;
+        }
+
+        SliceStorage {
+            ptr: unsafe { NonNull::new_unchecked(base.add(padding)) },
+            cap,
+            pairs: PhantomData,
+            src: PhantomData,
+        }
+    }
+}
+
+unsafe impl<K, V> Storage<HashMapLayout<K, V>> for SliceStorage<'_, K, V> {
+    #[inline]
+    fn get_ptr(&self) -> *const u8 {
+        self.ptr.as_ptr()
+    }
+
+    #[inline]
+    fn get_mut_ptr(&mut self) -> *mut u8 {
+        self.ptr.as_ptr()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.cap
+    }
+}
+
+/// A statically-sized storage block for a [`HashMap`].
+#[repr(C)]
+pub struct InlineStorage<K, V, const N: usize> {
+    ctrl: [MaybeUninit<u8>; N],
+    keys: [MaybeUninit<K>; N],
+    values: [MaybeUninit<V>; N],
+}
+
+unsafe impl<K, V, const N: usize> Storage<HashMapLayout<K, V>> for InlineStorage<K, V, N> {
+    fn get_ptr(&self) -> *const u8 {
+        addr_of!(*self).cast()
+    }
+
+    fn get_mut_ptr(&mut self) -> *mut u8 {
+        addr_of_mut!(*self).cast()
+    }
+
+    fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<K, V, const N: usize> InlineStorage<K, V, N> {
+    fn uninit() -> Self {
+        unsafe { InlineStorage {
+            ctrl: MaybeUninit::uninit().assume_init(),
+            keys: MaybeUninit::uninit().assume_init(),
+            values: MaybeUninit::uninit().assume_init(),
+        }}
+    }
+}
+
+impl<K: Hash + Eq, V, H: BuildHasher, const N: usize> HashMap<K, V, InlineStorage<K, V, N>, H> {
+    /// Constructs a new, empty [`InlineHashMap`](crate::collections::InlineHashMap)
+    /// using the specified hash builder.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use coca::collections::InlineHashMap;
+    /// type HashBuilder = core::hash::BuildHasherDefault<rustc_hash::FxHasher>;
+    ///
+    /// let mut map = InlineHashMap::<i32, &'static str, _, 8>::with_hasher(HashBuilder::default());
+    /// map.insert(1, "a");
+    /// assert_eq!(map.get(&1), Some(&"a"));
+    /// ```
+    pub fn with_hasher(hash_builder: H) -> Self {
+        Self::from_storage_and_hasher(InlineStorage::uninit(), hash_builder)
+    }
+}
+
+impl<K: Hash + Eq, V, H: Hasher + Default, const N: usize> HashMap<K, V, InlineStorage<K, V, N>, BuildHasherDefault<H>> {
+    /// Constructs a new, empty [`InlineHashMap`](crate::collections::InlineHashMap).
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use coca::collections::InlineHashMap;
+    /// type HashBuilder = core::hash::BuildHasherDefault<rustc_hash::FxHasher>;
+    ///
+    /// let mut map = InlineHashMap::<i32, &'static str, HashBuilder, 8>::new();
+    /// map.insert(1, "a");
+    /// assert_eq!(map.get(&1), Some(&"a"));
+    /// ```
+    pub fn new() -> Self {
+        Self::from_storage_and_hasher(InlineStorage::uninit(), BuildHasherDefault::default())
+    }
+}
+
+impl<K: Hash + Eq, V, H: Hasher + Default, const N: usize> Default for HashMap<K, V, InlineStorage<K, V, N>, BuildHasherDefault<H>> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clone, V: Clone, H: Clone, const N: usize> Clone for HashMap<K, V, InlineStorage<K, V, N>, H> {
+    fn clone(&self) -> Self {
+        let mut result = HashMap::from_storage_and_hasher(InlineStorage::uninit(), self.hash_builder.clone());
+        self.clone_entries_into(&mut result);
+        result
+    }
+}
+
+/// A view into an occupied entry in a [`HashMap`]. It is part of the [`Entry`] enum.
+pub struct OccupiedEntry<'a, K, V, S: Storage<HashMapLayout<K, V>>, H> {
+    key: K,
+    idx: usize,
+    map: &'a mut HashMap<K, V, S, H>,
+}
+
+impl<'a, K, V, S: Storage<HashMapLayout<K, V>>, H> OccupiedEntry<'a, K, V, S, H> {
+    /// Gets a reference to the key used to create the entry.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Take the ownership of the key and value from the map.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use coca::collections::hash_map::Entry;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    ///
+    /// map.entry("foobar").or_insert(12);
+    /// assert_eq!(map.get("foobar"), Some(&12));
+    ///
+    /// if let Entry::Occupied(o) = map.entry("foobar") {
+    ///     o.remove_entry();
+    /// }
+    ///
+    /// assert_eq!(map.contains_key("foobar"), false);
+    /// ```
+    pub fn remove_entry(self) -> (K, V) {
+        unsafe { self.map.remove_at(self.idx) }
+    }
+
+    /// Gets a reference to the value in the entry.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use coca::collections::hash_map::Entry;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    ///
+    /// map.entry("foobar").or_insert(12);
+    /// assert_eq!(map.get("foobar"), Some(&12));
+    ///
+    /// if let Entry::Occupied(o) = map.entry("foobar") {
+    ///     assert_eq!(o.get(), &12);
+    /// }
+    /// ```
+    pub fn get(&self) -> &V {
+        unsafe { &*self.map.value_ptr(self.idx) }
+    }
+
+    /// Gets a mutable reference to the value in the entry.
+    ///
+    /// If you need a reference to the value which may outlive the `Entry`,
+    /// see [`into_mut`](OccupiedEntry::into_mut).
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use coca::collections::hash_map::Entry;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    ///
+    /// map.entry("foobar").or_insert(12);
+    /// assert_eq!(map.get("foobar"), Some(&12));
+    ///
+    /// if let Entry::Occupied(mut o) = map.entry("foobar") {
+    ///     *o.get_mut() += 10;
+    ///     assert_eq!(*o.get(), 22);
+    ///
+    ///     // You can use the same Entry multiple times:
+    ///     *o.get_mut() += 2;
+    /// }
+    ///
+    /// assert_eq!(map.get("foobar"), Some(&24));
+    /// ```
+    pub fn get_mut(&mut self) -> &mut V {
+        unsafe { &mut *self.map.value_mut_ptr(self.idx) }
+    }
+
+    /// Converts the `OccupiedEntry` into a mutable reference to the value
+    /// in the entry with a lifetime bound to the map itself.
+    ///
+    /// If you need multiple references to the `OccupiedEntry`,
+    /// see [`get_mut`](OccupiedEntry::get_mut).
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use coca::collections::hash_map::Entry;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    ///
+    /// map.entry("foobar").or_insert(12);
+    /// assert_eq!(map.get("foobar"), Some(&12));
+    ///
+    /// if let Entry::Occupied(o) = map.entry("foobar") {
+    ///     *o.into_mut() += 10;
+    /// }
+    ///
+    /// assert_eq!(map.get("foobar"), Some(&22));
+    /// ```
+    pub fn into_mut(self) -> &'a mut V {
+        unsafe { &mut *self.map.value_mut_ptr(self.idx) }
+    }
+
+    /// Sets the value of the entry, and returns the entry's old value.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use coca::collections::hash_map::Entry;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    ///
+    /// map.entry("foobar").or_insert(12);
+    /// assert_eq!(map.get("foobar"), Some(&12));
+    ///
+    /// if let Entry::Occupied(mut o) = map.entry("foobar") {
+    ///     assert_eq!(o.insert(15), 12);
+    /// }
+    ///
+    /// assert_eq!(map.get("foobar"), Some(&15));
+    /// ```
+    pub fn insert(&mut self, value: V) -> V {
+        unsafe { self.map.value_mut_ptr(self.idx).replace(value) }
+    }
+
+    /// Takes the value out of the entry, and returns it.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use coca::collections::hash_map::Entry;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    ///
+    /// map.entry("foobar").or_insert(12);
+    /// assert_eq!(map.get("foobar"), Some(&12));
+    ///
+    /// if let Entry::Occupied(o) = map.entry("foobar") {
+    ///     assert_eq!(o.remove(), 12);
+    /// }
+    ///
+    /// assert_eq!(map.contains_key("foobar"), false);
+    /// ```
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Replaces the entry, returning the old key-value pair. The new key in the map will be the key used to create the entry.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use coca::collections::hash_map::Entry;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// map.insert("foobar", 15);
+    ///
+    /// if let Entry::Occupied(entry) = map.entry("foobar") {
+    ///     let (old_key, old_value) = entry.replace_entry(16);
+    ///
+    ///     assert_eq!(old_key, "foobar");
+    ///     assert_eq!(old_value, 15);
+    /// }
+    ///
+    /// assert_eq!(map.get("foobar"), Some(&16));
+    /// ```
+    pub fn replace_entry(self, value: V) -> (K, V) {
+        unsafe {
+            let k = self.map.key_mut_ptr(self.idx).replace(self.key);
+            let v = self.map.value_mut_ptr(self.idx).replace(value);
+            (k, v)
+        }
+    }
+
+    /// Replaces the key in the map with the one used to create the entry.
+    ///
+    /// This matters for key types that can be `==` without being identical.
+    pub fn replace_key(self) -> K {
+        unsafe { self.map.key_mut_ptr(self.idx).replace(self.key) }
+    }
+}
+
+impl<K: Debug, V: Debug, S: Storage<HashMapLayout<K, V>>, H> Debug for OccupiedEntry<'_, K, V, S, H> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("OccupiedEntry")
+            .field("key", &self.key)
+            .field("value", self.get())
+            .finish()
+    }
+}
+
+/// A view into a vacant entry in a [`HashMap`]. It is part of the [`Entry`] enum.
+pub struct VacantEntry<'a, K, V, S: Storage<HashMapLayout<K, V>>, H> {
+    key: K,
+    idx: usize,
+    tag: u8,
+    map: &'a mut HashMap<K, V, S, H>,
+}
+
+impl<'a, K, V, S: Storage<HashMapLayout<K, V>>, H> VacantEntry<'a, K, V, S, H> {
+    /// Gets a reference to the key that would be used when inserting through the `VacantEntry`.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Take ownership of the key.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Sets the value of the entry with the `VacantEntry`'s key, and returns a mutable reference to it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        unsafe { &mut *self.map.write_at(self.idx, self.tag, self.key, value) }
+    }
+}
+
+impl<K: Debug, V: Debug, S: Storage<HashMapLayout<K, V>>, H> Debug for VacantEntry<'_, K, V, S, H> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("VacantEntry").field(&self.key).finish()
+    }
+}
+
+/// A view into a single entry in a map, which may be either vacant or occupied.
+///
+/// This `enum` is constructed from the [`try_entry`](HashMap::try_entry) method on [`HashMap`].
+pub enum Entry<'a, K, V, S: Storage<HashMapLayout<K, V>>, H> {
+    /// An occupied entry.
+    Occupied(OccupiedEntry<'a, K, V, S, H>),
+    /// A vacant entry.
+    Vacant(VacantEntry<'a, K, V, S, H>),
+}
+
+impl<'a, K, V, S: Storage<HashMapLayout<K, V>>, H> Entry<'a, K, V, S, H> {
+    /// Ensures a value is in the entry by inserting the `default` if empty,
+    /// and returns a mutable reference to the value in the entry.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    ///
+    /// map.entry("foobar").or_insert(3);
+    /// assert_eq!(map.get("foobar"), Some(&3));
+    ///
+    /// *map.entry("foobar").or_insert(5) *= 2;
+    /// assert_eq!(map.get("foobar"), Some(&6));
+    /// ```
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Occupied(entry) => entry.into_mut(),
+            Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the `default`
+    /// function if empty, and returns a mutable reference to the value in the entry.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// let bazz = 0xDEADBEEF;
+    ///
+    /// map.entry("foobar").or_insert_with(|| bazz);
+    /// assert_eq!(map.get("foobar"), Some(&bazz));
+    /// ```
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Occupied(entry) => entry.into_mut(),
+            Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting, if empty, the result of the default function.
+    /// This method allows for generating key-derived values for insertion by providing the default
+    /// function a reference to the key that was moved during the `.entry(key)` method call.
+    ///
+    /// The reference to the moved key is provided so that cloning or copying the key is
+    /// unnecessary, unlike with `.or_insert_with(|| ... )`.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, usize, BuildHasherDefault<FxHasher>, 4>::new();
+    ///
+    /// map.entry("foobar").or_insert_with_key(|key| key.chars().count());
+    ///
+    /// assert_eq!(map.get("foobar"), Some(&6));
+    /// ```
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Occupied(entry) => entry.into_mut(),
+            Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+        }
+    }
+
+    /// Returns a reference to the key used to create the entry.
+    pub fn key(&self) -> &K {
+        match *self {
+            Occupied(ref entry) => entry.key(),
+            Vacant(ref entry) => entry.key(),
+        }
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any
+    /// potential inserts into the map.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    ///
+    /// map.entry("foobar")
+    ///     .and_modify(|v| { *v += 1 })
+    ///     .or_insert(37);
+    /// assert_eq!(map.get("foobar"), Some(&37));
+    ///
+    /// map.entry("foobar")
+    ///     .and_modify(|v| { *v += 1 })
+    ///     .or_insert(42);
+    /// assert_eq!(map.get("foobar"), Some(&38));
+    /// ```
+    #[must_use]
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        match self {
+            Occupied(mut entry) => {
+                f(entry.get_mut());
+                Occupied(entry)
+            },
+            Vacant(entry) => Vacant(entry),
+        }
+    }
+}
+
+impl<'a, K, V: Default, S: Storage<HashMapLayout<K, V>>, H> Entry<'a, K, V, S, H> {
+    /// Ensures a value is in the entry by inserting the default value if empty,
+    /// and returns a mutable reference to the value in the entry.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashMap;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut map = InlineHashMap::<&'static str, u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// map.entry("foobar").or_default();
+    ///
+    /// assert_eq!(map.get("foobar"), Some(&0));
+    /// ```
+    pub fn or_default(self) -> &'a mut V {
+        match self {
+            Occupied(entry) => entry.into_mut(),
+            Vacant(entry) => entry.insert(V::default())
+        }
+    }
+}
+
+impl<K: Debug, V: Debug, S: Storage<HashMapLayout<K, V>>, H> Debug for Entry<'_, K, V, S, H> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Vacant(ref v) => f.debug_tuple("Entry").field(v).finish(),
+            Occupied(ref o) => f.debug_tuple("Entry").field(o).finish(),
+        }
+    }
+}
+
+/// An iterator over the entries of a [`HashMap`].
+///
+/// This `struct` is created by the [`iter`](HashMap::iter) method on `HashMap`.
+/// See its documentation for more.
+pub struct Iter<'a, K, V, S: Storage<HashMapLayout<K, V>>, H> {
+    map: &'a HashMap<K, V, S, H>,
+    front: usize,
+    remaining: usize,
+}
+
+impl<'a, K, V, S: Storage<HashMapLayout<K, V>>, H> Iterator for Iter<'a, K, V, S, H> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 { return None; }
+
+        let idx = self.map.next_occupied_slot(self.front)?;
+        self.front = idx + 1;
+        self.remaining -= 1;
+
+        unsafe { Some((&*self.map.key_ptr(idx), &*self.map.value_ptr(idx))) }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> ExactSizeIterator for Iter<'_, K, V, S, H> {}
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> FusedIterator for Iter<'_, K, V, S, H> {}
+
+impl<'a, K, V, S: Storage<HashMapLayout<K, V>>, H> IntoIterator for &'a HashMap<K, V, S, H> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V, S, H>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A mutable iterator over the entries of a [`HashMap`].
+///
+/// This `struct` is created by the [`iter_mut`](HashMap::iter_mut)
+/// method on `HashMap`. See its documentation for more.
+pub struct IterMut<'a, K, V, S: Storage<HashMapLayout<K, V>>, H> {
+    map: &'a mut HashMap<K, V, S, H>,
+    front: usize,
+    remaining: usize,
+}
+
+impl<'a, K, V, S: Storage<HashMapLayout<K, V>>, H> Iterator for IterMut<'a, K, V, S, H> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 { return None; }
+
+        let idx = self.map.next_occupied_slot(self.front)?;
+        self.front = idx + 1;
+        self.remaining -= 1;
+
+        unsafe {
+            let k = self.map.key_ptr(idx).as_ref().unwrap();
+            let v = self.map.value_mut_ptr(idx).as_mut().unwrap();
+            Some((k, v))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> ExactSizeIterator for IterMut<'_, K, V, S, H> {}
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> FusedIterator for IterMut<'_, K, V, S, H> {}
+
+impl<'a, K, V, S: Storage<HashMapLayout<K, V>>, H> IntoIterator for &'a mut HashMap<K, V, S, H> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V, S, H>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// An iterator over the keys of a [`HashMap`].
+///
+/// This `struct` is created by the [`keys`](HashMap::keys) method on `HashMap`.
+/// See its documentation for more.
+pub struct Keys<'a, K, V, S: Storage<HashMapLayout<K, V>>, H> {
+    base: Iter<'a, K, V, S, H>,
+}
+
+impl<'a, K, V, S: Storage<HashMapLayout<K, V>>, H> Iterator for Keys<'a, K, V, S, H> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.base.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.base.size_hint()
+    }
+}
+
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> ExactSizeIterator for Keys<'_, K, V, S, H> {}
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> FusedIterator for Keys<'_, K, V, S, H> {}
+
+/// An iterator over the values of a [`HashMap`].
+///
+/// This `struct` is created by the [`values`](HashMap::values) method on `HashMap`.
+/// See its documentation for more.
+pub struct Values<'a, K, V, S: Storage<HashMapLayout<K, V>>, H> {
+    base: Iter<'a, K, V, S, H>,
+}
+
+impl<'a, K, V, S: Storage<HashMapLayout<K, V>>, H> Iterator for Values<'a, K, V, S, H> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.base.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.base.size_hint()
+    }
+}
+
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> ExactSizeIterator for Values<'_, K, V, S, H> {}
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> FusedIterator for Values<'_, K, V, S, H> {}
+
+/// A mutable iterator over the values of a [`HashMap`].
+///
+/// This `struct` is created by the [`values_mut`](HashMap::values_mut) method on `HashMap`.
+/// See its documentation for more.
+pub struct ValuesMut<'a, K, V, S: Storage<HashMapLayout<K, V>>, H> {
+    base: IterMut<'a, K, V, S, H>,
+}
+
+impl<'a, K, V, S: Storage<HashMapLayout<K, V>>, H> Iterator for ValuesMut<'a, K, V, S, H> {
+    type Item = &'a mut V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.base.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.base.size_hint()
+    }
+}
+
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> ExactSizeIterator for ValuesMut<'_, K, V, S, H> {}
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> FusedIterator for ValuesMut<'_, K, V, S, H> {}
+
+/// An owning iterator over the entries of a [`HashMap`].
+///
+/// This `struct` is created by the [`into_iter`](IntoIterator::into_iter)
+/// method on `HashMap` (provided by the [`IntoIterator`] trait). See its
+/// documentation for more.
+pub struct IntoIter<K, V, S: Storage<HashMapLayout<K, V>>, H> {
+    map: HashMap<K, V, S, H>,
+    front: usize,
+}
+
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> Iterator for IntoIter<K, V, S, H> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.map.is_empty() { return None; }
+
+        let idx = self.map.next_occupied_slot(self.front)?;
+        self.front = idx + 1;
+
+        // The map can no longer be searched, so tombstones are unnecessary:
+        self.map.control_bytes_mut()[idx] = EMPTY;
+        self.map.len -= 1;
+
+        unsafe { Some((self.map.key_ptr(idx).read(), self.map.value_ptr(idx).read())) }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.map.len();
+        (len, Some(len))
+    }
+}
+
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> ExactSizeIterator for IntoIter<K, V, S, H> {}
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> FusedIterator for IntoIter<K, V, S, H> {}
+
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> IntoIterator for HashMap<K, V, S, H> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V, S, H>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { map: self, front: 0 }
+    }
+}
+
+/// An owning iterator over the keys of a [`HashMap`].
+///
+/// This `struct` is created by the [`into_keys`](HashMap::into_keys) method on `HashMap`.
+/// See its documentation for more.
+pub struct IntoKeys<K, V, S: Storage<HashMapLayout<K, V>>, H> {
+    base: IntoIter<K, V, S, H>,
+}
+
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> Iterator for IntoKeys<K, V, S, H> {
+    type Item = K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.base.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.base.size_hint()
+    }
+}
+
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> ExactSizeIterator for IntoKeys<K, V, S, H> {}
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> FusedIterator for IntoKeys<K, V, S, H> {}
+
+/// An owning iterator over the values of a [`HashMap`].
+///
+/// This `struct` is created by the [`into_values`](HashMap::into_values) method on `HashMap`.
+/// See its documentation for more.
+pub struct IntoValues<K, V, S: Storage<HashMapLayout<K, V>>, H> {
+    base: IntoIter<K, V, S, H>,
+}
+
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> Iterator for IntoValues<K, V, S, H> {
+    type Item = V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.base.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.base.size_hint()
+    }
+}
+
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> ExactSizeIterator for IntoValues<K, V, S, H> {}
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> FusedIterator for IntoValues<K, V, S, H> {}
+
+/// A draining iterator over the entries of a [`HashMap`].
+///
+/// This `struct` is created by the [`drain`](HashMap::drain) method on `HashMap`.
+/// See its documentation for more.
+pub struct Drain<'a, K, V, S: Storage<HashMapLayout<K, V>>, H> {
+    map: &'a mut HashMap<K, V, S, H>,
+    front: usize,
+}
+
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> Iterator for Drain<'_, K, V, S, H> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.map.is_empty() { return None; }
+
+        let idx = self.map.next_occupied_slot(self.front)?;
+        self.front = idx + 1;
+
+        unsafe { Some(self.map.remove_at(idx)) }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.map.len();
+        (len, Some(len))
+    }
+}
+
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> ExactSizeIterator for Drain<'_, K, V, S, H> {}
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> FusedIterator for Drain<'_, K, V, S, H> {}
+
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H> Drop for Drain<'_, K, V, S, H> {
+    fn drop(&mut self) {
+        self.for_each(drop);
+        self.map.reset_control_bytes();
+    }
+}
+
+/// A draining, filtering iterator over the entries of a [`HashMap`].
+///
+/// This `struct` is created by the [`drain_filter`](HashMap::drain_filter)
+/// method on `HashMap`. See its documentation for more.
+pub struct DrainFilter<'a, K, V, S, H, F>
+where
+    S: Storage<HashMapLayout<K, V>>,
+    F: FnMut(&K, &mut V) -> bool,
+{
+    map: &'a mut HashMap<K, V, S, H>,
+    should_remove: F,
+    front: usize,
+}
+
+impl<K, V, S, H, F> Iterator for DrainFilter<'_, K, V, S, H, F>
+where
+    S: Storage<HashMapLayout<K, V>>,
+    F: FnMut(&K, &mut V) -> bool,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(idx) = self.map.next_occupied_slot(self.front) {
+            self.front = idx + 1;
+
+            let (k, v) = unsafe {
+                (self.map.key_ptr(idx).as_ref().unwrap(), self.map.value_mut_ptr(idx).as_mut().unwrap())
+            };
+
+            if (self.should_remove)(k, v) {
+                return unsafe { Some(self.map.remove_at(idx)) };
+            }
+        }
+
+        self.front = self.map.capacity();
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.map.len()))
+    }
+}
+
+impl<K, V, S: Storage<HashMapLayout<K, V>>, H, F: FnMut(&K, &mut V) -> bool> FusedIterator for DrainFilter<'_, K, V, S, H, F> {}
+
+impl<K, V, S, H, F> Drop for DrainFilter<'_, K, V, S, H, F>
+where
+    S: Storage<HashMapLayout<K, V>>,
+    F: FnMut(&K, &mut V) -> bool,
+{
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::hash::BuildHasherDefault;
+    use core::mem::MaybeUninit;
+
+    use rustc_hash::FxHasher;
+
+    use super::*;
+    use crate::arena::Arena;
+    use crate::collections::InlineHashMap;
+
+    type HashBuilder = BuildHasherDefault<FxHasher>;
+
+    #[test]
+    fn inline_storage_layout() {
+        fn test_layout<K, V, const N: usize>() {
+            let inline_layout = Layout::new::<InlineStorage<K, V, N>>();
+            let dynamic_layout = HashMapLayout::<K, V>::layout_with_capacity(N).unwrap();
+
+            assert_eq!(inline_layout, dynamic_layout);
+        }
+
+        test_layout::<u8, u8, 10>();
+        test_layout::<u32, [u8; 25], 20>();
+        test_layout::<u128, (), 40>();
+        test_layout::<&'static str, crate::collections::ArenaDeque<u8>, 80>();
+    }
+
+    #[test]
+    fn slice_storage_capacity() {
+        let mut backing_region = [MaybeUninit::<u8>::uninit(); 1024];
+        for offset in 0..8 {
+            let storage = SliceStorage::<u64, u16>::from(&mut backing_region[offset..]);
+            let layout = HashMapLayout::<u64, u16>::layout_with_capacity(storage.capacity()).unwrap();
+            let next_layout = HashMapLayout::<u64, u16>::layout_with_capacity(storage.capacity() + 1).unwrap();
+
+            assert_eq!(storage.get_ptr() as usize % layout.align(), 0);
+            let padding = storage.get_ptr() as usize - backing_region[offset..].as_ptr() as usize;
+            assert!(padding + layout.size() <= 1024 - offset);
+            assert!(padding + next_layout.size() > 1024 - offset);
+        }
+    }
+
+    #[test]
+    fn tombstones_do_not_break_probe_sequences() {
+        // A constant hasher forces all keys into the same probe sequence:
+        #[derive(Default)]
+        struct ConstantHasher;
+        impl Hasher for ConstantHasher {
+            fn finish(&self) -> u64 { 0 }
+            fn write(&mut self, _: &[u8]) {}
+        }
+
+        let mut map = InlineHashMap::<u32, u32, BuildHasherDefault<ConstantHasher>, 8>::new();
+        for i in 0..8 { map.insert(i, i); }
+        assert!(map.is_full());
+
+        for i in (0..8).step_by(2) {
+            assert_eq!(map.remove(&i), Some(i));
+        }
+
+        for i in 0..8 {
+            assert_eq!(map.get(&i), if i % 2 == 0 { None } else { Some(&i) });
+        }
+
+        for i in (8..16).step_by(2) { map.insert(i, i); }
+        assert!(map.is_full());
+        assert!(map.try_insert(100, 100).is_err());
+
+        for i in (1..8).step_by(2).chain((8..16).step_by(2)) {
+            assert_eq!(map.get(&i), Some(&i));
+        }
+    }
+
+    #[test]
+    fn churn_does_not_degrade_probe_sequences() {
+        // The longest run of non-empty slots bounds the number of slots
+        // probed when looking up a key that isn't present.
+        fn longest_probe<K, V, S: Storage<HashMapLayout<K, V>>, H>(map: &HashMap<K, V, S, H>) -> usize {
+            let ctrl = map.control_bytes();
+            let mut longest = 0;
+            let mut current = 0;
+            for &c in ctrl.iter().chain(ctrl.iter()) {
+                current = if c == EMPTY { 0 } else { current + 1 };
+                longest = longest.max(current);
+            }
+            longest.min(ctrl.len())
+        }
+
+        // Keys are scattered so that removals rarely border on an empty slot:
+        let key = |i: u32| i.wrapping_mul(0x9E37_79B9);
+
+        let mut map = InlineHashMap::<u32, u32, HashBuilder, 64>::new();
+        for i in 0..40 { map.insert(key(i), i); }
+
+        let mut worst = 0;
+        for i in 40..10_000 {
+            assert_eq!(map.remove(&key(i - 40)), Some(i - 40));
+            map.insert(key(i), i);
+            assert!(map.deleted <= map.capacity() / 8);
+            worst = worst.max(longest_probe(&map));
+        }
+
+        assert!(worst < 24, "longest probe sequence was {} slots", worst);
+        assert_eq!(map.len(), 40);
+        for i in 9_960..10_000 {
+            assert_eq!(map.get(&key(i)), Some(&i));
+        }
+        assert_eq!(map.get(&0), None);
+    }
+
+    #[test]
+    fn rehashing_preserves_entries() {
+        use crate::test_utils::{DropCounter, Droppable};
+
+        let drop_count = DropCounter::new();
+        {
+            let mut map = InlineHashMap::<u32, Droppable<'_, u32>, HashBuilder, 32>::new();
+            for i in 0..28 { map.insert(i, drop_count.new_droppable(i)); }
+            for i in (0..28).step_by(3) { map.remove(&i); }
+            assert_eq!(drop_count.dropped(), 10);
+
+            map.rehash_in_place();
+            assert_eq!(map.deleted, 0);
+            assert!(map.control_bytes().iter().all(|&c| c != DELETED));
+            assert_eq!(map.len(), 18);
+            assert_eq!(drop_count.dropped(), 10);
+
+            for i in 0..28 {
+                assert_eq!(map.get(&i).map(|v| v.value), if i % 3 == 0 { None } else { Some(i) });
+            }
+        }
+        assert_eq!(drop_count.dropped(), 28);
+    }
+
+    #[test]
+    fn randomized_operations() {
+        use crate::test_utils::{DropCounter, Droppable};
+        use rand::{rngs::SmallRng, RngCore, SeedableRng};
+        let mut rng = SmallRng::from_seed(crate::test_utils::RNG_SEED);
+
+        let drop_count = DropCounter::new();
+        let mut inserted = 0;
+
+        let mut storage = [MaybeUninit::uninit(); 4096];
+        let mut arena = Arena::from(&mut storage[..]);
+        let mut map: crate::collections::ArenaHashMap<u32, Droppable<u32>, HashBuilder> = arena.with_capacity(64);
+        let mut present = [false; 128];
+
+        for _ in 0..10_000 {
+            let key = rng.next_u32() % 128;
+            let idx = key as usize;
+            if rng.next_u32() % 2 == 0 {
+                match map.try_insert(key, drop_count.new_droppable(key)) {
+                    Ok(None) => {
+                        assert!(!present[idx]);
+                        present[idx] = true;
+                    }
+                    Ok(Some(old)) => {
+                        assert!(present[idx]);
+                        assert_eq!(old.value, key);
+                    }
+                    Err(_) => {
+                        assert!(!present[idx]);
+                        assert!(map.is_full());
+                    }
+                }
+                inserted += 1;
+            } else {
+                assert_eq!(map.remove(&key).map(|d| d.value), if present[idx] { Some(key) } else { None });
+                present[idx] = false;
+            }
+
+            assert_eq!(map.len(), present.iter().filter(|&&p| p).count());
+        }
+
+        for (key, &p) in present.iter().enumerate() {
+            assert_eq!(map.get(&(key as u32)).map(|d| d.value), if p { Some(key as u32) } else { None });
+        }
+
+        assert_eq!(drop_count.dropped(), inserted - map.len());
+        drop(map);
+        assert_eq!(drop_count.dropped(), inserted);
+    }
+}
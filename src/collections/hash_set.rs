@@ -0,0 +1,973 @@
+//! A hash set with constant capacity, implemented as a [`HashMap`] with `()` values.
+
+use core::borrow::Borrow;
+use core::fmt::Debug;
+use core::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
+use core::iter::FusedIterator;
+
+use crate::collections::hash_map::{self, HashMap, HashMapLayout, InlineStorage};
+use crate::collections::SetLike;
+use crate::storage::Storage;
+
+use self::hash_map::Entry::{Occupied, Vacant};
+
+/// A hash set with constant capacity, implemented as a [`HashMap`] with `()` values.
+///
+/// Looking up a value takes expected constant time, so set operators such as
+/// [`difference`], [`intersection`], and [`union`] require *O*(*n* + *m*)
+/// hash computations, as opposed to the *O*(*n* · *m*) comparisons required
+/// by a [`ListSet`](crate::collections::list_set::ListSet). See the
+/// [`HashMap`] documentation for details on the underlying hash table.
+///
+/// [`difference`]: HashSet::difference
+/// [`intersection`]: HashSet::intersection
+/// [`union`]: HashSet::union
+///
+/// It is required that the elements implement the [`Eq`] and [`Hash`] traits,
+/// although this can frequently be achieved by using
+/// `#[derive(PartialEq, Eq, Hash)]`.
+///
+/// It is a logic error for an item to be modified in such a way that its
+/// hash, as determined by the `Hash` trait, or its equality, as determined by
+/// the `Eq` trait, changes while it is in the set. This is normally only
+/// possible through [`Cell`](core::cell::Cell), [`RefCell`](core::cell::RefCell),
+/// global state, I/O, or unsafe code. The behavior resulting from such a logic
+/// error is not specified, but will not result in undefined behavior. This could
+/// include panics, incorrect results, aborts, memory leaks, and non-termination.
+pub struct HashSet<T, S: Storage<HashMapLayout<T, ()>>, H> {
+    map: HashMap<T, (), S, H>,
+}
+
+impl<T, S: Storage<HashMapLayout<T, ()>>, H: Default> From<S> for HashSet<T, S, H> {
+    fn from(buf: S) -> Self {
+        HashSet { map: HashMap::from(buf) }
+    }
+}
+
+impl<T, S: Storage<HashMapLayout<T, ()>>, H> HashSet<T, S, H> {
+    /// Constructs a new, empty hash set using the specified storage and hash builder.
+    pub fn from_storage_and_hasher(buf: S, hash_builder: H) -> Self {
+        HashSet { map: HashMap::from_storage_and_hasher(buf, hash_builder) }
+    }
+
+    /// Returns a reference to the set's [`BuildHasher`].
+    #[inline]
+    pub fn hasher(&self) -> &H {
+        self.map.hasher()
+    }
+
+    /// Returns the number of elements the set can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    /// Returns the number of elements in the set.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the set contains no elements, or `false` otherwise.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns `true` if the set contains the maximum number of elements it can hold, or `false` otherwise.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.map.is_full()
+    }
+
+    /// Removes all elements from the set.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// An iterator visiting all elements of the set in arbitrary order. The iterator element type is `&'a T`.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T, S, H> {
+        Iter { base: self.map.keys() }
+    }
+
+    /// Clears the set, returning all elements in an iterator.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut set = coca::collections::InlineHashSet::<u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// set.insert(1); set.insert(2); set.insert(3);
+    ///
+    /// for i in set.drain() {
+    ///     println!("{}", i);
+    /// }
+    ///
+    /// assert!(set.is_empty());
+    /// ```
+    #[inline]
+    pub fn drain(&mut self) -> Drain<'_, T, S, H> {
+        Drain { base: self.map.drain() }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, removes all elements `e` such that `f(&e)` returns `false`.
+    /// The elements are visited in unsorted (and unspecified) order.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut set = coca::collections::InlineHashSet::<u32, BuildHasherDefault<FxHasher>, 8>::new();
+    /// (0..8).for_each(|x| { set.insert(x); });
+    /// set.retain(|&x| x % 2 == 0);
+    /// assert_eq!(set.len(), 4);
+    /// ```
+    #[inline]
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut pred: F) {
+        self.map.retain(|k, ()| pred(k));
+    }
+}
+
+impl<T: Hash + Eq, S: Storage<HashMapLayout<T, ()>>, H: BuildHasher> HashSet<T, S, H> {
+    /// Returns an iterator visiting the values representing the set difference,
+    /// i.e. the values that are in `self` but not in `other`.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::{InlineHashSet, InlineVec};
+    /// use core::hash::BuildHasherDefault;
+    /// type Set = InlineHashSet<u32, BuildHasherDefault<FxHasher>, 8>;
+    ///
+    /// let mut a = Set::new();
+    /// (1..4).for_each(|x| { a.insert(x); });
+    ///
+    /// let mut b = Set::new();
+    /// (2..5).for_each(|x| { b.insert(x); });
+    ///
+    /// let mut d = InlineVec::<u32, 4>::new();
+    /// d.extend(a.difference(&b).cloned());
+    /// assert_eq!(d, [1]);
+    ///
+    /// d.clear();
+    /// d.extend(b.difference(&a).cloned());
+    /// assert_eq!(d, [4]);
+    /// ```
+    #[inline]
+    pub fn difference<'a, S2: Storage<HashMapLayout<T, ()>>, H2: BuildHasher>(&'a self, other: &'a HashSet<T, S2, H2>) -> Difference<'a, T, S, H, S2, H2> {
+        Difference { iter: self.iter(), other }
+    }
+
+    /// Returns an iterator visiting the values representing the symmetric difference,
+    /// i.e. the values that are in `self` or in `other`, but not both.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::{InlineHashSet, InlineVec};
+    /// use core::hash::BuildHasherDefault;
+    /// type Set = InlineHashSet<u32, BuildHasherDefault<FxHasher>, 8>;
+    ///
+    /// let mut a = Set::new();
+    /// (1..4).for_each(|x| { a.insert(x); });
+    ///
+    /// let mut b = Set::new();
+    /// (2..5).for_each(|x| { b.insert(x); });
+    ///
+    /// let mut d = InlineVec::<u32, 4>::new();
+    /// d.extend(a.symmetric_difference(&b).cloned());
+    /// d.sort();
+    ///
+    /// assert_eq!(&d, &[1, 4]);
+    /// ```
+    #[inline]
+    pub fn symmetric_difference<'a, S2: Storage<HashMapLayout<T, ()>>, H2: BuildHasher>(&'a self, other: &'a HashSet<T, S2, H2>) -> SymmetricDifference<'a, T, S, H, S2, H2> {
+        SymmetricDifference { front: self.difference(other), back: other.difference(self) }
+    }
+
+    /// Returns an iterator visiting the values representing the intersection,
+    /// i.e. the values that are both in `self` and `other`.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::{InlineHashSet, InlineVec};
+    /// use core::hash::BuildHasherDefault;
+    /// type Set = InlineHashSet<u32, BuildHasherDefault<FxHasher>, 8>;
+    ///
+    /// let mut a = Set::new();
+    /// (1..4).for_each(|x| { a.insert(x); });
+    ///
+    /// let mut b = Set::new();
+    /// (2..5).for_each(|x| { b.insert(x); });
+    ///
+    /// let mut i = InlineVec::<u32, 4>::new();
+    /// i.extend(a.intersection(&b).cloned());
+    /// i.sort();
+    ///
+    /// assert_eq!(&i, &[2, 3]);
+    /// ```
+    #[inline]
+    pub fn intersection<'a, S2: Storage<HashMapLayout<T, ()>>, H2: BuildHasher>(&'a self, other: &'a HashSet<T, S2, H2>) -> Intersection<'a, T, S, H, S2, H2> {
+        Intersection { iter: self.iter(), other }
+    }
+
+    /// Returns an iterator visiting the values representing the union,
+    /// i.e. all values in `self` or `other`, without duplicates.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::InlineHashSet;
+    /// use core::hash::BuildHasherDefault;
+    /// type Set = InlineHashSet<u32, BuildHasherDefault<FxHasher>, 8>;
+    ///
+    /// let mut a = Set::new();
+    /// (1..4).for_each(|x| { a.insert(x); });
+    ///
+    /// let mut b = Set::new();
+    /// (2..5).for_each(|x| { b.insert(x); });
+    ///
+    /// let mut u = Set::new();
+    /// a.union(&b).for_each(|x| { u.insert_unique_unchecked(*x); });
+    /// (1..5).for_each(|x| assert!(u.contains(&x)));
+    /// ```
+    #[inline]
+    pub fn union<'a, S2: Storage<HashMapLayout<T, ()>>, H2: BuildHasher>(&'a self, other: &'a HashSet<T, S2, H2>) -> Union<'a, T, S, H, S2, H2> {
+        Union { front: self.iter(), back: other.difference(self) }
+    }
+
+    /// Returns `true` if the set contains a value equal to the given value, or `false` otherwise.
+    ///
+    /// The value may be any borrowed form of the set's element type, but
+    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for the
+    /// element type.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut set = coca::collections::InlineHashSet::<u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// set.insert(1);
+    ///
+    /// assert_eq!(set.contains(&1), true);
+    /// assert_eq!(set.contains(&2), false);
+    /// ```
+    #[inline]
+    pub fn contains<Q: ?Sized + Hash + Eq>(&self, value: &Q) -> bool where T: Borrow<Q> {
+        self.map.contains_key(value)
+    }
+
+    /// Returns a reference to the value in the set, if any, that is equal to the given value.
+    ///
+    /// The value may be any borrowed form of the set's element type, but
+    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for the
+    /// element type.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut set = coca::collections::InlineHashSet::<u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// set.insert(1);
+    ///
+    /// assert_eq!(set.get(&1), Some(&1));
+    /// assert_eq!(set.get(&2), None);
+    /// ```
+    #[inline]
+    pub fn get<Q: ?Sized + Hash + Eq>(&self, value: &Q) -> Option<&T> where T: Borrow<Q> {
+        self.map.get_key_value(value).map(|(k, ())| k)
+    }
+
+    /// Returns `true` if the set has no elements in common with `other`.
+    /// This is equivalent to checking for an empty intersection.
+    ///
+    /// `other` may be any type implementing [`SetLike`], such as a
+    /// [`ListSet`](crate::collections::list_set::ListSet).
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::{InlineHashSet, InlineListSet};
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut a = InlineHashSet::<u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// a.insert(1); a.insert(2); a.insert(3);
+    ///
+    /// let mut b = InlineListSet::<u32, 4>::new();
+    /// assert_eq!(a.is_disjoint(&b), true);
+    ///
+    /// b.insert(4);
+    /// assert_eq!(a.is_disjoint(&b), true);
+    ///
+    /// b.insert(1);
+    /// assert_eq!(a.is_disjoint(&b), false);
+    /// ```
+    pub fn is_disjoint<O: SetLike<T>>(&self, other: &O) -> bool {
+        if self.len() <= other.len() {
+            self.iter().all(|item| !other.contains(item))
+        } else {
+            other.all(|item| !self.contains(item))
+        }
+    }
+
+    /// Returns `true` if the set is a subset of another,
+    /// i.e. `other` contains at least all the values in `self`.
+    ///
+    /// `other` may be any type implementing [`SetLike`], such as a
+    /// [`ListSet`](crate::collections::list_set::ListSet).
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::{InlineHashSet, InlineListSet};
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut sup = InlineListSet::<u32, 4>::new();
+    /// sup.insert(1); sup.insert(2); sup.insert(3);
+    ///
+    /// let mut set = InlineHashSet::<u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// assert_eq!(set.is_subset_of(&sup), true);
+    ///
+    /// set.insert(2);
+    /// assert_eq!(set.is_subset_of(&sup), true);
+    ///
+    /// set.insert(5);
+    /// assert_eq!(set.is_subset_of(&sup), false)
+    /// ```
+    pub fn is_subset_of<O: SetLike<T>>(&self, other: &O) -> bool {
+        self.len() <= other.len() && self.iter().all(|item| other.contains(item))
+    }
+
+    /// Returns `true` if the set is a superset of another,
+    /// i.e. `self` contains at least all the values in `other`.
+    ///
+    /// `other` may be any type implementing [`SetLike`], such as a
+    /// [`ListSet`](crate::collections::list_set::ListSet).
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use coca::collections::{InlineHashSet, InlineListSet};
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut sub = InlineListSet::<u32, 4>::new();
+    /// sub.insert(1); sub.insert(2);
+    ///
+    /// let mut set = InlineHashSet::<u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// assert_eq!(set.is_superset_of(&sub), false);
+    ///
+    /// set.insert(0);
+    /// set.insert(1);
+    /// assert_eq!(set.is_superset_of(&sub), false);
+    ///
+    /// set.insert(2);
+    /// assert_eq!(set.is_superset_of(&sub), true);
+    /// ```
+    pub fn is_superset_of<O: SetLike<T>>(&self, other: &O) -> bool {
+        other.len() <= self.len() && other.all(|item| self.contains(item))
+    }
+
+    /// Adds a value to the set.
+    ///
+    /// Returns `false` if the set already contained a value equal to
+    /// the given value, or `true` otherwise. If such a value was already
+    /// present, it is not updated; this matters for types that can be `==`
+    /// without being identical.
+    ///
+    /// # Panics
+    /// Panics if the set did not contain the value, but no space remains to
+    /// insert it. See [`try_insert`](HashSet::try_insert) for a checked version
+    /// that never panics.
+    #[inline]
+    #[track_caller]
+    pub fn insert(&mut self, value: T) -> bool {
+        self.try_insert(value).ok().expect("insufficient capacity")
+    }
+
+    /// Adds a value to the set.
+    ///
+    /// Returns `Ok(false)` if the set already contained a value equal to
+    /// the given value. Otherwise, returns `Ok(true)` if the given value
+    /// was successfully inserted, or `Err(value)` if the remaining space
+    /// is insufficient.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut set = coca::collections::InlineHashSet::<u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// assert_eq!(set.capacity(), 4);
+    ///
+    /// assert_eq!(set.try_insert(1), Ok(true));
+    /// assert_eq!(set.try_insert(2), Ok(true));
+    /// assert_eq!(set.try_insert(2), Ok(false));
+    /// assert_eq!(set.len(), 2);
+    ///
+    /// assert_eq!(set.try_insert(3), Ok(true));
+    /// assert_eq!(set.try_insert(4), Ok(true));
+    /// assert_eq!(set.try_insert(5), Err(5));
+    /// assert_eq!(set.len(), 4);
+    /// ```
+    pub fn try_insert(&mut self, value: T) -> Result<bool, T> {
+        match self.map.try_entry(value)? {
+            Occupied(_) => Ok(false),
+            Vacant(entry) => {
+                entry.insert(());
+                Ok(true)
+            }
+        }
+    }
+
+    /// Inserts a value into the set without checking if it was already part of
+    /// the set, and returns a reference to it.
+    ///
+    /// It is a logic error to insert a duplicate element, and the behavior
+    /// of the resulting set is unspecified, though it is guaranteed to be
+    /// memory-safe.
+    ///
+    /// This operation is faster than regular [`insert`](HashSet::insert),
+    /// because it does not compare any values before insertion. This is useful
+    /// during initial population of the set, e.g. when constructing a set from
+    /// another set, which guarantees unique values.
+    ///
+    /// # Panics
+    /// Panics if the set is already at capacity. See
+    /// [`try_insert_unique_unchecked`](HashSet::try_insert_unique_unchecked)
+    /// for a checked version that never panics.
+    #[inline]
+    #[track_caller]
+    pub fn insert_unique_unchecked(&mut self, value: T) -> &T {
+        self.try_insert_unique_unchecked(value).ok().expect("insufficient capacity")
+    }
+
+    /// Inserts a value into the set without checking if it was already part of
+    /// the set.
+    ///
+    /// Returns a reference to the inserted value, or `Err(value)` if the set
+    /// is already full.
+    ///
+    /// It is a logic error to insert a duplicate element, and the behavior
+    /// of the resulting set is unspecified, though it is guaranteed to be
+    /// memory-safe.
+    ///
+    /// This operation is faster than regular [`try_insert`](HashSet::try_insert),
+    /// because it does not compare any values before insertion. This is useful
+    /// during initial population of the set, e.g. when constructing a set from
+    /// another set, which guarantees unique values.
+    pub fn try_insert_unique_unchecked(&mut self, value: T) -> Result<&T, T> {
+        self.map.try_insert_unique_unchecked(value, ()).map_err(|(value, ())| value)
+    }
+
+    /// Adds a value to the set, replacing the existing value, if any, that is
+    /// equal to the given one. This matters for types that can be `==` without
+    /// being identical.
+    ///
+    /// Returns the replaced value.
+    ///
+    /// # Panics
+    /// Panics if the set is full and does not contain any values equal to
+    /// the given one. See [`try_replace`](HashSet::try_replace) for a checked
+    /// version that never panics.
+    #[track_caller]
+    pub fn replace(&mut self, value: T) -> Option<T> {
+        self.try_replace(value).ok().expect("insufficient capacity")
+    }
+
+    /// Adds a value to the set, replacing the existing value, if any, that is
+    /// equal to the given one. This matters for types that can be `==` without
+    /// being identical.
+    ///
+    /// Returns the replaced value, or `Ok(None)` if the value was successfully
+    /// inserted without replacing any other element, or `Err(value)` if the
+    /// set was already full.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use core::hash::{BuildHasherDefault, Hash, Hasher};
+    ///
+    /// struct Foo(u32, u32);
+    /// impl Eq for Foo {}
+    /// impl PartialEq for Foo {
+    ///     fn eq(&self, other: &Foo) -> bool {
+    ///         self.0 == other.0
+    ///     }
+    /// }
+    /// impl Hash for Foo {
+    ///     fn hash<H: Hasher>(&self, state: &mut H) {
+    ///         self.0.hash(state);
+    ///     }
+    /// }
+    ///
+    /// let mut set = coca::collections::InlineHashSet::<Foo, BuildHasherDefault<FxHasher>, 3>::new();
+    /// set.insert(Foo(1, 0));
+    ///
+    /// if let Ok(Some(foo)) = set.try_replace(Foo(1, 10)) {
+    ///     assert_eq!(foo.0, 1);
+    ///     assert_eq!(foo.1, 0);
+    /// }
+    /// # else {
+    /// #     unreachable!();
+    /// # }
+    ///
+    /// set.insert(Foo(2, 0));
+    /// set.insert(Foo(3, 0));
+    ///
+    /// assert!(set.try_replace(Foo(4, 10)).is_err());
+    /// ```
+    pub fn try_replace(&mut self, value: T) -> Result<Option<T>, T> {
+        match self.map.try_entry(value)? {
+            Occupied(entry) => Ok(Some(entry.replace_key())),
+            Vacant(entry) => {
+                entry.insert(());
+                Ok(None)
+            }
+        }
+    }
+
+    /// Removes a value from the set, and returns whether it was previously present.
+    ///
+    /// The given value may be any borrowed form of the set's element type,
+    /// but [`Hash`] and [`Eq`] on the borrowed form *must* match those for
+    /// the element type.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut set = coca::collections::InlineHashSet::<u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// set.insert(2);
+    ///
+    /// assert_eq!(set.remove(&2), true);
+    /// assert_eq!(set.remove(&2), false);
+    /// ```
+    pub fn remove<Q: ?Sized + Hash + Eq>(&mut self, value: &Q) -> bool where T: Borrow<Q> {
+        self.map.remove(value).is_some()
+    }
+
+    /// Removes and returns the value from the set, if any, that is equal to the given one.
+    ///
+    /// The given value may be any borrowed form of the set's element type,
+    /// but [`Hash`] and [`Eq`] on the borrowed form *must* match those for
+    /// the element type.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate rustc_hash;
+    /// use rustc_hash::FxHasher;
+    /// use core::hash::BuildHasherDefault;
+    ///
+    /// let mut set = coca::collections::InlineHashSet::<u32, BuildHasherDefault<FxHasher>, 4>::new();
+    /// set.insert(2);
+    ///
+    /// assert_eq!(set.take(&2), Some(2));
+    /// assert_eq!(set.take(&2), None);
+    /// ```
+    pub fn take<Q: ?Sized + Hash + Eq>(&mut self, value: &Q) -> Option<T> where T: Borrow<Q> {
+        self.map.remove_entry(value).map(|(k, ())| k)
+    }
+}
+
+impl<T: Hash + Eq, S: Storage<HashMapLayout<T, ()>>, H: BuildHasher> SetLike<T> for HashSet<T, S, H> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    #[inline]
+    fn contains(&self, value: &T) -> bool {
+        self.map.contains_key(value)
+    }
+
+    #[inline]
+    fn all<F: FnMut(&T) -> bool>(&self, pred: F) -> bool {
+        self.map.keys().all(pred)
+    }
+}
+
+impl<T, S1, S2, H1, H2> core::ops::BitAndAssign<&'_ HashSet<T, S2, H2>> for HashSet<T, S1, H1>
+where
+    T: Hash + Eq,
+    S1: Storage<HashMapLayout<T, ()>>,
+    S2: Storage<HashMapLayout<T, ()>>,
+    H2: BuildHasher,
+{
+    fn bitand_assign(&mut self, rhs: &HashSet<T, S2, H2>) {
+        self.retain(|item| rhs.contains(item));
+    }
+}
+
+impl<T, S1, S2, H1, H2> core::ops::BitOrAssign<&'_ HashSet<T, S2, H2>> for HashSet<T, S1, H1>
+where
+    T: Clone + Hash + Eq,
+    S1: Storage<HashMapLayout<T, ()>>,
+    S2: Storage<HashMapLayout<T, ()>>,
+    H1: BuildHasher,
+{
+    fn bitor_assign(&mut self, rhs: &HashSet<T, S2, H2>) {
+        for x in rhs {
+            if !self.contains(x) {
+                self.insert_unique_unchecked(x.clone());
+            }
+        }
+    }
+}
+
+impl<T: Debug, S: Storage<HashMapLayout<T, ()>>, H> Debug for HashSet<T, S, H> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Hash + Eq, S: Storage<HashMapLayout<T, ()>>, H: BuildHasher> Extend<T> for HashSet<T, S, H> {
+    fn extend<It: IntoIterator<Item = T>>(&mut self, iter: It) {
+        iter.into_iter().for_each(|x| { self.insert(x); });
+    }
+}
+
+impl<'a, T: Clone + Hash + Eq, S: Storage<HashMapLayout<T, ()>>, H: BuildHasher> Extend<&'a T> for HashSet<T, S, H> {
+    fn extend<It: IntoIterator<Item = &'a T>>(&mut self, iter: It) {
+        iter.into_iter().for_each(|x| { self.insert(x.clone()); });
+    }
+}
+
+impl<T, S: Storage<HashMapLayout<T, ()>>, H> IntoIterator for HashSet<T, S, H> {
+    type IntoIter = IntoIter<T, S, H>;
+    type Item = T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { base: self.map.into_keys() }
+    }
+}
+
+impl<'a, T, S: Storage<HashMapLayout<T, ()>>, H> IntoIterator for &'a HashSet<T, S, H> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T, S, H>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T, S1, S2, H1, H2> PartialEq<HashSet<T, S2, H2>> for HashSet<T, S1, H1>
+where
+    T: Hash + Eq,
+    S1: Storage<HashMapLayout<T, ()>>,
+    S2: Storage<HashMapLayout<T, ()>>,
+    H1: BuildHasher,
+    H2: BuildHasher,
+{
+    fn eq(&self, other: &HashSet<T, S2, H2>) -> bool {
+        self.len() == other.len() && self.is_subset_of(other)
+    }
+}
+
+impl<T: Hash + Eq, S: Storage<HashMapLayout<T, ()>>, H: BuildHasher> Eq for HashSet<T, S, H> {}
+
+impl<T, S: Storage<HashMapLayout<T, ()>>, H> Clone for HashSet<T, S, H> where HashMap<T, (), S, H>: Clone {
+    fn clone(&self) -> Self {
+        HashSet { map: self.map.clone() }
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+impl<T: Hash + Eq, H: BuildHasher> HashSet<T, crate::storage::AllocStorage<HashMapLayout<T, ()>>, H> {
+    /// Constructs a new, empty [`AllocHashSet`](crate::collections::AllocHashSet)
+    /// with the specified capacity and hash builder.
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: H) -> Self {
+        HashSet { map: HashMap::with_capacity_and_hasher(capacity, hash_builder) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+impl<T: Hash + Eq, H: Hasher + Default> HashSet<T, crate::storage::AllocStorage<HashMapLayout<T, ()>>, BuildHasherDefault<H>> {
+    /// Constructs a new, empty [`AllocHashSet`](crate::collections::AllocHashSet)
+    /// with the specified capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        HashSet { map: HashMap::with_capacity(capacity) }
+    }
+}
+
+impl<T: Hash + Eq, H: BuildHasher, const N: usize> HashSet<T, InlineStorage<T, (), N>, H> {
+    /// Constructs a new, empty [`InlineHashSet`](crate::collections::InlineHashSet)
+    /// using the specified hash builder.
+    pub fn with_hasher(hash_builder: H) -> Self {
+        HashSet { map: HashMap::with_hasher(hash_builder) }
+    }
+}
+
+impl<T: Hash + Eq, H: Hasher + Default, const N: usize> HashSet<T, InlineStorage<T, (), N>, BuildHasherDefault<H>> {
+    /// Constructs a new, empty [`InlineHashSet`](crate::collections::InlineHashSet).
+    pub fn new() -> Self {
+        HashSet { map: HashMap::new() }
+    }
+}
+
+impl<T: Hash + Eq, H: Hasher + Default, const N: usize> Default for HashSet<T, InlineStorage<T, (), N>, BuildHasherDefault<H>> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An iterator over the elements of a [`HashSet`].
+///
+/// This `struct` is created by the [`iter`](HashSet::iter) method on `HashSet`.
+/// See its documentation for more.
+pub struct Iter<'a, T, S: Storage<HashMapLayout<T, ()>>, H> {
+    base: hash_map::Keys<'a, T, (), S, H>,
+}
+
+impl<'a, T, S: Storage<HashMapLayout<T, ()>>, H> Iterator for Iter<'a, T, S, H> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.base.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.base.size_hint()
+    }
+}
+
+impl<T, S: Storage<HashMapLayout<T, ()>>, H> ExactSizeIterator for Iter<'_, T, S, H> {}
+impl<T, S: Storage<HashMapLayout<T, ()>>, H> FusedIterator for Iter<'_, T, S, H> {}
+
+/// An owning iterator over the elements of a [`HashSet`].
+///
+/// This `struct` is created by the [`into_iter`](IntoIterator::into_iter)
+/// method on `HashSet` (provided by the [`IntoIterator`] trait). See its
+/// documentation for more.
+pub struct IntoIter<T, S: Storage<HashMapLayout<T, ()>>, H> {
+    base: hash_map::IntoKeys<T, (), S, H>,
+}
+
+impl<T, S: Storage<HashMapLayout<T, ()>>, H> Iterator for IntoIter<T, S, H> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.base.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.base.size_hint()
+    }
+}
+
+impl<T, S: Storage<HashMapLayout<T, ()>>, H> ExactSizeIterator for IntoIter<T, S, H> {}
+impl<T, S: Storage<HashMapLayout<T, ()>>, H> FusedIterator for IntoIter<T, S, H> {}
+
+/// A draining iterator over the elements of a [`HashSet`].
+///
+/// This `struct` is created by the [`drain`](HashSet::drain) method on `HashSet`.
+/// See its documentation for more.
+pub struct Drain<'a, T, S: Storage<HashMapLayout<T, ()>>, H> {
+    base: hash_map::Drain<'a, T, (), S, H>,
+}
+
+impl<T, S: Storage<HashMapLayout<T, ()>>, H> Iterator for Drain<'_, T, S, H> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.base.next().map(|(k, ())| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.base.size_hint()
+    }
+}
+
+impl<T, S: Storage<HashMapLayout<T, ()>>, H> ExactSizeIterator for Drain<'_, T, S, H> {}
+impl<T, S: Storage<HashMapLayout<T, ()>>, H> FusedIterator for Drain<'_, T, S, H> {}
+
+/// A lazy iterator producing elements in the difference between two [`HashSet`]s.
+///
+/// This `struct` is created by the [`difference`](HashSet::difference)
+/// method on `HashSet`. See its documentation for more.
+pub struct Difference<'a, T, S1, H1, S2, H2>
+where
+    S1: Storage<HashMapLayout<T, ()>>,
+    S2: Storage<HashMapLayout<T, ()>>,
+{
+    iter: Iter<'a, T, S1, H1>,
+    other: &'a HashSet<T, S2, H2>,
+}
+
+impl<'a, T, S1, H1, S2, H2> Iterator for Difference<'a, T, S1, H1, S2, H2>
+where
+    T: Hash + Eq,
+    S1: Storage<HashMapLayout<T, ()>>,
+    S2: Storage<HashMapLayout<T, ()>>,
+    H2: BuildHasher,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let other = self.other;
+        self.iter.find(|item| !other.contains(*item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, max_len) = self.iter.size_hint();
+        (0, max_len)
+    }
+}
+
+impl<T, S1, H1, S2, H2> FusedIterator for Difference<'_, T, S1, H1, S2, H2>
+where
+    T: Hash + Eq,
+    S1: Storage<HashMapLayout<T, ()>>,
+    S2: Storage<HashMapLayout<T, ()>>,
+    H2: BuildHasher,
+{}
+
+/// A lazy iterator producing elements in the symmetric difference of [`HashSet`]s.
+///
+/// This `struct` is created by the [`symmetric_difference`](HashSet::symmetric_difference)
+/// method on `HashSet`. See its documentation for more.
+pub struct SymmetricDifference<'a, T, S1, H1, S2, H2>
+where
+    S1: Storage<HashMapLayout<T, ()>>,
+    S2: Storage<HashMapLayout<T, ()>>,
+{
+    front: Difference<'a, T, S1, H1, S2, H2>,
+    back: Difference<'a, T, S2, H2, S1, H1>,
+}
+
+impl<'a, T, S1, H1, S2, H2> Iterator for SymmetricDifference<'a, T, S1, H1, S2, H2>
+where
+    T: Hash + Eq,
+    S1: Storage<HashMapLayout<T, ()>>,
+    S2: Storage<HashMapLayout<T, ()>>,
+    H1: BuildHasher,
+    H2: BuildHasher,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.front.next().or_else(|| self.back.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, front_max) = self.front.size_hint();
+        let (_, back_max) = self.back.size_hint();
+        (0, front_max.zip(back_max).map(|(a, b)| a + b))
+    }
+}
+
+impl<T, S1, H1, S2, H2> FusedIterator for SymmetricDifference<'_, T, S1, H1, S2, H2>
+where
+    T: Hash + Eq,
+    S1: Storage<HashMapLayout<T, ()>>,
+    S2: Storage<HashMapLayout<T, ()>>,
+    H1: BuildHasher,
+    H2: BuildHasher,
+{}
+
+/// A lazy iterator producing elements in the intersection of [`HashSet`]s.
+///
+/// This struct is created by the [`intersection`](HashSet::intersection)
+/// method on `HashSet`. See its documentation for more.
+pub struct Intersection<'a, T, S1, H1, S2, H2>
+where
+    S1: Storage<HashMapLayout<T, ()>>,
+    S2: Storage<HashMapLayout<T, ()>>,
+{
+    iter: Iter<'a, T, S1, H1>,
+    other: &'a HashSet<T, S2, H2>,
+}
+
+impl<'a, T, S1, H1, S2, H2> Iterator for Intersection<'a, T, S1, H1, S2, H2>
+where
+    T: Hash + Eq,
+    S1: Storage<HashMapLayout<T, ()>>,
+    S2: Storage<HashMapLayout<T, ()>>,
+    H2: BuildHasher,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let other = self.other;
+        self.iter.find(|item| other.contains(*item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, max_len) = self.iter.size_hint();
+        (0, max_len)
+    }
+}
+
+impl<T, S1, H1, S2, H2> FusedIterator for Intersection<'_, T, S1, H1, S2, H2>
+where
+    T: Hash + Eq,
+    S1: Storage<HashMapLayout<T, ()>>,
+    S2: Storage<HashMapLayout<T, ()>>,
+    H2: BuildHasher,
+{}
+
+/// A lazy iterator producing elements in the union of [`HashSet`]s.
+///
+/// This `struct` is created by the [`union`](HashSet::union)
+/// method on `HashSet`. See its documentation for more.
+pub struct Union<'a, T, S1, H1, S2, H2>
+where
+    S1: Storage<HashMapLayout<T, ()>>,
+    S2: Storage<HashMapLayout<T, ()>>,
+{
+    front: Iter<'a, T, S1, H1>,
+    back: Difference<'a, T, S2, H2, S1, H1>,
+}
+
+impl<'a, T, S1, H1, S2, H2> Iterator for Union<'a, T, S1, H1, S2, H2>
+where
+    T: Hash + Eq,
+    S1: Storage<HashMapLayout<T, ()>>,
+    S2: Storage<HashMapLayout<T, ()>>,
+    H1: BuildHasher,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.front.next().or_else(|| self.back.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (min_len, _) = self.front.size_hint();
+        let (_, back_max) = self.back.size_hint();
+        (min_len, back_max.map(|b| min_len + b))
+    }
+}
+
+impl<T, S1, H1, S2, H2> FusedIterator for Union<'_, T, S1, H1, S2, H2>
+where
+    T: Hash + Eq,
+    S1: Storage<HashMapLayout<T, ()>>,
+    S2: Storage<HashMapLayout<T, ()>>,
+    H1: BuildHasher,
+{}
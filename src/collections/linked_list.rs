@@ -0,0 +1,1096 @@
+//! A doubly-linked list with nodes stored in a [`DirectPool`].
+//!
+//! Unlike most collection types, a [`LinkedList`] does not own its storage.
+//! Instead, its elements are wrapped in [`Node`]s that live in a separately
+//! owned [`DirectPool`], which is passed into every method accessing the list's
+//! contents. This allows multiple lists to share a single pool, and elements
+//! to be moved between them in constant time, without changing their handles.
+//!
+//! Each element is identified by the handle of its node. Because handles carry
+//! a generation count, using a stale handle to an element that was removed from
+//! the pool is detected rather than silently referring to a different element.
+//!
+//! It is a logic error to use a handle to an element of one list with another
+//! list, or to remove a list's nodes from the pool directly. Methods unlinking
+//! an element, such as [`LinkedList::remove`], panic when passed the handle of
+//! the first or last element of another list, or any handle when the list is
+//! empty; handles of other elements cannot be told apart in constant time.
+//! The behavior resulting from such a logic error is not specified, but will
+//! not result in undefined behavior.
+
+use core::fmt::Debug;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+
+use crate::collections::pool::direct::{DirectPool, DirectPoolLayout};
+use crate::collections::pool::{DefaultHandle, Handle};
+use crate::storage::Storage;
+
+/// A node of a [`LinkedList`], wrapping a single element along with the
+/// handles of its neighbors.
+#[derive(Clone)]
+pub struct Node<T, H = DefaultHandle> {
+    value: T,
+    prev: Option<H>,
+    next: Option<H>,
+}
+
+impl<T, H: Handle> Node<T, H> {
+    /// Returns a reference to the element.
+    #[inline]
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns a mutable reference to the element.
+    #[inline]
+    pub fn value_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+
+    /// Consumes the node, returning the element.
+    #[inline]
+    pub fn into_value(self) -> T {
+        self.value
+    }
+
+    /// Returns the handle of the preceding node, or [`None`] if this is the
+    /// first node in its list.
+    #[inline]
+    pub fn prev(&self) -> Option<H> {
+        self.prev
+    }
+
+    /// Returns the handle of the following node, or [`None`] if this is the
+    /// last node in its list.
+    #[inline]
+    pub fn next(&self) -> Option<H> {
+        self.next
+    }
+}
+
+impl<T: Debug, H: Handle> Debug for Node<T, H> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Node")
+            .field("value", &self.value)
+            .field("prev", &self.prev)
+            .field("next", &self.next)
+            .finish()
+    }
+}
+
+/// The [`LayoutSpec`](crate::storage::LayoutSpec) for the pool backing a [`LinkedList`].
+pub type LinkedListLayout<T, H = DefaultHandle> = DirectPoolLayout<Node<T, H>, H>;
+
+/// A doubly-linked list of elements stored in a shared [`DirectPool`].
+///
+/// See the [module-level documentation](self) for details.
+///
+/// Dropping a list does not remove its nodes from the pool; use
+/// [`clear`](LinkedList::clear) to do so.
+///
+/// # Examples
+/// ```
+/// use coca::collections::DirectInlinePool;
+/// use coca::collections::linked_list::{LinkedList, Node};
+///
+/// let mut pool = DirectInlinePool::<Node<u32>, 8>::new();
+/// let mut ready = LinkedList::new();
+/// let mut waiting = LinkedList::new();
+///
+/// let a = ready.push_back(&mut pool, 1);
+/// let b = ready.push_back(&mut pool, 2);
+/// waiting.push_back(&mut pool, 3);
+///
+/// ready.move_to_back(&mut pool, a);
+/// assert!(ready.iter(&pool).map(|(_, x)| *x).eq([2, 1]));
+///
+/// ready.append(&mut pool, &mut waiting);
+/// assert!(ready.iter(&pool).map(|(_, x)| *x).eq([2, 1, 3]));
+/// assert!(waiting.is_empty());
+///
+/// assert_eq!(ready.remove(&mut pool, b), Some(2));
+/// assert_eq!(ready.remove(&mut pool, b), None);
+/// ```
+pub struct LinkedList<T, H: Handle = DefaultHandle> {
+    head: Option<H>,
+    tail: Option<H>,
+    len: usize,
+    elements: PhantomData<fn() -> T>,
+}
+
+impl<T, H: Handle> Default for LinkedList<T, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, H: Handle> Debug for LinkedList<T, H> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LinkedList")
+            .field("head", &self.head)
+            .field("tail", &self.tail)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+#[inline(always)]
+fn node<T, S: Storage<LinkedListLayout<T, H>>, H: Handle>(pool: &DirectPool<Node<T, H>, S, H>, handle: H) -> &Node<T, H> {
+    pool.get(handle).expect("invalid linked list node handle")
+}
+
+#[inline(always)]
+fn node_mut<T, S: Storage<LinkedListLayout<T, H>>, H: Handle>(pool: &mut DirectPool<Node<T, H>, S, H>, handle: H) -> &mut Node<T, H> {
+    pool.get_mut(handle).expect("invalid linked list node handle")
+}
+
+impl<T, H: Handle> LinkedList<T, H> {
+    /// Constructs a new, empty `LinkedList`.
+    pub fn new() -> Self {
+        LinkedList { head: None, tail: None, len: 0, elements: PhantomData }
+    }
+
+    /// Returns the number of elements in the list.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the list contains no elements, or `false` otherwise.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the handle of the first element, or [`None`] if the list is empty.
+    #[inline]
+    pub fn front_handle(&self) -> Option<H> {
+        self.head
+    }
+
+    /// Returns the handle of the last element, or [`None`] if the list is empty.
+    #[inline]
+    pub fn back_handle(&self) -> Option<H> {
+        self.tail
+    }
+
+    /// Returns a reference to the first element, or [`None`] if the list is empty.
+    pub fn front<'a, S: Storage<LinkedListLayout<T, H>>>(&self, pool: &'a DirectPool<Node<T, H>, S, H>) -> Option<&'a T> {
+        self.head.map(|h| &node(pool, h).value)
+    }
+
+    /// Returns a mutable reference to the first element, or [`None`] if the list is empty.
+    pub fn front_mut<'a, S: Storage<LinkedListLayout<T, H>>>(&self, pool: &'a mut DirectPool<Node<T, H>, S, H>) -> Option<&'a mut T> {
+        self.head.map(move |h| &mut node_mut(pool, h).value)
+    }
+
+    /// Returns a reference to the last element, or [`None`] if the list is empty.
+    pub fn back<'a, S: Storage<LinkedListLayout<T, H>>>(&self, pool: &'a DirectPool<Node<T, H>, S, H>) -> Option<&'a T> {
+        self.tail.map(|h| &node(pool, h).value)
+    }
+
+    /// Returns a mutable reference to the last element, or [`None`] if the list is empty.
+    pub fn back_mut<'a, S: Storage<LinkedListLayout<T, H>>>(&self, pool: &'a mut DirectPool<Node<T, H>, S, H>) -> Option<&'a mut T> {
+        self.tail.map(move |h| &mut node_mut(pool, h).value)
+    }
+
+    /// Links the detached node `handle` in between `prev` and `next`, which
+    /// must be adjacent in this list (or `None` for the list's ends).
+    fn attach<S: Storage<LinkedListLayout<T, H>>>(&mut self, pool: &mut DirectPool<Node<T, H>, S, H>, handle: H, prev: Option<H>, next: Option<H>) {
+        let n = node_mut(pool, handle);
+        n.prev = prev;
+        n.next = next;
+
+        match prev {
+            Some(p) => node_mut(pool, p).next = Some(handle),
+            None => self.head = Some(handle),
+        }
+
+        match next {
+            Some(n) => node_mut(pool, n).prev = Some(handle),
+            None => self.tail = Some(handle),
+        }
+
+        self.len += 1;
+    }
+
+    /// Unlinks the node `handle` from this list, without removing it from the pool.
+    ///
+    /// # Panics
+    /// Panics if the list is empty, or if the node is the first or last node
+    /// of a different list, leaving both lists unchanged.
+    #[track_caller]
+    fn detach<S: Storage<LinkedListLayout<T, H>>>(&mut self, pool: &mut DirectPool<Node<T, H>, S, H>, handle: H) {
+        #[cold]
+        #[inline(never)]
+        #[track_caller]
+        fn assert_failed() -> ! {
+            panic!("node does not belong to this list")
+        }
+
+        let n = node(pool, handle);
+        let (prev, next) = (n.prev, n.next);
+        let is_head = prev.is_none();
+        let is_tail = next.is_none();
+        if self.len == 0 || (is_head && self.head != Some(handle)) || (is_tail && self.tail != Some(handle)) {
+            assert_failed();
+        }
+
+        let n = node_mut(pool, handle);
+        n.prev = None;
+        n.next = None;
+
+        if let Some(p) = prev {
+            node_mut(pool, p).next = next;
+        } else {
+            self.head = next;
+        }
+
+        if let Some(n) = next {
+            node_mut(pool, n).prev = prev;
+        } else {
+            self.tail = prev;
+        }
+
+        self.len -= 1;
+    }
+
+    fn try_insert_between<S: Storage<LinkedListLayout<T, H>>>(&mut self, pool: &mut DirectPool<Node<T, H>, S, H>, value: T, prev: Option<H>, next: Option<H>) -> Result<H, T> {
+        let handle = pool.try_insert(Node { value, prev: None, next: None }).map_err(Node::into_value)?;
+        self.attach(pool, handle, prev, next);
+        Ok(handle)
+    }
+
+    /// Moves all elements of `other` in between `prev` and `next`, which must
+    /// be adjacent in this list (or `None` for the list's ends), leaving `other` empty.
+    fn splice_between<S: Storage<LinkedListLayout<T, H>>>(&mut self, pool: &mut DirectPool<Node<T, H>, S, H>, other: &mut Self, prev: Option<H>, next: Option<H>) {
+        if let (Some(other_head), Some(other_tail)) = (other.head.take(), other.tail.take()) {
+            node_mut(pool, other_head).prev = prev;
+            node_mut(pool, other_tail).next = next;
+
+            match prev {
+                Some(p) => node_mut(pool, p).next = Some(other_head),
+                None => self.head = Some(other_head),
+            }
+
+            match next {
+                Some(n) => node_mut(pool, n).prev = Some(other_tail),
+                None => self.tail = Some(other_tail),
+            }
+
+            self.len += other.len;
+            other.len = 0;
+        }
+    }
+
+    /// Adds an element to the front of the list, returning its handle.
+    ///
+    /// Returns [`Err`] containing the element if the pool is already full.
+    pub fn try_push_front<S: Storage<LinkedListLayout<T, H>>>(&mut self, pool: &mut DirectPool<Node<T, H>, S, H>, value: T) -> Result<H, T> {
+        self.try_insert_between(pool, value, None, self.head)
+    }
+
+    /// Adds an element to the front of the list, returning its handle.
+    ///
+    /// # Panics
+    /// Panics if the pool is already full. See
+    /// [`try_push_front`](LinkedList::try_push_front) for a checked version.
+    #[track_caller]
+    pub fn push_front<S: Storage<LinkedListLayout<T, H>>>(&mut self, pool: &mut DirectPool<Node<T, H>, S, H>, value: T) -> H {
+        self.try_push_front(pool, value).ok().expect("pool is already at capacity")
+    }
+
+    /// Adds an element to the back of the list, returning its handle.
+    ///
+    /// Returns [`Err`] containing the element if the pool is already full.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::DirectInlinePool;
+    /// use coca::collections::linked_list::{LinkedList, Node};
+    ///
+    /// let mut pool = DirectInlinePool::<Node<char>, 2>::new();
+    /// let mut list = LinkedList::new();
+    ///
+    /// assert!(list.try_push_back(&mut pool, 'a').is_ok());
+    /// assert!(list.try_push_back(&mut pool, 'b').is_ok());
+    /// assert_eq!(list.try_push_back(&mut pool, 'c'), Err('c'));
+    /// ```
+    pub fn try_push_back<S: Storage<LinkedListLayout<T, H>>>(&mut self, pool: &mut DirectPool<Node<T, H>, S, H>, value: T) -> Result<H, T> {
+        self.try_insert_between(pool, value, self.tail, None)
+    }
+
+    /// Adds an element to the back of the list, returning its handle.
+    ///
+    /// # Panics
+    /// Panics if the pool is already full. See
+    /// [`try_push_back`](LinkedList::try_push_back) for a checked version.
+    #[track_caller]
+    pub fn push_back<S: Storage<LinkedListLayout<T, H>>>(&mut self, pool: &mut DirectPool<Node<T, H>, S, H>, value: T) -> H {
+        self.try_push_back(pool, value).ok().expect("pool is already at capacity")
+    }
+
+    /// Removes the first element from the list and returns it, or [`None`] if
+    /// the list is empty. This invalidates the element's handle.
+    pub fn pop_front<S: Storage<LinkedListLayout<T, H>>>(&mut self, pool: &mut DirectPool<Node<T, H>, S, H>) -> Option<T> {
+        let head = self.head?;
+        self.remove(pool, head)
+    }
+
+    /// Removes the last element from the list and returns it, or [`None`] if
+    /// the list is empty. This invalidates the element's handle.
+    pub fn pop_back<S: Storage<LinkedListLayout<T, H>>>(&mut self, pool: &mut DirectPool<Node<T, H>, S, H>) -> Option<T> {
+        let tail = self.tail?;
+        self.remove(pool, tail)
+    }
+
+    /// Removes the element referred to by the specified handle from the list
+    /// and the pool, returning it unless the handle is invalid. This
+    /// invalidates the handle.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::DirectInlinePool;
+    /// use coca::collections::linked_list::{LinkedList, Node};
+    ///
+    /// let mut pool = DirectInlinePool::<Node<u32>, 4>::new();
+    /// let mut list = LinkedList::new();
+    ///
+    /// list.push_back(&mut pool, 1);
+    /// let h = list.push_back(&mut pool, 2);
+    /// list.push_back(&mut pool, 3);
+    ///
+    /// assert_eq!(list.remove(&mut pool, h), Some(2));
+    /// assert_eq!(list.remove(&mut pool, h), None);
+    /// assert!(list.iter(&pool).map(|(_, x)| *x).eq([1, 3]));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the handle is valid, but detectably refers to an element of
+    /// a different list (see the [module-level documentation](self)).
+    #[track_caller]
+    pub fn remove<S: Storage<LinkedListLayout<T, H>>>(&mut self, pool: &mut DirectPool<Node<T, H>, S, H>, handle: H) -> Option<T> {
+        if !pool.contains(handle) {
+            return None;
+        }
+
+        self.detach(pool, handle);
+        pool.remove(handle).map(Node::into_value)
+    }
+
+    /// Moves the element referred to by the specified handle to the front of
+    /// the list, without invalidating its handle.
+    ///
+    /// Returns `false` if the handle is invalid, or `true` otherwise.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::DirectInlinePool;
+    /// use coca::collections::linked_list::{LinkedList, Node};
+    ///
+    /// let mut pool = DirectInlinePool::<Node<u32>, 4>::new();
+    /// let mut lru = LinkedList::new();
+    ///
+    /// let a = lru.push_front(&mut pool, 1);
+    /// let b = lru.push_front(&mut pool, 2);
+    /// let c = lru.push_front(&mut pool, 3);
+    ///
+    /// assert!(lru.move_to_front(&mut pool, a));
+    /// assert!(lru.iter(&pool).map(|(h, _)| h).eq([a, c, b]));
+    ///
+    /// lru.remove(&mut pool, b);
+    /// assert!(!lru.move_to_front(&mut pool, b));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the handle is valid, but detectably refers to an element of
+    /// a different list (see the [module-level documentation](self)).
+    #[track_caller]
+    pub fn move_to_front<S: Storage<LinkedListLayout<T, H>>>(&mut self, pool: &mut DirectPool<Node<T, H>, S, H>, handle: H) -> bool {
+        if !pool.contains(handle) {
+            return false;
+        }
+
+        if self.head != Some(handle) {
+            self.detach(pool, handle);
+            self.attach(pool, handle, None, self.head);
+        }
+
+        true
+    }
+
+    /// Moves the element referred to by the specified handle to the back of
+    /// the list, without invalidating its handle.
+    ///
+    /// Returns `false` if the handle is invalid, or `true` otherwise.
+    ///
+    /// # Panics
+    /// Panics if the handle is valid, but detectably refers to an element of
+    /// a different list (see the [module-level documentation](self)).
+    #[track_caller]
+    pub fn move_to_back<S: Storage<LinkedListLayout<T, H>>>(&mut self, pool: &mut DirectPool<Node<T, H>, S, H>, handle: H) -> bool {
+        if !pool.contains(handle) {
+            return false;
+        }
+
+        if self.tail != Some(handle) {
+            self.detach(pool, handle);
+            self.attach(pool, handle, self.tail, None);
+        }
+
+        true
+    }
+
+    /// Moves all elements of `other` to the back of `self`, leaving `other` empty.
+    ///
+    /// This operation takes *O*(1) time, and does not invalidate any handles.
+    pub fn append<S: Storage<LinkedListLayout<T, H>>>(&mut self, pool: &mut DirectPool<Node<T, H>, S, H>, other: &mut Self) {
+        self.splice_between(pool, other, self.tail, None);
+    }
+
+    /// Moves all elements of `other` to the front of `self`, leaving `other` empty.
+    ///
+    /// This operation takes *O*(1) time, and does not invalidate any handles.
+    pub fn prepend<S: Storage<LinkedListLayout<T, H>>>(&mut self, pool: &mut DirectPool<Node<T, H>, S, H>, other: &mut Self) {
+        self.splice_between(pool, other, None, self.head);
+    }
+
+    /// Removes all elements from the list and the pool, invalidating their handles.
+    pub fn clear<S: Storage<LinkedListLayout<T, H>>>(&mut self, pool: &mut DirectPool<Node<T, H>, S, H>) {
+        let mut next = self.head;
+        while let Some(handle) = next {
+            next = pool.remove(handle).and_then(|n| n.next);
+        }
+
+        *self = Self::new();
+    }
+
+    /// Returns an iterator visiting all handle-element pairs from front to back.
+    pub fn iter<'a, S: Storage<LinkedListLayout<T, H>>>(&self, pool: &'a DirectPool<Node<T, H>, S, H>) -> Iter<'a, T, S, H> {
+        Iter { pool, front: self.head, back: self.tail, len: self.len }
+    }
+
+    /// Returns an iterator visiting all handle-element pairs from front to
+    /// back, with mutable references to the elements.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::DirectInlinePool;
+    /// use coca::collections::linked_list::{LinkedList, Node};
+    ///
+    /// let mut pool = DirectInlinePool::<Node<u32>, 4>::new();
+    /// let mut list = LinkedList::new();
+    /// list.push_back(&mut pool, 1);
+    /// list.push_back(&mut pool, 2);
+    ///
+    /// for (_, x) in list.iter_mut(&mut pool) {
+    ///     *x *= 10;
+    /// }
+    ///
+    /// assert!(list.iter(&pool).map(|(_, x)| *x).eq([10, 20]));
+    /// ```
+    pub fn iter_mut<'a, S: Storage<LinkedListLayout<T, H>>>(&self, pool: &'a mut DirectPool<Node<T, H>, S, H>) -> IterMut<'a, T, S, H> {
+        IterMut { pool, front: self.head, back: self.tail, len: self.len }
+    }
+
+    /// Returns a cursor pointing at the first element of the list, or at the
+    /// "ghost" non-element if the list is empty.
+    pub fn cursor_front<'a, S: Storage<LinkedListLayout<T, H>>>(&'a self, pool: &'a DirectPool<Node<T, H>, S, H>) -> Cursor<'a, T, S, H> {
+        Cursor { list: self, pool, current: self.head }
+    }
+
+    /// Returns a cursor pointing at the last element of the list, or at the
+    /// "ghost" non-element if the list is empty.
+    pub fn cursor_back<'a, S: Storage<LinkedListLayout<T, H>>>(&'a self, pool: &'a DirectPool<Node<T, H>, S, H>) -> Cursor<'a, T, S, H> {
+        Cursor { list: self, pool, current: self.tail }
+    }
+
+    /// Returns a cursor pointing at the element referred to by the specified
+    /// handle, or [`None`] if the handle is invalid.
+    pub fn cursor_at<'a, S: Storage<LinkedListLayout<T, H>>>(&'a self, pool: &'a DirectPool<Node<T, H>, S, H>, handle: H) -> Option<Cursor<'a, T, S, H>> {
+        if !pool.contains(handle) { return None; }
+        Some(Cursor { list: self, pool, current: Some(handle) })
+    }
+
+    /// Returns a cursor with editing operations pointing at the first element
+    /// of the list, or at the "ghost" non-element if the list is empty.
+    pub fn cursor_front_mut<'a, S: Storage<LinkedListLayout<T, H>>>(&'a mut self, pool: &'a mut DirectPool<Node<T, H>, S, H>) -> CursorMut<'a, T, S, H> {
+        let current = self.head;
+        CursorMut { list: self, pool, current }
+    }
+
+    /// Returns a cursor with editing operations pointing at the last element
+    /// of the list, or at the "ghost" non-element if the list is empty.
+    pub fn cursor_back_mut<'a, S: Storage<LinkedListLayout<T, H>>>(&'a mut self, pool: &'a mut DirectPool<Node<T, H>, S, H>) -> CursorMut<'a, T, S, H> {
+        let current = self.tail;
+        CursorMut { list: self, pool, current }
+    }
+
+    /// Returns a cursor with editing operations pointing at the element
+    /// referred to by the specified handle, or [`None`] if the handle is invalid.
+    pub fn cursor_at_mut<'a, S: Storage<LinkedListLayout<T, H>>>(&'a mut self, pool: &'a mut DirectPool<Node<T, H>, S, H>, handle: H) -> Option<CursorMut<'a, T, S, H>> {
+        if !pool.contains(handle) { return None; }
+        Some(CursorMut { list: self, pool, current: Some(handle) })
+    }
+}
+
+/// An iterator over the elements of a [`LinkedList`].
+///
+/// This `struct` is created by the [`iter`](LinkedList::iter) method on `LinkedList`.
+/// See its documentation for more.
+pub struct Iter<'a, T, S: Storage<LinkedListLayout<T, H>>, H: Handle> {
+    pool: &'a DirectPool<Node<T, H>, S, H>,
+    front: Option<H>,
+    back: Option<H>,
+    len: usize,
+}
+
+impl<'a, T, S: Storage<LinkedListLayout<T, H>>, H: Handle> Iterator for Iter<'a, T, S, H> {
+    type Item = (H, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 { return None; }
+        let handle = self.front?;
+        let n = node(self.pool, handle);
+        self.front = n.next;
+        self.len -= 1;
+        Some((handle, &n.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T, S: Storage<LinkedListLayout<T, H>>, H: Handle> DoubleEndedIterator for Iter<'_, T, S, H> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 { return None; }
+        let handle = self.back?;
+        let n = node(self.pool, handle);
+        self.back = n.prev;
+        self.len -= 1;
+        Some((handle, &n.value))
+    }
+}
+
+impl<T, S: Storage<LinkedListLayout<T, H>>, H: Handle> ExactSizeIterator for Iter<'_, T, S, H> {}
+impl<T, S: Storage<LinkedListLayout<T, H>>, H: Handle> FusedIterator for Iter<'_, T, S, H> {}
+
+/// A mutable iterator over the elements of a [`LinkedList`].
+///
+/// This `struct` is created by the [`iter_mut`](LinkedList::iter_mut) method on `LinkedList`.
+/// See its documentation for more.
+pub struct IterMut<'a, T, S: Storage<LinkedListLayout<T, H>>, H: Handle> {
+    pool: &'a mut DirectPool<Node<T, H>, S, H>,
+    front: Option<H>,
+    back: Option<H>,
+    len: usize,
+}
+
+impl<'a, T, S: Storage<LinkedListLayout<T, H>>, H: Handle> Iterator for IterMut<'a, T, S, H> {
+    type Item = (H, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 { return None; }
+        let handle = self.front?;
+        let n: *mut Node<T, H> = node_mut(self.pool, handle);
+        self.len -= 1;
+        unsafe {
+            self.front = (*n).next;
+            Some((handle, &mut (*n).value))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T, S: Storage<LinkedListLayout<T, H>>, H: Handle> DoubleEndedIterator for IterMut<'_, T, S, H> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 { return None; }
+        let handle = self.back?;
+        let n: *mut Node<T, H> = node_mut(self.pool, handle);
+        self.len -= 1;
+        unsafe {
+            self.back = (*n).prev;
+            Some((handle, &mut (*n).value))
+        }
+    }
+}
+
+impl<T, S: Storage<LinkedListLayout<T, H>>, H: Handle> ExactSizeIterator for IterMut<'_, T, S, H> {}
+impl<T, S: Storage<LinkedListLayout<T, H>>, H: Handle> FusedIterator for IterMut<'_, T, S, H> {}
+
+/// A cursor over a [`LinkedList`].
+///
+/// A cursor is like an iterator, except that it can freely seek back-and-forth.
+/// Cursors always rest between two elements in the list, and index in a
+/// logically circular way. To accommodate this, there is a "ghost" non-element
+/// that yields [`None`] between the tail and head of the list.
+pub struct Cursor<'a, T, S: Storage<LinkedListLayout<T, H>>, H: Handle> {
+    list: &'a LinkedList<T, H>,
+    pool: &'a DirectPool<Node<T, H>, S, H>,
+    current: Option<H>,
+}
+
+impl<'a, T, S: Storage<LinkedListLayout<T, H>>, H: Handle> Cursor<'a, T, S, H> {
+    /// Returns the handle of the element the cursor is pointing at, or
+    /// [`None`] if it is pointing at the "ghost" non-element.
+    pub fn handle(&self) -> Option<H> {
+        self.current
+    }
+
+    /// Returns a reference to the element the cursor is pointing at, or
+    /// [`None`] if it is pointing at the "ghost" non-element.
+    pub fn current(&self) -> Option<&'a T> {
+        self.current.map(|h| &node(self.pool, h).value)
+    }
+
+    /// Moves the cursor to the next element of the list.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element, this moves it to
+    /// the first element. If it is pointing at the last element, this moves it
+    /// to the "ghost" non-element.
+    pub fn move_next(&mut self) {
+        self.current = match self.current {
+            Some(h) => node(self.pool, h).next,
+            None => self.list.head,
+        };
+    }
+
+    /// Moves the cursor to the previous element of the list.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element, this moves it to
+    /// the last element. If it is pointing at the first element, this moves it
+    /// to the "ghost" non-element.
+    pub fn move_prev(&mut self) {
+        self.current = match self.current {
+            Some(h) => node(self.pool, h).prev,
+            None => self.list.tail,
+        };
+    }
+
+    /// Returns a reference to the next element, without moving the cursor.
+    pub fn peek_next(&self) -> Option<&'a T> {
+        let next = match self.current {
+            Some(h) => node(self.pool, h).next,
+            None => self.list.head,
+        };
+
+        next.map(|h| &node(self.pool, h).value)
+    }
+
+    /// Returns a reference to the previous element, without moving the cursor.
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        let prev = match self.current {
+            Some(h) => node(self.pool, h).prev,
+            None => self.list.tail,
+        };
+
+        prev.map(|h| &node(self.pool, h).value)
+    }
+}
+
+/// A cursor over a [`LinkedList`] with editing operations.
+///
+/// A cursor is like an iterator, except that it can freely seek back-and-forth,
+/// and can safely mutate the list during iteration. Cursors always rest between
+/// two elements in the list, and index in a logically circular way. To
+/// accommodate this, there is a "ghost" non-element that yields [`None`]
+/// between the tail and head of the list.
+///
+/// # Examples
+/// ```
+/// use coca::collections::DirectInlinePool;
+/// use coca::collections::linked_list::{LinkedList, Node};
+///
+/// let mut pool = DirectInlinePool::<Node<u32>, 8>::new();
+/// let mut list = LinkedList::new();
+/// let mut other = LinkedList::new();
+/// (1..=3).for_each(|x| { list.push_back(&mut pool, x); });
+/// (7..=9).for_each(|x| { other.push_back(&mut pool, x); });
+///
+/// let mut cursor = list.cursor_front_mut(&mut pool);
+/// cursor.move_next();
+/// assert_eq!(cursor.current(), Some(&mut 2));
+///
+/// cursor.splice_after(&mut other);
+/// assert_eq!(cursor.remove_current(), Some(2));
+/// assert_eq!(cursor.current(), Some(&mut 7));
+///
+/// let tail = cursor.split_after();
+/// assert!(list.iter(&pool).map(|(_, x)| *x).eq([1, 7]));
+/// assert!(tail.iter(&pool).map(|(_, x)| *x).eq([8, 9, 3]));
+/// ```
+pub struct CursorMut<'a, T, S: Storage<LinkedListLayout<T, H>>, H: Handle> {
+    list: &'a mut LinkedList<T, H>,
+    pool: &'a mut DirectPool<Node<T, H>, S, H>,
+    current: Option<H>,
+}
+
+impl<T, S: Storage<LinkedListLayout<T, H>>, H: Handle> CursorMut<'_, T, S, H> {
+    fn next_handle(&self) -> Option<H> {
+        match self.current {
+            Some(h) => node(self.pool, h).next,
+            None => self.list.head,
+        }
+    }
+
+    fn prev_handle(&self) -> Option<H> {
+        match self.current {
+            Some(h) => node(self.pool, h).prev,
+            None => self.list.tail,
+        }
+    }
+
+    /// Returns the handle of the element the cursor is pointing at, or
+    /// [`None`] if it is pointing at the "ghost" non-element.
+    pub fn handle(&self) -> Option<H> {
+        self.current
+    }
+
+    /// Returns a mutable reference to the element the cursor is pointing at,
+    /// or [`None`] if it is pointing at the "ghost" non-element.
+    pub fn current(&mut self) -> Option<&mut T> {
+        let current = self.current?;
+        Some(&mut node_mut(self.pool, current).value)
+    }
+
+    /// Moves the cursor to the next element of the list.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element, this moves it to
+    /// the first element. If it is pointing at the last element, this moves it
+    /// to the "ghost" non-element.
+    pub fn move_next(&mut self) {
+        self.current = self.next_handle();
+    }
+
+    /// Moves the cursor to the previous element of the list.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element, this moves it to
+    /// the last element. If it is pointing at the first element, this moves it
+    /// to the "ghost" non-element.
+    pub fn move_prev(&mut self) {
+        self.current = self.prev_handle();
+    }
+
+    /// Returns a mutable reference to the next element, without moving the cursor.
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = self.next_handle()?;
+        Some(&mut node_mut(self.pool, next).value)
+    }
+
+    /// Returns a mutable reference to the previous element, without moving the cursor.
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let prev = self.prev_handle()?;
+        Some(&mut node_mut(self.pool, prev).value)
+    }
+
+    /// Returns a read-only cursor pointing to the current element.
+    pub fn as_cursor(&self) -> Cursor<'_, T, S, H> {
+        Cursor { list: self.list, pool: self.pool, current: self.current }
+    }
+
+    /// Inserts a new element into the list after the current one, returning its handle.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element, the new element is
+    /// inserted at the front of the list.
+    ///
+    /// Returns [`Err`] containing the element if the pool is already full.
+    pub fn try_insert_after(&mut self, value: T) -> Result<H, T> {
+        let next = self.next_handle();
+        self.list.try_insert_between(self.pool, value, self.current, next)
+    }
+
+    /// Inserts a new element into the list after the current one, returning its handle.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element, the new element is
+    /// inserted at the front of the list.
+    ///
+    /// # Panics
+    /// Panics if the pool is already full. See
+    /// [`try_insert_after`](CursorMut::try_insert_after) for a checked version.
+    #[track_caller]
+    pub fn insert_after(&mut self, value: T) -> H {
+        self.try_insert_after(value).ok().expect("pool is already at capacity")
+    }
+
+    /// Inserts a new element into the list before the current one, returning its handle.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element, the new element is
+    /// inserted at the back of the list.
+    ///
+    /// Returns [`Err`] containing the element if the pool is already full.
+    pub fn try_insert_before(&mut self, value: T) -> Result<H, T> {
+        let prev = self.prev_handle();
+        self.list.try_insert_between(self.pool, value, prev, self.current)
+    }
+
+    /// Inserts a new element into the list before the current one, returning its handle.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element, the new element is
+    /// inserted at the back of the list.
+    ///
+    /// # Panics
+    /// Panics if the pool is already full. See
+    /// [`try_insert_before`](CursorMut::try_insert_before) for a checked version.
+    #[track_caller]
+    pub fn insert_before(&mut self, value: T) -> H {
+        self.try_insert_before(value).ok().expect("pool is already at capacity")
+    }
+
+    /// Removes the current element from the list and the pool, and returns it.
+    /// The cursor is moved to point to the next element.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element, no element is
+    /// removed and [`None`] is returned.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let current = self.current?;
+        self.current = node(self.pool, current).next;
+        self.list.remove(self.pool, current)
+    }
+
+    /// Moves all elements of `other` into the list after the current one,
+    /// leaving `other` empty.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element, the new elements
+    /// are inserted at the front of the list.
+    ///
+    /// This operation takes *O*(1) time, and does not invalidate any handles.
+    pub fn splice_after(&mut self, other: &mut LinkedList<T, H>) {
+        let next = self.next_handle();
+        self.list.splice_between(self.pool, other, self.current, next);
+    }
+
+    /// Moves all elements of `other` into the list before the current one,
+    /// leaving `other` empty.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element, the new elements
+    /// are inserted at the back of the list.
+    ///
+    /// This operation takes *O*(1) time, and does not invalidate any handles.
+    pub fn splice_before(&mut self, other: &mut LinkedList<T, H>) {
+        let prev = self.prev_handle();
+        self.list.splice_between(self.pool, other, prev, self.current);
+    }
+
+    /// Splits the list into two after the current element, returning a new
+    /// list consisting of everything after the cursor, with the original list
+    /// retaining everything before.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element, the entire
+    /// contents of the list are moved.
+    ///
+    /// This operation takes time linear in the length of the returned list,
+    /// and does not invalidate any handles.
+    pub fn split_after(&mut self) -> LinkedList<T, H> {
+        match self.current {
+            None => core::mem::take(self.list),
+            Some(current) => {
+                let mut result = LinkedList::new();
+                if let Some(head) = node_mut(self.pool, current).next.take() {
+                    node_mut(self.pool, head).prev = None;
+                    result.head = Some(head);
+                    result.tail = self.list.tail;
+                    self.list.tail = Some(current);
+
+                    let mut next = Some(head);
+                    while let Some(h) = next {
+                        result.len += 1;
+                        next = node(self.pool, h).next;
+                    }
+
+                    self.list.len -= result.len;
+                }
+
+                result
+            }
+        }
+    }
+
+    /// Splits the list into two before the current element, returning a new
+    /// list consisting of everything before the cursor, with the original list
+    /// retaining everything after.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element, the entire
+    /// contents of the list are moved.
+    ///
+    /// This operation takes time linear in the length of the returned list,
+    /// and does not invalidate any handles.
+    pub fn split_before(&mut self) -> LinkedList<T, H> {
+        match self.current {
+            None => core::mem::take(self.list),
+            Some(current) => {
+                let mut result = LinkedList::new();
+                if let Some(tail) = node_mut(self.pool, current).prev.take() {
+                    node_mut(self.pool, tail).next = None;
+                    result.head = self.list.head;
+                    result.tail = Some(tail);
+                    self.list.head = Some(current);
+
+                    let mut prev = Some(tail);
+                    while let Some(h) = prev {
+                        result.len += 1;
+                        prev = node(self.pool, h).prev;
+                    }
+
+                    self.list.len -= result.len;
+                }
+
+                result
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::DirectInlinePool;
+
+    fn check_links<T, S: Storage<LinkedListLayout<T, H>>, H: Handle>(list: &LinkedList<T, H>, pool: &DirectPool<Node<T, H>, S, H>) {
+        let mut prev = None;
+        let mut next = list.head;
+        let mut len = 0;
+        while let Some(h) = next {
+            let n = node(pool, h);
+            assert_eq!(n.prev, prev);
+            prev = Some(h);
+            next = n.next;
+            len += 1;
+        }
+
+        assert_eq!(list.tail, prev);
+        assert_eq!(list.len(), len);
+    }
+
+    #[test]
+    fn randomized_operations() {
+        use crate::test_utils::{DropCounter, Droppable};
+        use crate::collections::InlineVec;
+        use rand::{rngs::SmallRng, RngCore, SeedableRng};
+        let mut rng = SmallRng::from_seed(crate::test_utils::RNG_SEED);
+
+        let drop_count = DropCounter::new();
+        let mut pool = DirectInlinePool::<Node<Droppable<u32>>, 64>::new();
+        let mut lists = [LinkedList::new(), LinkedList::new()];
+        let mut reference = [InlineVec::<(DefaultHandle, u32), 64>::new(), InlineVec::new()];
+        let mut created = 0;
+
+        for i in 0..10_000 {
+            let l = (rng.next_u32() % 2) as usize;
+            let value = rng.next_u32();
+
+            match rng.next_u32() % 8 {
+                0 | 1 if !pool.is_full() => {
+                    let h = lists[l].push_back(&mut pool, drop_count.new_droppable(value));
+                    reference[l].push((h, value));
+                    created += 1;
+                }
+                2 if !pool.is_full() => {
+                    let h = lists[l].push_front(&mut pool, drop_count.new_droppable(value));
+                    reference[l].insert(0, (h, value));
+                    created += 1;
+                }
+                3 if !reference[l].is_empty() => {
+                    let idx = value as usize % reference[l].len();
+                    let (h, v) = reference[l].remove(idx);
+                    assert_eq!(lists[l].remove(&mut pool, h).map(|d| d.value), Some(v));
+                    assert!(lists[l].remove(&mut pool, h).is_none());
+                }
+                4 if !reference[l].is_empty() => {
+                    let idx = value as usize % reference[l].len();
+                    let entry = reference[l].remove(idx);
+                    reference[l].insert(0, entry);
+                    assert!(lists[l].move_to_front(&mut pool, entry.0));
+                }
+                5 => {
+                    let (a, b) = lists.split_at_mut(1);
+                    if l == 0 {
+                        a[0].append(&mut pool, &mut b[0]);
+                        let moved: InlineVec<_, 64> = reference[1].drain(..).collect();
+                        reference[0].extend(moved);
+                    } else {
+                        b[0].prepend(&mut pool, &mut a[0]);
+                        let mut moved: InlineVec<_, 64> = reference[0].drain(..).collect();
+                        moved.extend(reference[1].drain(..));
+                        reference[1] = moved;
+                    }
+                }
+                6 => {
+                    let idx = if reference[l].is_empty() { 0 } else { value as usize % reference[l].len() };
+                    let mut cursor = lists[l].cursor_front_mut(&mut pool);
+                    for _ in 0..idx { cursor.move_next(); }
+                    let split = cursor.split_after();
+
+                    let other = 1 - l;
+                    let (a, b) = lists.split_at_mut(1);
+                    let target = if other == 0 { &mut a[0] } else { &mut b[0] };
+                    let mut split = split;
+                    target.append(&mut pool, &mut split);
+
+                    let keep = if reference[l].is_empty() { 0 } else { idx + 1 };
+                    let moved: InlineVec<_, 64> = reference[l].drain(keep..).collect();
+                    reference[other].extend(moved);
+                }
+                _ => {
+                    let popped = if value % 2 == 0 { lists[l].pop_front(&mut pool) } else { lists[l].pop_back(&mut pool) };
+                    let expected = if reference[l].is_empty() {
+                        None
+                    } else if value % 2 == 0 {
+                        Some(reference[l].remove(0).1)
+                    } else {
+                        reference[l].pop().map(|(_, v)| v)
+                    };
+                    assert_eq!(popped.map(|d| d.value), expected);
+                }
+            }
+
+            if i % 53 == 0 {
+                for l in 0..2 {
+                    check_links(&lists[l], &pool);
+                    assert!(lists[l].iter(&pool).map(|(h, d)| (h, d.value)).eq(reference[l].iter().copied()));
+                    assert!(lists[l].iter(&pool).rev().map(|(h, _)| h).eq(reference[l].iter().rev().map(|(h, _)| *h)));
+                }
+            }
+        }
+
+        for list in lists.iter_mut() {
+            list.clear(&mut pool);
+        }
+
+        assert!(pool.is_empty());
+        assert_eq!(drop_count.dropped(), created);
+    }
+
+    #[test]
+    fn foreign_end_nodes_are_rejected() {
+        extern crate std;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let mut pool = DirectInlinePool::<Node<u32>, 8>::new();
+        let mut a = LinkedList::new();
+        let mut b = LinkedList::new();
+        a.push_back(&mut pool, 1);
+        a.push_back(&mut pool, 2);
+        let b_head = b.push_back(&mut pool, 3);
+        b.push_back(&mut pool, 4);
+        let b_tail = b.push_back(&mut pool, 5);
+
+        let mut empty = LinkedList::new();
+        for &h in &[b_head, b_tail] {
+            assert!(catch_unwind(AssertUnwindSafe(|| a.remove(&mut pool, h))).is_err());
+            assert!(catch_unwind(AssertUnwindSafe(|| a.move_to_front(&mut pool, h))).is_err());
+            assert!(catch_unwind(AssertUnwindSafe(|| a.move_to_back(&mut pool, h))).is_err());
+            assert!(catch_unwind(AssertUnwindSafe(|| empty.remove(&mut pool, h))).is_err());
+        }
+
+        check_links(&a, &pool);
+        check_links(&b, &pool);
+        assert!(a.iter(&pool).map(|(_, x)| *x).eq([1, 2]));
+        assert!(b.iter(&pool).map(|(_, x)| *x).eq([3, 4, 5]));
+        assert!(empty.is_empty());
+    }
+}
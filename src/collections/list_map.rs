@@ -801,6 +801,15 @@ pub struct InlineStorage<K, V, const N: usize> {
     values: [MaybeUninit<V>; N],
 }
 
+impl<K, V, const N: usize> InlineStorage<K, V, N> {
+    pub(crate) fn uninit() -> Self {
+        unsafe { InlineStorage {
+            keys: MaybeUninit::uninit().assume_init(),
+            values: MaybeUninit::uninit().assume_init(),
+        }}
+    }
+}
+
 unsafe impl<K, V, const N: usize> Storage<ListMapLayout<K, V>> for InlineStorage<K, V, N> {
     fn get_ptr(&self) -> *const u8 {
         (self as *const Self).cast()
@@ -818,12 +827,7 @@ unsafe impl<K, V, const N: usize> Storage<ListMapLayout<K, V>> for InlineStorage
 impl<K, V, I: Capacity, const N: usize> ListMap<K, V, InlineStorage<K, V, N>, I> {
     /// Constructs a new, empty [`InlineListMap`](crate::collections::InlineListMap).
     pub fn new() -> Self {
-        let buf = unsafe { InlineStorage {
-            keys: MaybeUninit::uninit().assume_init(),
-            values: MaybeUninit::uninit().assume_init(),
-        }};
-
-        Self::from(buf)
+        Self::from(InlineStorage::uninit())
     }
 }
 
@@ -835,10 +839,7 @@ impl<K, V, I: Capacity, const N: usize> Default for ListMap<K, V, InlineStorage<
 
 impl<K: Clone, V: Clone, I: Capacity, const N: usize> Clone for ListMap<K, V, InlineStorage<K, V, N>, I> {
     fn clone(&self) -> Self {
-        let buf = unsafe { InlineStorage {
-            keys: MaybeUninit::uninit().assume_init(),
-            values: MaybeUninit::uninit().assume_init(),
-        }};
+        let buf = InlineStorage::uninit();
 
         let mut result = ListMap {
             buf, len: self.len, pairs: PhantomData,
@@ -5,6 +5,7 @@ use core::fmt::Debug;
 use core::iter::FusedIterator;
 use core::slice::Iter;
 
+use crate::collections::SetLike;
 use crate::collections::vec::{Vec, Drain};
 use crate::storage::{ArrayLayout, Capacity, Storage, InlineStorage};
 
@@ -319,12 +320,8 @@ impl<T: Eq, S: Storage<ArrayLayout<T>>, I: Capacity> ListSet<T, S, I> {
     /// b.insert(1);
     /// assert_eq!(a.is_disjoint(&b), false);
     /// ```
-    pub fn is_disjoint<S2: Storage<ArrayLayout<T>>, I2: Capacity>(&self, other: &ListSet<T, S2, I2>) -> bool {
-        for item in other.iter() {
-            if self.contains(item) { return false; }
-        }
-
-        true
+    pub fn is_disjoint<O: SetLike<T>>(&self, other: &O) -> bool {
+        other.all(|item| !self.contains(item))
     }
 
     /// Returns `true` if the set is a subset of another,
@@ -346,7 +343,7 @@ impl<T: Eq, S: Storage<ArrayLayout<T>>, I: Capacity> ListSet<T, S, I> {
     /// set.insert(5);
     /// assert_eq!(set.is_subset_of(&sup), false)
     /// ```
-    pub fn is_subset_of<S2: Storage<ArrayLayout<T>>, I2: Capacity>(&self, other: &ListSet<T, S2, I2>) -> bool {
+    pub fn is_subset_of<O: SetLike<T>>(&self, other: &O) -> bool {
         for item in self.iter() {
             if !other.contains(item) { return false; }
         }
@@ -375,8 +372,8 @@ impl<T: Eq, S: Storage<ArrayLayout<T>>, I: Capacity> ListSet<T, S, I> {
     /// assert_eq!(set.is_superset_of(&sub), true);
     /// ```
     #[inline]
-    pub fn is_superset_of<S2: Storage<ArrayLayout<T>>, I2: Capacity>(&self, other: &ListSet<T, S2, I2>) -> bool {
-        other.is_subset_of(self)
+    pub fn is_superset_of<O: SetLike<T>>(&self, other: &O) -> bool {
+        other.all(|item| self.contains(item))
     }
 
     /// Adds a value to the set.
@@ -622,6 +619,23 @@ where
     }
 }
 
+impl<T: Eq, S: Storage<ArrayLayout<T>>, I: Capacity> SetLike<T> for ListSet<T, S, I> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    #[inline]
+    fn contains(&self, value: &T) -> bool {
+        ListSet::contains(self, value)
+    }
+
+    #[inline]
+    fn all<F: FnMut(&T) -> bool>(&self, pred: F) -> bool {
+        self.vec.iter().all(pred)
+    }
+}
+
 impl<T: Debug, S: Storage<ArrayLayout<T>>, I: Capacity> Debug for ListSet<T, S, I> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_set().entries(self.vec.as_slice()).finish()
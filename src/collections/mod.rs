@@ -1,27 +1,88 @@
 //! Collection types.
 
 pub mod binary_heap;
+pub mod bit_set;
+pub mod bit_vec;
+pub mod btree_map;
 pub mod cache;
 pub mod deque;
+pub mod hash_map;
+pub mod hash_set;
+pub mod linked_list;
 pub mod list_map;
 pub mod list_set;
 pub mod option_group;
 pub mod pool;
+pub mod sorted_map;
+pub mod mpmc;
+pub mod spsc;
 pub mod vec;
 
 use crate::storage::{ArenaStorage, ArrayLayout, InlineStorage, SliceStorage};
 
 use binary_heap::BinaryHeap;
-use cache::{CacheTable, UnitCache, LruCache2};
+use bit_set::BitSet;
+use bit_vec::BitVec;
+use btree_map::{BTreeMap, BTreeMapLayout};
+use cache::{CacheTable, UnitCache, LruCache2, LruCacheN, ClockCache, RandomCache, XorShiftRng};
 use deque::Deque;
+use hash_map::{HashMap, HashMapLayout};
+use hash_set::HashSet;
 use list_map::{ListMap, ListMapLayout};
 use list_set::ListSet;
 use option_group::OptionGroup;
 use pool::DefaultHandle;
 use pool::direct::{DirectPool, DirectPoolLayout};
 use pool::packed::{PackedPool, PackedPoolLayout};
+use pool::secondary::{SecondaryMap, SecondaryMapLayout, SparseSecondaryMap};
+use pool::sync::{SyncPool, SyncPoolLayout};
+use sorted_map::SortedMap;
+use mpmc::MpmcQueue;
+use spsc::SpscQueue;
 use vec::Vec;
 
+/// Set types that can be compared with one another, regardless of their
+/// implementation.
+///
+/// This allows checking e.g. a [`ListSet`] for being a subset of a [`HashSet`]
+/// or vice versa.
+///
+/// # Examples
+/// ```
+/// # extern crate rustc_hash;
+/// use rustc_hash::FxHasher;
+/// use coca::collections::{InlineHashSet, InlineListSet};
+/// use core::hash::BuildHasherDefault;
+///
+/// let mut list = InlineListSet::<u32, 4>::new();
+/// list.insert(1); list.insert(2);
+///
+/// let mut hash = InlineHashSet::<u32, BuildHasherDefault<FxHasher>, 8>::new();
+/// hash.insert(1); hash.insert(2); hash.insert(3);
+///
+/// assert!(list.is_subset_of(&hash));
+/// assert!(hash.is_superset_of(&list));
+/// assert!(!hash.is_subset_of(&list));
+/// ```
+pub trait SetLike<T> {
+    /// Returns the number of elements in the set.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the set contains no elements, or `false` otherwise.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the set contains a value equal to the given value, or `false` otherwise.
+    fn contains(&self, value: &T) -> bool;
+
+    /// Tests whether the predicate holds for every element of the set.
+    ///
+    /// This is short-circuiting, i.e. no more elements are visited after the
+    /// predicate returns `false` for the first time.
+    fn all<F: FnMut(&T) -> bool>(&self, pred: F) -> bool;
+}
+
 /// A binary heap using a mutable slice for storage.
 ///
 /// # Examples
@@ -251,6 +312,238 @@ pub type AllocDirectMappedCache<K, V, H> = CacheTable<K, V, crate::storage::Allo
 #[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
 pub type Alloc2WayLruCache<K, V, H> = CacheTable<K, V, crate::storage::AllocStorage<ArrayLayout<LruCache2<K, V>>>, LruCache2<K, V>, H>;
 
+
+/// A `W`-way set-associative cache with a least recently used eviction policy,
+/// using an arena-allocated slice for storage.
+/// 
+/// Note that the constant generic parameter `W` is the number of ways, i.e.
+/// the capacity of each cache line, while the capacity passed to the arena is
+/// the number of cache lines.
+/// 
+/// # Examples
+/// ```
+/// # extern crate rustc_hash;
+/// use rustc_hash::FxHasher;
+/// use coca::{arena::Arena, collections::ArenaNWayLruCache};
+/// use core::{hash::BuildHasherDefault, mem::MaybeUninit};
+/// 
+/// # fn test() -> Option<()> {
+/// let mut backing_region = [MaybeUninit::uninit(); 1024];
+/// let mut arena = Arena::from(&mut backing_region[..]);
+/// let mut cache: ArenaNWayLruCache<'_, i32, &'static str, BuildHasherDefault<FxHasher>, 4> = arena.try_with_capacity(4)?;
+/// assert_eq!(cache.capacity(), 16);
+/// cache.insert(1, "a");
+/// assert_eq!(cache.get(&1), Some(&"a"));
+/// # Some(())
+/// # }
+/// # assert!(test().is_some());
+/// ```
+pub type ArenaNWayLruCache<'src, K, V, H, const W: usize> = CacheTable<K, V, ArenaStorage<'src, ArrayLayout<LruCacheN<K, V, W>>>, LruCacheN<K, V, W>, H>;
+
+/// A `W`-way set-associative cache with a least recently used eviction policy,
+/// using an inline array for storage.
+/// 
+/// Note that the constant generic parameter `W` is the number of ways, i.e.
+/// the capacity of each cache line, and `N` is the number of cache lines, so
+/// caches of this type have capacity for `W * N` key-value pairs.
+/// 
+/// # Examples
+/// ```
+/// # extern crate rustc_hash;
+/// use rustc_hash::FxHasher;
+/// # use coca::collections::InlineNWayLruCache;
+/// # use core::hash::BuildHasherDefault;
+/// let keys = ["Alice", "Bob", "Charlie", "David", "Eve", "Faythe", "Grace", "Heidi", "Ivan"];
+/// let mut cache = InlineNWayLruCache::<&'static str, usize, BuildHasherDefault<FxHasher>, 4, 2>::new();
+/// assert_eq!(cache.capacity(), 8);
+/// 
+/// for k in &keys {
+///     cache.insert(k, k.len());
+///     assert_eq!(cache.get(k), Some(&k.len()));
+/// }
+/// 
+/// let remembered = keys.iter().filter(|k| cache.get(*k).is_some()).count();
+/// assert!(0 < remembered);
+/// assert!(remembered < keys.len());
+/// ```
+pub type InlineNWayLruCache<K, V, H, const W: usize, const N: usize> = CacheTable<K, V, InlineStorage<LruCacheN<K, V, W>, N>, LruCacheN<K, V, W>, H>;
+
+/// A `W`-way set-associative cache with a least recently used eviction policy,
+/// using a heap-allocated array for storage.
+/// 
+/// Note that the constant generic parameter `W` is the number of ways, i.e.
+/// the capacity of each cache line.
+/// 
+/// # Examples
+/// ```
+/// # extern crate rustc_hash;
+/// use rustc_hash::FxHasher;
+/// # use coca::collections::AllocNWayLruCache;
+/// # use core::hash::BuildHasherDefault;
+/// let mut cache = AllocNWayLruCache::<&'static str, usize, BuildHasherDefault<FxHasher>, 4>::with_capacity(30);
+/// assert_eq!(cache.capacity(), 32);
+/// 
+/// cache.insert("Alice", 5);
+/// assert_eq!(cache.get(&"Alice"), Some(&5));
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+pub type AllocNWayLruCache<K, V, H, const W: usize> = CacheTable<K, V, crate::storage::AllocStorage<ArrayLayout<LruCacheN<K, V, W>>>, LruCacheN<K, V, W>, H>;
+
+/// A `W`-way set-associative cache with the CLOCK eviction policy,
+/// using an arena-allocated slice for storage.
+/// 
+/// Note that the constant generic parameter `W` is the number of ways, i.e.
+/// the capacity of each cache line, while the capacity passed to the arena is
+/// the number of cache lines.
+/// 
+/// # Examples
+/// ```
+/// # extern crate rustc_hash;
+/// use rustc_hash::FxHasher;
+/// use coca::{arena::Arena, collections::ArenaClockCache};
+/// use core::{hash::BuildHasherDefault, mem::MaybeUninit};
+/// 
+/// # fn test() -> Option<()> {
+/// let mut backing_region = [MaybeUninit::uninit(); 1024];
+/// let mut arena = Arena::from(&mut backing_region[..]);
+/// let mut cache: ArenaClockCache<'_, i32, &'static str, BuildHasherDefault<FxHasher>, 4> = arena.try_with_capacity(4)?;
+/// assert_eq!(cache.capacity(), 16);
+/// cache.insert(1, "a");
+/// assert_eq!(cache.get(&1), Some(&"a"));
+/// # Some(())
+/// # }
+/// # assert!(test().is_some());
+/// ```
+pub type ArenaClockCache<'src, K, V, H, const W: usize> = CacheTable<K, V, ArenaStorage<'src, ArrayLayout<ClockCache<K, V, W>>>, ClockCache<K, V, W>, H>;
+
+/// A `W`-way set-associative cache with the CLOCK eviction policy,
+/// using an inline array for storage.
+/// 
+/// Note that the constant generic parameter `W` is the number of ways, i.e.
+/// the capacity of each cache line, and `N` is the number of cache lines, so
+/// caches of this type have capacity for `W * N` key-value pairs.
+/// 
+/// # Examples
+/// ```
+/// # extern crate rustc_hash;
+/// use rustc_hash::FxHasher;
+/// # use coca::collections::InlineClockCache;
+/// # use core::hash::BuildHasherDefault;
+/// let keys = ["Alice", "Bob", "Charlie", "David", "Eve", "Faythe", "Grace", "Heidi", "Ivan"];
+/// let mut cache = InlineClockCache::<&'static str, usize, BuildHasherDefault<FxHasher>, 4, 2>::new();
+/// assert_eq!(cache.capacity(), 8);
+/// 
+/// for k in &keys {
+///     cache.insert(k, k.len());
+///     assert_eq!(cache.get(k), Some(&k.len()));
+/// }
+/// 
+/// let remembered = keys.iter().filter(|k| cache.get(*k).is_some()).count();
+/// assert!(0 < remembered);
+/// assert!(remembered < keys.len());
+/// ```
+pub type InlineClockCache<K, V, H, const W: usize, const N: usize> = CacheTable<K, V, InlineStorage<ClockCache<K, V, W>, N>, ClockCache<K, V, W>, H>;
+
+/// A `W`-way set-associative cache with the CLOCK eviction policy,
+/// using a heap-allocated array for storage.
+/// 
+/// Note that the constant generic parameter `W` is the number of ways, i.e.
+/// the capacity of each cache line.
+/// 
+/// # Examples
+/// ```
+/// # extern crate rustc_hash;
+/// use rustc_hash::FxHasher;
+/// # use coca::collections::AllocClockCache;
+/// # use core::hash::BuildHasherDefault;
+/// let mut cache = AllocClockCache::<&'static str, usize, BuildHasherDefault<FxHasher>, 4>::with_capacity(30);
+/// assert_eq!(cache.capacity(), 32);
+/// 
+/// cache.insert("Alice", 5);
+/// assert_eq!(cache.get(&"Alice"), Some(&5));
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+pub type AllocClockCache<K, V, H, const W: usize> = CacheTable<K, V, crate::storage::AllocStorage<ArrayLayout<ClockCache<K, V, W>>>, ClockCache<K, V, W>, H>;
+
+/// A `W`-way set-associative cache with a random eviction policy,
+/// using an arena-allocated slice for storage.
+/// 
+/// Note that the constant generic parameter `W` is the number of ways, i.e.
+/// the capacity of each cache line, while the capacity passed to the arena is
+/// the number of cache lines.
+/// 
+/// # Examples
+/// ```
+/// # extern crate rustc_hash;
+/// use rustc_hash::FxHasher;
+/// use coca::{arena::Arena, collections::ArenaRandomCache};
+/// use core::{hash::BuildHasherDefault, mem::MaybeUninit};
+/// 
+/// # fn test() -> Option<()> {
+/// let mut backing_region = [MaybeUninit::uninit(); 1024];
+/// let mut arena = Arena::from(&mut backing_region[..]);
+/// let mut cache: ArenaRandomCache<'_, i32, &'static str, BuildHasherDefault<FxHasher>, 4> = arena.try_with_capacity(4)?;
+/// assert_eq!(cache.capacity(), 16);
+/// cache.insert(1, "a");
+/// assert_eq!(cache.get(&1), Some(&"a"));
+/// # Some(())
+/// # }
+/// # assert!(test().is_some());
+/// ```
+pub type ArenaRandomCache<'src, K, V, H, const W: usize, R = XorShiftRng> = CacheTable<K, V, ArenaStorage<'src, ArrayLayout<RandomCache<K, V, W, R>>>, RandomCache<K, V, W, R>, H>;
+
+/// A `W`-way set-associative cache with a random eviction policy,
+/// using an inline array for storage.
+/// 
+/// Note that the constant generic parameter `W` is the number of ways, i.e.
+/// the capacity of each cache line, and `N` is the number of cache lines, so
+/// caches of this type have capacity for `W * N` key-value pairs.
+/// 
+/// # Examples
+/// ```
+/// # extern crate rustc_hash;
+/// use rustc_hash::FxHasher;
+/// # use coca::collections::InlineRandomCache;
+/// # use core::hash::BuildHasherDefault;
+/// let keys = ["Alice", "Bob", "Charlie", "David", "Eve", "Faythe", "Grace", "Heidi", "Ivan"];
+/// let mut cache = InlineRandomCache::<&'static str, usize, BuildHasherDefault<FxHasher>, 4, 2>::new();
+/// assert_eq!(cache.capacity(), 8);
+/// 
+/// for k in &keys {
+///     cache.insert(k, k.len());
+///     assert_eq!(cache.get(k), Some(&k.len()));
+/// }
+/// 
+/// let remembered = keys.iter().filter(|k| cache.get(*k).is_some()).count();
+/// assert!(0 < remembered);
+/// assert!(remembered < keys.len());
+/// ```
+pub type InlineRandomCache<K, V, H, const W: usize, const N: usize, R = XorShiftRng> = CacheTable<K, V, InlineStorage<RandomCache<K, V, W, R>, N>, RandomCache<K, V, W, R>, H>;
+
+/// A `W`-way set-associative cache with a random eviction policy,
+/// using a heap-allocated array for storage.
+/// 
+/// Note that the constant generic parameter `W` is the number of ways, i.e.
+/// the capacity of each cache line.
+/// 
+/// # Examples
+/// ```
+/// # extern crate rustc_hash;
+/// use rustc_hash::FxHasher;
+/// # use coca::collections::AllocRandomCache;
+/// # use core::hash::BuildHasherDefault;
+/// let mut cache = AllocRandomCache::<&'static str, usize, BuildHasherDefault<FxHasher>, 4>::with_capacity(30);
+/// assert_eq!(cache.capacity(), 32);
+/// 
+/// cache.insert("Alice", 5);
+/// assert_eq!(cache.get(&"Alice"), Some(&5));
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+pub type AllocRandomCache<K, V, H, const W: usize, R = XorShiftRng> = CacheTable<K, V, crate::storage::AllocStorage<ArrayLayout<RandomCache<K, V, W, R>>>, RandomCache<K, V, W, R>, H>;
+
 /// A double-ended queue using any mutable slice for storage.
 ///
 /// # Examples
@@ -320,6 +613,235 @@ pub type AllocDeque<T, I = usize> = Deque<T, crate::storage::AllocStorage<ArrayL
 /// ```
 pub type InlineDeque<T, const C: usize, I = usize> = Deque<T, InlineStorage<T, C>, I>;
 
+/// A single-producer single-consumer queue using an arena-allocated slice for storage.
+///
+/// # Examples
+/// ```
+/// use core::mem::MaybeUninit;
+/// use coca::arena::Arena;
+/// use coca::collections::ArenaSpscQueue;
+///
+/// # fn test() -> Option<()> {
+/// let mut backing_region = [MaybeUninit::uninit(); 1024];
+/// let mut arena = Arena::from(&mut backing_region[..]);
+/// let mut queue: ArenaSpscQueue<'_, char> = arena.try_with_capacity(4)?;
+///
+/// let (mut producer, mut consumer) = queue.split();
+/// assert_eq!(producer.push_slice(&['a', 'b', 'c', 'd', 'e']), 4);
+/// assert_eq!(consumer.try_pop(), Some('a'));
+/// # Some(())
+/// # }
+/// # assert!(test().is_some());
+/// ```
+pub type ArenaSpscQueue<'a, T> = SpscQueue<T, ArenaStorage<'a, ArrayLayout<T>>>;
+/// A single-producer single-consumer queue using a heap-allocated slice for storage.
+///
+/// Note that this still has a fixed capacity, and will never reallocate.
+///
+/// # Examples
+/// ```
+/// let mut queue = coca::collections::AllocSpscQueue::<char>::with_capacity(4);
+/// let (mut producer, mut consumer) = queue.split();
+/// assert_eq!(producer.push_slice(&['a', 'b', 'c', 'd', 'e']), 4);
+/// assert_eq!(consumer.try_pop(), Some('a'));
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+pub type AllocSpscQueue<T> = SpscQueue<T, crate::storage::AllocStorage<ArrayLayout<T>>>;
+/// A single-producer single-consumer queue using an inline array for storage.
+///
+/// # Examples
+/// ```
+/// let mut queue = coca::collections::InlineSpscQueue::<char, 4>::new();
+/// let (mut producer, mut consumer) = queue.split();
+/// producer.try_push('a').unwrap();
+/// assert_eq!(consumer.peek(), Some(&'a'));
+/// ```
+pub type InlineSpscQueue<T, const C: usize> = SpscQueue<T, InlineStorage<T, C>>;
+
+/// A multi-producer multi-consumer queue using an arena-allocated slice for storage.
+///
+/// # Examples
+/// ```
+/// use core::mem::MaybeUninit;
+/// use coca::arena::Arena;
+/// use coca::collections::ArenaMpmcQueue;
+///
+/// # fn test() -> Option<()> {
+/// let mut backing_region = [MaybeUninit::uninit(); 1024];
+/// let mut arena = Arena::from(&mut backing_region[..]);
+/// let queue: ArenaMpmcQueue<'_, char> = arena.try_with_capacity(4)?;
+///
+/// queue.push('a');
+/// assert_eq!(queue.try_pop(), Some('a'));
+/// # Some(())
+/// # }
+/// # assert!(test().is_some());
+/// ```
+pub type ArenaMpmcQueue<'a, T> = MpmcQueue<T, ArenaStorage<'a, mpmc::MpmcQueueLayout<T>>>;
+/// A multi-producer multi-consumer queue using a heap-allocated slice for storage.
+///
+/// Note that this still has a fixed capacity, and will never reallocate.
+///
+/// # Examples
+/// ```
+/// let queue = coca::collections::AllocMpmcQueue::<char>::with_capacity(3);
+/// assert_eq!(queue.capacity(), 4);
+/// queue.push('a');
+/// assert_eq!(queue.try_pop(), Some('a'));
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+pub type AllocMpmcQueue<T> = MpmcQueue<T, crate::storage::AllocStorage<mpmc::MpmcQueueLayout<T>>>;
+/// A multi-producer multi-consumer queue using an inline array for storage.
+///
+/// # Examples
+/// ```
+/// let queue = coca::collections::InlineMpmcQueue::<char, 4>::new();
+/// queue.push('a');
+/// assert_eq!(queue.try_pop(), Some('a'));
+/// ```
+pub type InlineMpmcQueue<T, const C: usize> = MpmcQueue<T, mpmc::InlineStorage<T, C>>;
+
+/// A hash map using any mutable byte slice for storage.
+///
+/// # Examples
+/// ```
+/// # extern crate rustc_hash;
+/// use rustc_hash::FxHasher;
+/// use coca::collections::{hash_map::SliceStorage, SliceHashMap};
+/// use core::{hash::BuildHasherDefault, mem::MaybeUninit};
+///
+/// let mut backing_region = [MaybeUninit::uninit(); 256];
+/// let storage = SliceStorage::from(&mut backing_region[..]);
+/// let mut map = SliceHashMap::<'_, u32, &'static str, BuildHasherDefault<FxHasher>>::from(storage);
+/// map.insert(1, "a");
+/// assert_eq!(map.get(&1), Some(&"a"));
+/// ```
+pub type SliceHashMap<'a, K, V, H> = HashMap<K, V, hash_map::SliceStorage<'a, K, V>, H>;
+/// A hash map using an arena-allocated memory block for storage.
+///
+/// # Examples
+/// ```
+/// # extern crate rustc_hash;
+/// use rustc_hash::FxHasher;
+/// use coca::{arena::Arena, collections::ArenaHashMap};
+/// use core::{hash::BuildHasherDefault, mem::MaybeUninit};
+///
+/// # fn test() -> Option<()> {
+/// let mut backing_region = [MaybeUninit::uninit(); 1024];
+/// let mut arena = Arena::from(&mut backing_region[..]);
+/// let mut map: ArenaHashMap<'_, i32, &'static str, BuildHasherDefault<FxHasher>> = arena.try_with_capacity(8)?;
+/// map.insert(1, "a");
+/// assert_eq!(map.get(&1), Some(&"a"));
+/// # Some(())
+/// # }
+/// # assert!(test().is_some());
+/// ```
+pub type ArenaHashMap<'src, K, V, H> = HashMap<K, V, ArenaStorage<'src, HashMapLayout<K, V>>, H>;
+/// A hash map using a heap-allocated memory block for storage.
+///
+/// Note that this still has a fixed capacity, and will never reallocate.
+///
+/// # Examples
+/// ```
+/// # extern crate rustc_hash;
+/// use rustc_hash::FxHasher;
+/// # use coca::collections::AllocHashMap;
+/// # use core::hash::BuildHasherDefault;
+/// let mut map = AllocHashMap::<&'static str, usize, BuildHasherDefault<FxHasher>>::with_capacity(4);
+/// assert_eq!(map.capacity(), 4);
+///
+/// for k in &["Alice", "Bob", "Charlie", "Eve"] {
+///     map.insert(k, k.len());
+/// }
+///
+/// assert!(map.is_full());
+/// assert!(map.try_insert("Faythe", 6).is_err());
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+pub type AllocHashMap<K, V, H> = HashMap<K, V, crate::storage::AllocStorage<HashMapLayout<K, V>>, H>;
+/// A hash map using an inline array for storage.
+///
+/// # Examples
+/// ```
+/// # extern crate rustc_hash;
+/// use rustc_hash::FxHasher;
+/// # use coca::collections::InlineHashMap;
+/// # use core::hash::BuildHasherDefault;
+/// let mut map = InlineHashMap::<&'static str, usize, BuildHasherDefault<FxHasher>, 4>::new();
+///
+/// for k in &["Alice", "Bob", "Charlie", "Eve"] {
+///     map.insert(k, k.len());
+/// }
+///
+/// assert!(map.is_full());
+/// assert_eq!(map.get("Charlie"), Some(&7));
+/// ```
+pub type InlineHashMap<K, V, H, const N: usize> = HashMap<K, V, hash_map::InlineStorage<K, V, N>, H>;
+
+/// A hash set using any mutable byte slice for storage.
+///
+/// # Examples
+/// ```
+/// # extern crate rustc_hash;
+/// use rustc_hash::FxHasher;
+/// use coca::collections::{hash_map::SliceStorage, SliceHashSet};
+/// use core::{hash::BuildHasherDefault, mem::MaybeUninit};
+///
+/// let mut backing_region = [MaybeUninit::uninit(); 256];
+/// let storage = SliceStorage::from(&mut backing_region[..]);
+/// let mut set = SliceHashSet::<'_, u32, BuildHasherDefault<FxHasher>>::from(storage);
+/// set.insert(1);
+/// assert!(set.contains(&1));
+/// ```
+pub type SliceHashSet<'a, T, H> = HashSet<T, hash_map::SliceStorage<'a, T, ()>, H>;
+/// A hash set using an arena-allocated memory block for storage.
+///
+/// # Examples
+/// ```
+/// # extern crate rustc_hash;
+/// use rustc_hash::FxHasher;
+/// use coca::{arena::Arena, collections::ArenaHashSet};
+/// use core::{hash::BuildHasherDefault, mem::MaybeUninit};
+///
+/// let mut backing_region = [MaybeUninit::uninit(); 1024];
+/// let mut arena = Arena::from(&mut backing_region[..]);
+///
+/// let set: ArenaHashSet<'_, &'static str, BuildHasherDefault<FxHasher>> = arena.try_with_capacity(50).unwrap();
+/// assert!(arena.try_with_capacity::<_, ArenaHashSet<'_, &'static str, BuildHasherDefault<FxHasher>>>(50).is_none());
+/// ```
+pub type ArenaHashSet<'src, T, H> = HashSet<T, ArenaStorage<'src, HashMapLayout<T, ()>>, H>;
+/// A hash set using a heap-allocated memory block for storage.
+///
+/// Note that this still has a fixed capacity, and will never reallocate.
+///
+/// # Examples
+/// ```
+/// # extern crate rustc_hash;
+/// use rustc_hash::FxHasher;
+/// # use coca::collections::AllocHashSet;
+/// # use core::hash::BuildHasherDefault;
+/// let mut set = AllocHashSet::<&'static str, BuildHasherDefault<FxHasher>>::with_capacity(13);
+/// assert_eq!(set.capacity(), 13);
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+pub type AllocHashSet<T, H> = HashSet<T, crate::storage::AllocStorage<HashMapLayout<T, ()>>, H>;
+/// A hash set using an inline array for storage.
+///
+/// # Examples
+/// ```
+/// # extern crate rustc_hash;
+/// use rustc_hash::FxHasher;
+/// # use coca::collections::InlineHashSet;
+/// # use core::hash::BuildHasherDefault;
+/// let mut set = InlineHashSet::<&'static str, BuildHasherDefault<FxHasher>, 20>::new();
+/// assert_eq!(set.capacity(), 20);
+/// ```
+pub type InlineHashSet<T, H, const N: usize> = HashSet<T, hash_map::InlineStorage<T, (), N>, H>;
+
 /// An association list that stores its contents in an arena-allocated memory block.
 /// 
 /// # Examples
@@ -356,6 +878,79 @@ pub type AllocListMap<K, V, I = usize> = ListMap<K, V, crate::storage::AllocStor
 /// ```
 pub type InlineListMap<K, V, const N: usize, I = usize> = ListMap<K, V, list_map::InlineStorage<K, V, N>, I>;
 
+/// A sorted map that stores its contents in an arena-allocated memory block.
+/// 
+/// # Examples
+/// ```
+/// use coca::arena::Arena;
+/// use coca::collections::ArenaSortedMap;
+/// use core::mem::MaybeUninit;
+///
+/// let mut backing_region = [MaybeUninit::uninit(); 2048];
+/// let mut arena = Arena::from(&mut backing_region[..]);
+/// 
+/// let map: ArenaSortedMap<'_, &'static str, u32> = arena.try_with_capacity(100).unwrap();
+/// assert!(arena.try_with_capacity::<_, ArenaSortedMap<'_, &'static str, u32>>(100).is_none());
+/// ```
+pub type ArenaSortedMap<'a, K, V, I = usize> = SortedMap<K, V, ArenaStorage<'a, ListMapLayout<K, V>>, I>;
+/// A sorted map that stores its contents in globally allocated memory.
+/// 
+/// # Examples
+/// ```
+/// use coca::collections::AllocSortedMap;
+/// let mut map = AllocSortedMap::<&'static str, u32>::with_capacity(13);
+/// assert_eq!(map.capacity(), 13);
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+pub type AllocSortedMap<K, V, I = usize> = SortedMap<K, V, crate::storage::AllocStorage<ListMapLayout<K, V>>, I>;
+/// A sorted map that stores its contents inline.
+/// 
+/// # Examples
+/// ```
+/// use coca::collections::InlineSortedMap;
+/// let mut map = InlineSortedMap::<&'static str, u32, 3, u8>::new();
+/// # assert!(map.is_empty());
+/// ```
+pub type InlineSortedMap<K, V, const N: usize, I = usize> = SortedMap<K, V, list_map::InlineStorage<K, V, N>, I>;
+
+/// A B-tree map that allocates its nodes from an arena-allocated memory block.
+///
+/// # Examples
+/// ```
+/// use coca::arena::Arena;
+/// use coca::collections::ArenaBTreeMap;
+/// use core::mem::MaybeUninit;
+///
+/// let mut backing_region = [MaybeUninit::uninit(); 4096];
+/// let mut arena = Arena::from(&mut backing_region[..]);
+///
+/// let map: ArenaBTreeMap<'_, u32, u32> = arena.try_with_capacity(12).unwrap();
+/// assert_eq!(map.node_capacity(), 12);
+/// assert!(arena.try_with_capacity::<_, ArenaBTreeMap<'_, u32, u32>>(12).is_none());
+/// ```
+pub type ArenaBTreeMap<'src, K, V, H = DefaultHandle> = BTreeMap<K, V, ArenaStorage<'src, BTreeMapLayout<K, V, H>>, H>;
+/// A B-tree map that allocates its nodes from globally allocated memory.
+///
+/// # Examples
+/// ```
+/// use coca::collections::AllocBTreeMap;
+/// let mut map = AllocBTreeMap::<&'static str, u32>::with_node_capacity(4);
+/// assert_eq!(map.node_capacity(), 4);
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+pub type AllocBTreeMap<K, V, H = DefaultHandle> = BTreeMap<K, V, crate::storage::AllocStorage<BTreeMapLayout<K, V, H>>, H>;
+/// A B-tree map that stores its nodes inline.
+///
+/// # Examples
+/// ```
+/// use coca::collections::InlineBTreeMap;
+/// let mut map = InlineBTreeMap::<&'static str, u32, 4>::new();
+/// # assert!(map.is_empty());
+/// ```
+pub type InlineBTreeMap<K, V, const N: usize, H = DefaultHandle> = BTreeMap<K, V, pool::direct::InlineStorage<btree_map::Node<K, V, H>, H, N>, H>;
+
 /// A set based on an arena-allocated array.
 /// 
 /// # Examples
@@ -404,6 +999,88 @@ pub type SliceListSet<'a, T, I = usize> = ListSet<T, SliceStorage<'a, T>, I>;
 /// ```
 pub type InlineListSet<T, const N: usize, I = usize> = ListSet<T, InlineStorage<T, N>, I>;
 
+/// A bit set based on an arena-allocated array of words.
+///
+/// Note that [`ArenaBitSet::with_capacity`] and [`ArenaBitSet::try_with_capacity`]
+/// take the capacity in bits, whereas the generic
+/// [`Arena::with_capacity`](crate::arena::Arena::with_capacity) takes the
+/// number of `usize` words; see [`bit_set::words_required`].
+///
+/// # Examples
+/// ```
+/// use coca::arena::Arena;
+/// use coca::collections::ArenaBitSet;
+/// use core::mem::MaybeUninit;
+///
+/// let mut backing_region = [MaybeUninit::uninit(); 512];
+/// let mut arena = Arena::from(&mut backing_region[..]);
+///
+/// let set = ArenaBitSet::<u16>::try_with_capacity(&mut arena, 3000).unwrap();
+/// assert!(set.capacity() >= 3000);
+/// assert!(ArenaBitSet::<u16>::try_with_capacity(&mut arena, 3000).is_none());
+/// ```
+pub type ArenaBitSet<'a, I = usize> = BitSet<ArenaStorage<'a, ArrayLayout<usize>>, I>;
+/// A bit set based on a globally allocated array of words.
+///
+/// # Examples
+/// ```
+/// use coca::collections::AllocBitSet;
+/// let set = AllocBitSet::<u16>::with_capacity(1000);
+/// assert!(set.capacity() >= 1000);
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+pub type AllocBitSet<I = usize> = BitSet<crate::storage::AllocStorage<ArrayLayout<usize>>, I>;
+/// A bit set based on an inline array of `W` words.
+///
+/// # Examples
+/// ```
+/// use coca::collections::InlineBitSet;
+/// let set = InlineBitSet::<4, u8>::new();
+/// assert_eq!(set.capacity(), 4 * usize::BITS as usize);
+/// ```
+pub type InlineBitSet<const W: usize, I = usize> = BitSet<InlineStorage<usize, W>, I>;
+
+/// A bit vector based on an arena-allocated array of words.
+///
+/// Note that the capacity passed to the arena is the number of `usize` words,
+/// not the number of bits; see [`bit_set::words_required`].
+///
+/// # Examples
+/// ```
+/// use coca::arena::Arena;
+/// use coca::collections::{ArenaBitVec, bit_set::words_required};
+/// use core::mem::MaybeUninit;
+///
+/// let mut backing_region = [MaybeUninit::uninit(); 512];
+/// let mut arena = Arena::from(&mut backing_region[..]);
+///
+/// let bits: ArenaBitVec<'_> = arena.try_with_capacity(words_required(3000)).unwrap();
+/// assert!(bits.capacity() >= 3000);
+/// assert!(arena.try_with_capacity::<_, ArenaBitVec<'_>>(words_required(3000)).is_none());
+/// ```
+pub type ArenaBitVec<'a, I = usize> = BitVec<ArenaStorage<'a, ArrayLayout<usize>>, I>;
+/// A bit vector based on a globally allocated array of words.
+///
+/// # Examples
+/// ```
+/// use coca::collections::AllocBitVec;
+/// let bits = AllocBitVec::<u16>::with_capacity(1000);
+/// assert!(bits.capacity() >= 1000);
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+pub type AllocBitVec<I = usize> = BitVec<crate::storage::AllocStorage<ArrayLayout<usize>>, I>;
+/// A bit vector based on an inline array of `W` words.
+///
+/// # Examples
+/// ```
+/// use coca::collections::InlineBitVec;
+/// let bits = InlineBitVec::<4, u16>::new();
+/// assert_eq!(bits.capacity(), 4 * usize::BITS as usize);
+/// ```
+pub type InlineBitVec<const W: usize, I = usize> = BitVec<InlineStorage<usize, W>, I>;
+
 /// A group of up to eight [`Option`]s with the discriminants packed into a single `u8`.
 pub type OptionGroup8<T> = OptionGroup<u8, T>;
 /// A group of up to sixteen [`Option`]s with the discriminants packed into a single `u16`.
@@ -530,6 +1207,158 @@ pub type PackedAllocPool<T, H = DefaultHandle> = PackedPool<T, crate::storage::A
 /// ```
 pub type PackedInlinePool<T, const N: usize, H = DefaultHandle> = PackedPool<T, pool::packed::InlineStorage<T, H, N>, H>;
 
+/// A thread-safe direct-mapped pool that stores its contents in an arena-allocated memory block.
+///
+/// # Examples
+/// ```
+/// use coca::arena::Arena;
+/// use coca::collections::{SyncArenaPool, pool::DefaultHandle};
+/// use core::mem::MaybeUninit;
+///
+/// let mut backing_region = [MaybeUninit::uninit(); 1024];
+/// let mut arena = Arena::from(&mut backing_region[..]);
+///
+/// let pool: SyncArenaPool<'_, i64, DefaultHandle> = arena.try_with_capacity(30).unwrap();
+/// assert!(arena.try_with_capacity::<_, SyncArenaPool<'_, i64, DefaultHandle>>(30).is_none());
+/// ```
+pub type SyncArenaPool<'src, T, H = DefaultHandle> =
+    SyncPool<T, ArenaStorage<'src, SyncPoolLayout<T, H>>, H>;
+
+/// A thread-safe direct-mapped pool that stores its contents in globally allocated memory.
+///
+/// # Examples
+/// ```
+/// # use coca::collections::SyncAllocPool;
+/// let pool = SyncAllocPool::<u128>::with_capacity(2);
+/// assert_eq!(pool.capacity(), 2);
+///
+/// pool.insert(1);
+/// pool.insert(2);
+/// assert_eq!(pool.try_insert(3), Err(3));
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+pub type SyncAllocPool<T, H = DefaultHandle> =
+    SyncPool<T, crate::storage::AllocStorage<SyncPoolLayout<T, H>>, H>;
+
+/// A thread-safe direct-mapped pool that stores its contents in an inline array.
+///
+/// # Examples
+/// ```
+/// # use coca::handle_type;
+/// # use coca::collections::SyncInlinePool;
+/// handle_type! { CustomHandle: 8 / 32; }
+///
+/// let pool = SyncInlinePool::<u128, 8, CustomHandle>::new();
+/// let a: CustomHandle = pool.insert(1);
+/// assert_eq!(*pool.get(a).unwrap(), 1);
+/// assert_eq!(pool.remove(a), Some(1));
+/// assert!(pool.is_empty());
+/// ```
+pub type SyncInlinePool<T, const N: usize, H = DefaultHandle> = SyncPool<T, pool::sync::InlineStorage<T, N>, H>;
+
+/// A secondary map that stores its contents in an arena-allocated memory block.
+///
+/// # Examples
+/// ```
+/// use coca::arena::Arena;
+/// use coca::collections::{ArenaSecondaryMap, DirectArenaPool, pool::DefaultHandle};
+/// use core::mem::MaybeUninit;
+///
+/// let mut backing_region = [MaybeUninit::uninit(); 1024];
+/// let mut arena = Arena::from(&mut backing_region[..]);
+///
+/// let mut pool: DirectArenaPool<'_, &'static str> = arena.with_capacity(16);
+/// let mut map: ArenaSecondaryMap<'_, DefaultHandle, u64> = arena.with_capacity(16);
+///
+/// let h = pool.insert("hello");
+/// map.insert(h, 42);
+/// assert_eq!(map[h], 42);
+/// ```
+pub type ArenaSecondaryMap<'src, H, V> = SecondaryMap<H, V, ArenaStorage<'src, SecondaryMapLayout<V>>>;
+
+/// A secondary map that stores its contents in globally allocated memory.
+///
+/// # Examples
+/// ```
+/// # use coca::collections::{AllocSecondaryMap, DirectAllocPool, pool::DefaultHandle};
+/// let mut pool = DirectAllocPool::<&'static str>::with_capacity(4);
+/// let mut map = AllocSecondaryMap::<DefaultHandle, u64>::with_capacity(4);
+/// assert_eq!(map.capacity(), 4);
+///
+/// let h = pool.insert("hello");
+/// map.insert(h, 42);
+/// assert_eq!(map[h], 42);
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+pub type AllocSecondaryMap<H, V> = SecondaryMap<H, V, crate::storage::AllocStorage<SecondaryMapLayout<V>>>;
+
+/// A secondary map that stores its contents in an inline array.
+///
+/// # Examples
+/// ```
+/// # use coca::collections::{InlineSecondaryMap, DirectInlinePool, pool::DefaultHandle};
+/// let mut pool = DirectInlinePool::<&'static str, 4>::new();
+/// let mut map = InlineSecondaryMap::<DefaultHandle, u64, 4>::new();
+///
+/// let h = pool.insert("hello");
+/// map.insert(h, 42);
+/// assert_eq!(map[h], 42);
+/// ```
+pub type InlineSecondaryMap<H, V, const N: usize> = SecondaryMap<H, V, pool::secondary::InlineStorage<V, N>>;
+
+/// A sparse secondary map that stores its contents in an arena-allocated memory block.
+///
+/// # Examples
+/// ```
+/// use coca::arena::Arena;
+/// use coca::collections::{ArenaSparseSecondaryMap, DirectArenaPool, pool::DefaultHandle};
+/// use core::mem::MaybeUninit;
+///
+/// let mut backing_region = [MaybeUninit::uninit(); 1024];
+/// let mut arena = Arena::from(&mut backing_region[..]);
+///
+/// let mut pool: DirectArenaPool<'_, &'static str> = arena.with_capacity(16);
+/// let mut map: ArenaSparseSecondaryMap<'_, DefaultHandle, u64> = arena.with_capacity(2);
+///
+/// let h = pool.insert("hello");
+/// map.insert(h, 42);
+/// assert_eq!(map[h], 42);
+/// ```
+pub type ArenaSparseSecondaryMap<'src, H, V> = SparseSecondaryMap<H, V, ArenaStorage<'src, ListMapLayout<H, V>>>;
+
+/// A sparse secondary map that stores its contents in globally allocated memory.
+///
+/// # Examples
+/// ```
+/// # use coca::collections::{AllocSparseSecondaryMap, DirectAllocPool, pool::DefaultHandle};
+/// let mut pool = DirectAllocPool::<&'static str>::with_capacity(16);
+/// let mut map = AllocSparseSecondaryMap::<DefaultHandle, u64>::with_capacity(2);
+/// assert_eq!(map.capacity(), 2);
+///
+/// let h = pool.insert("hello");
+/// map.insert(h, 42);
+/// assert_eq!(map[h], 42);
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+pub type AllocSparseSecondaryMap<H, V> = SparseSecondaryMap<H, V, crate::storage::AllocStorage<ListMapLayout<H, V>>>;
+
+/// A sparse secondary map that stores its contents in an inline array.
+///
+/// # Examples
+/// ```
+/// # use coca::collections::{InlineSparseSecondaryMap, DirectInlinePool, pool::DefaultHandle};
+/// let mut pool = DirectInlinePool::<&'static str, 16>::new();
+/// let mut map = InlineSparseSecondaryMap::<DefaultHandle, u64, 2>::new();
+///
+/// let h = pool.insert("hello");
+/// map.insert(h, 42);
+/// assert_eq!(map[h], 42);
+/// ```
+pub type InlineSparseSecondaryMap<H, V, const N: usize> = SparseSecondaryMap<H, V, list_map::InlineStorage<H, V, N>>;
+
 /// A vector using any mutable slice for storage.
 ///
 /// # Examples
@@ -0,0 +1,373 @@
+//! A bounded lock-free multi-producer multi-consumer queue.
+//!
+//! [`MpmcQueue`] is an implementation of [Dmitry Vyukov's bounded MPMC queue][vyukov],
+//! an array of slots each carrying a sequence number in addition to the value.
+//! Producers and consumers claim slots by advancing a shared position with a
+//! compare-and-swap, and use the slot's sequence number to detect whether it
+//! is ready to be written or read. Unlike [`SpscQueue`](crate::collections::spsc::SpscQueue),
+//! the queue is used through shared references, so any number of threads may
+//! push and pop concurrently.
+//!
+//! The capacity of the queue must be a power of two, and at least two, since
+//! a single slot cannot tell a value waiting to be read from one that has been
+//! read already.
+//!
+//! [vyukov]: https://www.1024cores.net/home/lock-free-algorithms/queues/bounded-mpmc-queue
+//!
+//! ```
+//! use coca::collections::InlineMpmcQueue;
+//!
+//! let queue = InlineMpmcQueue::<u32, 4>::new();
+//! assert!(queue.try_push(1).is_ok());
+//! assert!(queue.try_push(2).is_ok());
+//! assert_eq!(queue.try_pop(), Some(1));
+//! assert_eq!(queue.try_pop(), Some(2));
+//! assert_eq!(queue.try_pop(), None);
+//! ```
+
+use core::alloc::{Layout, LayoutError};
+use core::cell::UnsafeCell;
+use core::cmp::Ordering as CmpOrdering;
+use core::fmt::{self, Debug, Formatter};
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::storage::{LayoutSpec, Storage};
+
+/// Compares a slot's sequence number against the expected one, accounting
+/// for wrap-around of both.
+#[inline(always)]
+#[allow(clippy::cast_possible_wrap)]
+fn lap_difference(sequence: usize, expected: usize) -> CmpOrdering {
+    (sequence.wrapping_sub(expected) as isize).cmp(&0)
+}
+
+struct Slot<T> {
+    sequence: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// The [`LayoutSpec`] for a [`MpmcQueue`], an array of values paired with
+/// sequence numbers.
+pub struct MpmcQueueLayout<T>(PhantomData<T>);
+impl<T> LayoutSpec for MpmcQueueLayout<T> {
+    fn layout_with_capacity(items: usize) -> Result<Layout, LayoutError> {
+        Layout::array::<Slot<T>>(items)
+    }
+}
+
+/// A bounded queue supporting any number of concurrent producers and consumers
+/// without locking.
+///
+/// See the [module-level documentation](crate::collections::mpmc) for more.
+pub struct MpmcQueue<T, S: Storage<MpmcQueueLayout<T>>> {
+    enqueue_pos: AtomicUsize,
+    dequeue_pos: AtomicUsize,
+    buf: S,
+    elem: PhantomData<T>,
+}
+
+unsafe impl<T: Send, S: Storage<MpmcQueueLayout<T>> + Send> Send for MpmcQueue<T, S> {}
+unsafe impl<T: Send, S: Storage<MpmcQueueLayout<T>> + Sync> Sync for MpmcQueue<T, S> {}
+
+impl<T, S: Storage<MpmcQueueLayout<T>>> From<S> for MpmcQueue<T, S> {
+    /// Converts a contiguous block of memory into an empty queue.
+    ///
+    /// # Panics
+    /// Panics if the capacity of `buf` is not a power of two, or less than two.
+    fn from(mut buf: S) -> Self {
+        let capacity = buf.capacity();
+        assert!(
+            capacity.is_power_of_two() && capacity >= 2,
+            "queue capacity must be a power of two and at least 2, but is {}",
+            capacity
+        );
+
+        let slots = buf.get_mut_ptr().cast::<Slot<T>>();
+        for i in 0..capacity {
+            unsafe { core::ptr::addr_of_mut!((*slots.add(i)).sequence).write(AtomicUsize::new(i)); }
+        }
+
+        MpmcQueue {
+            enqueue_pos: AtomicUsize::new(0),
+            dequeue_pos: AtomicUsize::new(0),
+            buf,
+            elem: PhantomData,
+        }
+    }
+}
+
+impl<T, S: Storage<MpmcQueueLayout<T>>> MpmcQueue<T, S> {
+    #[inline(always)]
+    fn slot(&self, pos: usize) -> &Slot<T> {
+        let index = pos & (self.buf.capacity() - 1);
+        unsafe { &*self.buf.get_ptr().cast::<Slot<T>>().add(index) }
+    }
+
+    /// Returns the number of elements the queue can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    /// Returns the number of elements in the queue.
+    ///
+    /// Since other threads may push or pop elements concurrently, this
+    /// is only a snapshot, and may be outdated by the time it is returned.
+    pub fn len(&self) -> usize {
+        let dequeue_pos = self.dequeue_pos.load(Ordering::Relaxed);
+        let enqueue_pos = self.enqueue_pos.load(Ordering::Relaxed);
+        enqueue_pos.wrapping_sub(dequeue_pos).min(self.capacity())
+    }
+
+    /// Returns `true` if the queue contains no elements.
+    ///
+    /// See [`len`](MpmcQueue::len) for caveats.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the queue is full.
+    ///
+    /// See [`len`](MpmcQueue::len) for caveats.
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+
+    /// Appends an element to the back of the queue.
+    ///
+    /// Returns [`CapacityError`](crate::CapacityError) if the queue is full,
+    /// in which case `value` is dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// let queue = coca::collections::InlineMpmcQueue::<char, 2>::new();
+    /// assert!(queue.try_push('a').is_ok());
+    /// assert!(queue.try_push('b').is_ok());
+    /// assert!(queue.try_push('c').is_err());
+    /// ```
+    pub fn try_push(&self, value: T) -> crate::Result<()> {
+        let mut pos = self.enqueue_pos.load(Ordering::Relaxed);
+        let slot = loop {
+            let slot = self.slot(pos);
+            let sequence = slot.sequence.load(Ordering::Acquire);
+            match lap_difference(sequence, pos) {
+                CmpOrdering::Equal => match self.enqueue_pos.compare_exchange_weak(pos, pos.wrapping_add(1), Ordering::Relaxed, Ordering::Relaxed) {
+                    Ok(_) => break slot,
+                    Err(current) => pos = current,
+                },
+                CmpOrdering::Less => return crate::CapacityError::new(),
+                CmpOrdering::Greater => pos = self.enqueue_pos.load(Ordering::Relaxed),
+            }
+        };
+
+        unsafe { (*slot.value.get()).as_mut_ptr().write(value); }
+        slot.sequence.store(pos.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+
+    /// Appends an element to the back of the queue.
+    ///
+    /// # Panics
+    /// Panics if the queue is full. See [`try_push`](MpmcQueue::try_push)
+    /// for a checked version that never panics.
+    #[track_caller]
+    pub fn push(&self, value: T) {
+        assert!(self.try_push(value).is_ok(), "queue is already at capacity");
+    }
+
+    /// Removes the element at the front of the queue and returns it, or
+    /// [`None`] if the queue is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// let queue = coca::collections::InlineMpmcQueue::<char, 2>::new();
+    /// queue.push('a');
+    /// assert_eq!(queue.try_pop(), Some('a'));
+    /// assert_eq!(queue.try_pop(), None);
+    /// ```
+    pub fn try_pop(&self) -> Option<T> {
+        let mut pos = self.dequeue_pos.load(Ordering::Relaxed);
+        let slot = loop {
+            let slot = self.slot(pos);
+            let sequence = slot.sequence.load(Ordering::Acquire);
+            match lap_difference(sequence, pos.wrapping_add(1)) {
+                CmpOrdering::Equal => match self.dequeue_pos.compare_exchange_weak(pos, pos.wrapping_add(1), Ordering::Relaxed, Ordering::Relaxed) {
+                    Ok(_) => break slot,
+                    Err(current) => pos = current,
+                },
+                CmpOrdering::Less => return None,
+                CmpOrdering::Greater => pos = self.dequeue_pos.load(Ordering::Relaxed),
+            }
+        };
+
+        let value = unsafe { (*slot.value.get()).as_ptr().read() };
+        slot.sequence.store(pos.wrapping_add(self.capacity()), Ordering::Release);
+        Some(value)
+    }
+}
+
+impl<T, S: Storage<MpmcQueueLayout<T>>> Drop for MpmcQueue<T, S> {
+    fn drop(&mut self) {
+        while self.try_pop().is_some() {}
+    }
+}
+
+impl<T, S: Storage<MpmcQueueLayout<T>>> Debug for MpmcQueue<T, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MpmcQueue")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+impl<T> crate::collections::AllocMpmcQueue<T> {
+    /// Constructs a new, empty [`AllocMpmcQueue`](crate::collections::AllocMpmcQueue)
+    /// with at least the specified capacity, rounded up to the next power of
+    /// two, and to no less than two.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from(crate::storage::AllocStorage::with_capacity(capacity.max(2).next_power_of_two()))
+    }
+}
+
+/// A statically-sized storage block for a [`MpmcQueue`].
+#[repr(transparent)]
+pub struct InlineStorage<T, const N: usize> {
+    slots: [MaybeUninit<Slot<T>>; N],
+}
+
+// The slots are only accessed through the queue, which synchronizes access.
+unsafe impl<T: Send, const N: usize> Sync for InlineStorage<T, N> {}
+
+unsafe impl<T, const N: usize> Storage<MpmcQueueLayout<T>> for InlineStorage<T, N> {
+    fn get_ptr(&self) -> *const u8 {
+        self.slots.as_ptr().cast()
+    }
+
+    fn get_mut_ptr(&mut self) -> *mut u8 {
+        self.slots.as_mut_ptr().cast()
+    }
+
+    fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<T, const N: usize> MpmcQueue<T, InlineStorage<T, N>> {
+    /// Constructs a new, empty [`InlineMpmcQueue`](crate::collections::InlineMpmcQueue).
+    ///
+    /// # Panics
+    /// Panics if `N` is not a power of two, or less than two.
+    pub fn new() -> Self {
+        Self::from(InlineStorage { slots: unsafe { MaybeUninit::uninit().assume_init() } })
+    }
+}
+
+impl<T, const N: usize> Default for MpmcQueue<T, InlineStorage<T, N>> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use crate::collections::InlineMpmcQueue;
+    use crate::test_utils::DropCounter;
+
+    #[test]
+    fn wraps_around_and_drops_remaining() {
+        let counter = DropCounter::new();
+        {
+            let queue = InlineMpmcQueue::<_, 4>::new();
+            for i in 0..10 {
+                queue.push(counter.new_droppable(i));
+                assert_eq!(queue.try_pop().map(|d| d.value), Some(i));
+            }
+
+            for i in 0..4 {
+                queue.push(counter.new_droppable(i));
+            }
+
+            assert!(queue.is_full());
+            assert!(queue.try_push(counter.new_droppable(10)).is_err());
+            assert_eq!(counter.dropped(), 11);
+        }
+        assert_eq!(counter.dropped(), 15);
+    }
+
+    #[test]
+    #[should_panic]
+    fn single_slot_is_rejected() {
+        let _ = InlineMpmcQueue::<u32, 1>::new();
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn alloc_capacity_is_at_least_two() {
+        for &requested in &[0, 1, 2] {
+            let queue = crate::collections::AllocMpmcQueue::<u32>::with_capacity(requested);
+            assert_eq!(queue.capacity(), 2);
+            assert!(queue.try_push(1).is_ok());
+            assert!(queue.try_push(2).is_ok());
+            assert!(queue.try_push(3).is_err());
+            assert_eq!(queue.try_pop(), Some(1));
+            assert_eq!(queue.try_pop(), Some(2));
+            assert_eq!(queue.try_pop(), None);
+        }
+    }
+
+    // The number of items each producer pushes can be raised by setting the
+    // `COCA_STRESS_ITERATIONS` environment variable, e.g. on dedicated CI runs.
+    #[test]
+    fn concurrent_stress() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::vec::Vec;
+
+        const THREADS: usize = 4;
+        let per_thread: usize = std::env::var("COCA_STRESS_ITERATIONS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(2_000);
+
+        let queue: &'static InlineMpmcQueue<usize, 8> = std::boxed::Box::leak(std::boxed::Box::default());
+        let popped: &'static AtomicUsize = std::boxed::Box::leak(std::boxed::Box::default());
+        let sum: &'static AtomicUsize = std::boxed::Box::leak(std::boxed::Box::default());
+
+        let mut handles = Vec::new();
+        for t in 0..THREADS {
+            handles.push(std::thread::spawn(move || {
+                for i in 0..per_thread {
+                    while queue.try_push(t * per_thread + i).is_err() {
+                        std::thread::yield_now();
+                    }
+                }
+            }));
+
+            handles.push(std::thread::spawn(move || {
+                while popped.load(Ordering::Relaxed) < THREADS * per_thread {
+                    if let Some(x) = queue.try_pop() {
+                        sum.fetch_add(x, Ordering::Relaxed);
+                        popped.fetch_add(1, Ordering::Relaxed);
+                    } else {
+                        std::thread::yield_now();
+                    }
+                }
+            }));
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let n = THREADS * per_thread;
+        assert_eq!(popped.load(Ordering::Relaxed), n);
+        assert_eq!(sum.load(Ordering::Relaxed), n * (n - 1) / 2);
+        assert!(queue.is_empty());
+    }
+}
@@ -10,7 +10,10 @@ use core::marker::PhantomData;
 use core::mem::{ManuallyDrop, MaybeUninit};
 use core::ops::{Index, IndexMut};
 
-use super::{buffer_too_large_for_handle_type, DebugEntry, DefaultHandle, Handle};
+use super::{
+    buffer_too_large_for_handle_type, checked_raw_parts, tag_too_large_for_handle_type, vacated_generation,
+    DebugEntry, DefaultHandle, Handle,
+};
 use crate::storage::{Capacity, LayoutSpec, Storage};
 
 union Slot<T, I: Capacity> {
@@ -38,6 +41,8 @@ pub struct DirectPool<T, S: Storage<DirectPoolLayout<T, H>>, H: Handle = Default
     buf: S,
     len: H::Index,
     next_free_slot: H::Index,
+    retired: H::Index,
+    tag: u32,
     items: PhantomData<T>,
 }
 
@@ -52,6 +57,8 @@ impl<T, S: Storage<DirectPoolLayout<T, H>>, H: Handle> From<S> for DirectPool<T,
             buf,
             len: H::Index::from_usize(0),
             next_free_slot: H::Index::from_usize(0),
+            retired: H::Index::from_usize(0),
+            tag: 0,
             items: PhantomData,
         };
 
@@ -124,9 +131,82 @@ impl<T, S: Storage<DirectPoolLayout<T, H>>, H: Handle> DirectPool<T, S, H> {
     }
 
     /// Returns [`true`] if the pool contains the maximum number of elements.
+    ///
+    /// This excludes [retired slots](DirectPool::retired_slots), which can
+    /// not hold any elements.
     #[inline]
     pub fn is_full(&self) -> bool {
-        self.len.as_usize() == self.buf.capacity()
+        self.len.as_usize() + self.retired.as_usize() == self.buf.capacity()
+    }
+
+    /// Returns the number of slots that have been retired because their
+    /// generation count was exhausted.
+    ///
+    /// This is always zero unless [`H::RETIRE_EXHAUSTED_SLOTS`](Handle::RETIRE_EXHAUSTED_SLOTS)
+    /// is set, see the [super module documentation](crate::collections::pool)
+    /// for details.
+    #[inline]
+    pub fn retired_slots(&self) -> usize {
+        self.retired.as_usize()
+    }
+
+    /// Returns the tag carried by all handles returned from the pool.
+    ///
+    /// This is always zero unless the handle type reserves bits for a tag
+    /// (see [`Handle::MAX_TAG`]).
+    #[inline]
+    pub fn tag(&self) -> u32 {
+        self.tag
+    }
+
+    /// Sets the tag carried by all handles returned from the pool from now
+    /// on. Handles with a different tag are rejected as invalid, so this
+    /// invalidates all handles previously returned from the pool unless
+    /// their tag matches the new one.
+    ///
+    /// # Panics
+    /// Panics if `tag` is greater than [`H::MAX_TAG`](Handle::MAX_TAG).
+    ///
+    /// # Examples
+    /// ```
+    /// # use coca::{handle_type, collections::DirectInlinePool};
+    /// handle_type! { TaggedHandle: 8 / 32 tag 2; }
+    ///
+    /// let mut pool = DirectInlinePool::<char, 4, TaggedHandle>::new();
+    /// pool.set_tag(3);
+    /// let h = pool.insert('a');
+    /// assert_eq!(pool.get(h), Some(&'a'));
+    ///
+    /// pool.set_tag(1);
+    /// assert_eq!(pool.get(h), None);
+    /// ```
+    pub fn set_tag(&mut self, tag: u32) {
+        if tag > H::MAX_TAG {
+            tag_too_large_for_handle_type::<H>(tag);
+        }
+        self.tag = tag;
+    }
+
+    /// Moves the value out of the occupied slot at `index`, and either adds
+    /// the slot to the free list or retires it.
+    ///
+    /// # Safety
+    /// The slot at `index` must be occupied with the generation count `gen_count`.
+    unsafe fn vacate(&mut self, index: usize, gen_count: u32) -> T {
+        let (new_gen_count, retire) = vacated_generation::<H>(gen_count);
+        self.gen_counts_mut().add(index).write(new_gen_count);
+
+        let slot_ptr = self.slots_mut().add(index);
+        let item = slot_ptr.cast::<T>().read();
+        if retire {
+            (*slot_ptr).next_free_slot = H::Index::from_usize(Self::FREE_LIST_SENTINEL);
+            self.retired = H::Index::from_usize(self.retired.as_usize() + 1);
+        } else {
+            (*slot_ptr).next_free_slot = self.next_free_slot;
+            self.next_free_slot = H::Index::from_usize(index);
+        }
+
+        item
     }
 
     /// Returns [`true`] if the specified handle is valid for this pool.
@@ -143,7 +223,7 @@ impl<T, S: Storage<DirectPoolLayout<T, H>>, H: Handle> DirectPool<T, S, H> {
     /// assert!(!pool.contains(h));
     /// ```
     pub fn contains(&self, handle: H) -> bool {
-        let (index, input_gen_count) = handle.into_raw_parts();
+        let (index, input_gen_count) = checked_raw_parts(handle, self.tag);
         if index >= self.buf.capacity() {
             return false;
         }
@@ -155,7 +235,7 @@ impl<T, S: Storage<DirectPoolLayout<T, H>>, H: Handle> DirectPool<T, S, H> {
     ///
     /// Returns [`None`] if the handle is invalid for this pool.
     pub fn get(&self, handle: H) -> Option<&T> {
-        let (index, input_gen_count) = handle.into_raw_parts();
+        let (index, input_gen_count) = checked_raw_parts(handle, self.tag);
         if index >= self.buf.capacity() {
             return None;
         }
@@ -170,7 +250,7 @@ impl<T, S: Storage<DirectPoolLayout<T, H>>, H: Handle> DirectPool<T, S, H> {
     ///
     /// Returns [`None`] if the handle is invalid for this pool.
     pub fn get_mut(&mut self, handle: H) -> Option<&mut T> {
-        let (index, input_gen_count) = handle.into_raw_parts();
+        let (index, input_gen_count) = checked_raw_parts(handle, self.tag);
         if index >= self.buf.capacity() {
             return None;
         }
@@ -211,7 +291,7 @@ impl<T, S: Storage<DirectPoolLayout<T, H>>, H: Handle> DirectPool<T, S, H> {
 
         let mut i = 0;
         while i < N {
-            let (index, input_gen_count) = handles[i].into_raw_parts();
+            let (index, input_gen_count) = checked_raw_parts(handles[i], self.tag);
             if index >= self.capacity() || input_gen_count % 2 == 0 {
                 break;
             }
@@ -275,7 +355,7 @@ impl<T, S: Storage<DirectPoolLayout<T, H>>, H: Handle> DirectPool<T, S, H> {
             self.next_free_slot = (*slot).next_free_slot;
             (*slot).item = ManuallyDrop::new(value);
 
-            Ok(H::new(insert_position, gen_count))
+            Ok(H::new_tagged(insert_position, gen_count, self.tag))
         }
     }
 
@@ -332,7 +412,7 @@ impl<T, S: Storage<DirectPoolLayout<T, H>>, H: Handle> DirectPool<T, S, H> {
 
             let slot = self.slots_mut().add(insert_position);
             self.next_free_slot = (*slot).next_free_slot;
-            let handle = H::new(insert_position, gen_count);
+            let handle = H::new_tagged(insert_position, gen_count, self.tag);
 
             (*slot).item = ManuallyDrop::new(f(handle));
             Some(handle)
@@ -366,7 +446,7 @@ impl<T, S: Storage<DirectPoolLayout<T, H>>, H: Handle> DirectPool<T, S, H> {
     /// assert_eq!(pool.remove(h), None);
     /// ```
     pub fn remove(&mut self, handle: H) -> Option<T> {
-        let (index, input_gen_count) = handle.into_raw_parts();
+        let (index, input_gen_count) = checked_raw_parts(handle, self.tag);
         if index >= self.buf.capacity() || input_gen_count % 2 == 0 {
             return None;
         }
@@ -378,17 +458,7 @@ impl<T, S: Storage<DirectPoolLayout<T, H>>, H: Handle> DirectPool<T, S, H> {
         }
 
         self.len = H::Index::from_usize(self.len() - 1);
-        let item = unsafe {
-            *gen_count_ptr = current_gen_count.wrapping_add(1) & H::MAX_GENERATION;
-
-            let slot_ptr = self.slots_mut().add(index);
-            let item = slot_ptr.read().item;
-            (*slot_ptr).next_free_slot = self.next_free_slot;
-
-            item
-        };
-        self.next_free_slot = H::Index::from_usize(index);
-        Some(ManuallyDrop::into_inner(item))
+        Some(unsafe { self.vacate(index, current_gen_count) })
     }
 
     /// Retains only the elements specified by the predicate.
@@ -676,6 +746,232 @@ impl<T, S: Storage<DirectPoolLayout<T, H>>, H: Handle> DirectPool<T, S, H> {
             kept: H::Index::from_usize(0),
         }
     }
+
+    /// Moves all values to the front of the pool's storage, so that subsequent
+    /// iterations skip fewer vacant slots, and calls `remap` with the old and
+    /// new handle of each relocated value.
+    ///
+    /// Handles to relocated values are invalidated; all other handles remain
+    /// valid. [Retired slots](DirectPool::retired_slots) stay in place, and
+    /// vacating a slot during compaction may retire it.
+    ///
+    /// # Examples
+    /// ```
+    /// # use coca::collections::{pool::DefaultHandle, DirectArenaPool};
+    /// # let mut backing = [core::mem::MaybeUninit::uninit(); 1024];
+    /// # let mut arena = coca::arena::Arena::from(&mut backing[..]);
+    /// let mut pool: DirectArenaPool<u128, DefaultHandle> = arena.with_capacity(4);
+    /// let h0 = pool.insert(0);
+    /// let h1 = pool.insert(1);
+    /// let h2 = pool.insert(2);
+    /// pool.remove(h0);
+    ///
+    /// let mut new_h2 = h2;
+    /// pool.compact(|old, new| {
+    ///     assert_eq!(old, h2);
+    ///     new_h2 = new;
+    /// });
+    ///
+    /// assert!(!pool.contains(h2));
+    /// assert_eq!(pool[h1], 1);
+    /// assert_eq!(pool[new_h2], 2);
+    /// assert_eq!(pool.iter().map(|(_, &x)| x).collect::<Vec<_>>(), [2, 1]);
+    /// ```
+    pub fn compact<F: FnMut(H, H)>(&mut self, mut remap: F) {
+        // Mark all slots in the free list by linking them to themselves,
+        // so they can be told apart from retired slots.
+        let mut next = self.next_free_slot.as_usize();
+        while next != Self::FREE_LIST_SENTINEL {
+            unsafe {
+                let slot = self.slots_mut().add(next);
+                let following = (*slot).next_free_slot.as_usize();
+                (*slot).next_free_slot = H::Index::from_usize(next);
+                next = following;
+            }
+        }
+
+        // The free list is rebuilt even if `remap` panics:
+        let guard = RebuildFreeList { pool: self };
+        let pool = &mut *guard.pool;
+
+        let mut front = 0;
+        let mut back = pool.capacity();
+        loop {
+            while front < back && !unsafe { pool.is_marked_free(front) } {
+                front += 1;
+            }
+            while back > front && unsafe { pool.gen_counts().add(back - 1).read() } % 2 == 0 {
+                back -= 1;
+            }
+            if front >= back {
+                break;
+            }
+
+            back -= 1;
+            unsafe {
+                let old_gen_count = pool.gen_counts().add(back).read();
+                let new_gen_count = pool.gen_counts().add(front).read().wrapping_add(1) & H::MAX_GENERATION;
+                pool.gen_counts_mut().add(front).write(new_gen_count);
+
+                let item = pool.slots_mut().add(back).cast::<T>().read();
+                pool.slots_mut().add(front).cast::<T>().write(item);
+
+                let (vacated_gen_count, retire) = vacated_generation::<H>(old_gen_count);
+                pool.gen_counts_mut().add(back).write(vacated_gen_count);
+                let vacated_slot = pool.slots_mut().add(back);
+                if retire {
+                    (*vacated_slot).next_free_slot = H::Index::from_usize(Self::FREE_LIST_SENTINEL);
+                    pool.retired = H::Index::from_usize(pool.retired.as_usize() + 1);
+                } else {
+                    (*vacated_slot).next_free_slot = H::Index::from_usize(back);
+                }
+
+                remap(
+                    H::new_tagged(back, old_gen_count, pool.tag),
+                    H::new_tagged(front, new_gen_count, pool.tag),
+                );
+            }
+            front += 1;
+        }
+    }
+
+    /// Returns [`true`] if the slot at `index` is vacant and was marked as
+    /// free by [`compact`](DirectPool::compact).
+    ///
+    /// # Safety
+    /// `index` must be less than the pool's capacity.
+    #[inline]
+    unsafe fn is_marked_free(&self, index: usize) -> bool {
+        let occupied = self.gen_counts().add(index).read() % 2 == 1;
+        !occupied && (*self.slots().add(index)).next_free_slot.as_usize() == index
+    }
+
+    /// Moves all values into a new pool backed by `buf`, calling `remap` with
+    /// the old and new handle of each value.
+    ///
+    /// The new pool's generation counts start from scratch, so handles into
+    /// the old pool, including invalid ones, must not be used with it.
+    ///
+    /// Returns `Err((self, buf))` if `buf` cannot hold all values in the pool.
+    ///
+    /// # Panics
+    /// Panics if the capacity of `buf` is greater than or equal to `H::MAX_INDEX`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use coca::collections::{pool::DefaultHandle, DirectArenaPool, DirectInlinePool};
+    /// # let mut backing = [core::mem::MaybeUninit::uninit(); 1024];
+    /// # let mut arena = coca::arena::Arena::from(&mut backing[..]);
+    /// let mut pool: DirectArenaPool<u128, DefaultHandle> = arena.with_capacity(16);
+    /// let handles = [pool.insert(0), pool.insert(1), pool.insert(2)];
+    /// pool.remove(handles[0]);
+    ///
+    /// let storage = arena.storage_with_capacity(1);
+    /// let pool = pool.shrink_into(storage, |_, _| {}).unwrap_err().0;
+    ///
+    /// let storage = arena.storage_with_capacity(2);
+    /// let mut new_handles = [handles[0]; 2];
+    /// let small_pool = pool.shrink_into(storage, |old, new| {
+    ///     let i = if old == handles[1] { 0 } else { 1 };
+    ///     new_handles[i] = new;
+    /// }).ok().unwrap();
+    ///
+    /// assert_eq!(small_pool.capacity(), 2);
+    /// assert_eq!(small_pool[new_handles[0]], 1);
+    /// assert_eq!(small_pool[new_handles[1]], 2);
+    /// ```
+    pub fn shrink_into<S2, F>(mut self, buf: S2, mut remap: F) -> Result<DirectPool<T, S2, H>, (Self, S2)>
+    where
+        S2: Storage<DirectPoolLayout<T, H>>,
+        F: FnMut(H, H),
+    {
+        if buf.capacity() < self.len() {
+            return Err((self, buf));
+        }
+
+        let mut result = DirectPool::from(buf);
+        result.tag = self.tag;
+        for (old_handle, item) in self.drain() {
+            let new_handle = result.try_insert(item).ok().unwrap();
+            remap(old_handle, new_handle);
+        }
+
+        Ok(result)
+    }
+}
+
+/// Rebuilds the free list of a pool that is being compacted, keeping all
+/// marked slots in ascending order.
+struct RebuildFreeList<'a, T, S: Storage<DirectPoolLayout<T, H>>, H: Handle> {
+    pool: &'a mut DirectPool<T, S, H>,
+}
+
+impl<T, S: Storage<DirectPoolLayout<T, H>>, H: Handle> Drop for RebuildFreeList<'_, T, S, H> {
+    fn drop(&mut self) {
+        let mut next_free_slot = H::Index::from_usize(DirectPool::<T, S, H>::FREE_LIST_SENTINEL);
+        for index in (0..self.pool.capacity()).rev() {
+            unsafe {
+                if self.pool.is_marked_free(index) {
+                    (*self.pool.slots_mut().add(index)).next_free_slot = next_free_slot;
+                    next_free_slot = H::Index::from_usize(index);
+                }
+            }
+        }
+        self.pool.next_free_slot = next_free_slot;
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, S: Storage<DirectPoolLayout<T, H>>, H: Handle> super::serialize::PoolSlots for DirectPool<T, S, H> {
+    type Item = T;
+    type Handle = H;
+    const NAME: &'static str = "DirectPool";
+
+    fn slot_capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    fn occupied_slots(&self) -> usize {
+        self.len.as_usize()
+    }
+
+    fn first_free_slot(&self) -> Option<usize> {
+        Some(self.next_free_slot.as_usize()).filter(|&index| index != Self::FREE_LIST_SENTINEL)
+    }
+
+    fn set_free_list(&mut self, first: Option<usize>, retired: usize) {
+        self.next_free_slot = H::Index::from_usize(first.unwrap_or(Self::FREE_LIST_SENTINEL));
+        self.retired = H::Index::from_usize(retired);
+    }
+
+    unsafe fn slot(&self, index: usize) -> (u32, Option<&T>) {
+        let gen_count = self.gen_counts().add(index).read();
+        let value = if gen_count % 2 == 1 {
+            Some(&*self.slots().add(index).cast::<T>())
+        } else {
+            None
+        };
+        (gen_count, value)
+    }
+
+    unsafe fn next_free_slot(&self, index: usize) -> Option<usize> {
+        Some((*self.slots().add(index)).next_free_slot.as_usize())
+            .filter(|&next| next != Self::FREE_LIST_SENTINEL)
+    }
+
+    unsafe fn set_next_free_slot(&mut self, index: usize, next: Option<usize>) {
+        (*self.slots_mut().add(index)).next_free_slot = H::Index::from_usize(next.unwrap_or(Self::FREE_LIST_SENTINEL));
+    }
+
+    unsafe fn occupy(&mut self, index: usize, generation: u32, value: T) {
+        self.gen_counts_mut().add(index).write(generation);
+        self.slots_mut().add(index).cast::<T>().write(value);
+        self.len = H::Index::from_usize(self.len.as_usize() + 1);
+    }
+
+    unsafe fn set_vacant(&mut self, index: usize, generation: u32) {
+        self.gen_counts_mut().add(index).write(generation);
+    }
 }
 
 impl<T, S: Storage<DirectPoolLayout<T, H>>, H: Handle> Index<H> for DirectPool<T, S, H> {
@@ -779,7 +1075,7 @@ impl<'a, T, S: Storage<DirectPoolLayout<T, H>>, H: Handle> Iterator for Iter<'a,
             self.visited = H::Index::from_usize(self.visited.as_usize() + 1);
             self.front = H::Index::from_usize(i + 1);
             unsafe {
-                let handle = H::new(i, gen_count);
+                let handle = H::new_tagged(i, gen_count, self.pool.tag);
                 let item = (item_ptr.add(i).cast::<T>()).as_ref().unwrap();
                 return Some((handle, item));
             }
@@ -834,7 +1130,7 @@ impl<'a, T, S: Storage<DirectPoolLayout<T, H>>, H: Handle> Iterator for IterMut<
             self.visited = H::Index::from_usize(self.visited.as_usize() + 1);
             self.front = H::Index::from_usize(i + 1);
             unsafe {
-                let handle = H::new(i, gen_count);
+                let handle = H::new_tagged(i, gen_count, self.pool.tag);
                 let item = (item_ptr.add(i).cast::<T>()).as_mut().unwrap();
                 return Some((handle, item));
             }
@@ -939,8 +1235,7 @@ impl<T, S: Storage<DirectPoolLayout<T, H>>, H: Handle> Iterator for Drain<'_, T,
             return None;
         }
 
-        let gen_count_ptr = self.pool.gen_counts_mut();
-        let item_ptr = self.pool.slots_mut();
+        let gen_count_ptr = self.pool.gen_counts();
 
         for i in self.front.as_usize()..self.pool.capacity() {
             let gen_count = unsafe { gen_count_ptr.add(i).read() };
@@ -950,15 +1245,10 @@ impl<T, S: Storage<DirectPoolLayout<T, H>>, H: Handle> Iterator for Drain<'_, T,
 
             self.front = H::Index::from_usize(i + 1);
             self.pool.len = H::Index::from_usize(self.pool.len() - 1);
-            let new_gen_count = gen_count.wrapping_add(1) & H::MAX_GENERATION;
 
             unsafe {
-                let handle = H::new(i, gen_count);
-                gen_count_ptr.add(i).write(new_gen_count);
-                let result = (item_ptr.add(i).cast::<T>()).read();
-                (*item_ptr.add(i)).next_free_slot = self.pool.next_free_slot;
-                self.pool.next_free_slot = H::Index::from_usize(i);
-
+                let handle = H::new_tagged(i, gen_count, self.pool.tag);
+                let result = self.pool.vacate(i, gen_count);
                 return Some((handle, result));
             }
         }
@@ -1017,7 +1307,7 @@ impl<T, S: Storage<DirectPoolLayout<T, H>>, H: Handle, F: FnMut(H, &mut T) -> bo
                 continue;
             }
 
-            let handle = unsafe { H::new(i, gen_count) };
+            let handle = unsafe { H::new_tagged(i, gen_count, self.pool.tag) };
             let item = unsafe { (item_ptr.add(i).cast::<T>()).as_mut().unwrap() };
             if !(self.filter_fn)(handle, item) {
                 self.kept = H::Index::from_usize(self.kept.as_usize() + 1);
@@ -1026,16 +1316,9 @@ impl<T, S: Storage<DirectPoolLayout<T, H>>, H: Handle, F: FnMut(H, &mut T) -> bo
 
             self.front = H::Index::from_usize(i + 1);
             self.pool.len = H::Index::from_usize(self.pool.len() - 1);
-            let gen_count = gen_count.wrapping_add(1) & H::MAX_GENERATION;
-
-            unsafe {
-                gen_count_ptr.add(i).write(gen_count);
-                let result = (item_ptr.add(i).cast::<T>()).read();
-                (*item_ptr.add(i)).next_free_slot = self.pool.next_free_slot;
-                self.pool.next_free_slot = H::Index::from_usize(i);
 
-                return Some((handle, result));
-            }
+            let result = unsafe { self.pool.vacate(i, gen_count) };
+            return Some((handle, result));
         }
 
         None
@@ -1088,6 +1371,8 @@ impl<T: Clone, H: Handle> Clone for crate::collections::DirectAllocPool<T, H> {
             buf: storage,
             len: self.len,
             next_free_slot: self.next_free_slot,
+            retired: self.retired,
+            tag: self.tag,
             items: PhantomData,
         };
 
@@ -1170,6 +1455,8 @@ impl<T: Clone, H: Handle, const N: usize> Clone for DirectPool<T, InlineStorage<
             },
             len: self.len,
             next_free_slot: self.next_free_slot,
+            retired: self.retired,
+            tag: self.tag,
             items: PhantomData,
         };
 
@@ -1299,4 +1586,141 @@ mod tests {
         pool.drain();
         assert_eq!(drop_count.dropped() as u64, inserted);
     }
+
+    #[test]
+    fn exhausted_slots_are_retired() {
+        crate::handle_type! { TinyHandle: 16 / 32 retiring; }
+        const CYCLES: u32 = (TinyHandle::MAX_GENERATION + 1) / 2;
+
+        let mut storage = [MaybeUninit::uninit(); 2048];
+        let mut arena = Arena::from(&mut storage[..]);
+        let mut pool: crate::collections::DirectArenaPool<u32, TinyHandle> = arena.with_capacity(3);
+
+        let first = pool.insert(0);
+        pool.remove(first);
+        for i in 1..CYCLES {
+            let h = pool.insert(i);
+            assert_eq!(pool.remove(h), Some(i));
+        }
+
+        assert_eq!(pool.retired_slots(), 1);
+        assert!(!pool.contains(first));
+
+        for _ in 0..CYCLES {
+            pool.insert(1);
+            pool.insert(2);
+            pool.clear();
+        }
+
+        assert_eq!(pool.retired_slots(), 3);
+        assert!(pool.is_full());
+        assert!(pool.try_insert(3).is_err());
+    }
+
+    #[test]
+    fn compact_skips_retired_slots() {
+        crate::handle_type! { TinyHandle: 16 / 32 retiring; }
+        const CYCLES: u32 = (TinyHandle::MAX_GENERATION + 1) / 2;
+
+        let mut storage = [MaybeUninit::uninit(); 2048];
+        let mut arena = Arena::from(&mut storage[..]);
+        let mut pool: crate::collections::DirectArenaPool<u32, TinyHandle> = arena.with_capacity(8);
+
+        // retire the slot at index 0:
+        for i in 0..CYCLES {
+            let h = pool.insert(i);
+            pool.remove(h);
+        }
+        assert_eq!(pool.retired_slots(), 1);
+
+        let mut handles = [None; 8];
+        for i in 1..8 {
+            handles[i] = Some(pool.insert(i as u32));
+        }
+        for &i in &[1, 2, 4, 6] {
+            pool.remove(handles[i].take().unwrap());
+        }
+
+        let mut relocations = 0;
+        pool.compact(|old, new| {
+            let entry = handles.iter_mut().find(|h| **h == Some(old)).unwrap();
+            *entry = Some(new);
+            relocations += 1;
+        });
+
+        assert_eq!(relocations, 2);
+        assert_eq!(pool.len(), 3);
+        assert_eq!(pool.retired_slots(), 1);
+        for (i, h) in handles.iter().enumerate() {
+            if let Some(h) = h {
+                assert_eq!(pool[*h], i as u32);
+                assert!((1..=3).contains(&h.into_raw_parts().0));
+            }
+        }
+
+        for i in 0..4 {
+            let h = pool.insert(i);
+            assert!((4..8).contains(&h.into_raw_parts().0));
+        }
+        assert!(pool.is_full());
+
+        let small_pool: crate::collections::DirectArenaPool<u32, TinyHandle> = pool
+            .shrink_into(arena.storage_with_capacity(7), |old, new| {
+                assert_ne!(old.into_raw_parts().0, 0);
+                assert!(new.into_raw_parts().0 < 7);
+            })
+            .ok()
+            .unwrap();
+        assert_eq!(small_pool.len(), 7);
+        assert!(small_pool.is_full());
+        assert_eq!(small_pool.retired_slots(), 0);
+    }
+
+    #[test]
+    fn mistagged_handles_are_rejected() {
+        crate::handle_type! { TaggedHandle: 8 / 32 tag 3; }
+
+        let mut storage = [MaybeUninit::uninit(); 2048];
+        let mut arena = Arena::from(&mut storage[..]);
+        let mut first: crate::collections::DirectArenaPool<u32, TaggedHandle> = arena.with_capacity(4);
+        let mut second: crate::collections::DirectArenaPool<u32, TaggedHandle> = arena.with_capacity(4);
+        first.set_tag(5);
+        second.set_tag(6);
+
+        let a = first.insert(1);
+        let b = second.insert(2);
+        assert_eq!(a.into_raw_parts(), b.into_raw_parts());
+        assert_eq!((a.tag(), b.tag()), (5, 6));
+
+        assert!(!first.contains(b));
+        assert!(first.get(b).is_none());
+        assert!(first.get_mut(b).is_none());
+        assert!(first.get_disjoint_mut([a, b]).is_none());
+        assert!(first.remove(b).is_none());
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[a], 1);
+
+        let mut relocated = None;
+        first.remove(a);
+        let c = first.insert(3);
+        first.insert(4);
+        first.remove(c);
+        first.compact(|_, new| relocated = Some(new));
+        assert_eq!(relocated.unwrap().tag(), 5);
+        assert!(first.iter().all(|(h, _)| h.tag() == 5));
+
+        let third: crate::collections::DirectArenaPool<u32, TaggedHandle> = first
+            .shrink_into(arena.storage_with_capacity(2), |_, new| assert_eq!(new.tag(), 5))
+            .ok()
+            .unwrap();
+        assert_eq!(third.tag(), 5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn tag_must_be_representable() {
+        crate::handle_type! { TaggedHandle: 8 / 32 tag 3; }
+        let mut pool = crate::collections::DirectInlinePool::<u32, 4, TaggedHandle>::new();
+        pool.set_tag(8);
+    }
 }
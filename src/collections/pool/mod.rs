@@ -3,8 +3,32 @@
 //! A pool takes ownership of inserted values and returns unique, stable handles
 //! that can be used to refer back to those same values later on. While it is
 //! safe to index into a pool with a handle obtained from a different one, this
-//! is nonsensical; to avoid this mistake, users may define custom handle types
-//! using the [`handle_type!`] macro.
+//! is nonsensical, and generally goes undetected; to avoid this mistake, users
+//! may define custom handle types using the [`handle_type!`] macro.
+//!
+//! Where several pools share a handle type, the handle type may reserve some
+//! bits for a tag identifying the pool it belongs to. Each pool then checks
+//! the tag of any handle passed to it, and rejects those obtained from a pool
+//! with a different tag at runtime:
+//!
+//! ```
+//! # use coca::handle_type;
+//! use coca::collections::{pool::Handle, DirectInlinePool};
+//! handle_type! { NodeHandle: 20 / 32 tag 4; }
+//!
+//! let mut left = DirectInlinePool::<&'static str, 4, NodeHandle>::new();
+//! let mut right = DirectInlinePool::<&'static str, 4, NodeHandle>::new();
+//! left.set_tag(1);
+//! right.set_tag(2);
+//!
+//! let l = left.insert("left");
+//! let r = right.insert("right");
+//! assert_eq!((l.tag(), r.tag()), (1, 2));
+//!
+//! assert_eq!(left.get(l), Some(&"left"));
+//! assert_eq!(left.get(r), None);
+//! assert!(!right.contains(l));
+//! ```
 //!
 //! Each handle contains a generation identifier, so that, should a value be
 //! removed and a new one be inserted at the same location, the old handle
@@ -37,9 +61,44 @@
 //! assert_eq!(pool[first], "this is not first");
 //! # }
 //! ```
+//!
+//! Where this is unacceptable, handle types can opt into retiring slots whose
+//! generation count is exhausted, by setting [`Handle::RETIRE_EXHAUSTED_SLOTS`]
+//! (see [`handle_type!`] for how to do so with generated handle types). Retired
+//! slots are never reused, so the usable capacity of a pool shrinks over time;
+//! each pool type reports the number of retired slots through a `retired_slots`
+//! method:
+//!
+//! ```
+//! # #[cfg(not(miri))] {
+//! # use coca::handle_type;
+//! handle_type! { TinyHandle: 16 / 32 retiring; }
+//!
+//! # let mut storage = [core::mem::MaybeUninit::uninit(); 128];
+//! # let mut arena = coca::arena::Arena::from(&mut storage[..]);
+//! let mut pool: coca::collections::DirectArenaPool<&'static str, TinyHandle> = arena.with_capacity(4);
+//! let first = pool.insert("this was first");
+//!
+//! let mut last_handle = first;
+//! for _ in 0..0x8000 {
+//!     pool.remove(last_handle);
+//!     last_handle = pool.insert("this is not first");
+//! }
+//!
+//! assert!(!pool.contains(first));
+//! assert_eq!(pool.retired_slots(), 1);
+//! # }
+//! ```
 
 pub mod direct;
 pub mod packed;
+pub mod secondary;
+pub mod sync;
+
+#[cfg(feature = "serde")]
+mod serialize;
+
+pub use secondary::{SecondaryMap, SparseSecondaryMap};
 
 use core::fmt::Debug;
 use core::hash::Hash;
@@ -52,8 +111,12 @@ use crate::storage::Capacity;
 /// Implementors must ensure that the following restrictions are met:
 ///
 /// * `Handle::new(i, g).into_raw_parts() == (i, g)` for all `i <= MAX_INDEX, g <= MAX_GENERATION`,
+/// * `Handle::new_tagged(i, g, t).into_raw_parts() == (i, g)` and
+///   `Handle::new_tagged(i, g, t).tag() == t` for all such `i, g` and `t <= MAX_TAG`,
+/// * `Handle::new(i, g).tag() == 0`,
 /// * `MAX_INDEX` must be less than or equal to `Index::MAX_REPRESENTABLE`,
-/// * `MAX_GENERATION` must be one less than a power of two.
+/// * `MAX_GENERATION` must be one less than a power of two,
+/// * `MAX_TAG` must be zero or one less than a power of two.
 ///
 /// Using [`handle_type!`] should be preferred over implementing this manually.
 pub unsafe trait Handle: Copy + Debug + Eq + Hash + Ord {
@@ -65,6 +128,13 @@ pub unsafe trait Handle: Copy + Debug + Eq + Hash + Ord {
     const MAX_INDEX: usize;
     /// The maximum representable generation count.
     const MAX_GENERATION: u32;
+    /// Whether pools should permanently retire a slot instead of reusing it
+    /// when its generation count would wrap around to zero, guaranteeing that
+    /// stale handles never refer to a new value. Defaults to `false`.
+    const RETIRE_EXHAUSTED_SLOTS: bool = false;
+    /// The maximum representable tag. Defaults to zero, meaning the handle
+    /// type has no tag bits, and all its handles share the tag `0`.
+    const MAX_TAG: u32 = 0;
 
     /// Constructs a new handle from the storage location and generation count.
     /// 
@@ -79,6 +149,27 @@ pub unsafe trait Handle: Copy + Debug + Eq + Hash + Ord {
     unsafe fn new(index: usize, generation: u32) -> Self;
     /// Returns the storage location and generation count packed into the handle.
     fn into_raw_parts(self) -> (usize, u32);
+
+    /// Constructs a new handle from the storage location, generation count
+    /// and tag. The default implementation ignores the tag, which is only
+    /// correct if `MAX_TAG` is zero.
+    ///
+    /// # Safety
+    /// Implementors may assume the same preconditions as for
+    /// [`new`](Handle::new), and additionally that `tag` is less than or equal
+    /// to `MAX_TAG`.
+    ///
+    /// Violating these requirements may cause undefined behavior.
+    unsafe fn new_tagged(index: usize, generation: u32, tag: u32) -> Self {
+        debug_assert_eq!(tag, 0);
+        Self::new(index, generation)
+    }
+
+    /// Returns the tag packed into the handle. The default implementation
+    /// always returns zero, which is only correct if `MAX_TAG` is zero.
+    fn tag(self) -> u32 {
+        0
+    }
 }
 
 #[cold]
@@ -92,6 +183,47 @@ pub(crate) fn buffer_too_large_for_handle_type<H: Handle>() {
     );
 }
 
+#[cold]
+#[inline(never)]
+#[track_caller]
+pub(crate) fn tag_too_large_for_handle_type<H: Handle>(tag: u32) -> ! {
+    panic!(
+        "tag {} cannot be represented by type {} (max tag is {})",
+        tag,
+        core::any::type_name::<H>(),
+        H::MAX_TAG,
+    );
+}
+
+/// Returns the storage location and generation count packed into `handle`,
+/// substituting the generation count zero, which never belongs to a valid
+/// handle, if the handle's tag does not match `tag`.
+#[inline]
+pub(crate) fn checked_raw_parts<H: Handle>(handle: H, tag: u32) -> (usize, u32) {
+    let (index, generation) = handle.into_raw_parts();
+    if handle.tag() == tag {
+        (index, generation)
+    } else {
+        (index, 0)
+    }
+}
+
+/// Returns `true` if the generation count `a` precedes `b`, assuming that the
+/// two are less than half of the generation counter's range apart.
+#[inline]
+pub(crate) fn is_older_generation<H: Handle>(a: u32, b: u32) -> bool {
+    let distance = b.wrapping_sub(a) & H::MAX_GENERATION;
+    distance != 0 && distance <= H::MAX_GENERATION / 2
+}
+
+/// Returns the generation count of a slot after removing the value with the
+/// generation count `occupied` from it, and whether the slot must be retired.
+#[inline]
+pub(crate) fn vacated_generation<H: Handle>(occupied: u32) -> (u32, bool) {
+    let vacated = occupied.wrapping_add(1) & H::MAX_GENERATION;
+    (vacated, H::RETIRE_EXHAUSTED_SLOTS && vacated == 0)
+}
+
 #[allow(dead_code)] // "unused" fields are actually used by the derived Debug impl
 #[derive(Debug)]
 enum DebugEntry<'a, T: Debug, H: Handle> {
@@ -116,10 +248,26 @@ enum DebugEntry<'a, T: Debug, H: Handle> {
 ///   [`DefaultHandle`], with 32 bits each for the index and generation.
 /// * `($meta)* ($vis)? $name: $i / 32` generates a 32-bit handle with `$i`
 ///   bits used for the index, and the remainder used for the generation count.
+///   The handle's [`Index`](Handle::Index) type is `u16` if `$i` is at most 16,
+///   and `u32` otherwise.
+/// * `($meta)* ($vis)? $name: $i / 32 tag $t` generates a 32-bit handle with
+///   `$i` bits used for the index, `$t` bits used for a tag, and the remainder
+///   used for the generation count. Pools using such a handle type only accept
+///   handles carrying the pool's tag, which can be configured with methods
+///   like [`DirectPool::set_tag`](direct::DirectPool::set_tag).
+///
+/// There is no `tag` form of the `64` layout; handle types that need tag bits
+/// must use one of the 32-bit layouts.
 ///
 /// `($meta)*` stands for any number of attributes, including doc comments, and
 /// `($vis)?` is an optional visibility specifier (i.e. `pub` or `pub(crate)`).
 ///
+/// Any format may be followed by the `retiring` keyword, which sets
+/// [`Handle::RETIRE_EXHAUSTED_SLOTS`] for the generated type.
+///
+/// With the `serde` feature enabled, the generated types implement `Serialize`
+/// and `Deserialize`, using their packed integer representation.
+///
 /// # Examples
 /// ```
 /// use coca::{handle_type, collections::pool::Handle};
@@ -128,6 +276,10 @@ enum DebugEntry<'a, T: Debug, H: Handle> {
 ///     /// Documentation for `B` goes here.
 ///     pub B: 16 / 32;
 ///     D: 64;
+///     E: 8 / 32 retiring;
+///     F: 12 / 32 tag 4;
+///     G: 8 / 32 tag 8 retiring;
+///     H: 20 / 32 tag 4;
 /// }
 ///
 /// assert_eq!(A::MAX_INDEX, 4095);
@@ -138,16 +290,64 @@ enum DebugEntry<'a, T: Debug, H: Handle> {
 ///
 /// assert_eq!(D::MAX_INDEX, 4_294_967_295);
 /// assert_eq!(D::MAX_GENERATION, 4_294_967_295);
+///
+/// assert!(!A::RETIRE_EXHAUSTED_SLOTS);
+/// assert!(E::RETIRE_EXHAUSTED_SLOTS);
+///
+/// assert_eq!(A::MAX_TAG, 0);
+/// assert_eq!(F::MAX_TAG, 15);
+/// assert_eq!(F::MAX_GENERATION, 65535);
+/// assert_eq!(G::MAX_TAG, 255);
+/// assert!(G::RETIRE_EXHAUSTED_SLOTS);
+///
+/// let f = unsafe { F::new_tagged(7, 3, 9) };
+/// assert_eq!(f.tag(), 9);
+/// assert_eq!(f.into_raw_parts(), (7, 3));
+///
+/// assert_eq!(H::MAX_INDEX, 1_048_575);
+/// assert_eq!(H::MAX_TAG, 15);
+/// assert_eq!(H::MAX_GENERATION, 255);
+/// let _: <H as Handle>::Index = u32::MAX;
 /// ```
-/// Note that the maximum number of bits you can reserve for the index is 16.
-/// This is enforced with a static assertion:
+/// At least one bit must remain for the generation count, so the index and the
+/// tag may not occupy more than 31 bits combined. This is enforced with a
+/// static assertion:
 /// ```compile_fail
 /// # use coca::handle_type;
-/// handle_type!{ C: 20 / 32; } // attempt to compute `0_usize - 1_usize`, which would overflow
+/// handle_type!{ C: 32 / 32; } // attempt to compute `0_usize - 1_usize`, which would overflow
+/// ```
+/// ```compile_fail
+/// # use coca::handle_type;
+/// handle_type!{ C: 16 / 32 tag 16; } // attempt to compute `0_usize - 1_usize`, which would overflow
 /// ```
 #[macro_export]
 macro_rules! handle_type {
     ( $(#[$attrs:meta])* $v:vis $name:ident: 64 ; $($rest:tt)* ) => {
+        $crate::handle_type!(@64 [$(#[$attrs])*] $v $name, false);
+        $crate::handle_type!($($rest)*);
+    };
+    ( $(#[$attrs:meta])* $v:vis $name:ident: 64 retiring ; $($rest:tt)* ) => {
+        $crate::handle_type!(@64 [$(#[$attrs])*] $v $name, true);
+        $crate::handle_type!($($rest)*);
+    };
+    ( $(#[$attrs:meta])* $v:vis $name:ident: $n:literal / 32 ; $($rest:tt)* ) => {
+        $crate::handle_type!(@32 [$(#[$attrs])*] $v $name, $n, 0, false);
+        $crate::handle_type!($($rest)*);
+    };
+    ( $(#[$attrs:meta])* $v:vis $name:ident: $n:literal / 32 retiring ; $($rest:tt)* ) => {
+        $crate::handle_type!(@32 [$(#[$attrs])*] $v $name, $n, 0, true);
+        $crate::handle_type!($($rest)*);
+    };
+    ( $(#[$attrs:meta])* $v:vis $name:ident: $n:literal / 32 tag $t:literal ; $($rest:tt)* ) => {
+        $crate::handle_type!(@32 [$(#[$attrs])*] $v $name, $n, $t, false);
+        $crate::handle_type!($($rest)*);
+    };
+    ( $(#[$attrs:meta])* $v:vis $name:ident: $n:literal / 32 tag $t:literal retiring ; $($rest:tt)* ) => {
+        $crate::handle_type!(@32 [$(#[$attrs])*] $v $name, $n, $t, true);
+        $crate::handle_type!($($rest)*);
+    };
+    () => {};
+    (@64 [$(#[$attrs:meta])*] $v:vis $name:ident, $retire:literal) => {
         $(#[$attrs])*
         #[derive(
             core::marker::Copy,
@@ -161,6 +361,8 @@ macro_rules! handle_type {
         #[repr(transparent)]
         $v struct $name(core::num::NonZeroU64);
 
+        $crate::__handle_type_serde!($name, core::num::NonZeroU64);
+
         impl $name {
             /// Returns a handle that is guaranteed to always be invalid.
             #[allow(dead_code)]
@@ -173,6 +375,7 @@ macro_rules! handle_type {
             type Index = u32;
             const MAX_INDEX: usize = 0xFFFF_FFFF;
             const MAX_GENERATION: u32 = 0xFFFF_FFFF;
+            const RETIRE_EXHAUSTED_SLOTS: bool = $retire;
             unsafe fn new(index: usize, generation: u32) -> Self {
                 debug_assert!(index <= Self::MAX_INDEX);
                 debug_assert_eq!(generation % 2, 1);
@@ -186,12 +389,10 @@ macro_rules! handle_type {
                 (index, generation)
             }
         }
-
-        handle_type!($($rest)*);
     };
-    ( $(#[$attrs:meta])* $v:vis $name:ident: $n:literal / 32 ; $($rest:tt)* ) => {
+    (@32 [$(#[$attrs:meta])*] $v:vis $name:ident, $n:literal, $t:literal, $retire:literal) => {
         #[allow(unknown_lints, eq_op)]
-        const _: [(); 0 - !{ const ASSERT: bool = $n <= 16; ASSERT } as usize] = []; // static assertion
+        const _: [(); 0 - !{ const ASSERT: bool = $n + $t <= 31; ASSERT } as usize] = []; // static assertion
 
         $(#[$attrs])*
         #[derive(
@@ -206,6 +407,8 @@ macro_rules! handle_type {
         #[repr(transparent)]
         $v struct $name(core::num::NonZeroU32);
 
+        $crate::__handle_type_serde!($name, core::num::NonZeroU32);
+
         impl $name {
             /// Returns a handle that is guaranteed to always be invalid.
             #[allow(dead_code)]
@@ -217,28 +420,80 @@ macro_rules! handle_type {
         }
 
         unsafe impl $crate::collections::pool::Handle for $name {
-            type Index = u16;
+            type Index = <$crate::collections::pool::IndexBits<{ $n > 16 }> as $crate::collections::pool::IndexType>::Index;
             const MAX_INDEX: usize = !(!0 << $n);
-            const MAX_GENERATION: u32 = !(!0 << (32 - $n)) as u32;
+            const MAX_GENERATION: u32 = !(!0 << (32 - $n - $t)) as u32;
+            const RETIRE_EXHAUSTED_SLOTS: bool = $retire;
+            const MAX_TAG: u32 = !(!0 << $t) as u32;
             unsafe fn new(index: usize, generation: u32) -> Self {
+                Self::new_tagged(index, generation, 0)
+            }
+            fn into_raw_parts(self) -> (usize, u32) {
+                let raw: u32 = self.0.into();
+                let index = (raw & (Self::MAX_INDEX as u32)) as usize;
+                let generation = (raw >> ($n + $t)) as u32;
+                (index, generation)
+            }
+            unsafe fn new_tagged(index: usize, generation: u32, tag: u32) -> Self {
                 debug_assert!(index <= Self::MAX_INDEX);
                 debug_assert!(generation <= Self::MAX_GENERATION);
+                debug_assert!(tag <= Self::MAX_TAG);
                 debug_assert_eq!(generation % 2, 1);
-                let assembled = index as u32 & (Self::MAX_INDEX as u32) | ((generation as u32) << $n);
+                let assembled = index as u32 & (Self::MAX_INDEX as u32)
+                    | ((tag & Self::MAX_TAG) << $n)
+                    | ((generation as u32) << ($n + $t));
                 $name(core::num::NonZeroU32::new_unchecked(assembled))
             }
-            fn into_raw_parts(self) -> (usize, u32) {
+            fn tag(self) -> u32 {
                 let raw: u32 = self.0.into();
-                let index = (raw & (Self::MAX_INDEX as u32)) as usize;
-                let generation = (raw >> $n) as u32;
-                (index, generation)
+                (raw >> $n) & Self::MAX_TAG
             }
         }
+    };
+}
+
+/// Selects the [`Handle::Index`] type for handles generated by [`handle_type!`]
+/// based on whether their index is wider than 16 bits.
+#[doc(hidden)]
+pub struct IndexBits<const WIDE: bool>;
+
+#[doc(hidden)]
+pub trait IndexType {
+    type Index: Capacity;
+}
 
-        handle_type!($($rest)*);
+impl IndexType for IndexBits<false> {
+    type Index = u16;
+}
 
+impl IndexType for IndexBits<true> {
+    type Index = u32;
+}
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __handle_type_serde {
+    ($name:ident, $repr:ty) => {
+        impl $crate::serde::Serialize for $name {
+            fn serialize<S: $crate::serde::Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+                $crate::serde::Serialize::serialize(&self.0, serializer)
+            }
+        }
+
+        impl<'de> $crate::serde::Deserialize<'de> for $name {
+            fn deserialize<D: $crate::serde::Deserializer<'de>>(deserializer: D) -> core::result::Result<Self, D::Error> {
+                <$repr as $crate::serde::Deserialize<'de>>::deserialize(deserializer).map($name)
+            }
+        }
     };
-    () => {}
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __handle_type_serde {
+    ($name:ident, $repr:ty) => {};
 }
 
 handle_type! {
@@ -11,7 +11,10 @@ use core::marker::PhantomData;
 use core::mem::MaybeUninit;
 use core::ops::{Index, IndexMut};
 
-use super::{buffer_too_large_for_handle_type, DebugEntry, DefaultHandle, Handle};
+use super::{
+    buffer_too_large_for_handle_type, checked_raw_parts, tag_too_large_for_handle_type, vacated_generation,
+    DebugEntry, DefaultHandle, Handle,
+};
 use crate::storage::{Capacity, LayoutSpec, Storage};
 
 /// The [`LayoutSpec`] for a [`PackedPool`].
@@ -40,6 +43,8 @@ pub struct PackedPool<T, S: Storage<PackedPoolLayout<T, H>>, H: Handle = Default
     buf: S,
     len: H::Index,
     next_free_slot: H::Index,
+    retired: H::Index,
+    tag: u32,
     items: PhantomData<T>,
 }
 
@@ -54,6 +59,8 @@ impl<T, S: Storage<PackedPoolLayout<T, H>>, H: Handle> From<S> for PackedPool<T,
             buf,
             len: H::Index::from_usize(0),
             next_free_slot: H::Index::from_usize(0),
+            retired: H::Index::from_usize(0),
+            tag: 0,
             items: PhantomData,
         };
 
@@ -190,9 +197,88 @@ impl<T, S: Storage<PackedPoolLayout<T, H>>, H: Handle> PackedPool<T, S, H> {
     }
 
     /// Returns [`true`] if the pool contains the maximum number of elements.
+    ///
+    /// This excludes [retired slots](PackedPool::retired_slots), which can
+    /// not hold any elements.
     #[inline]
     pub fn is_full(&self) -> bool {
-        self.len() == self.capacity()
+        self.len() + self.retired_slots() == self.capacity()
+    }
+
+    /// Returns the number of slots that have been retired because their
+    /// generation count was exhausted.
+    ///
+    /// This is always zero unless [`H::RETIRE_EXHAUSTED_SLOTS`](Handle::RETIRE_EXHAUSTED_SLOTS)
+    /// is set, see the [super module documentation](crate::collections::pool)
+    /// for details.
+    #[inline]
+    pub fn retired_slots(&self) -> usize {
+        self.retired.as_usize()
+    }
+
+    /// Returns the tag carried by all handles returned from the pool.
+    ///
+    /// This is always zero unless the handle type reserves bits for a tag
+    /// (see [`Handle::MAX_TAG`]).
+    #[inline]
+    pub fn tag(&self) -> u32 {
+        self.tag
+    }
+
+    /// Sets the tag carried by all handles returned from the pool from now
+    /// on, including those returned by [`handles`](PackedPool::handles) and
+    /// the pool's iterators. Handles with a different tag are rejected as
+    /// invalid, so this invalidates all handles previously returned from the
+    /// pool unless their tag matches the new one.
+    ///
+    /// # Panics
+    /// Panics if `tag` is greater than [`H::MAX_TAG`](Handle::MAX_TAG).
+    ///
+    /// # Examples
+    /// ```
+    /// # use coca::{handle_type, collections::PackedInlinePool};
+    /// handle_type! { TaggedHandle: 8 / 32 tag 2; }
+    ///
+    /// let mut pool = PackedInlinePool::<char, 4, TaggedHandle>::new();
+    /// let h = pool.insert('a');
+    ///
+    /// pool.set_tag(2);
+    /// assert_eq!(pool.get(h), None);
+    /// assert_eq!(pool.get(pool.handles()[0]), Some(&'a'));
+    /// ```
+    pub fn set_tag(&mut self, tag: u32) {
+        if tag > H::MAX_TAG {
+            tag_too_large_for_handle_type::<H>(tag);
+        }
+        self.tag = tag;
+
+        let handles = self.handles_mut_ptr();
+        for i in 0..self.len() {
+            unsafe {
+                let (index, generation) = handles.add(i).read().into_raw_parts();
+                handles.add(i).write(H::new_tagged(index, generation, tag));
+            }
+        }
+    }
+
+    /// Advances the generation count of the slot at `index`, and either adds
+    /// the slot to the free list or retires it.
+    ///
+    /// # Safety
+    /// The slot at `index` must be occupied with the generation count
+    /// `gen_count`, and its value must already have been moved out.
+    unsafe fn vacate(&mut self, index: usize, gen_count: u32) {
+        let (new_gen_count, retire) = vacated_generation::<H>(gen_count);
+        self.counters_mut().add(index).write(new_gen_count);
+
+        let slot_ptr = self.next_free_slot_or_packed_index_array_mut().add(index);
+        if retire {
+            slot_ptr.write(H::Index::from_usize(Self::FREE_LIST_SENTINEL));
+            self.retired = H::Index::from_usize(self.retired.as_usize() + 1);
+        } else {
+            slot_ptr.write(self.next_free_slot);
+            self.next_free_slot = H::Index::from_usize(index);
+        }
     }
 
     /// Returns a slice of all values currently held in the pool in arbitrary order.
@@ -311,7 +397,7 @@ impl<T, S: Storage<PackedPoolLayout<T, H>>, H: Handle> PackedPool<T, S, H> {
     /// assert!(!pool.contains(h));
     /// ```
     pub fn contains(&self, handle: H) -> bool {
-        let (idx, input_gen_count) = handle.into_raw_parts();
+        let (idx, input_gen_count) = checked_raw_parts(handle, self.tag);
         if idx >= self.buf.capacity() {
             return false;
         }
@@ -324,7 +410,7 @@ impl<T, S: Storage<PackedPoolLayout<T, H>>, H: Handle> PackedPool<T, S, H> {
     /// 
     /// Returns [`None`] if the handle is invalid for this pool.
     pub fn get(&self, handle: H) -> Option<&T> {
-        let (index, input_gen_count) = handle.into_raw_parts();
+        let (index, input_gen_count) = checked_raw_parts(handle, self.tag);
         if index >= self.buf.capacity() {
             return None;
         }
@@ -344,7 +430,7 @@ impl<T, S: Storage<PackedPoolLayout<T, H>>, H: Handle> PackedPool<T, S, H> {
     /// 
     /// Returns [`None`] if the handle is invalid for this pool.
     pub fn get_mut(&mut self, handle: H) -> Option<&mut T> {
-        let (index, input_gen_count) = handle.into_raw_parts();
+        let (index, input_gen_count) = checked_raw_parts(handle, self.tag);
         if index >= self.buf.capacity() {
             return None;
         }
@@ -393,7 +479,7 @@ impl<T, S: Storage<PackedPoolLayout<T, H>>, H: Handle> PackedPool<T, S, H> {
 
         let mut i = 0;
         while i < N {
-            let (index, input_gen_count) = handles[i].into_raw_parts();
+            let (index, input_gen_count) = checked_raw_parts(handles[i], self.tag);
             if index >= self.capacity() || input_gen_count % 2 == 0 {
                 break;
             }
@@ -454,7 +540,7 @@ impl<T, S: Storage<PackedPoolLayout<T, H>>, H: Handle> PackedPool<T, S, H> {
             self.next_free_slot = slot_ptr.read();
             slot_ptr.write(packed_insert_position);
 
-            let handle = H::new(insert_position, gen_count);
+            let handle = H::new_tagged(insert_position, gen_count, self.tag);
             self.handles_mut_ptr().add(packed_insert_position.as_usize()).write(handle);
             self.values_mut_ptr().add(packed_insert_position.as_usize()).write(value);
             
@@ -513,7 +599,7 @@ impl<T, S: Storage<PackedPoolLayout<T, H>>, H: Handle> PackedPool<T, S, H> {
             self.next_free_slot = slot_ptr.read();
             slot_ptr.write(packed_insert_position);
 
-            let handle = H::new(insert_position, gen_count);
+            let handle = H::new_tagged(insert_position, gen_count, self.tag);
             self.handles_mut_ptr().add(packed_insert_position.as_usize()).write(handle);
             self.values_mut_ptr().add(packed_insert_position.as_usize()).write(f(handle));
             
@@ -550,7 +636,7 @@ impl<T, S: Storage<PackedPoolLayout<T, H>>, H: Handle> PackedPool<T, S, H> {
     /// assert_eq!(pool.len(), 0);
     /// ```
     pub fn remove(&mut self, handle: H) -> Option<T> {
-        let (index, input_gen_count) = handle.into_raw_parts();
+        let (index, input_gen_count) = checked_raw_parts(handle, self.tag);
         if index >= self.buf.capacity() || input_gen_count % 2 == 0 {
             return None;
         }
@@ -562,12 +648,8 @@ impl<T, S: Storage<PackedPoolLayout<T, H>>, H: Handle> PackedPool<T, S, H> {
         }
 
         unsafe {
-            gen_count_ptr.write(current_gen_count.wrapping_add(1));
-            
-            let slot_ptr = self.next_free_slot_or_packed_index_array_mut().add(index);
-            let packed_index = slot_ptr.read();
-            slot_ptr.write(self.next_free_slot);
-            self.next_free_slot = H::Index::from_usize(index);
+            let packed_index = self.next_free_slot_or_packed_index_array().add(index).read();
+            self.vacate(index, current_gen_count);
 
             let hole = self.values_mut_ptr().add(packed_index.as_usize());
             let result = hole.read();
@@ -632,12 +714,8 @@ impl<T, S: Storage<PackedPoolLayout<T, H>>, H: Handle> PackedPool<T, S, H> {
             unsafe {
                 self.values_mut_ptr().add(packed_index).drop_in_place();
 
-                let (index, _) = self.handles_ptr().add(packed_index).read().into_raw_parts();
-                *self.counters_mut().add(index) += 1;
-
-                let slot_ptr = self.next_free_slot_or_packed_index_array_mut().add(index);
-                *slot_ptr = self.next_free_slot;
-                self.next_free_slot = H::Index::from_usize(index);
+                let (index, gen_count) = self.handles_ptr().add(packed_index).read().into_raw_parts();
+                self.vacate(index, gen_count);
             }
         }
 
@@ -755,6 +833,65 @@ impl<T, S: Storage<PackedPoolLayout<T, H>>, H: Handle> PackedPool<T, S, H> {
     }
 }
 
+#[cfg(feature = "serde")]
+impl<T, S: Storage<PackedPoolLayout<T, H>>, H: Handle> super::serialize::PoolSlots for PackedPool<T, S, H> {
+    type Item = T;
+    type Handle = H;
+    const NAME: &'static str = "PackedPool";
+
+    fn slot_capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    fn occupied_slots(&self) -> usize {
+        self.len.as_usize()
+    }
+
+    fn first_free_slot(&self) -> Option<usize> {
+        Some(self.next_free_slot.as_usize()).filter(|&index| index != Self::FREE_LIST_SENTINEL)
+    }
+
+    fn set_free_list(&mut self, first: Option<usize>, retired: usize) {
+        self.next_free_slot = H::Index::from_usize(first.unwrap_or(Self::FREE_LIST_SENTINEL));
+        self.retired = H::Index::from_usize(retired);
+    }
+
+    unsafe fn slot(&self, index: usize) -> (u32, Option<&T>) {
+        let gen_count = self.counters().add(index).read();
+        let value = if gen_count % 2 == 1 {
+            let packed_index = self.next_free_slot_or_packed_index_array().add(index).read();
+            Some(&*self.values_ptr().add(packed_index.as_usize()))
+        } else {
+            None
+        };
+        (gen_count, value)
+    }
+
+    unsafe fn next_free_slot(&self, index: usize) -> Option<usize> {
+        Some(self.next_free_slot_or_packed_index_array().add(index).read().as_usize())
+            .filter(|&next| next != Self::FREE_LIST_SENTINEL)
+    }
+
+    unsafe fn set_next_free_slot(&mut self, index: usize, next: Option<usize>) {
+        let next = H::Index::from_usize(next.unwrap_or(Self::FREE_LIST_SENTINEL));
+        self.next_free_slot_or_packed_index_array_mut().add(index).write(next);
+    }
+
+    unsafe fn occupy(&mut self, index: usize, generation: u32, value: T) {
+        let packed_index = self.len;
+        self.len = H::Index::from_usize(packed_index.as_usize() + 1);
+
+        self.counters_mut().add(index).write(generation);
+        self.next_free_slot_or_packed_index_array_mut().add(index).write(packed_index);
+        self.handles_mut_ptr().add(packed_index.as_usize()).write(H::new_tagged(index, generation, self.tag));
+        self.values_mut_ptr().add(packed_index.as_usize()).write(value);
+    }
+
+    unsafe fn set_vacant(&mut self, index: usize, generation: u32) {
+        self.counters_mut().add(index).write(generation);
+    }
+}
+
 impl<T, S: Storage<PackedPoolLayout<T, H>>, H: Handle> Index<H> for PackedPool<T, S, H> {
     type Output = T;
     fn index(&self, handle: H) -> &Self::Output {
@@ -921,12 +1058,7 @@ impl<'a, T, S: Storage<PackedPoolLayout<T, H>>, H: Handle> Iterator for Drain<'a
         let value = unsafe { self.pool.values_ptr().add(new_len).read() };
 
         let (index, gen_count) = handle.into_raw_parts();
-        unsafe {
-            self.pool.counters_mut().add(index).write(gen_count.wrapping_add(1));
-            self.pool.next_free_slot_or_packed_index_array_mut().add(index).write(self.pool.next_free_slot);
-        }
-
-        self.pool.next_free_slot = H::Index::from_usize(index);
+        unsafe { self.pool.vacate(index, gen_count); }
         self.pool.len = H::Index::from_usize(new_len);
         Some((handle, value))
     }
@@ -974,8 +1106,7 @@ impl<'a, T, S: Storage<PackedPoolLayout<T, H>>, H: Handle, F: FnMut(H, &mut T) -
 
             let (index, gen_count) = handle.into_raw_parts();
             unsafe {
-                self.pool.counters_mut().add(index).write(gen_count.wrapping_add(1));
-                self.pool.next_free_slot_or_packed_index_array_mut().add(index).write(self.pool.next_free_slot);
+                self.pool.vacate(index, gen_count);
 
                 if index != new_len {
                     let value_src = self.pool.values_ptr().add(new_len);
@@ -1036,6 +1167,8 @@ impl<T: Clone, H: Handle> Clone for crate::collections::PackedAllocPool<T, H> {
             buf: storage,
             len: self.len,
             next_free_slot: self.next_free_slot,
+            retired: self.retired,
+            tag: self.tag,
             items: PhantomData,
         };
 
@@ -1119,6 +1252,8 @@ impl<T: Clone, H: Handle, const N: usize> Clone for PackedPool<T, InlineStorage<
             },
             len: self.len,
             next_free_slot: self.next_free_slot,
+            retired: self.retired,
+            tag: self.tag,
             items: PhantomData,
         };
 
@@ -1216,4 +1351,63 @@ mod tests {
         test_layout::<u128, DefaultHandle, 40>();
         test_layout::<crate::collections::ArenaDeque<u8>, DefaultHandle, 80>();
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn exhausted_slots_are_retired() {
+        crate::handle_type! { TinyHandle: 16 / 32 retiring; }
+        const CYCLES: u32 = (TinyHandle::MAX_GENERATION + 1) / 2;
+
+        let mut storage = [MaybeUninit::uninit(); 2048];
+        let mut arena = Arena::from(&mut storage[..]);
+        let mut pool: crate::collections::PackedArenaPool<u32, TinyHandle> = arena.with_capacity(3);
+
+        let first = pool.insert(0);
+        pool.remove(first);
+        for i in 1..CYCLES {
+            let h = pool.insert(i);
+            assert_eq!(pool.remove(h), Some(i));
+        }
+
+        assert_eq!(pool.retired_slots(), 1);
+        assert!(!pool.contains(first));
+
+        for _ in 0..CYCLES {
+            pool.insert(1);
+            pool.insert(2);
+            pool.clear();
+        }
+
+        assert_eq!(pool.retired_slots(), 3);
+        assert!(pool.is_full());
+        assert!(pool.try_insert(3).is_err());
+    }
+
+    #[test]
+    fn set_tag_retags_stored_handles() {
+        crate::handle_type! { TaggedHandle: 8 / 32 tag 3; }
+
+        let mut storage = [MaybeUninit::uninit(); 2048];
+        let mut arena = Arena::from(&mut storage[..]);
+        let mut pool: crate::collections::PackedArenaPool<u32, TaggedHandle> = arena.with_capacity(4);
+        pool.set_tag(2);
+
+        let a = pool.insert(1);
+        let b = pool.insert(2);
+        assert_eq!((a.tag(), b.tag()), (2, 2));
+
+        pool.set_tag(7);
+        assert!(!pool.contains(a));
+        assert!(pool.remove(b).is_none());
+        assert!(pool.handles().iter().all(|h| h.tag() == 7));
+        assert!(pool.iter().all(|(h, _)| h.tag() == 7));
+
+        let c = pool.insert(3);
+        assert_eq!(c.tag(), 7);
+        assert_eq!(pool.len(), 3);
+
+        pool.set_tag(2);
+        assert_eq!(pool[a], 1);
+        assert_eq!(pool[b], 2);
+        assert!(!pool.contains(c));
+    }
+}
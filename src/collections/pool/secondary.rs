@@ -0,0 +1,951 @@
+//! Maps associating additional values with the handles of another pool.
+//!
+//! A secondary map does not hand out handles of its own, but stores values
+//! under handles issued by a [`DirectPool`](super::direct::DirectPool) or a
+//! [`PackedPool`](super::packed::PackedPool), similar to an additional column
+//! in a table. Like pools, secondary maps check generation counts, so a value
+//! stored for a handle that has since been invalidated is treated as absent
+//! when looked up with a newer handle to the same slot, and is replaced on the
+//! next insertion into that slot with a newer handle. Insertions with handles
+//! older than the stored value's are rejected, so a stale handle can't displace
+//! the value of a live one.
+//!
+//! A [`SecondaryMap`] stores its values in an array indexed by the handles'
+//! storage locations, so its capacity should match that of the primary pool,
+//! as should its [tag](SecondaryMap::set_tag) if the handle type has any tag bits.
+//! A [`SparseSecondaryMap`] stores handle-value pairs in a [`ListMap`], which
+//! is preferable for rarely populated components.
+//!
+//! ```
+//! use coca::collections::{InlineSecondaryMap, DirectInlinePool, pool::DefaultHandle};
+//!
+//! let mut pool = DirectInlinePool::<&'static str, 4>::new();
+//! let mut ages = InlineSecondaryMap::<DefaultHandle, u32, 4>::new();
+//!
+//! let alice = pool.insert("Alice");
+//! ages.insert(alice, 35);
+//! assert_eq!(ages.get(alice), Some(&35));
+//!
+//! pool.remove(alice);
+//! let bob = pool.insert("Bob");
+//! assert_eq!(ages.get(bob), None);
+//! ```
+
+use core::alloc::{Layout, LayoutError};
+use core::fmt::{Debug, Formatter};
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ops::{Index, IndexMut};
+
+use super::{buffer_too_large_for_handle_type, checked_raw_parts, is_older_generation, tag_too_large_for_handle_type, Handle};
+use crate::collections::list_map::{self, ListMap, ListMapLayout};
+use crate::storage::{Capacity, LayoutSpec, Storage};
+
+/// The [`LayoutSpec`] for a [`SecondaryMap`].
+pub struct SecondaryMapLayout<V>(PhantomData<V>);
+impl<V> LayoutSpec for SecondaryMapLayout<V> {
+    fn layout_with_capacity(items: usize) -> Result<Layout, LayoutError> {
+        let value_array = Layout::array::<V>(items)?;
+        let gen_count_array = Layout::array::<u32>(items)?;
+        let (extended, _) = value_array.extend(gen_count_array)?;
+        Ok(extended.pad_to_align())
+    }
+}
+
+/// A map from pool handles to values, stored in an array indexed by the
+/// handles' storage locations.
+///
+/// See the [module documentation](crate::collections::pool::secondary) for details.
+pub struct SecondaryMap<H: Handle, V, S: Storage<SecondaryMapLayout<V>>> {
+    buf: S,
+    len: H::Index,
+    tag: u32,
+    values: PhantomData<V>,
+}
+
+impl<H: Handle, V, S: Storage<SecondaryMapLayout<V>>> From<S> for SecondaryMap<H, V, S> {
+    fn from(buf: S) -> Self {
+        let cap = buf.capacity();
+        if cap >= H::MAX_INDEX {
+            buffer_too_large_for_handle_type::<H>();
+        }
+
+        let mut result = SecondaryMap {
+            buf,
+            len: H::Index::from_usize(0),
+            tag: 0,
+            values: PhantomData,
+        };
+
+        // a generation count of zero marks an empty slot:
+        let gen_ptr = result.gen_counts_mut();
+        for i in 0..cap {
+            unsafe { gen_ptr.add(i).write(0) };
+        }
+
+        result
+    }
+}
+
+impl<H: Handle, V, S: Storage<SecondaryMapLayout<V>>> SecondaryMap<H, V, S> {
+    #[inline]
+    fn values(&self) -> *const V {
+        self.buf.get_ptr().cast()
+    }
+
+    #[inline]
+    fn values_mut(&mut self) -> *mut V {
+        self.buf.get_mut_ptr().cast()
+    }
+
+    #[inline]
+    fn gen_counts(&self) -> *const u32 {
+        let gen_offset = Layout::array::<V>(self.buf.capacity())
+            .unwrap()
+            .extend(Layout::new::<u32>())
+            .unwrap()
+            .1;
+        unsafe { self.buf.get_ptr().add(gen_offset).cast() }
+    }
+
+    #[inline]
+    fn gen_counts_mut(&mut self) -> *mut u32 {
+        let gen_offset = Layout::array::<V>(self.buf.capacity())
+            .unwrap()
+            .extend(Layout::new::<u32>())
+            .unwrap()
+            .1;
+        unsafe { self.buf.get_mut_ptr().add(gen_offset).cast() }
+    }
+
+    /// Returns the index of the slot corresponding to the handle if it holds
+    /// a value stored for the handle's generation.
+    #[inline]
+    fn occupied_index(&self, handle: H) -> Option<usize> {
+        let (index, input_gen_count) = checked_raw_parts(handle, self.tag);
+        if index >= self.buf.capacity() || input_gen_count % 2 == 0 {
+            return None;
+        }
+        let current_gen_count = unsafe { self.gen_counts().add(index).read() };
+        if current_gen_count == input_gen_count {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of slots in the map, i.e. the largest number of
+    /// values it can hold at once.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    /// Returns the number of values stored in the map.
+    ///
+    /// This includes values stored for handles that have since been removed
+    /// from the primary pool, unless they were removed from the map as well.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len.as_usize()
+    }
+
+    /// Returns [`true`] if the map contains no values.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len.as_usize() == 0
+    }
+
+    /// Returns the tag a handle must carry for the map to accept it.
+    ///
+    /// This is always zero unless the handle type reserves bits for a tag
+    /// (see [`Handle::MAX_TAG`]).
+    #[inline]
+    pub fn tag(&self) -> u32 {
+        self.tag
+    }
+
+    /// Sets the tag a handle must carry for the map to accept it, which
+    /// should match the tag of the primary pool. This is also the tag carried
+    /// by the handles passed to [`retain`](SecondaryMap::retain) or yielded
+    /// by the map's iterators.
+    ///
+    /// # Panics
+    /// Panics if `tag` is greater than [`H::MAX_TAG`](Handle::MAX_TAG).
+    pub fn set_tag(&mut self, tag: u32) {
+        if tag > H::MAX_TAG {
+            tag_too_large_for_handle_type::<H>(tag);
+        }
+        self.tag = tag;
+    }
+
+    /// Returns [`true`] if the map holds a value for the specified handle.
+    ///
+    /// # Examples
+    /// ```
+    /// # use coca::collections::{pool::DefaultHandle, DirectInlinePool, InlineSecondaryMap};
+    /// let mut pool = DirectInlinePool::<char, 4>::new();
+    /// let mut map = InlineSecondaryMap::<DefaultHandle, u32, 4>::new();
+    /// let h = pool.insert('a');
+    /// assert!(!map.contains_key(h));
+    /// map.insert(h, 1);
+    /// assert!(map.contains_key(h));
+    /// ```
+    pub fn contains_key(&self, handle: H) -> bool {
+        self.occupied_index(handle).is_some()
+    }
+
+    /// Returns a reference to the value corresponding to the handle.
+    ///
+    /// Returns [`None`] if the map holds no value for the handle, including
+    /// when the value was stored for a different generation of the same slot.
+    pub fn get(&self, handle: H) -> Option<&V> {
+        let index = self.occupied_index(handle)?;
+        unsafe { self.values().add(index).as_ref() }
+    }
+
+    /// Returns a mutable reference to the value corresponding to the handle.
+    ///
+    /// Returns [`None`] if the map holds no value for the handle, including
+    /// when the value was stored for a different generation of the same slot.
+    pub fn get_mut(&mut self, handle: H) -> Option<&mut V> {
+        let index = self.occupied_index(handle)?;
+        unsafe { self.values_mut().add(index).as_mut() }
+    }
+
+    /// Inserts a value into the map, associating it with the handle.
+    ///
+    /// If the map already held a value for this handle, it is replaced and
+    /// `Ok(Some(old_value))` is returned. A value stored for an older
+    /// generation of the same slot is dropped and `Ok(None)` is returned.
+    ///
+    /// Returns `Err(value)` if the handle's storage location is out of bounds
+    /// for the map, if its tag does not match the map's, or if the map holds
+    /// a value for a newer generation of the same slot.
+    ///
+    /// # Examples
+    /// ```
+    /// # use coca::collections::{pool::DefaultHandle, DirectInlinePool, InlineSecondaryMap};
+    /// let mut pool = DirectInlinePool::<char, 4>::new();
+    /// let mut map = InlineSecondaryMap::<DefaultHandle, u32, 2>::new();
+    /// let handles = [pool.insert('a'), pool.insert('b'), pool.insert('c')];
+    ///
+    /// assert_eq!(map.try_insert(handles[0], 1), Ok(None));
+    /// assert_eq!(map.try_insert(handles[0], 2), Ok(Some(1)));
+    /// assert_eq!(map.try_insert(handles[1], 3), Ok(None));
+    /// assert_eq!(map.try_insert(handles[2], 4), Err(4));
+    /// ```
+    pub fn try_insert(&mut self, handle: H, value: V) -> Result<Option<V>, V> {
+        let (index, input_gen_count) = checked_raw_parts(handle, self.tag);
+        if index >= self.buf.capacity() || input_gen_count % 2 == 0 {
+            return Err(value);
+        }
+
+        let gen_ptr = unsafe { self.gen_counts_mut().add(index) };
+        let current_gen_count = unsafe { gen_ptr.read() };
+        let value_ptr = unsafe { self.values_mut().add(index) };
+
+        if current_gen_count == input_gen_count {
+            return Ok(Some(unsafe { value_ptr.replace(value) }));
+        }
+        if current_gen_count != 0 && is_older_generation::<H>(input_gen_count, current_gen_count) {
+            return Err(value);
+        }
+
+        unsafe {
+            gen_ptr.write(input_gen_count);
+            if current_gen_count == 0 {
+                self.len = H::Index::from_usize(self.len.as_usize() + 1);
+                value_ptr.write(value);
+            } else {
+                // the stale value is only dropped once the slot holds the new
+                // one, so a panic in its destructor can't cause a double drop
+                drop(value_ptr.replace(value));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Inserts a value into the map, associating it with the handle.
+    ///
+    /// If the map already held a value for this handle, it is replaced and
+    /// the old value is returned. A value stored for an older generation of
+    /// the same slot is dropped. If the map holds a value for a newer
+    /// generation, `value` is dropped instead and [`None`] is returned.
+    ///
+    /// # Panics
+    /// Panics if the handle's storage location is out of bounds for the map,
+    /// or if its tag does not match the map's.
+    /// See [`try_insert`](SecondaryMap::try_insert) for a checked version.
+    pub fn insert(&mut self, handle: H, value: V) -> Option<V> {
+        #[cold]
+        #[inline(never)]
+        fn assert_failed() -> ! {
+            panic!("handle is out of bounds or mistagged for secondary map")
+        }
+
+        let (index, gen_count) = checked_raw_parts(handle, self.tag);
+        if index >= self.buf.capacity() || gen_count % 2 == 0 {
+            assert_failed();
+        }
+
+        self.try_insert(handle, value).unwrap_or(None)
+    }
+
+    /// Removes the value corresponding to the handle from the map, returning
+    /// it if it was present.
+    ///
+    /// # Examples
+    /// ```
+    /// # use coca::collections::{pool::DefaultHandle, DirectInlinePool, InlineSecondaryMap};
+    /// let mut pool = DirectInlinePool::<char, 4>::new();
+    /// let mut map = InlineSecondaryMap::<DefaultHandle, u32, 4>::new();
+    /// let h = pool.insert('a');
+    /// map.insert(h, 1);
+    /// assert_eq!(map.remove(h), Some(1));
+    /// assert_eq!(map.remove(h), None);
+    /// ```
+    pub fn remove(&mut self, handle: H) -> Option<V> {
+        let index = self.occupied_index(handle)?;
+        unsafe { Some(self.vacate(index)) }
+    }
+
+    /// Moves the value out of the specified slot and marks it as empty.
+    ///
+    /// # Safety
+    /// The slot at `index` must be occupied.
+    unsafe fn vacate(&mut self, index: usize) -> V {
+        self.gen_counts_mut().add(index).write(0);
+        self.len = H::Index::from_usize(self.len.as_usize() - 1);
+        self.values().add(index).read()
+    }
+
+    /// Retains only the values specified by the predicate.
+    ///
+    /// In other words, remove all handle-value pairs `(h, v)` such that
+    /// `f(h, &mut v)` returns `false`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use coca::collections::{pool::DefaultHandle, DirectInlinePool, InlineSecondaryMap};
+    /// let mut pool = DirectInlinePool::<char, 4>::new();
+    /// let mut map = InlineSecondaryMap::<DefaultHandle, u32, 4>::new();
+    /// for (i, &c) in ['a', 'b', 'c', 'd'].iter().enumerate() {
+    ///     map.insert(pool.insert(c), i as u32);
+    /// }
+    ///
+    /// map.retain(|_, v| *v % 2 == 0);
+    /// assert_eq!(map.len(), 2);
+    /// assert!(map.iter().all(|(h, v)| *v % 2 == 0 && pool[h] != 'b'));
+    /// ```
+    pub fn retain<F: FnMut(H, &mut V) -> bool>(&mut self, mut pred: F) {
+        for index in 0..self.capacity() {
+            let gen_count = unsafe { self.gen_counts().add(index).read() };
+            if gen_count == 0 {
+                continue;
+            }
+
+            let handle = unsafe { H::new_tagged(index, gen_count, self.tag) };
+            let value = unsafe { &mut *self.values_mut().add(index) };
+            if !pred(handle, value) {
+                unsafe { drop(self.vacate(index)) };
+            }
+        }
+    }
+
+    /// Removes all values from the map.
+    pub fn clear(&mut self) {
+        self.retain(|_, _| false);
+    }
+
+    /// Creates an iterator visiting all handle-value pairs in arbitrary order,
+    /// yielding `(H, &'a V)`.
+    ///
+    /// The handles carry the generation for which each value was stored,
+    /// which need not be the current generation in the primary pool.
+    pub fn iter(&self) -> Iter<'_, H, V, S> {
+        Iter {
+            map: self,
+            front: H::Index::from_usize(0),
+            visited: H::Index::from_usize(0),
+        }
+    }
+
+    /// Creates an iterator visiting all handle-value pairs in arbitrary order,
+    /// yielding `(H, &'a mut V)`.
+    pub fn iter_mut(&mut self) -> IterMut<'_, H, V, S> {
+        IterMut {
+            map: self,
+            front: H::Index::from_usize(0),
+            visited: H::Index::from_usize(0),
+        }
+    }
+}
+
+impl<H: Handle, V, S: Storage<SecondaryMapLayout<V>>> Index<H> for SecondaryMap<H, V, S> {
+    type Output = V;
+
+    fn index(&self, handle: H) -> &Self::Output {
+        self.get(handle).expect("indexed with invalid pool handle")
+    }
+}
+
+impl<H: Handle, V, S: Storage<SecondaryMapLayout<V>>> IndexMut<H> for SecondaryMap<H, V, S> {
+    fn index_mut(&mut self, handle: H) -> &mut Self::Output {
+        self.get_mut(handle)
+            .expect("indexed with invalid pool handle")
+    }
+}
+
+impl<H: Handle, V, S: Storage<SecondaryMapLayout<V>>> Drop for SecondaryMap<H, V, S> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<H: Handle, V: Debug, S: Storage<SecondaryMapLayout<V>>> Debug for SecondaryMap<H, V, S> {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> core::fmt::Result {
+        fmt.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, H: Handle, V, S: Storage<SecondaryMapLayout<V>>> IntoIterator for &'a SecondaryMap<H, V, S> {
+    type Item = (H, &'a V);
+    type IntoIter = Iter<'a, H, V, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, H: Handle, V, S: Storage<SecondaryMapLayout<V>>> IntoIterator for &'a mut SecondaryMap<H, V, S> {
+    type Item = (H, &'a mut V);
+    type IntoIter = IterMut<'a, H, V, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+impl<H: Handle, V> crate::collections::AllocSecondaryMap<H, V> {
+    /// Constructs a new, empty [`AllocSecondaryMap`](crate::collections::AllocSecondaryMap)
+    /// with the specified capacity.
+    ///
+    /// # Panics
+    /// Panics if the specified capacity is greater than or equal to `H::MAX_INDEX`.
+    pub fn with_capacity(capacity: H::Index) -> Self {
+        let cap = capacity.as_usize();
+        if cap >= H::MAX_INDEX {
+            buffer_too_large_for_handle_type::<H>();
+        }
+
+        let storage = crate::storage::AllocStorage::with_capacity(cap);
+        Self::from(storage)
+    }
+}
+
+/// A statically-sized storage block for a [`SecondaryMap`].
+#[repr(C)]
+pub struct InlineStorage<V, const N: usize> {
+    values: [MaybeUninit<V>; N],
+    gen_counts: [u32; N],
+}
+
+unsafe impl<V, const N: usize> Storage<SecondaryMapLayout<V>> for InlineStorage<V, N> {
+    #[inline]
+    fn get_ptr(&self) -> *const u8 {
+        self.values.as_ptr().cast()
+    }
+
+    #[inline]
+    fn get_mut_ptr(&mut self) -> *mut u8 {
+        self.values.as_mut_ptr().cast()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<H: Handle, V, const N: usize> SecondaryMap<H, V, InlineStorage<V, N>> {
+    /// Constructs a new, empty `SecondaryMap` backed by [`InlineStorage`].
+    pub fn new() -> Self {
+        if N >= H::MAX_INDEX {
+            buffer_too_large_for_handle_type::<H>();
+        }
+
+        Self::from(InlineStorage {
+            values: unsafe { MaybeUninit::uninit().assume_init() },
+            gen_counts: [0; N],
+        })
+    }
+}
+
+impl<H: Handle, V, const N: usize> Default for SecondaryMap<H, V, InlineStorage<V, N>> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An iterator visiting all handle-value pairs in a secondary map in arbitrary
+/// order, yielding `(H, &'a V)`.
+///
+/// This `struct` is created by [`SecondaryMap::iter`], see its documentation for more.
+pub struct Iter<'a, H: Handle, V, S: Storage<SecondaryMapLayout<V>>> {
+    map: &'a SecondaryMap<H, V, S>,
+    front: H::Index,
+    visited: H::Index,
+}
+
+impl<'a, H: Handle, V, S: Storage<SecondaryMapLayout<V>>> Iterator for Iter<'a, H, V, S> {
+    type Item = (H, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.visited == self.map.len {
+            return None;
+        }
+
+        let gen_count_ptr = self.map.gen_counts();
+        let value_ptr = self.map.values();
+
+        for i in self.front.as_usize()..self.map.capacity() {
+            let gen_count = unsafe { gen_count_ptr.add(i).read() };
+            if gen_count == 0 {
+                continue;
+            }
+
+            self.visited = H::Index::from_usize(self.visited.as_usize() + 1);
+            self.front = H::Index::from_usize(i + 1);
+            unsafe {
+                let handle = H::new_tagged(i, gen_count, self.map.tag);
+                let value = value_ptr.add(i).as_ref().unwrap();
+                return Some((handle, value));
+            }
+        }
+
+        unreachable!("internal error: mismatch between map.len and number of occupied slots")
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.map.len() - self.visited.as_usize();
+        (len, Some(len))
+    }
+}
+
+impl<H: Handle, V, S: Storage<SecondaryMapLayout<V>>> ExactSizeIterator for Iter<'_, H, V, S> {}
+impl<H: Handle, V, S: Storage<SecondaryMapLayout<V>>> FusedIterator for Iter<'_, H, V, S> {}
+
+/// An iterator visiting all handle-value pairs in a secondary map in arbitrary
+/// order, yielding `(H, &'a mut V)`.
+///
+/// This `struct` is created by [`SecondaryMap::iter_mut`], see its documentation for more.
+pub struct IterMut<'a, H: Handle, V, S: Storage<SecondaryMapLayout<V>>> {
+    map: &'a mut SecondaryMap<H, V, S>,
+    front: H::Index,
+    visited: H::Index,
+}
+
+impl<'a, H: Handle, V, S: Storage<SecondaryMapLayout<V>>> Iterator for IterMut<'a, H, V, S> {
+    type Item = (H, &'a mut V);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.visited == self.map.len {
+            return None;
+        }
+
+        let gen_count_ptr = self.map.gen_counts();
+        let value_ptr = self.map.values_mut();
+
+        for i in self.front.as_usize()..self.map.capacity() {
+            let gen_count = unsafe { gen_count_ptr.add(i).read() };
+            if gen_count == 0 {
+                continue;
+            }
+
+            self.visited = H::Index::from_usize(self.visited.as_usize() + 1);
+            self.front = H::Index::from_usize(i + 1);
+            unsafe {
+                let handle = H::new_tagged(i, gen_count, self.map.tag);
+                let value = value_ptr.add(i).as_mut().unwrap();
+                return Some((handle, value));
+            }
+        }
+
+        unreachable!("internal error: mismatch between map.len and number of occupied slots")
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.map.len() - self.visited.as_usize();
+        (len, Some(len))
+    }
+}
+
+impl<H: Handle, V, S: Storage<SecondaryMapLayout<V>>> ExactSizeIterator for IterMut<'_, H, V, S> {}
+impl<H: Handle, V, S: Storage<SecondaryMapLayout<V>>> FusedIterator for IterMut<'_, H, V, S> {}
+
+/// A map from pool handles to values, stored as an association list.
+///
+/// Lookups take linear time, but the storage required is proportional to the
+/// number of values rather than the capacity of the primary pool, making this
+/// preferable to a [`SecondaryMap`] for rarely populated components.
+///
+/// See the [module documentation](crate::collections::pool::secondary) for details.
+pub struct SparseSecondaryMap<H: Handle, V, S: Storage<ListMapLayout<H, V>>> {
+    map: ListMap<H, V, S, usize>,
+}
+
+impl<H: Handle, V, S: Storage<ListMapLayout<H, V>>> From<S> for SparseSecondaryMap<H, V, S> {
+    fn from(buf: S) -> Self {
+        SparseSecondaryMap { map: ListMap::from(buf) }
+    }
+}
+
+impl<H: Handle, V, S: Storage<ListMapLayout<H, V>>> SparseSecondaryMap<H, V, S> {
+    /// Returns the position of the entry stored for any generation of the
+    /// handle's storage location in the pool identified by the handle's tag.
+    #[inline]
+    fn position_of_slot(&self, handle: H) -> Option<usize> {
+        let (index, _) = handle.into_raw_parts();
+        let tag = handle.tag();
+        self.map.keys().iter().position(|k| k.into_raw_parts().0 == index && k.tag() == tag)
+    }
+
+    /// Returns the number of values the map can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    /// Returns the number of values stored in the map.
+    ///
+    /// This includes values stored for handles that have since been removed
+    /// from the primary pool, unless they were removed from the map as well.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns [`true`] if the map contains no values.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns [`true`] if the map can hold no further values.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.map.is_full()
+    }
+
+    /// Returns [`true`] if the map holds a value for the specified handle.
+    pub fn contains_key(&self, handle: H) -> bool {
+        self.map.contains_key(&handle)
+    }
+
+    /// Returns a reference to the value corresponding to the handle.
+    ///
+    /// Returns [`None`] if the map holds no value for the handle, including
+    /// when the value was stored for a different generation of the same slot.
+    pub fn get(&self, handle: H) -> Option<&V> {
+        self.map.get(&handle)
+    }
+
+    /// Returns a mutable reference to the value corresponding to the handle.
+    ///
+    /// Returns [`None`] if the map holds no value for the handle, including
+    /// when the value was stored for a different generation of the same slot.
+    pub fn get_mut(&mut self, handle: H) -> Option<&mut V> {
+        self.map.get_mut(&handle)
+    }
+
+    /// Inserts a value into the map, associating it with the handle.
+    ///
+    /// If the map already held a value for this handle, it is replaced and
+    /// `Ok(Some(old_value))` is returned. A value stored for an older
+    /// generation of the same slot is dropped and `Ok(None)` is returned.
+    ///
+    /// Returns `Err(value)` if the map is already full, or if it holds a value
+    /// for a newer generation of the same slot.
+    ///
+    /// # Examples
+    /// ```
+    /// # use coca::collections::{pool::DefaultHandle, DirectInlinePool, InlineSparseSecondaryMap};
+    /// let mut pool = DirectInlinePool::<char, 4>::new();
+    /// let mut map = InlineSparseSecondaryMap::<DefaultHandle, u32, 1>::new();
+    /// let a = pool.insert('a');
+    /// assert_eq!(map.try_insert(a, 1), Ok(None));
+    /// assert_eq!(map.try_insert(a, 2), Ok(Some(1)));
+    ///
+    /// let b = pool.insert('b');
+    /// assert_eq!(map.try_insert(b, 3), Err(3));
+    ///
+    /// pool.remove(a);
+    /// let c = pool.insert('c');
+    /// assert_eq!(map.try_insert(c, 4), Ok(None));
+    /// assert_eq!(map.get(a), None);
+    /// assert_eq!(map.get(c), Some(&4));
+    /// ```
+    pub fn try_insert(&mut self, handle: H, value: V) -> Result<Option<V>, V> {
+        if let Some(position) = self.position_of_slot(handle) {
+            let key = self.map.keys()[position];
+            if key == handle {
+                let old_value = core::mem::replace(&mut self.map.values_mut()[position], value);
+                return Ok(Some(old_value));
+            }
+            if is_older_generation::<H>(handle.into_raw_parts().1, key.into_raw_parts().1) {
+                return Err(value);
+            }
+            self.map.remove(&key);
+        }
+
+        self.map.try_insert(handle, value).map_err(|(_, value)| value)
+    }
+
+    /// Inserts a value into the map, associating it with the handle.
+    ///
+    /// If the map already held a value for this handle, it is replaced and
+    /// the old value is returned. A value stored for an older generation of
+    /// the same slot is dropped. If the map holds a value for a newer
+    /// generation, `value` is dropped instead and [`None`] is returned.
+    ///
+    /// # Panics
+    /// Panics if the map is already full. See [`try_insert`](SparseSecondaryMap::try_insert)
+    /// for a checked version.
+    pub fn insert(&mut self, handle: H, value: V) -> Option<V> {
+        #[cold]
+        #[inline(never)]
+        fn assert_failed() -> ! {
+            panic!("map is already at capacity")
+        }
+
+        let result = self.try_insert(handle, value);
+        match result {
+            Ok(old_value) => old_value,
+            // a full map can only reject handles to slots it holds no value for
+            Err(_) if self.position_of_slot(handle).is_some() => None,
+            Err(_) => assert_failed(),
+        }
+    }
+
+    /// Removes the value corresponding to the handle from the map, returning
+    /// it if it was present.
+    pub fn remove(&mut self, handle: H) -> Option<V> {
+        self.map.remove(&handle)
+    }
+
+    /// Retains only the values specified by the predicate.
+    ///
+    /// In other words, remove all handle-value pairs `(h, v)` such that
+    /// `f(h, &mut v)` returns `false`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use coca::collections::{pool::DefaultHandle, DirectInlinePool, InlineSparseSecondaryMap};
+    /// let mut pool = DirectInlinePool::<char, 4>::new();
+    /// let mut map = InlineSparseSecondaryMap::<DefaultHandle, u32, 4>::new();
+    /// for (i, &c) in ['a', 'b', 'c', 'd'].iter().enumerate() {
+    ///     map.insert(pool.insert(c), i as u32);
+    /// }
+    ///
+    /// map.retain(|_, v| *v % 2 == 0);
+    /// assert_eq!(map.len(), 2);
+    /// assert!(map.iter().all(|(h, v)| *v % 2 == 0 && pool[h] != 'b'));
+    /// ```
+    pub fn retain<F: FnMut(H, &mut V) -> bool>(&mut self, mut pred: F) {
+        self.map.retain(|k, v| pred(*k, v));
+    }
+
+    /// Removes all values from the map.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Creates an iterator visiting all handle-value pairs in arbitrary order,
+    /// yielding `(H, &'a V)`.
+    ///
+    /// The handles carry the generation for which each value was stored,
+    /// which need not be the current generation in the primary pool.
+    pub fn iter(&self) -> SparseIter<'_, H, V, S> {
+        SparseIter { inner: self.map.iter() }
+    }
+}
+
+impl<H: Handle, V, S: Storage<ListMapLayout<H, V>>> Index<H> for SparseSecondaryMap<H, V, S> {
+    type Output = V;
+
+    fn index(&self, handle: H) -> &Self::Output {
+        self.get(handle).expect("indexed with invalid pool handle")
+    }
+}
+
+impl<H: Handle, V, S: Storage<ListMapLayout<H, V>>> IndexMut<H> for SparseSecondaryMap<H, V, S> {
+    fn index_mut(&mut self, handle: H) -> &mut Self::Output {
+        self.get_mut(handle)
+            .expect("indexed with invalid pool handle")
+    }
+}
+
+impl<H: Handle, V: Debug, S: Storage<ListMapLayout<H, V>>> Debug for SparseSecondaryMap<H, V, S> {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> core::fmt::Result {
+        fmt.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, H: Handle, V, S: Storage<ListMapLayout<H, V>>> IntoIterator for &'a SparseSecondaryMap<H, V, S> {
+    type Item = (H, &'a V);
+    type IntoIter = SparseIter<'a, H, V, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+impl<H: Handle, V> crate::collections::AllocSparseSecondaryMap<H, V> {
+    /// Constructs a new, empty [`AllocSparseSecondaryMap`](crate::collections::AllocSparseSecondaryMap)
+    /// with the specified capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from(crate::storage::AllocStorage::with_capacity(capacity))
+    }
+}
+
+impl<H: Handle, V, const N: usize> SparseSecondaryMap<H, V, list_map::InlineStorage<H, V, N>> {
+    /// Constructs a new, empty [`InlineSparseSecondaryMap`](crate::collections::InlineSparseSecondaryMap).
+    pub fn new() -> Self {
+        Self::from(list_map::InlineStorage::uninit())
+    }
+}
+
+impl<H: Handle, V, const N: usize> Default for SparseSecondaryMap<H, V, list_map::InlineStorage<H, V, N>> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An iterator visiting all handle-value pairs in a sparse secondary map in
+/// arbitrary order, yielding `(H, &'a V)`.
+///
+/// This `struct` is created by [`SparseSecondaryMap::iter`], see its documentation for more.
+pub struct SparseIter<'a, H: Handle, V, S: Storage<ListMapLayout<H, V>>> {
+    inner: list_map::Iter<'a, H, V, S, usize>,
+}
+
+impl<'a, H: Handle, V, S: Storage<ListMapLayout<H, V>>> Iterator for SparseIter<'a, H, V, S> {
+    type Item = (H, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, v)| (*k, v))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<H: Handle, V, S: Storage<ListMapLayout<H, V>>> ExactSizeIterator for SparseIter<'_, H, V, S> {}
+impl<H: Handle, V, S: Storage<ListMapLayout<H, V>>> FusedIterator for SparseIter<'_, H, V, S> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::pool::{DefaultHandle, Handle};
+    use crate::collections::{DirectInlinePool, InlineSecondaryMap, InlineSparseSecondaryMap, PackedInlinePool};
+    use crate::test_utils::*;
+
+    #[test]
+    fn values_for_stale_handles_are_absent() {
+        let drop_count = DropCounter::new();
+        {
+            let mut direct = DirectInlinePool::<u32, 4>::new();
+            let mut packed = PackedInlinePool::<u32, 4>::new();
+            let mut dense = InlineSecondaryMap::<DefaultHandle, Droppable, 4>::new();
+            let mut sparse = InlineSparseSecondaryMap::<DefaultHandle, Droppable, 4>::new();
+
+            let old_direct = direct.insert(1);
+            let old_packed = packed.insert(2);
+            dense.insert(old_direct, drop_count.new_droppable(()));
+            sparse.insert(old_packed, drop_count.new_droppable(()));
+
+            direct.remove(old_direct);
+            packed.remove(old_packed);
+            let new_direct = direct.insert(3);
+            let new_packed = packed.insert(4);
+            assert_eq!(new_direct.into_raw_parts().0, old_direct.into_raw_parts().0);
+            assert_eq!(new_packed.into_raw_parts().0, old_packed.into_raw_parts().0);
+
+            assert!(dense.get(new_direct).is_none());
+            assert!(sparse.get(new_packed).is_none());
+            assert!(dense.contains_key(old_direct));
+            assert!(sparse.contains_key(old_packed));
+
+            dense.insert(new_direct, drop_count.new_droppable(()));
+            sparse.insert(new_packed, drop_count.new_droppable(()));
+            assert_eq!(drop_count.dropped(), 2);
+            assert!(dense.get(old_direct).is_none());
+            assert!(sparse.get(old_packed).is_none());
+            assert_eq!(dense.len(), 1);
+            assert_eq!(sparse.len(), 1);
+
+            assert_eq!(dense.iter().map(|(h, _)| h).next(), Some(new_direct));
+            assert_eq!(sparse.iter().map(|(h, _)| h).next(), Some(new_packed));
+        }
+        assert_eq!(drop_count.dropped(), 4);
+    }
+
+    #[test]
+    fn stale_inserts_are_rejected() {
+        let mut pool = DirectInlinePool::<&'static str, 4>::new();
+        let mut ages = InlineSecondaryMap::<DefaultHandle, u32, 4>::new();
+        let mut sparse_ages = InlineSparseSecondaryMap::<DefaultHandle, u32, 4>::new();
+
+        let alice = pool.insert("Alice");
+        pool.remove(alice);
+        let bob = pool.insert("Bob");
+        assert_eq!(bob.into_raw_parts().0, alice.into_raw_parts().0);
+
+        assert_eq!(ages.insert(bob, 20), None);
+        assert_eq!(ages.insert(alice, 35), None);
+        assert_eq!(ages.try_insert(alice, 35), Err(35));
+        assert_eq!(ages.get(bob), Some(&20));
+        assert_eq!(ages.get(alice), None);
+
+        assert_eq!(sparse_ages.insert(bob, 20), None);
+        assert_eq!(sparse_ages.insert(alice, 35), None);
+        assert_eq!(sparse_ages.try_insert(alice, 35), Err(35));
+        assert_eq!(sparse_ages.get(bob), Some(&20));
+        assert_eq!(sparse_ages.get(alice), None);
+        assert_eq!(sparse_ages.len(), 1);
+    }
+
+    #[test]
+    fn panicking_drop_of_stale_value_is_not_repeated() {
+        extern crate std;
+        use core::cell::Cell;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        struct PanicOnDrop<'a>(&'a Cell<usize>, bool);
+        impl Drop for PanicOnDrop<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+                assert!(!self.1, "drop panicked");
+            }
+        }
+
+        let drop_count = Cell::new(0);
+        {
+            let mut pool = DirectInlinePool::<u32, 4>::new();
+            let mut map = InlineSecondaryMap::<DefaultHandle, PanicOnDrop<'_>, 4>::new();
+
+            let old = pool.insert(1);
+            map.insert(old, PanicOnDrop(&drop_count, true));
+            pool.remove(old);
+            let new = pool.insert(2);
+
+            let result = catch_unwind(AssertUnwindSafe(|| map.insert(new, PanicOnDrop(&drop_count, false))));
+            assert!(result.is_err());
+            assert_eq!(drop_count.get(), 1);
+            assert!(map.contains_key(new));
+            assert!(!map.contains_key(old));
+        }
+        assert_eq!(drop_count.get(), 2);
+    }
+}
@@ -0,0 +1,598 @@
+//! `Serialize` and `Deserialize` implementations for object pools.
+//!
+//! Pools are serialized as a struct with two fields: `slots`, a sequence of
+//! `(generation, value)` pairs covering the entire capacity of the pool, where
+//! `value` is `None` for vacant slots, and `free_list`, the sequence of indices
+//! of vacant slots in the order in which they will be reused. Vacant slots that
+//! are not on the free list are retired. [`DirectPool`] and [`PackedPool`] share
+//! this format, so handles stay valid across both pool types.
+//!
+//! A pool's [tag](DirectPool::set_tag) is not part of this format, so pools
+//! using a handle type with tag bits must have their tag restored after
+//! deserialization for handles to remain valid.
+
+use core::fmt::{self, Formatter};
+
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Unexpected, Visitor};
+use serde::ser::{SerializeSeq, SerializeStruct, Serializer};
+use serde::{Deserialize, Serialize};
+
+use super::direct::{self, DirectPool, DirectPoolLayout};
+use super::packed::{self, PackedPool, PackedPoolLayout};
+use super::Handle;
+use crate::storage::Storage;
+use crate::CapacityError;
+
+/// Raw access to the slots of a pool for serialization and deserialization.
+pub(super) trait PoolSlots {
+    /// The type of the values stored in the pool.
+    type Item;
+    /// The handle type used by the pool.
+    type Handle: Handle;
+    /// The name of the pool type, used as the name of the serialized struct.
+    const NAME: &'static str;
+
+    /// Returns the number of slots in the pool.
+    fn slot_capacity(&self) -> usize;
+    /// Returns the number of occupied slots in the pool.
+    fn occupied_slots(&self) -> usize;
+    /// Returns the index of the first slot on the free list.
+    fn first_free_slot(&self) -> Option<usize>;
+    /// Sets the first slot on the free list and the number of retired slots.
+    fn set_free_list(&mut self, first: Option<usize>, retired: usize);
+
+    /// Returns the generation count of the slot at `index`, and its value if
+    /// the slot is occupied.
+    ///
+    /// # Safety
+    /// `index` must be less than the capacity of the pool.
+    unsafe fn slot(&self, index: usize) -> (u32, Option<&Self::Item>);
+    /// Returns the index of the slot following the slot at `index` on the free list.
+    ///
+    /// # Safety
+    /// The slot at `index` must be vacant.
+    unsafe fn next_free_slot(&self, index: usize) -> Option<usize>;
+    /// Sets the index of the slot following the slot at `index` on the free list.
+    ///
+    /// # Safety
+    /// The slot at `index` must be vacant.
+    unsafe fn set_next_free_slot(&mut self, index: usize, next: Option<usize>);
+    /// Stores `value` in the slot at `index` with the generation count `generation`.
+    ///
+    /// # Safety
+    /// The slot at `index` must be vacant, and `generation` must be odd.
+    unsafe fn occupy(&mut self, index: usize, generation: u32, value: Self::Item);
+    /// Sets the generation count of the slot at `index`.
+    ///
+    /// # Safety
+    /// The slot at `index` must be vacant, and `generation` must be even.
+    unsafe fn set_vacant(&mut self, index: usize, generation: u32);
+}
+
+impl<T: Serialize, S: Storage<DirectPoolLayout<T, H>>, H: Handle> Serialize for DirectPool<T, S, H> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        serialize_pool(self, serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>, H: Handle, const N: usize> Deserialize<'de>
+    for DirectPool<T, direct::InlineStorage<T, H, N>, H>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_pool(Self::new(), deserializer)
+    }
+}
+
+impl<T, S: Storage<DirectPoolLayout<T, H>>, H: Handle> DirectPool<T, S, H> {
+    /// Deserializes a pool previously serialized from a [`DirectPool`] or a
+    /// [`PackedPool`] into the provided storage block, preserving the slot
+    /// indices, generation counts and free list, so that handles into the
+    /// serialized pool remain valid.
+    ///
+    /// If `buf` has more slots than the serialized pool, the additional slots
+    /// are appended to the free list. If it has fewer, deserialization fails
+    /// with a [`CapacityError`] converted into `D::Error`.
+    ///
+    /// The pool's tag is not serialized, and must be restored with
+    /// [`set_tag`](Self::set_tag) if the handle type has any tag bits.
+    ///
+    /// # Panics
+    /// Panics if the capacity of `buf` is greater than or equal to `H::MAX_INDEX`.
+    #[cfg_attr(docs_rs, doc(cfg(feature = "serde")))]
+    pub fn deserialize_with_storage<'de, D>(buf: S, deserializer: D) -> Result<Self, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        deserialize_pool(Self::from(buf), deserializer)
+    }
+}
+
+impl<T: Serialize, S: Storage<PackedPoolLayout<T, H>>, H: Handle> Serialize for PackedPool<T, S, H> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        serialize_pool(self, serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>, H: Handle, const N: usize> Deserialize<'de>
+    for PackedPool<T, packed::InlineStorage<T, H, N>, H>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_pool(Self::new(), deserializer)
+    }
+}
+
+impl<T, S: Storage<PackedPoolLayout<T, H>>, H: Handle> PackedPool<T, S, H> {
+    /// Deserializes a pool previously serialized from a [`DirectPool`] or a
+    /// [`PackedPool`] into the provided storage block, preserving the slot
+    /// indices, generation counts and free list, so that handles into the
+    /// serialized pool remain valid.
+    ///
+    /// If `buf` has more slots than the serialized pool, the additional slots
+    /// are appended to the free list. If it has fewer, deserialization fails
+    /// with a [`CapacityError`] converted into `D::Error`.
+    ///
+    /// The pool's tag is not serialized, and must be restored with
+    /// [`set_tag`](Self::set_tag) if the handle type has any tag bits.
+    ///
+    /// # Panics
+    /// Panics if the capacity of `buf` is greater than or equal to `H::MAX_INDEX`.
+    #[cfg_attr(docs_rs, doc(cfg(feature = "serde")))]
+    pub fn deserialize_with_storage<'de, D>(buf: S, deserializer: D) -> Result<Self, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        deserialize_pool(Self::from(buf), deserializer)
+    }
+}
+
+const FIELDS: &[&str] = &["slots", "free_list"];
+
+fn serialize_pool<P, Ser>(pool: &P, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+where
+    P: PoolSlots,
+    P::Item: Serialize,
+    Ser: Serializer,
+{
+    let mut state = serializer.serialize_struct(P::NAME, FIELDS.len())?;
+    state.serialize_field("slots", &SerializeSlots(pool))?;
+    state.serialize_field("free_list", &SerializeFreeList(pool))?;
+    state.end()
+}
+
+struct SerializeSlots<'a, P>(&'a P);
+
+impl<P: PoolSlots> Serialize for SerializeSlots<'_, P>
+where
+    P::Item: Serialize,
+{
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        let capacity = self.0.slot_capacity();
+        let mut seq = serializer.serialize_seq(Some(capacity))?;
+        for index in 0..capacity {
+            seq.serialize_element(&unsafe { self.0.slot(index) })?;
+        }
+        seq.end()
+    }
+}
+
+struct SerializeFreeList<'a, P>(&'a P);
+
+impl<P: PoolSlots> SerializeFreeList<'_, P> {
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        core::iter::successors(self.0.first_free_slot(), move |&index| unsafe { self.0.next_free_slot(index) })
+    }
+}
+
+impl<P: PoolSlots> Serialize for SerializeFreeList<'_, P> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.iter().count()))?;
+        for index in self.iter() {
+            seq.serialize_element(&index)?;
+        }
+        seq.end()
+    }
+}
+
+fn deserialize_pool<'de, P, D>(pool: P, deserializer: D) -> Result<P, D::Error>
+where
+    P: PoolSlots,
+    P::Item: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    let builder = PoolBuilder { pool, slot_count: None, free_list: None };
+    deserializer.deserialize_struct(P::NAME, FIELDS, builder)
+}
+
+/// A pool under construction, which is only valid once [`finish`](PoolBuilder::finish)
+/// succeeds. Until then, the pool's values are dropped properly, but its free
+/// list may be corrupted.
+struct PoolBuilder<P> {
+    pool: P,
+    slot_count: Option<usize>,
+    free_list: Option<(Option<usize>, usize)>,
+}
+
+impl<P: PoolSlots> PoolBuilder<P> {
+    fn finish<E: de::Error>(self) -> Result<P, E> {
+        let slot_count = self.slot_count.ok_or_else(|| E::missing_field("slots"))?;
+        let (mut first, free_list_len) = self.free_list.ok_or_else(|| E::missing_field("free_list"))?;
+        let mut pool = self.pool;
+
+        // The free list must not run into an occupied slot or loop back on itself:
+        let mut last = None;
+        let mut next = first;
+        let mut visited = 0;
+        while let Some(index) = next {
+            if visited == free_list_len || index >= slot_count || unsafe { pool.slot(index) }.1.is_some() {
+                return Err(E::custom("malformed free list"));
+            }
+            last = Some(index);
+            next = unsafe { pool.next_free_slot(index) };
+            visited += 1;
+        }
+        if visited != free_list_len {
+            return Err(E::custom("malformed free list"));
+        }
+
+        let retired = slot_count - pool.occupied_slots() - free_list_len;
+        for index in slot_count..pool.slot_capacity() {
+            match last {
+                Some(prev) => unsafe { pool.set_next_free_slot(prev, Some(index)) },
+                None => first = Some(index),
+            }
+            last = Some(index);
+        }
+        if let Some(prev) = last {
+            unsafe { pool.set_next_free_slot(prev, None) };
+        }
+
+        pool.set_free_list(first, retired);
+        Ok(pool)
+    }
+}
+
+enum Field {
+    Slots,
+    FreeList,
+}
+
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldVisitor;
+
+        impl Visitor<'_> for FieldVisitor {
+            type Value = Field;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.write_str("`slots` or `free_list`")
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Field, E> {
+                match v {
+                    0 => Ok(Field::Slots),
+                    1 => Ok(Field::FreeList),
+                    _ => Err(E::invalid_value(Unexpected::Unsigned(v), &self)),
+                }
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Field, E> {
+                match v {
+                    "slots" => Ok(Field::Slots),
+                    "free_list" => Ok(Field::FreeList),
+                    _ => Err(E::unknown_field(v, FIELDS)),
+                }
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Field, E> {
+                match v {
+                    b"slots" => Ok(Field::Slots),
+                    b"free_list" => Ok(Field::FreeList),
+                    _ => Err(E::invalid_value(Unexpected::Bytes(v), &self)),
+                }
+            }
+        }
+
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+impl<'de, P: PoolSlots> Visitor<'de> for PoolBuilder<P>
+where
+    P::Item: Deserialize<'de>,
+{
+    type Value = P;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "struct {}", P::NAME)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(mut self, mut seq: A) -> Result<P, A::Error> {
+        if seq.next_element_seed(SlotsSeed(&mut self))?.is_none() {
+            return Err(de::Error::invalid_length(0, &self));
+        }
+        if seq.next_element_seed(FreeListSeed(&mut self))?.is_none() {
+            return Err(de::Error::invalid_length(1, &self));
+        }
+        self.finish()
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<P, A::Error> {
+        while let Some(field) = map.next_key()? {
+            match field {
+                Field::Slots => {
+                    if self.slot_count.is_some() {
+                        return Err(de::Error::duplicate_field("slots"));
+                    }
+                    map.next_value_seed(SlotsSeed(&mut self))?;
+                }
+                Field::FreeList => {
+                    if self.free_list.is_some() {
+                        return Err(de::Error::duplicate_field("free_list"));
+                    }
+                    map.next_value_seed(FreeListSeed(&mut self))?;
+                }
+            }
+        }
+        self.finish()
+    }
+}
+
+struct SlotsSeed<'a, P>(&'a mut PoolBuilder<P>);
+
+impl<'de, P: PoolSlots> DeserializeSeed<'de> for SlotsSeed<'_, P>
+where
+    P::Item: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, P: PoolSlots> Visitor<'de> for SlotsSeed<'_, P>
+where
+    P::Item: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("a sequence of pool slots")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let pool = &mut self.0.pool;
+        let capacity = pool.slot_capacity();
+
+        let mut index = 0;
+        while let Some((generation, value)) = seq.next_element::<(u32, Option<P::Item>)>()? {
+            if index == capacity {
+                return Err(de::Error::custom(CapacityError));
+            }
+            if generation > <P::Handle as Handle>::MAX_GENERATION {
+                return Err(de::Error::invalid_value(
+                    Unexpected::Unsigned(generation.into()),
+                    &"a generation count representable by the handle type",
+                ));
+            }
+
+            match (value, generation % 2 == 1) {
+                (Some(value), true) => unsafe { pool.occupy(index, generation, value) },
+                (None, false) => unsafe { pool.set_vacant(index, generation) },
+                _ => return Err(de::Error::custom("generation count does not match slot occupancy")),
+            }
+            index += 1;
+        }
+
+        self.0.slot_count = Some(index);
+        Ok(())
+    }
+}
+
+struct FreeListSeed<'a, P>(&'a mut PoolBuilder<P>);
+
+impl<'de, P: PoolSlots> DeserializeSeed<'de> for FreeListSeed<'_, P> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, P: PoolSlots> Visitor<'de> for FreeListSeed<'_, P> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("a sequence of slot indices")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let builder = self.0;
+        let capacity = builder.pool.slot_capacity();
+
+        let mut first = None;
+        let mut last = None;
+        let mut len = 0;
+        while let Some(index) = seq.next_element::<usize>()? {
+            if index >= capacity {
+                return Err(de::Error::custom(CapacityError));
+            }
+
+            // Linking an occupied slot would overwrite its value, so this must
+            // be checked here if the slots are already known; otherwise all
+            // slots are still vacant, and `finish` checks the free list later.
+            if let Some(slot_count) = builder.slot_count {
+                if index >= slot_count || unsafe { builder.pool.slot(index) }.1.is_some() {
+                    return Err(de::Error::invalid_value(
+                        Unexpected::Unsigned(index as u64),
+                        &"the index of a vacant slot",
+                    ));
+                }
+            }
+
+            match last {
+                Some(prev) => unsafe { builder.pool.set_next_free_slot(prev, Some(index)) },
+                None => first = Some(index),
+            }
+            last = Some(index);
+            len += 1;
+        }
+
+        if let Some(prev) = last {
+            unsafe { builder.pool.set_next_free_slot(prev, None) };
+        }
+
+        builder.free_list = Some((first, len));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::marker::PhantomData;
+
+    use serde::{Deserialize, Deserializer};
+    use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_ser_tokens, Token};
+
+    use crate::collections::pool::{DefaultHandle, Handle};
+    use crate::collections::{DirectInlinePool, PackedInlinePool};
+
+    fn slot_tokens(pool_name: &'static str) -> [Token; 25] {
+        [
+            Token::Struct { name: pool_name, len: 2 },
+            Token::Str("slots"),
+            Token::Seq { len: Some(3) },
+            Token::Tuple { len: 2 }, Token::U32(2), Token::None, Token::TupleEnd,
+            Token::Tuple { len: 2 }, Token::U32(1), Token::Some, Token::Char('b'), Token::TupleEnd,
+            Token::Tuple { len: 2 }, Token::U32(0), Token::None, Token::TupleEnd,
+            Token::SeqEnd,
+            Token::Str("free_list"),
+            Token::Seq { len: Some(2) },
+            Token::U64(0),
+            Token::U64(2),
+            Token::SeqEnd,
+            Token::StructEnd,
+            // padding, sliced off by the caller:
+            Token::Unit, Token::Unit,
+        ]
+    }
+
+    /// Runs assertions against a deserialized pool, since the pools do not
+    /// implement `PartialEq` as required by `assert_de_tokens`.
+    trait Check {
+        type Pool: for<'de> Deserialize<'de>;
+        fn check(pool: Self::Pool);
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Checked<C>(PhantomData<C>);
+
+    impl<'de, C: Check> Deserialize<'de> for Checked<C> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            C::Pool::deserialize(deserializer).map(C::check)?;
+            Ok(Checked(PhantomData))
+        }
+    }
+
+    fn assert_checked<C: Check + core::fmt::Debug + PartialEq>(tokens: &[Token]) {
+        assert_de_tokens(&Checked::<C>(PhantomData), tokens);
+    }
+
+    fn handle(index: u32, generation: u32) -> DefaultHandle {
+        unsafe { DefaultHandle::new(index as usize, generation) }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct DirectRoundTrip;
+    impl Check for DirectRoundTrip {
+        type Pool = DirectInlinePool<char, 3>;
+        fn check(mut pool: Self::Pool) {
+            assert_eq!(pool.len(), 1);
+            assert_eq!(pool[handle(1, 1)], 'b');
+            assert_eq!(pool.insert('c'), handle(0, 3));
+            assert_eq!(pool.insert('d'), handle(2, 1));
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct PackedRoundTrip;
+    impl Check for PackedRoundTrip {
+        type Pool = PackedInlinePool<char, 3>;
+        fn check(mut pool: Self::Pool) {
+            assert_eq!(pool.len(), 1);
+            assert_eq!(pool[handle(1, 1)], 'b');
+            assert_eq!(pool.insert('c'), handle(0, 3));
+            assert_eq!(pool.insert('d'), handle(2, 1));
+            assert_eq!(pool.values(), &['b', 'c', 'd']);
+        }
+    }
+
+    #[test]
+    fn pools_round_trip() {
+        let mut direct = DirectInlinePool::<char, 3>::new();
+        let a = direct.insert('a');
+        let b = direct.insert('b');
+        direct.remove(a);
+        // the free list now starts at the slot vacated by `a`, followed by slot 2
+        assert_ser_tokens(&direct, &slot_tokens("DirectPool")[..23]);
+        assert_checked::<DirectRoundTrip>(&slot_tokens("DirectPool")[..23]);
+
+        let mut packed = PackedInlinePool::<char, 3>::new();
+        let a = packed.insert('a');
+        assert_eq!(packed.insert('b'), b);
+        packed.remove(a);
+        assert_ser_tokens(&packed, &slot_tokens("PackedPool")[..23]);
+        assert_checked::<PackedRoundTrip>(&slot_tokens("PackedPool")[..23]);
+
+        let handle_tokens = [Token::U64(b.into_raw_parts().0 as u64 | (1 << 32))];
+        serde_test::assert_tokens(&b, &handle_tokens);
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct UnlistedSlotRetired;
+    impl Check for UnlistedSlotRetired {
+        type Pool = PackedInlinePool<char, 4>;
+        fn check(mut pool: Self::Pool) {
+            assert_eq!(pool.len(), 1);
+            assert_eq!(pool.retired_slots(), 1);
+            assert_eq!(pool[handle(1, 1)], 'b');
+            assert!(!pool.contains(handle(0, 1)));
+
+            // slot 3 exceeds the serialized capacity and is appended to the free list
+            assert_eq!(pool.insert('c'), handle(0, 3));
+            assert_eq!(pool.insert('d'), handle(3, 1));
+            assert!(pool.is_full());
+        }
+    }
+
+    #[test]
+    fn handles_stay_valid() {
+        let mut tokens = slot_tokens("PackedPool");
+        // drop slot 2 from the free list, retiring it
+        tokens[18] = Token::Seq { len: Some(1) };
+        tokens[20] = Token::SeqEnd;
+        tokens[21] = Token::StructEnd;
+        assert_checked::<UnlistedSlotRetired>(&tokens[..22]);
+    }
+
+    #[test]
+    fn insufficient_capacity() {
+        assert_de_tokens_error::<DirectInlinePool<char, 2>>(&slot_tokens("DirectPool")[..16], "insufficient capacity");
+        assert_de_tokens_error::<PackedInlinePool<char, 2>>(&slot_tokens("PackedPool")[..16], "insufficient capacity");
+    }
+
+    #[test]
+    fn malformed_free_list() {
+        let mut tokens = slot_tokens("DirectPool");
+        tokens[20] = Token::U64(1);
+        assert_de_tokens_error::<DirectInlinePool<char, 3>>(
+            &tokens[..21],
+            "invalid value: integer `1`, expected the index of a vacant slot",
+        );
+
+        tokens[20] = Token::U64(0);
+        assert_de_tokens_error::<DirectInlinePool<char, 3>>(&tokens[..23], "malformed free list");
+    }
+}
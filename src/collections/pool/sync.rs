@@ -0,0 +1,561 @@
+//! Object pools with direct indexing that can be shared between threads.
+//!
+//! Like a [`DirectPool`](super::direct::DirectPool), a [`SyncPool`] stores its
+//! values in an incontiguously populated array, but values can be inserted,
+//! accessed and removed through shared references. Vacant slots are kept in a
+//! lock-free free list, and each slot's generation count is updated atomically.
+//!
+//! Accessing a value with [`SyncPool::get`] yields a [`Guard`], which keeps the
+//! value alive until it is dropped. Removing a value waits for all of its
+//! guards to be dropped first, so a thread must not remove a value while it
+//! holds a guard to it, or it will deadlock.
+//!
+//! ```
+//! use coca::collections::SyncInlinePool;
+//!
+//! let pool = SyncInlinePool::<&'static str, 4>::new();
+//! let h = pool.insert("hello");
+//! assert_eq!(*pool.get(h).unwrap(), "hello");
+//! assert_eq!(pool.remove(h), Some("hello"));
+//! assert!(pool.get(h).is_none());
+//! ```
+
+use core::alloc::{Layout, LayoutError};
+use core::cell::UnsafeCell;
+use core::fmt::{Debug, Formatter};
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ops::Deref;
+use core::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use super::{
+    buffer_too_large_for_handle_type, checked_raw_parts, tag_too_large_for_handle_type, vacated_generation,
+    DefaultHandle, Handle,
+};
+use crate::storage::{LayoutSpec, Storage};
+
+struct Slot<T> {
+    value: UnsafeCell<MaybeUninit<T>>,
+    generation: AtomicU32,
+    readers: AtomicU32,
+    next_free: AtomicUsize,
+}
+
+/// The [`LayoutSpec`] for a [`SyncPool`].
+pub struct SyncPoolLayout<T, H>(PhantomData<(T, H)>);
+impl<T, H: Handle> LayoutSpec for SyncPoolLayout<T, H> {
+    fn layout_with_capacity(items: usize) -> Result<Layout, LayoutError> {
+        Layout::array::<Slot<T>>(items)
+    }
+}
+
+/// A direct-mapped object pool with constant capacity that supports
+/// concurrent insertion, access and removal.
+///
+/// See the [super module documentation](crate::collections::pool) for information on
+/// pool-based memory management, and [this module's documentation](crate::collections::pool::sync)
+/// for details on this variation of it.
+pub struct SyncPool<T, S: Storage<SyncPoolLayout<T, H>>, H: Handle = DefaultHandle> {
+    buf: S,
+    len: AtomicUsize,
+    // The index of the first vacant slot in the low bits, tagged with a
+    // modification counter in the high bits to avoid the ABA problem.
+    free_list_head: AtomicUsize,
+    retired: AtomicUsize,
+    tag: u32,
+    items: PhantomData<(T, H)>,
+}
+
+unsafe impl<T: Send, S: Storage<SyncPoolLayout<T, H>> + Send, H: Handle> Send for SyncPool<T, S, H> {}
+unsafe impl<T: Send + Sync, S: Storage<SyncPoolLayout<T, H>> + Sync, H: Handle> Sync for SyncPool<T, S, H> {}
+
+impl<T, S: Storage<SyncPoolLayout<T, H>>, H: Handle> From<S> for SyncPool<T, S, H> {
+    fn from(mut buf: S) -> Self {
+        let cap = buf.capacity();
+        if cap >= H::MAX_INDEX {
+            buffer_too_large_for_handle_type::<H>();
+        }
+
+        let slots = buf.get_mut_ptr().cast::<Slot<T>>();
+        for i in 0..cap {
+            unsafe {
+                let slot = slots.add(i);
+                core::ptr::addr_of_mut!((*slot).generation).write(AtomicU32::new(0));
+                core::ptr::addr_of_mut!((*slot).readers).write(AtomicU32::new(0));
+                core::ptr::addr_of_mut!((*slot).next_free).write(AtomicUsize::new(i + 1));
+            }
+        }
+
+        SyncPool {
+            buf,
+            len: AtomicUsize::new(0),
+            free_list_head: AtomicUsize::new(0),
+            retired: AtomicUsize::new(0),
+            tag: 0,
+            items: PhantomData,
+        }
+    }
+}
+
+impl<T, S: Storage<SyncPoolLayout<T, H>>, H: Handle> SyncPool<T, S, H> {
+    #[inline]
+    fn slot(&self, index: usize) -> &Slot<T> {
+        debug_assert!(index < self.capacity());
+        unsafe { &*self.buf.get_ptr().cast::<Slot<T>>().add(index) }
+    }
+
+    /// Returns the number of low bits of the free list head used for the
+    /// index; the capacity itself is used as the end-of-list sentinel.
+    #[inline]
+    fn index_bits(&self) -> u32 {
+        usize::BITS - self.capacity().leading_zeros()
+    }
+
+    fn pop_free_slot(&self) -> Option<usize> {
+        let index_bits = self.index_bits();
+        let index_mask = !(!0 << index_bits);
+        let mut head = self.free_list_head.load(Ordering::Acquire);
+        loop {
+            let index = head & index_mask;
+            if index == self.capacity() {
+                return None;
+            }
+
+            let next = self.slot(index).next_free.load(Ordering::Relaxed);
+            let tag = (head >> index_bits).wrapping_add(1);
+            let new_head = next | tag.wrapping_shl(index_bits);
+            match self.free_list_head.compare_exchange_weak(head, new_head, Ordering::Acquire, Ordering::Acquire) {
+                Ok(_) => return Some(index),
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    fn push_free_slot(&self, index: usize) {
+        let index_bits = self.index_bits();
+        let index_mask = !(!0 << index_bits);
+        let mut head = self.free_list_head.load(Ordering::Relaxed);
+        loop {
+            self.slot(index).next_free.store(head & index_mask, Ordering::Relaxed);
+            let tag = (head >> index_bits).wrapping_add(1);
+            let new_head = index | tag.wrapping_shl(index_bits);
+            match self.free_list_head.compare_exchange_weak(head, new_head, Ordering::Release, Ordering::Relaxed) {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    /// Returns the number of elements the pool can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    /// Returns the number of elements currently in the pool.
+    ///
+    /// Since other threads may insert or remove elements concurrently, this
+    /// is only a snapshot, and may be outdated by the time it is returned.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    /// Returns [`true`] if the pool contains no elements.
+    ///
+    /// See [`len`](SyncPool::len) for caveats.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns [`true`] if the pool contains the maximum number of elements.
+    ///
+    /// See [`len`](SyncPool::len) for caveats.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len() + self.retired_slots() == self.capacity()
+    }
+
+    /// Returns the number of slots that have been retired because their
+    /// generation count was exhausted.
+    ///
+    /// This is always zero unless [`H::RETIRE_EXHAUSTED_SLOTS`](Handle::RETIRE_EXHAUSTED_SLOTS)
+    /// is set, see the [super module documentation](crate::collections::pool)
+    /// for details.
+    #[inline]
+    pub fn retired_slots(&self) -> usize {
+        self.retired.load(Ordering::Relaxed)
+    }
+
+    /// Returns the tag carried by all handles returned from the pool.
+    ///
+    /// This is always zero unless the handle type reserves bits for a tag
+    /// (see [`Handle::MAX_TAG`]).
+    #[inline]
+    pub fn tag(&self) -> u32 {
+        self.tag
+    }
+
+    /// Sets the tag carried by all handles returned from the pool from now
+    /// on. Handles with a different tag are rejected as invalid, so this
+    /// invalidates all handles previously returned from the pool unless
+    /// their tag matches the new one.
+    ///
+    /// # Panics
+    /// Panics if `tag` is greater than [`H::MAX_TAG`](Handle::MAX_TAG).
+    pub fn set_tag(&mut self, tag: u32) {
+        if tag > H::MAX_TAG {
+            tag_too_large_for_handle_type::<H>(tag);
+        }
+        self.tag = tag;
+    }
+
+    /// Returns [`true`] if the specified handle is valid for this pool.
+    ///
+    /// # Examples
+    /// ```
+    /// let pool = coca::collections::SyncInlinePool::<u128, 8>::new();
+    /// let h = pool.insert(0xDEAD_BEEF);
+    /// assert!(pool.contains(h));
+    /// pool.remove(h);
+    /// assert!(!pool.contains(h));
+    /// ```
+    pub fn contains(&self, handle: H) -> bool {
+        let (index, input_gen_count) = checked_raw_parts(handle, self.tag);
+        if index >= self.capacity() || input_gen_count % 2 == 0 {
+            return false;
+        }
+        self.slot(index).generation.load(Ordering::Acquire) == input_gen_count
+    }
+
+    /// Returns a guard granting shared access to the value corresponding to
+    /// the handle, which prevents the value from being removed while it is
+    /// alive.
+    ///
+    /// Returns [`None`] if the handle is invalid for this pool.
+    ///
+    /// # Examples
+    /// ```
+    /// let pool = coca::collections::SyncInlinePool::<u32, 8>::new();
+    /// let h = pool.insert(42);
+    /// {
+    ///     let guard = pool.get(h).unwrap();
+    ///     assert_eq!(*guard, 42);
+    /// }
+    /// pool.remove(h);
+    /// assert!(pool.get(h).is_none());
+    /// ```
+    pub fn get(&self, handle: H) -> Option<Guard<'_, T>> {
+        let (index, input_gen_count) = checked_raw_parts(handle, self.tag);
+        if index >= self.capacity() || input_gen_count % 2 == 0 {
+            return None;
+        }
+
+        // Registering as a reader before checking the generation count
+        // ensures that any concurrent `remove` either sees the reader and
+        // waits for it, or has already changed the generation count.
+        let slot = self.slot(index);
+        slot.readers.fetch_add(1, Ordering::SeqCst);
+        if slot.generation.load(Ordering::SeqCst) != input_gen_count {
+            slot.readers.fetch_sub(1, Ordering::Release);
+            return None;
+        }
+
+        Some(Guard { slot, value: PhantomData })
+    }
+
+    /// Returns a mutable reference to the value corresponding to the handle.
+    ///
+    /// Returns [`None`] if the handle is invalid for this pool.
+    pub fn get_mut(&mut self, handle: H) -> Option<&mut T> {
+        let (index, input_gen_count) = checked_raw_parts(handle, self.tag);
+        if index >= self.capacity() || input_gen_count % 2 == 0 {
+            return None;
+        }
+
+        let slot = unsafe { &mut *self.buf.get_mut_ptr().cast::<Slot<T>>().add(index) };
+        if *slot.generation.get_mut() != input_gen_count {
+            return None;
+        }
+
+        Some(unsafe { &mut *slot.value.get_mut().as_mut_ptr() })
+    }
+
+    /// Inserts a value into the pool, returning a unique handle to access it.
+    ///
+    /// Returns `Err(value)` if the pool is already full.
+    ///
+    /// # Examples
+    /// ```
+    /// let pool = coca::collections::SyncInlinePool::<u32, 1>::new();
+    /// assert!(pool.try_insert(1).is_ok());
+    /// assert_eq!(pool.try_insert(2), Err(2));
+    /// ```
+    pub fn try_insert(&self, value: T) -> Result<H, T> {
+        match self.pop_free_slot() {
+            Some(index) => Ok(self.occupy(index, value)),
+            None => Err(value),
+        }
+    }
+
+    fn occupy(&self, index: usize, value: T) -> H {
+        self.len.fetch_add(1, Ordering::Relaxed);
+        let slot = self.slot(index);
+        let gen_count = slot.generation.load(Ordering::Relaxed).wrapping_add(1) & H::MAX_GENERATION;
+        debug_assert_eq!(gen_count % 2, 1);
+
+        unsafe { (*slot.value.get()).as_mut_ptr().write(value); }
+        slot.generation.store(gen_count, Ordering::SeqCst);
+
+        unsafe { H::new_tagged(index, gen_count, self.tag) }
+    }
+
+    /// Inserts a value into the pool, returning a unique handle to access it.
+    ///
+    /// # Panics
+    /// Panics if the pool is already full. See [`try_insert`](SyncPool::try_insert)
+    /// for a checked version.
+    pub fn insert(&self, value: T) -> H {
+        #[cold]
+        #[inline(never)]
+        fn assert_failed() -> ! {
+            panic!("pool is already at capacity")
+        }
+
+        let result = self.try_insert(value);
+        match result {
+            Ok(handle) => handle,
+            Err(_) => assert_failed(),
+        }
+    }
+
+    /// Removes the value referred to by the specified handle from the pool,
+    /// returning it unless the handle is invalid. This invalidates the handle.
+    ///
+    /// If any [`Guard`]s to the value are alive, this waits for them to be
+    /// dropped before returning.
+    ///
+    /// # Examples
+    /// ```
+    /// let pool = coca::collections::SyncInlinePool::<u128, 8>::new();
+    /// let h = pool.insert(42);
+    /// assert_eq!(pool.remove(h), Some(42));
+    /// assert_eq!(pool.remove(h), None);
+    /// ```
+    pub fn remove(&self, handle: H) -> Option<T> {
+        let (index, input_gen_count) = checked_raw_parts(handle, self.tag);
+        if index >= self.capacity() || input_gen_count % 2 == 0 {
+            return None;
+        }
+
+        let slot = self.slot(index);
+        let (new_gen_count, retire) = vacated_generation::<H>(input_gen_count);
+        if slot.generation.compare_exchange(input_gen_count, new_gen_count, Ordering::SeqCst, Ordering::Relaxed).is_err() {
+            return None;
+        }
+
+        while slot.readers.load(Ordering::SeqCst) != 0 {
+            core::hint::spin_loop();
+        }
+
+        let value = unsafe { (*slot.value.get()).as_ptr().read() };
+        self.len.fetch_sub(1, Ordering::Relaxed);
+        if retire {
+            self.retired.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.push_free_slot(index);
+        }
+        Some(value)
+    }
+}
+
+impl<T, S: Storage<SyncPoolLayout<T, H>>, H: Handle> Drop for SyncPool<T, S, H> {
+    fn drop(&mut self) {
+        for i in 0..self.capacity() {
+            let slot = self.slot(i);
+            if slot.generation.load(Ordering::Relaxed) % 2 == 1 {
+                unsafe { (*slot.value.get()).as_mut_ptr().drop_in_place(); }
+            }
+        }
+    }
+}
+
+impl<T, S: Storage<SyncPoolLayout<T, H>>, H: Handle> Debug for SyncPool<T, S, H> {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> core::fmt::Result {
+        fmt.debug_struct("SyncPool")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Shared access to a value stored in a [`SyncPool`].
+///
+/// While a guard is alive, the value it refers to cannot be removed from the
+/// pool. This `struct` is created by [`SyncPool::get`], see its documentation
+/// for more.
+pub struct Guard<'a, T> {
+    slot: &'a Slot<T>,
+    value: PhantomData<&'a T>,
+}
+
+unsafe impl<T: Sync> Send for Guard<'_, T> {}
+unsafe impl<T: Sync> Sync for Guard<'_, T> {}
+
+impl<T> Deref for Guard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*(*self.slot.value.get()).as_ptr() }
+    }
+}
+
+impl<T> Drop for Guard<'_, T> {
+    fn drop(&mut self) {
+        self.slot.readers.fetch_sub(1, Ordering::Release);
+    }
+}
+
+impl<T: Debug> Debug for Guard<'_, T> {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> core::fmt::Result {
+        (**self).fmt(fmt)
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+impl<T, H: Handle> crate::collections::SyncAllocPool<T, H> {
+    /// Constructs a new, empty [`SyncAllocPool`](crate::collections::SyncAllocPool)
+    /// with the specified capacity.
+    ///
+    /// # Panics
+    /// Panics if the specified capacity is greater than or equal to `H::MAX_INDEX`.
+    pub fn with_capacity(capacity: H::Index) -> Self {
+        use crate::storage::Capacity;
+        let cap = capacity.as_usize();
+        if cap >= H::MAX_INDEX {
+            buffer_too_large_for_handle_type::<H>();
+        }
+
+        let storage = crate::storage::AllocStorage::with_capacity(cap);
+        Self::from(storage)
+    }
+}
+
+/// A statically-sized storage block for a [`SyncPool`].
+#[repr(transparent)]
+pub struct InlineStorage<T, const N: usize> {
+    slots: [MaybeUninit<Slot<T>>; N],
+}
+
+// The slots are only accessed through the pool, which synchronizes access.
+unsafe impl<T: Send + Sync, const N: usize> Sync for InlineStorage<T, N> {}
+
+unsafe impl<T, H: Handle, const N: usize> Storage<SyncPoolLayout<T, H>> for InlineStorage<T, N> {
+    #[inline]
+    fn get_ptr(&self) -> *const u8 {
+        self.slots.as_ptr().cast()
+    }
+
+    #[inline]
+    fn get_mut_ptr(&mut self) -> *mut u8 {
+        self.slots.as_mut_ptr().cast()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<T, H: Handle, const N: usize> SyncPool<T, InlineStorage<T, N>, H> {
+    /// Constructs a new, empty `SyncPool` backed by [`InlineStorage`].
+    pub fn new() -> Self {
+        if N >= H::MAX_INDEX {
+            buffer_too_large_for_handle_type::<H>();
+        }
+
+        Self::from(InlineStorage { slots: unsafe { MaybeUninit::uninit().assume_init() } })
+    }
+}
+
+impl<T, H: Handle, const N: usize> Default for SyncPool<T, InlineStorage<T, N>, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::collections::SyncInlinePool;
+
+    #[test]
+    fn handles_are_invalidated() {
+        let pool = SyncInlinePool::<u32, 2>::new();
+        let a = pool.insert(1);
+        let b = pool.insert(2);
+        assert_eq!(pool.try_insert(3), Err(3));
+
+        assert_eq!(pool.remove(a), Some(1));
+        let c = pool.insert(3);
+        assert_ne!(a, c);
+        assert!(pool.get(a).is_none());
+        assert_eq!(pool.remove(a), None);
+        assert_eq!(*pool.get(b).unwrap(), 2);
+        assert_eq!(*pool.get(c).unwrap(), 3);
+        assert_eq!(pool.len(), 2);
+    }
+
+    #[test]
+    fn pools_with_sync_storage_are_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<SyncInlinePool<u32, 4>>();
+        assert_sync::<crate::collections::SyncArenaPool<'static, u32>>();
+        #[cfg(feature = "alloc")]
+        assert_sync::<crate::collections::SyncAllocPool<u32>>();
+    }
+
+    #[test]
+    fn concurrent_insert_get_remove() {
+        use std::vec::Vec;
+
+        const THREADS: usize = 4;
+        const ROUNDS: usize = 2_000;
+
+        let pool: &'static SyncInlinePool<usize, 8> = std::boxed::Box::leak(std::boxed::Box::default());
+
+        let mut threads = Vec::new();
+        for t in 0..THREADS {
+            threads.push(std::thread::spawn(move || {
+                for i in 0..ROUNDS {
+                    let value = t * ROUNDS + i;
+                    let h = loop {
+                        match pool.try_insert(value) {
+                            Ok(h) => break h,
+                            Err(_) => std::thread::yield_now(),
+                        }
+                    };
+
+                    assert_eq!(pool.get(h).map(|g| *g), Some(value));
+                    assert_eq!(pool.remove(h), Some(value));
+                    assert!(pool.get(h).is_none());
+                }
+            }));
+        }
+
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert!(pool.is_empty());
+        let mut handles = Vec::new();
+        while let Ok(h) = pool.try_insert(0) {
+            handles.push(h);
+        }
+        assert_eq!(handles.len(), pool.capacity());
+    }
+}
@@ -0,0 +1,1946 @@
+//! A map based on a sorted array, using binary search for key lookups.
+
+use core::alloc::Layout;
+use core::borrow::Borrow;
+use core::fmt::Debug;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::ops::{Bound, RangeBounds};
+
+use crate::collections::list_map::{InlineStorage, ListMapLayout};
+use crate::storage::{Capacity, Storage};
+
+use self::Entry::{Occupied, Vacant};
+
+/// A map based on a sorted array, using binary search for key lookups.
+///
+/// Like a [`ListMap`](crate::collections::list_map::ListMap), this stores keys
+/// and values in two separate arrays, which share the same [`LayoutSpec`](crate::storage::LayoutSpec),
+/// so either one can be used with the same storage types. Unlike a `ListMap`,
+/// the entries are kept sorted by key, which makes looking up a key *O*(log *n*),
+/// and allows iterating over the map in order, as well as querying ranges of
+/// keys, similar to a [`BTreeMap`](https://doc.rust-lang.org/std/collections/struct.BTreeMap.html).
+///
+/// The price for this is paid on insertion and removal, both of which need to
+/// shift all entries with greater keys, taking *O*(*n*) time. This makes
+/// `SortedMap` best suited for lookup tables that are populated once and
+/// queried many times.
+///
+/// It is required that the keys implement the [`Ord`] trait, although this can
+/// frequently be achieved using `#[derive(PartialEq, Eq, PartialOrd, Ord)]`.
+///
+/// It is a logic error for a key to be modified in such a way that its ordering
+/// relative to any other key, as determined by the `Ord` trait, changes while
+/// it is in the map. This is normally only possible through [`Cell`](core::cell::Cell),
+/// [`RefCell`](core::cell::RefCell), global state, I/O, or unsafe code. The
+/// behavior resulting from such a logic error is not specified, but will not
+/// result in undefined behavior. This could include panics, incorrect results,
+/// aborts, memory leaks, and non-termination.
+pub struct SortedMap<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> {
+    buf: S,
+    len: I,
+    pairs: PhantomData<(K, V)>,
+}
+
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> From<S> for SortedMap<K, V, S, I> {
+    fn from(buf: S) -> Self {
+        SortedMap { buf, len: I::from_usize(0), pairs: PhantomData }
+    }
+}
+
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> SortedMap<K, V, S, I> {
+    /// Returns the number of entries the map can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    #[inline(always)]
+    fn values_offset(&self) -> usize {
+        let cap = self.capacity();
+        let keys_array = Layout::array::<K>(cap).unwrap();
+        let values_array = Layout::array::<V>(cap).unwrap();
+        let (_, offset) = keys_array.extend(values_array).unwrap();
+        offset
+    }
+
+    #[inline(always)]
+    fn keys_mut_ptr(&mut self) -> *mut K {
+        self.buf.get_mut_ptr().cast()
+    }
+
+    #[inline(always)]
+    fn values_mut_ptr(&mut self) -> *mut V {
+        let offset = self.values_offset();
+        unsafe { self.buf.get_mut_ptr().add(offset).cast() }
+    }
+
+    /// Inserts the given key-value pair at position `idx`, shifting all
+    /// entries after it by one, and returns a pointer to the inserted value.
+    ///
+    /// # Safety
+    /// Callers must ensure that the map is not full, that `idx <= self.len()`,
+    /// and that the map remains sorted after the insertion.
+    unsafe fn insert_at(&mut self, idx: usize, key: K, value: V) -> *mut V {
+        let len = self.len();
+        let keys_ptr = self.keys_mut_ptr().add(idx);
+        let values_ptr = self.values_mut_ptr().add(idx);
+
+        core::ptr::copy(keys_ptr, keys_ptr.add(1), len - idx);
+        core::ptr::copy(values_ptr, values_ptr.add(1), len - idx);
+
+        keys_ptr.write(key);
+        values_ptr.write(value);
+
+        self.len = I::from_usize(len + 1);
+        values_ptr
+    }
+
+    /// Removes the key-value pair at position `idx`, shifting all entries
+    /// after it by one, and returns it.
+    ///
+    /// # Safety
+    /// Callers must ensure that `idx < self.len()`.
+    unsafe fn remove_at(&mut self, idx: usize) -> (K, V) {
+        let new_len = self.len() - 1;
+        let keys_ptr = self.keys_mut_ptr().add(idx);
+        let values_ptr = self.values_mut_ptr().add(idx);
+
+        let k = keys_ptr.read();
+        let v = values_ptr.read();
+
+        core::ptr::copy(keys_ptr.add(1), keys_ptr, new_len - idx);
+        core::ptr::copy(values_ptr.add(1), values_ptr, new_len - idx);
+
+        self.len = I::from_usize(new_len);
+        (k, v)
+    }
+
+    /// Returns a slice of all keys in the map in ascending order.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    /// map.insert("c", 3);
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    ///
+    /// assert_eq!(map.keys(), &["a", "b", "c"]);
+    /// ```
+    #[inline]
+    pub fn keys(&self) -> &[K] {
+        let ptr = self.buf.get_ptr().cast();
+        unsafe { core::slice::from_raw_parts(ptr, self.len()) }
+    }
+
+    /// Returns a slice of all values in the map, in ascending order of their keys.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    /// map.insert("c", 1);
+    /// map.insert("a", 2);
+    /// map.insert("b", 3);
+    ///
+    /// assert_eq!(map.values(), &[2, 3, 1]);
+    /// ```
+    #[inline]
+    pub fn values(&self) -> &[V] {
+        unsafe {
+            let ptr = self.buf.get_ptr().add(self.values_offset()).cast();
+            core::slice::from_raw_parts(ptr, self.len())
+        }
+    }
+
+    /// Returns a mutable slice of all values in the map, in ascending order of their keys.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    /// map.insert("c", 3);
+    ///
+    /// for val in map.values_mut() {
+    ///     *val = *val * 2;
+    /// }
+    ///
+    /// assert_eq!(map.values(), &[2, 4, 6]);
+    /// ```
+    #[inline]
+    pub fn values_mut(&mut self) -> &mut [V] {
+        let len = self.len();
+        unsafe { core::slice::from_raw_parts_mut(self.values_mut_ptr(), len) }
+    }
+
+    #[inline(always)]
+    fn keys_and_values_mut(&mut self) -> (&[K], &mut [V]) {
+        let len = self.len();
+        unsafe {
+            let keys = core::slice::from_raw_parts(self.keys_mut_ptr(), len);
+            let values = core::slice::from_raw_parts_mut(self.values_mut_ptr(), len);
+            (keys, values)
+        }
+    }
+
+    /// An iterator visiting all key-value pairs in ascending order of their keys.
+    /// The iterator element type is `(&'a K, &'a V)`.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    /// map.insert("c", 3);
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    ///
+    /// let mut iter = map.iter();
+    /// assert_eq!(iter.next(), Some((&"a", &1)));
+    /// assert_eq!(iter.next(), Some((&"b", &2)));
+    /// assert_eq!(iter.next(), Some((&"c", &3)));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter { keys: self.keys().iter(), values: self.values().iter() }
+    }
+
+    /// An iterator visiting all key-value pairs in ascending order of their
+    /// keys, with mutable references to the values. The iterator element type
+    /// is `(&'a K, &'a mut V)`.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    /// map.insert("c", 3);
+    ///
+    /// for (_, val) in map.iter_mut() {
+    ///     *val *= 2;
+    /// }
+    ///
+    /// assert_eq!(map.get("a"), Some(&2));
+    /// assert_eq!(map.get("b"), Some(&4));
+    /// assert_eq!(map.get("c"), Some(&6));
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        let (keys, values) = self.keys_and_values_mut();
+        IterMut { keys: keys.iter(), values: values.iter_mut() }
+    }
+
+    /// Returns the number of entries in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len.as_usize()
+    }
+
+    /// Returns `true` if the map contains no entries, or `false` otherwise.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len.as_usize() == 0
+    }
+
+    /// Returns `true` if the map contains the maximum number of entries it can hold, or `false` otherwise.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len.as_usize() == self.buf.capacity()
+    }
+
+    /// Returns the key-value pair with the smallest key in the map, or [`None`] if the map is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<u32, &'static str, 4>::new();
+    /// assert_eq!(map.first_key_value(), None);
+    ///
+    /// map.insert(2, "b");
+    /// map.insert(1, "a");
+    /// assert_eq!(map.first_key_value(), Some((&1, &"a")));
+    /// ```
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        Some((self.keys().first()?, self.values().first()?))
+    }
+
+    /// Returns the key-value pair with the greatest key in the map, or [`None`] if the map is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<u32, &'static str, 4>::new();
+    /// assert_eq!(map.last_key_value(), None);
+    ///
+    /// map.insert(2, "b");
+    /// map.insert(1, "a");
+    /// assert_eq!(map.last_key_value(), Some((&2, &"b")));
+    /// ```
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        Some((self.keys().last()?, self.values().last()?))
+    }
+
+    /// Removes and returns the key-value pair with the smallest key in the map,
+    /// or [`None`] if the map is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<u32, &'static str, 4>::new();
+    /// map.insert(1, "a");
+    /// map.insert(2, "b");
+    ///
+    /// assert_eq!(map.pop_first(), Some((1, "a")));
+    /// assert_eq!(map.pop_first(), Some((2, "b")));
+    /// assert_eq!(map.pop_first(), None);
+    /// ```
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        if self.is_empty() {
+            return None;
+        }
+
+        unsafe { Some(self.remove_at(0)) }
+    }
+
+    /// Removes and returns the key-value pair with the greatest key in the map,
+    /// or [`None`] if the map is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<u32, &'static str, 4>::new();
+    /// map.insert(1, "a");
+    /// map.insert(2, "b");
+    ///
+    /// assert_eq!(map.pop_last(), Some((2, "b")));
+    /// assert_eq!(map.pop_last(), Some((1, "a")));
+    /// assert_eq!(map.pop_last(), None);
+    /// ```
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        if self.is_empty() {
+            return None;
+        }
+
+        unsafe { Some(self.remove_at(self.len() - 1)) }
+    }
+
+    /// Clears the map without taking ownership, and returns all key-value
+    /// pairs as an iterator, in ascending order of their keys.
+    ///
+    /// If the iterator is only partially consumed, or not consumed at all,
+    /// all remaining key-value pairs will still be removed.
+    ///
+    /// It is unspecified how many pairs will be removed if a panic occurs while
+    /// dropping an element, or if the [`Drain`] value is leaked.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    /// map.insert("b", 2);
+    /// map.insert("a", 1);
+    ///
+    /// for (k, v) in map.drain().take(1) {
+    ///     assert_eq!((k, v), ("a", 1));
+    /// }
+    ///
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, K, V, S, I> {
+        let back = self.len();
+        self.len = I::from_usize(0);
+        Drain { map: self, front: 0, back }
+    }
+
+    /// Creates an iterator which uses a closure to determine if an element should be removed.
+    ///
+    /// If the closure returns `true`, the element is removed from the map and
+    /// yielded. If the closure returns `false`, or panics, the element remains
+    /// in the map and will not be yielded. Elements are visited in ascending
+    /// order of their keys, and the order of retained elements is preserved.
+    ///
+    /// Note that `drain_filter` lets you mutate every value in the filter
+    /// closure, regardless of whether you choose to keep or remove it.
+    ///
+    /// If the iterator is only partially consumed, or not consumed at all,
+    /// all remaining key-value pairs will still be subjected to the closure
+    /// and removed and dropped if it returns true.
+    ///
+    /// It is unspecified how many pairs will be subjected to the closure if a
+    /// panic occurs in the closure, or a panic occurs while dropping an element,
+    /// or if the [`DrainFilter`] value is leaked.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::{InlineSortedMap, InlineVec};
+    ///
+    /// let mut map = InlineSortedMap::<u32, u32, 8>::new();
+    /// (0..8).for_each(|x| { map.insert(x, x); });
+    /// let drained = map.drain_filter(|k, v| { *v = *v * *v; k % 2 == 0 });
+    ///
+    /// let mut evens = InlineVec::<u32, 4>::new();
+    /// let mut odds = InlineVec::<u32, 4>::new();
+    ///
+    /// evens.extend(drained.map(|(_x, x_squared)| x_squared));
+    /// assert_eq!(evens, [0, 4, 16, 36]);
+    ///
+    /// odds.extend(map.into_values());
+    /// assert_eq!(odds, [1, 9, 25, 49]);
+    /// ```
+    pub fn drain_filter<F: FnMut(&K, &mut V) -> bool>(&mut self, pred: F) -> DrainFilter<'_, K, V, S, I, F> {
+        let old_len = self.len();
+        self.len = I::from_usize(0);
+        DrainFilter { map: self, should_remove: pred, front: 0, deleted: 0, old_len }
+    }
+
+    /// Clears the map, removing all key-value pairs.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    ///
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    /// map.insert("c", 3);
+    /// map.insert("d", 4);
+    /// assert!(map.is_full());
+    ///
+    /// map.clear();
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        let len = self.len();
+        self.len = I::from_usize(0);
+
+        unsafe {
+            let keys = self.keys_mut_ptr();
+            let values = self.values_mut_ptr();
+
+            for i in 0..len {
+                keys.add(i).drop_in_place();
+                values.add(i).drop_in_place();
+            }
+        }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all key-value pairs `(k, v)` such that `pred(&k, &mut v)`
+    /// returns `false`. The elements are visited in ascending order of their keys.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<u32, u32, 8>::new();
+    /// (0..8).for_each(|x| { map.insert(x, x*10); });
+    /// assert_eq!(map.len(), 8);
+    ///
+    /// map.retain(|&k, _| k % 2 == 0);
+    /// assert_eq!(map.keys(), &[0, 2, 4, 6]);
+    /// ```
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut pred: F) {
+        self.drain_filter(|k, v| !(pred)(k, v)).for_each(drop);
+    }
+
+    /// Creates a consuming iterator visiting all keys in ascending order.
+    /// The map cannot be used after calling this. The iterator element type is `K`.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::{InlineSortedMap, InlineVec};
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    /// map.insert("b", 2);
+    /// map.insert("c", 3);
+    /// map.insert("a", 1);
+    ///
+    /// let mut vec = InlineVec::<&'static str, 4>::new();
+    /// vec.extend(map.into_keys());
+    /// assert_eq!(vec, ["a", "b", "c"]);
+    /// ```
+    pub fn into_keys(self) -> IntoKeys<K, V, S, I> {
+        IntoKeys { base: self.into_iter() }
+    }
+
+    /// Creates a consuming iterator visiting all values in ascending order of
+    /// their keys. The map cannot be used after calling this. The iterator
+    /// element type is `V`.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::{InlineSortedMap, InlineVec};
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    /// map.insert("b", 2);
+    /// map.insert("c", 3);
+    /// map.insert("a", 1);
+    ///
+    /// let mut vec = InlineVec::<u32, 4>::new();
+    /// vec.extend(map.into_values());
+    /// assert_eq!(vec, [1, 2, 3]);
+    /// ```
+    pub fn into_values(self) -> IntoValues<K, V, S, I> {
+        IntoValues { base: self.into_iter() }
+    }
+
+    /// Moves all entries from position `at` onwards into a new map using the given storage.
+    ///
+    /// # Safety
+    /// Callers must ensure that `at <= self.len()`, and that `buf` has
+    /// sufficient capacity for `self.len() - at` entries.
+    unsafe fn move_tail_into<S2: Storage<ListMapLayout<K, V>>>(&mut self, at: usize, buf: S2) -> SortedMap<K, V, S2, I> {
+        let count = self.len() - at;
+        let mut result = SortedMap::from(buf);
+
+        core::ptr::copy_nonoverlapping(self.keys_mut_ptr().add(at), result.keys_mut_ptr(), count);
+        core::ptr::copy_nonoverlapping(self.values_mut_ptr().add(at), result.values_mut_ptr(), count);
+
+        self.len = I::from_usize(at);
+        result.len = I::from_usize(count);
+        result
+    }
+}
+
+impl<K: Ord, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> SortedMap<K, V, S, I> {
+    #[inline(always)]
+    fn lookup<Q>(&self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.keys().binary_search_by(|k| k.borrow().cmp(key))
+    }
+
+    fn range_indices<Q, R>(&self, range: &R) -> (usize, usize)
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        match (range.start_bound(), range.end_bound()) {
+            (Bound::Excluded(s), Bound::Excluded(e)) if s == e => {
+                panic!("range start and end are equal and excluded in SortedMap")
+            }
+            (Bound::Included(s) | Bound::Excluded(s), Bound::Included(e) | Bound::Excluded(e)) if s > e => {
+                panic!("range start is greater than range end in SortedMap")
+            }
+            _ => {}
+        }
+
+        let keys = self.keys();
+        let start = match range.start_bound() {
+            Bound::Included(s) => keys.partition_point(|k| k.borrow() < s),
+            Bound::Excluded(s) => keys.partition_point(|k| k.borrow() <= s),
+            Bound::Unbounded => 0,
+        };
+
+        let end = match range.end_bound() {
+            Bound::Included(e) => keys.partition_point(|k| k.borrow() <= e),
+            Bound::Excluded(e) => keys.partition_point(|k| k.borrow() < e),
+            Bound::Unbounded => keys.len(),
+        };
+
+        (start, end)
+    }
+
+    /// Gets the given key's corresponding [`Entry`] in the map for in-place manipulation.
+    ///
+    /// # Panics
+    /// Panics if the map is full and does not contain the given key.
+    /// See [`try_entry`](SortedMap::try_entry) for a checked version that never panics.
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, S, I> {
+        self.try_entry(key).ok().expect("map is already at capacity")
+    }
+
+    /// Gets the given key's corresponding [`Entry`] in the map for in-place manipulation.
+    ///
+    /// Returns [`Err(key)`] if the map is full and does not contain the given key.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut letters = InlineSortedMap::<char, u32, 32>::new();
+    ///
+    /// for ch in "i am, therefore i'm coded".chars() {
+    ///     let counter = letters.try_entry(ch).unwrap().or_insert(0);
+    ///     *counter += 1;
+    /// }
+    ///
+    /// assert_eq!(letters.get(&'a'), Some(&1));
+    /// assert_eq!(letters.get(&'e'), Some(&4));
+    /// assert_eq!(letters.get(&'i'), Some(&2));
+    /// assert_eq!(letters.get(&'o'), Some(&2));
+    /// assert_eq!(letters.get(&'u'), None);
+    /// ```
+    pub fn try_entry(&mut self, key: K) -> Result<Entry<'_, K, V, S, I>, K> {
+        match self.lookup(&key) {
+            Ok(idx) => Ok(Occupied(OccupiedEntry { key, idx, map: self })),
+            Err(_) if self.is_full() => Err(key),
+            Err(idx) => Ok(Vacant(VacantEntry { key, idx, map: self })),
+        }
+    }
+
+    /// Returns a reference to the value associated with the given key.
+    ///
+    /// The key may be any borrowed form of the map's key type,
+    /// but `Ord` on the borrowed form *must* match that for the key type.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    /// map.insert("a", 1);
+    ///
+    /// assert_eq!(map.get("a"), Some(&1));
+    /// assert_eq!(map.get("b"), None);
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let idx = self.lookup(key).ok()?;
+        Some(&self.values()[idx])
+    }
+
+    /// Returns the key-value pair corresponding to the given key.
+    ///
+    /// The key may be any borrowed form of the map's key type,
+    /// but `Ord` on the borrowed form *must* match that for the key type.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    /// map.insert("a", 1);
+    ///
+    /// assert_eq!(map.get_key_value("a"), Some((&"a", &1)));
+    /// assert_eq!(map.get_key_value("b"), None);
+    /// ```
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let idx = self.lookup(key).ok()?;
+        Some((&self.keys()[idx], &self.values()[idx]))
+    }
+
+    /// Returns `true` if the map contains a value for the given key, or `false` otherwise.
+    ///
+    /// The key may be any borrowed form of the map's key type,
+    /// but `Ord` on the borrowed form *must* match that for the key type.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    /// map.insert("a", 1);
+    ///
+    /// assert_eq!(map.contains_key("a"), true);
+    /// assert_eq!(map.contains_key("b"), false);
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.lookup(key).is_ok()
+    }
+
+    /// Returns a mutable reference to the value associated with the given key.
+    ///
+    /// The key may be any borrowed form of the map's key type,
+    /// but `Ord` on the borrowed form *must* match that for the key type.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    /// map.insert("a", 1);
+    ///
+    /// if let Some(x) = map.get_mut(&"a") {
+    ///     *x = *x + 2;
+    /// }
+    ///
+    /// assert_eq!(map.get("a"), Some(&3));
+    /// ```
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let idx = self.lookup(key).ok()?;
+        Some(&mut self.values_mut()[idx])
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the map did not have this key present, [`None`] is returned.
+    ///
+    /// If the map did have this key present, the value is updated, and the
+    /// old value is returned. The key is not updated though; this matters for
+    /// types that can be `==` without being identical.
+    ///
+    /// # Panics
+    /// Panics if the map is full and the given key is not present. See
+    /// [`try_insert`](SortedMap::try_insert) for a checked version that never panics.
+    #[inline]
+    #[track_caller]
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.try_insert(key, value).ok().expect("map is already at capacity")
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the map did not have this key present, `Ok(None)` is returned if the
+    /// key-value pair is inserted, or [`Err((key, value))`] if the map is full.
+    ///
+    /// If the map did have this key present, the value is updated, and the
+    /// old value is returned. The key is not updated though; this matters for
+    /// types that can be `==` without being identical.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    /// assert_eq!(map.try_insert("a", 37), Ok(None));
+    /// assert_eq!(map.try_insert("a", 42), Ok(Some(37)));
+    ///
+    /// map.insert("b", 23);
+    /// map.insert("c", 19);
+    /// map.insert("d", 8);
+    /// assert_eq!(map.is_full(), true);
+    /// assert_eq!(map.try_insert("e", 0), Err(("e", 0)));
+    /// ```
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, (K, V)> {
+        match self.lookup(&key) {
+            Ok(idx) => Ok(Some(core::mem::replace(&mut self.values_mut()[idx], value))),
+            Err(_) if self.is_full() => Err((key, value)),
+            Err(idx) => {
+                unsafe { self.insert_at(idx, key, value); }
+                Ok(None)
+            }
+        }
+    }
+
+    /// Removes a key from the map, returning the value associated with the key
+    /// if it was previously in the map.
+    ///
+    /// The key may be any borrowed form of the map's key type,
+    /// but [`Ord`] on the borrowed form *must* match that for the key type.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    /// map.insert("a", 1);
+    ///
+    /// assert_eq!(map.remove("a"), Some(1));
+    /// assert_eq!(map.remove("a"), None);
+    /// ```
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// Removes a key from the map, returning the stored key and associated
+    /// value if the key was previously in the map.
+    ///
+    /// The key may be any borrowed form of the map's key type,
+    /// but [`Ord`] on the borrowed form *must* match that for the key type.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    /// map.insert("a", 1);
+    ///
+    /// assert_eq!(map.remove_entry("a"), Some(("a", 1)));
+    /// assert_eq!(map.remove_entry("a"), None);
+    /// ```
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let idx = self.lookup(key).ok()?;
+        unsafe { Some(self.remove_at(idx)) }
+    }
+
+    /// Constructs an iterator over a sub-range of entries in the map, in
+    /// ascending order of their keys.
+    ///
+    /// The simplest way is to use the range syntax `min..max`, thus
+    /// `range(min..max)` will yield elements from `min` (inclusive) to
+    /// `max` (exclusive). The range may also be entered as
+    /// `(Bound<T>, Bound<T>)`, so for example `range((Excluded(4), Included(10)))`
+    /// will yield a left-exclusive, right-inclusive range from 4 to 10.
+    ///
+    /// # Panics
+    /// Panics if range `start > end`, or if range `start == end` and both
+    /// bounds are `Excluded`.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    /// use core::ops::Bound::Included;
+    ///
+    /// let mut map = InlineSortedMap::<u32, &'static str, 4>::new();
+    /// map.insert(3, "a");
+    /// map.insert(5, "b");
+    /// map.insert(8, "c");
+    ///
+    /// for (&key, &value) in map.range((Included(&4), Included(&8))) {
+    ///     println!("{}: {}", key, value);
+    /// }
+    ///
+    /// assert_eq!(Some((&5, &"b")), map.range(4..).next());
+    /// assert_eq!(Some((&5, &"b")), map.range(..8).next_back());
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> Iter<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let (start, end) = self.range_indices(&range);
+        Iter { keys: self.keys()[start..end].iter(), values: self.values()[start..end].iter() }
+    }
+
+    /// Constructs a mutable iterator over a sub-range of entries in the map,
+    /// in ascending order of their keys.
+    ///
+    /// See [`range`](SortedMap::range) for details on how the range is specified.
+    ///
+    /// # Panics
+    /// Panics if range `start > end`, or if range `start == end` and both
+    /// bounds are `Excluded`.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    /// map.insert("Alice", 0);
+    /// map.insert("Bob", 0);
+    /// map.insert("Carol", 0);
+    /// map.insert("Cheryl", 0);
+    ///
+    /// for (_, balance) in map.range_mut("B".."Cheryl") {
+    ///     *balance += 100;
+    /// }
+    ///
+    /// assert_eq!(map.values(), &[0, 100, 100, 0]);
+    /// ```
+    pub fn range_mut<Q, R>(&mut self, range: R) -> IterMut<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let (start, end) = self.range_indices(&range);
+        let (keys, values) = self.keys_and_values_mut();
+        IterMut { keys: keys[start..end].iter(), values: values[start..end].iter_mut() }
+    }
+
+    /// Splits the map in two at the given key, moving all entries with keys
+    /// greater than or equal to `key` into a new map using the given storage.
+    ///
+    /// Returns `Err(buf)` without modifying `self` if the given storage has
+    /// insufficient capacity for the split off entries.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::{arena::Arena, collections::InlineSortedMap};
+    /// use core::mem::MaybeUninit;
+    ///
+    /// let mut backing_region = [MaybeUninit::uninit(); 1024];
+    /// let mut arena = Arena::from(&mut backing_region[..]);
+    ///
+    /// let mut a = InlineSortedMap::<u32, char, 8>::new();
+    /// a.insert(1, 'a');
+    /// a.insert(2, 'b');
+    /// a.insert(3, 'c');
+    /// a.insert(17, 'd');
+    /// a.insert(41, 'e');
+    ///
+    /// let too_small = arena.storage_with_capacity(2);
+    /// assert!(a.try_split_off_into(&3, too_small).is_err());
+    /// assert_eq!(a.len(), 5);
+    ///
+    /// let large_enough = arena.storage_with_capacity(3);
+    /// let b = a.try_split_off_into(&3, large_enough).ok().unwrap();
+    /// assert_eq!(a.keys(), &[1, 2]);
+    /// assert_eq!(b.keys(), &[3, 17, 41]);
+    /// ```
+    pub fn try_split_off_into<Q, S2>(&mut self, key: &Q, buf: S2) -> Result<SortedMap<K, V, S2, I>, S2>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        S2: Storage<ListMapLayout<K, V>>,
+    {
+        let at = self.keys().partition_point(|k| k.borrow() < key);
+        if buf.capacity() < self.len() - at {
+            return Err(buf);
+        }
+
+        unsafe { Ok(self.move_tail_into(at, buf)) }
+    }
+
+    /// Splits the map in two at the given key, moving all entries with keys
+    /// greater than or equal to `key` into a new map using the given storage.
+    ///
+    /// # Panics
+    /// Panics if the given storage has insufficient capacity for the split off
+    /// entries. See [`try_split_off_into`](SortedMap::try_split_off_into) for
+    /// a checked version that never panics.
+    #[track_caller]
+    pub fn split_off_into<Q, S2>(&mut self, key: &Q, buf: S2) -> SortedMap<K, V, S2, I>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        S2: Storage<ListMapLayout<K, V>>,
+    {
+        self.try_split_off_into(key, buf).ok().expect("insufficient capacity")
+    }
+}
+
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> Drop for SortedMap<K, V, S, I> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<K: Debug, V: Debug, S: Storage<ListMapLayout<K, V>>, I: Capacity> Debug for SortedMap<K, V, S, I> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<Q, K, V, S, I> core::ops::Index<&'_ Q> for SortedMap<K, V, S, I>
+where
+    Q: Ord + ?Sized,
+    K: Ord + Borrow<Q>,
+    S: Storage<ListMapLayout<K, V>>,
+    I: Capacity,
+{
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+impl<K, V, S, I> Extend<(K, V)> for SortedMap<K, V, S, I>
+where
+    K: Ord,
+    S: Storage<ListMapLayout<K, V>>,
+    I: Capacity
+{
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        let iter = iter.into_iter();
+        iter.for_each(move |(k, v)| { self.insert(k, v); });
+    }
+}
+
+impl<'a, K, V, S, I> Extend<(&'a K, &'a V)> for SortedMap<K, V, S, I>
+where
+    K: Clone + Ord,
+    V: Clone,
+    S: Storage<ListMapLayout<K, V>>,
+    I: Capacity
+{
+    fn extend<T: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: T) {
+        let iter = iter.into_iter();
+        iter.for_each(|(k, v)| {
+            self.insert(k.clone(), v.clone());
+        });
+    }
+}
+
+impl<K, V, S1, I1, S2, I2> PartialEq<SortedMap<K, V, S2, I2>> for SortedMap<K, V, S1, I1>
+where
+    K: PartialEq,
+    V: PartialEq,
+    S1: Storage<ListMapLayout<K, V>>,
+    S2: Storage<ListMapLayout<K, V>>,
+    I1: Capacity,
+    I2: Capacity,
+{
+    /// Tests for `self` and `other` to be equal, and is used by `==`.
+    ///
+    /// Since both maps are sorted, this is *O*(*n*).
+    fn eq(&self, other: &SortedMap<K, V, S2, I2>) -> bool {
+        self.keys() == other.keys() && self.values() == other.values()
+    }
+}
+
+impl<K: Eq, V: Eq, S: Storage<ListMapLayout<K, V>>, I: Capacity> Eq for SortedMap<K, V, S, I> {}
+
+impl<K: Clone, V: Clone, S: Storage<ListMapLayout<K, V>>, I: Capacity> SortedMap<K, V, S, I> {
+    fn clone_entries_into<S2: Storage<ListMapLayout<K, V>>>(&self, result: &mut SortedMap<K, V, S2, I>) {
+        let keys_ptr = result.keys_mut_ptr();
+        let values_ptr = result.values_mut_ptr();
+
+        for (idx, (k, v)) in self.iter().enumerate() {
+            unsafe {
+                keys_ptr.add(idx).write(k.clone());
+                values_ptr.add(idx).write(v.clone());
+            }
+
+            result.len = I::from_usize(idx + 1);
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+impl<K, V, I: Capacity> crate::collections::AllocSortedMap<K, V, I> {
+    /// Constructs a new, empty [`AllocSortedMap`](crate::collections::AllocSortedMap)
+    /// with the specified capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from(crate::storage::AllocStorage::with_capacity(capacity))
+    }
+
+    /// Splits the map in two at the given key. Returns a new map with the
+    /// same capacity, containing all entries with keys greater than or equal
+    /// to `key`.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::AllocSortedMap;
+    ///
+    /// let mut a = AllocSortedMap::<u32, char>::with_capacity(8);
+    /// a.insert(1, 'a');
+    /// a.insert(2, 'b');
+    /// a.insert(3, 'c');
+    /// a.insert(17, 'd');
+    /// a.insert(41, 'e');
+    ///
+    /// let b = a.split_off(&3);
+    /// assert_eq!(b.capacity(), 8);
+    ///
+    /// assert_eq!(a.keys(), &[1, 2]);
+    /// assert_eq!(b.keys(), &[3, 17, 41]);
+    /// ```
+    #[must_use]
+    pub fn split_off<Q>(&mut self, key: &Q) -> Self
+    where
+        K: Ord + Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let at = self.keys().partition_point(|k| k.borrow() < key);
+        let buf = crate::storage::AllocStorage::with_capacity(self.capacity());
+        unsafe { self.move_tail_into(at, buf) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+impl<K: Clone, V: Clone, I: Capacity> Clone for crate::collections::AllocSortedMap<K, V, I> {
+    fn clone(&self) -> Self {
+        let buf = crate::storage::AllocStorage::with_capacity(self.capacity());
+        let mut result = SortedMap::from(buf);
+        self.clone_entries_into(&mut result);
+        result
+    }
+}
+
+impl<K, V, I: Capacity, const N: usize> SortedMap<K, V, InlineStorage<K, V, N>, I> {
+    /// Constructs a new, empty [`InlineSortedMap`](crate::collections::InlineSortedMap).
+    pub fn new() -> Self {
+        Self::from(InlineStorage::uninit())
+    }
+
+    /// Splits the map in two at the given key. Returns a new map containing
+    /// all entries with keys greater than or equal to `key`.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut a = InlineSortedMap::<u32, char, 8>::new();
+    /// a.insert(1, 'a');
+    /// a.insert(2, 'b');
+    /// a.insert(3, 'c');
+    /// a.insert(17, 'd');
+    /// a.insert(41, 'e');
+    ///
+    /// let b = a.split_off(&3);
+    ///
+    /// assert_eq!(a.keys(), &[1, 2]);
+    /// assert_eq!(b.keys(), &[3, 17, 41]);
+    /// ```
+    #[must_use]
+    pub fn split_off<Q>(&mut self, key: &Q) -> Self
+    where
+        K: Ord + Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let at = self.keys().partition_point(|k| k.borrow() < key);
+        unsafe { self.move_tail_into(at, InlineStorage::uninit()) }
+    }
+}
+
+impl<K, V, I: Capacity, const N: usize> Default for SortedMap<K, V, InlineStorage<K, V, N>, I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clone, V: Clone, I: Capacity, const N: usize> Clone for SortedMap<K, V, InlineStorage<K, V, N>, I> {
+    fn clone(&self) -> Self {
+        let mut result = Self::new();
+        self.clone_entries_into(&mut result);
+        result
+    }
+}
+
+/// A view into an occupied entry in a [`SortedMap`]. It is part of the [`Entry`] enum.
+pub struct OccupiedEntry<'a, K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> {
+    key: K,
+    idx: usize,
+    map: &'a mut SortedMap<K, V, S, I>,
+}
+
+impl<'a, K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> OccupiedEntry<'a, K, V, S, I> {
+    /// Gets a reference to the key used to create the entry.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Take the ownership of the key and value from the map.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    /// use coca::collections::sorted_map::Entry;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    ///
+    /// map.entry("foobar").or_insert(12);
+    /// assert_eq!(map.get("foobar"), Some(&12));
+    ///
+    /// if let Entry::Occupied(o) = map.entry("foobar") {
+    ///     o.remove_entry();
+    /// }
+    ///
+    /// assert_eq!(map.contains_key("foobar"), false);
+    /// ```
+    pub fn remove_entry(self) -> (K, V) {
+        unsafe { self.map.remove_at(self.idx) }
+    }
+
+    /// Gets a reference to the value in the entry.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    /// use coca::collections::sorted_map::Entry;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    ///
+    /// map.entry("foobar").or_insert(12);
+    /// assert_eq!(map.get("foobar"), Some(&12));
+    ///
+    /// if let Entry::Occupied(o) = map.entry("foobar") {
+    ///     assert_eq!(o.get(), &12);
+    /// }
+    /// ```
+    pub fn get(&self) -> &V {
+        &self.map.values()[self.idx]
+    }
+
+    /// Gets a mutable reference to the value in the entry.
+    ///
+    /// If you need a reference to the value which may outlive the `Entry`,
+    /// see [`into_mut`](OccupiedEntry::into_mut).
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    /// use coca::collections::sorted_map::Entry;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    ///
+    /// map.entry("foobar").or_insert(12);
+    /// assert_eq!(map.get("foobar"), Some(&12));
+    ///
+    /// if let Entry::Occupied(mut o) = map.entry("foobar") {
+    ///     *o.get_mut() += 10;
+    ///     assert_eq!(*o.get(), 22);
+    ///
+    ///     // You can use the same Entry multiple times:
+    ///     *o.get_mut() += 2;
+    /// }
+    ///
+    /// assert_eq!(map.get("foobar"), Some(&24));
+    /// ```
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.map.values_mut()[self.idx]
+    }
+
+    /// Converts the `OccupiedEntry` into a mutable reference to the value
+    /// in the entry with a lifetime bound to the map itself.
+    ///
+    /// If you need multiple references to the `OccupiedEntry`,
+    /// see [`get_mut`](OccupiedEntry::get_mut).
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    /// use coca::collections::sorted_map::Entry;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    ///
+    /// map.entry("foobar").or_insert(12);
+    /// assert_eq!(map.get("foobar"), Some(&12));
+    ///
+    /// if let Entry::Occupied(o) = map.entry("foobar") {
+    ///     *o.into_mut() += 10;
+    /// }
+    ///
+    /// assert_eq!(map.get("foobar"), Some(&22));
+    /// ```
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.values_mut()[self.idx]
+    }
+
+    /// Sets the value of the entry, and returns the entry's old value.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    /// use coca::collections::sorted_map::Entry;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    ///
+    /// map.entry("foobar").or_insert(12);
+    /// assert_eq!(map.get("foobar"), Some(&12));
+    ///
+    /// if let Entry::Occupied(mut o) = map.entry("foobar") {
+    ///     assert_eq!(o.insert(15), 12);
+    /// }
+    ///
+    /// assert_eq!(map.get("foobar"), Some(&15));
+    /// ```
+    pub fn insert(&mut self, value: V) -> V {
+        core::mem::replace(self.get_mut(), value)
+    }
+
+    /// Takes the value out of the entry, and returns it.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    /// use coca::collections::sorted_map::Entry;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    ///
+    /// map.entry("foobar").or_insert(12);
+    /// assert_eq!(map.get("foobar"), Some(&12));
+    ///
+    /// if let Entry::Occupied(o) = map.entry("foobar") {
+    ///     assert_eq!(o.remove(), 12);
+    /// }
+    ///
+    /// assert_eq!(map.contains_key("foobar"), false);
+    /// ```
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Replaces the entry, returning the old key-value pair. The new key in the map will be the key used to create the entry.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    /// use coca::collections::sorted_map::Entry;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    /// map.insert("foobar", 15);
+    ///
+    /// if let Entry::Occupied(entry) = map.entry("foobar") {
+    ///     let (old_key, old_value) = entry.replace_entry(16);
+    ///
+    ///     assert_eq!(old_key, "foobar");
+    ///     assert_eq!(old_value, 15);
+    /// }
+    ///
+    /// assert_eq!(map.get("foobar"), Some(&16));
+    /// ```
+    pub fn replace_entry(self, value: V) -> (K, V) {
+        unsafe {
+            let k = self.map.keys_mut_ptr().add(self.idx).replace(self.key);
+            let v = self.map.values_mut_ptr().add(self.idx).replace(value);
+            (k, v)
+        }
+    }
+
+    /// Replaces the key in the map with the one used to create the entry.
+    ///
+    /// This matters for key types that can be `==` without being identical.
+    pub fn replace_key(self) -> K {
+        unsafe { self.map.keys_mut_ptr().add(self.idx).replace(self.key) }
+    }
+}
+
+impl<K: Debug, V: Debug, S: Storage<ListMapLayout<K, V>>, I: Capacity> Debug for OccupiedEntry<'_, K, V, S, I> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("OccupiedEntry")
+            .field("key", &self.key)
+            .field("value", self.get())
+            .finish()
+    }
+}
+
+/// A view into a vacant entry in a [`SortedMap`]. It is part of the [`Entry`] enum.
+pub struct VacantEntry<'a, K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> {
+    key: K,
+    idx: usize,
+    map: &'a mut SortedMap<K, V, S, I>,
+}
+
+impl<'a, K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> VacantEntry<'a, K, V, S, I> {
+    /// Gets a reference to the key that would be used when inserting through the `VacantEntry`.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Take ownership of the key.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Sets the value of the entry with the `VacantEntry`'s key, and returns a mutable reference to it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        unsafe { &mut *self.map.insert_at(self.idx, self.key, value) }
+    }
+}
+
+impl<K: Debug, V: Debug, S: Storage<ListMapLayout<K, V>>, I: Capacity> Debug for VacantEntry<'_, K, V, S, I> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("VacantEntry").field(&self.key).finish()
+    }
+}
+
+/// A view into a single entry in a map, which may be either vacant or occupied.
+///
+/// This `enum` is constructed from the [`try_entry`](SortedMap::try_entry) method on [`SortedMap`].
+pub enum Entry<'a, K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> {
+    /// An occupied entry.
+    Occupied(OccupiedEntry<'a, K, V, S, I>),
+    /// A vacant entry.
+    Vacant(VacantEntry<'a, K, V, S, I>),
+}
+
+impl<'a, K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> Entry<'a, K, V, S, I> {
+    /// Ensures a value is in the entry by inserting the `default` if empty,
+    /// and returns a mutable reference to the value in the entry.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    ///
+    /// map.entry("foobar").or_insert(3);
+    /// assert_eq!(map.get("foobar"), Some(&3));
+    ///
+    /// *map.entry("foobar").or_insert(5) *= 2;
+    /// assert_eq!(map.get("foobar"), Some(&6));
+    /// ```
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Occupied(entry) => entry.into_mut(),
+            Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the `default`
+    /// function if empty, and returns a mutable reference to the value in the entry.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    /// let bazz = 0xDEADBEEF;
+    ///
+    /// map.entry("foobar").or_insert_with(|| bazz);
+    /// assert_eq!(map.get("foobar"), Some(&bazz));
+    /// ```
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Occupied(entry) => entry.into_mut(),
+            Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting, if empty, the result of the default function.
+    /// This method allows for generating key-derived values for insertion by providing the default
+    /// function a reference to the key that was moved during the `.entry(key)` method call.
+    ///
+    /// The reference to the moved key is provided so that cloning or copying the key is
+    /// unnecessary, unlike with `.or_insert_with(|| ... )`.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, usize, 4>::new();
+    ///
+    /// map.entry("foobar").or_insert_with_key(|key| key.chars().count());
+    ///
+    /// assert_eq!(map.get("foobar"), Some(&6));
+    /// ```
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Occupied(entry) => entry.into_mut(),
+            Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+        }
+    }
+
+    /// Returns a reference to the key used to create the entry.
+    pub fn key(&self) -> &K {
+        match *self {
+            Occupied(ref entry) => entry.key(),
+            Vacant(ref entry) => entry.key(),
+        }
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any
+    /// potential inserts into the map.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    ///
+    /// map.entry("foobar")
+    ///     .and_modify(|v| { *v += 1 })
+    ///     .or_insert(37);
+    /// assert_eq!(map.get("foobar"), Some(&37));
+    ///
+    /// map.entry("foobar")
+    ///     .and_modify(|v| { *v += 1 })
+    ///     .or_insert(42);
+    /// assert_eq!(map.get("foobar"), Some(&38));
+    /// ```
+    #[must_use]
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        match self {
+            Occupied(mut entry) => {
+                f(entry.get_mut());
+                Occupied(entry)
+            },
+            Vacant(entry) => Vacant(entry),
+        }
+    }
+}
+
+impl<'a, K, V: Default, S: Storage<ListMapLayout<K, V>>, I: Capacity> Entry<'a, K, V, S, I> {
+    /// Ensures a value is in the entry by inserting the default value if empty,
+    /// and returns a mutable reference to the value in the entry.
+    ///
+    /// # Examples
+    /// ```
+    /// use coca::collections::InlineSortedMap;
+    ///
+    /// let mut map = InlineSortedMap::<&'static str, u32, 4>::new();
+    /// map.entry("foobar").or_default();
+    ///
+    /// assert_eq!(map.get("foobar"), Some(&0));
+    /// ```
+    pub fn or_default(self) -> &'a mut V {
+        match self {
+            Occupied(entry) => entry.into_mut(),
+            Vacant(entry) => entry.insert(V::default())
+        }
+    }
+}
+
+impl<K: Debug, V: Debug, S: Storage<ListMapLayout<K, V>>, I: Capacity> Debug for Entry<'_, K, V, S, I> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Vacant(ref v) => f.debug_tuple("Entry").field(v).finish(),
+            Occupied(ref o) => f.debug_tuple("Entry").field(o).finish(),
+        }
+    }
+}
+
+/// An iterator over the entries of a [`SortedMap`].
+///
+/// This `struct` is created by the [`iter`](SortedMap::iter) and
+/// [`range`](SortedMap::range) methods on `SortedMap`. See their
+/// documentation for more.
+pub struct Iter<'a, K, V> {
+    keys: core::slice::Iter<'a, K>,
+    values: core::slice::Iter<'a, V>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some((self.keys.next()?, self.values.next()?))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        Some((self.keys.next_back()?, self.values.next_back()?))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+impl<'a, K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> IntoIterator for &'a SortedMap<K, V, S, I> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A mutable iterator over the entries of a [`SortedMap`].
+///
+/// This `struct` is created by the [`iter_mut`](SortedMap::iter_mut) and
+/// [`range_mut`](SortedMap::range_mut) methods on `SortedMap`. See their
+/// documentation for more.
+pub struct IterMut<'a, K, V> {
+    keys: core::slice::Iter<'a, K>,
+    values: core::slice::IterMut<'a, V>,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some((self.keys.next()?, self.values.next()?))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IterMut<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        Some((self.keys.next_back()?, self.values.next_back()?))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+impl<K, V> FusedIterator for IterMut<'_, K, V> {}
+
+impl<'a, K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> IntoIterator for &'a mut SortedMap<K, V, S, I> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// An owning iterator over the entries of a [`SortedMap`].
+///
+/// This `struct` is created by the [`into_iter`](IntoIterator::into_iter)
+/// method on `SortedMap` (provided by the [`IntoIterator`] trait). See its
+/// documentation for more.
+pub struct IntoIter<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> {
+    map: SortedMap<K, V, S, I>,
+    front: usize,
+}
+
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> Iterator for IntoIter<K, V, S, I> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.map.len() { return None; }
+
+        let idx = self.front;
+        self.front += 1;
+
+        unsafe {
+            let k = self.map.keys_mut_ptr().add(idx).read();
+            let v = self.map.values_mut_ptr().add(idx).read();
+            Some((k, v))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.map.len() - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> DoubleEndedIterator for IntoIter<K, V, S, I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.map.len() { return None; }
+
+        let new_len = self.map.len() - 1;
+        self.map.len = I::from_usize(new_len);
+
+        unsafe {
+            let k = self.map.keys_mut_ptr().add(new_len).read();
+            let v = self.map.values_mut_ptr().add(new_len).read();
+            Some((k, v))
+        }
+    }
+}
+
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> ExactSizeIterator for IntoIter<K, V, S, I> {}
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> FusedIterator for IntoIter<K, V, S, I> {}
+
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> Drop for IntoIter<K, V, S, I> {
+    fn drop(&mut self) {
+        let len = self.map.len();
+        self.map.len = I::from_usize(0);
+
+        unsafe {
+            let keys = self.map.keys_mut_ptr();
+            let values = self.map.values_mut_ptr();
+
+            for i in self.front..len {
+                keys.add(i).drop_in_place();
+                values.add(i).drop_in_place();
+            }
+        }
+    }
+}
+
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> IntoIterator for SortedMap<K, V, S, I> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V, S, I>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { map: self, front: 0 }
+    }
+}
+
+/// An owning iterator over the keys of a [`SortedMap`].
+///
+/// This `struct` is created by the [`into_keys`](SortedMap::into_keys) method on `SortedMap`.
+/// See its documentation for more.
+pub struct IntoKeys<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> {
+    base: IntoIter<K, V, S, I>,
+}
+
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> Iterator for IntoKeys<K, V, S, I> {
+    type Item = K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.base.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.base.size_hint()
+    }
+}
+
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> DoubleEndedIterator for IntoKeys<K, V, S, I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.base.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> ExactSizeIterator for IntoKeys<K, V, S, I> {}
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> FusedIterator for IntoKeys<K, V, S, I> {}
+
+/// An owning iterator over the values of a [`SortedMap`].
+///
+/// This `struct` is created by the [`into_values`](SortedMap::into_values) method on `SortedMap`.
+/// See its documentation for more.
+pub struct IntoValues<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> {
+    base: IntoIter<K, V, S, I>,
+}
+
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> Iterator for IntoValues<K, V, S, I> {
+    type Item = V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.base.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.base.size_hint()
+    }
+}
+
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> DoubleEndedIterator for IntoValues<K, V, S, I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.base.next_back().map(|(_, v)| v)
+    }
+}
+
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> ExactSizeIterator for IntoValues<K, V, S, I> {}
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> FusedIterator for IntoValues<K, V, S, I> {}
+
+/// A draining iterator over the entries of a [`SortedMap`].
+///
+/// This `struct` is created by the [`drain`](SortedMap::drain) method on `SortedMap`.
+/// See its documentation for more.
+pub struct Drain<'a, K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> {
+    map: &'a mut SortedMap<K, V, S, I>,
+    front: usize,
+    back: usize,
+}
+
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> Iterator for Drain<'_, K, V, S, I> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back { return None; }
+
+        let idx = self.front;
+        self.front += 1;
+
+        unsafe {
+            let k = self.map.keys_mut_ptr().add(idx).read();
+            let v = self.map.values_mut_ptr().add(idx).read();
+            Some((k, v))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> DoubleEndedIterator for Drain<'_, K, V, S, I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back { return None; }
+
+        self.back -= 1;
+        let idx = self.back;
+
+        unsafe {
+            let k = self.map.keys_mut_ptr().add(idx).read();
+            let v = self.map.values_mut_ptr().add(idx).read();
+            Some((k, v))
+        }
+    }
+}
+
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> ExactSizeIterator for Drain<'_, K, V, S, I> {}
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> FusedIterator for Drain<'_, K, V, S, I> {}
+
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity> Drop for Drain<'_, K, V, S, I> {
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+/// A draining, filtering iterator over the entries of a [`SortedMap`].
+///
+/// This `struct` is created by the [`drain_filter`](SortedMap::drain_filter)
+/// method on `SortedMap`. See its documentation for more.
+pub struct DrainFilter<'a, K, V, S, I, F>
+where
+    S: Storage<ListMapLayout<K, V>>,
+    I: Capacity,
+    F: FnMut(&K, &mut V) -> bool,
+{
+    map: &'a mut SortedMap<K, V, S, I>,
+    should_remove: F,
+    front: usize,
+    deleted: usize,
+    old_len: usize,
+}
+
+impl<K, V, S, I, F> Iterator for DrainFilter<'_, K, V, S, I, F>
+where
+    S: Storage<ListMapLayout<K, V>>,
+    I: Capacity,
+    F: FnMut(&K, &mut V) -> bool
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let keys_ptr = self.map.keys_mut_ptr();
+        let values_ptr = self.map.values_mut_ptr();
+
+        while self.front < self.old_len {
+            let idx = self.front;
+            self.front += 1;
+
+            unsafe {
+                let k = keys_ptr.add(idx);
+                let v = values_ptr.add(idx);
+
+                if (self.should_remove)(&*k, &mut *v) {
+                    self.deleted += 1;
+                    return Some((k.read(), v.read()));
+                } else if self.deleted > 0 {
+                    core::ptr::copy_nonoverlapping(k, k.sub(self.deleted), 1);
+                    core::ptr::copy_nonoverlapping(v, v.sub(self.deleted), 1);
+                }
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let max_len = self.old_len - self.front;
+        (0, Some(max_len))
+    }
+}
+
+impl<K, V, S: Storage<ListMapLayout<K, V>>, I: Capacity, F: FnMut(&K, &mut V) -> bool> FusedIterator for DrainFilter<'_, K, V, S, I, F> {}
+
+impl<K, V, S, I, F> Drop for DrainFilter<'_, K, V, S, I, F>
+where
+    S: Storage<ListMapLayout<K, V>>,
+    I: Capacity,
+    F: FnMut(&K, &mut V) -> bool
+{
+    fn drop(&mut self) {
+        self.for_each(drop);
+        self.map.len = I::from_usize(self.old_len - self.deleted);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::InlineSortedMap;
+
+    #[test]
+    fn randomized_operations() {
+        use crate::test_utils::{DropCounter, Droppable};
+        use rand::{rngs::SmallRng, RngCore, SeedableRng};
+        let mut rng = SmallRng::from_seed(crate::test_utils::RNG_SEED);
+
+        let drop_count = DropCounter::new();
+        let mut inserted = 0;
+
+        let mut map = InlineSortedMap::<u32, Droppable<u32>, 64>::new();
+        let mut present = [false; 128];
+
+        for _ in 0..10_000 {
+            let key = rng.next_u32() % 128;
+            let idx = key as usize;
+            match rng.next_u32() % 4 {
+                0 | 1 => {
+                    match map.try_insert(key, drop_count.new_droppable(key)) {
+                        Ok(None) => {
+                            assert!(!present[idx]);
+                            present[idx] = true;
+                        }
+                        Ok(Some(old)) => {
+                            assert!(present[idx]);
+                            assert_eq!(old.value, key);
+                        }
+                        Err(_) => {
+                            assert!(!present[idx]);
+                            assert!(map.is_full());
+                        }
+                    }
+                    inserted += 1;
+                }
+                2 => {
+                    assert_eq!(map.remove(&key).map(|d| d.value), if present[idx] { Some(key) } else { None });
+                    present[idx] = false;
+                }
+                _ => {
+                    let modulus = rng.next_u32() % 7 + 2;
+                    for (k, _) in map.drain_filter(|k, _| k % modulus == 0).take(3) {
+                        assert!(present[k as usize]);
+                    }
+
+                    // Dropping the iterator removes all remaining matches:
+                    for k in (0..128).step_by(modulus as usize) {
+                        present[k] = false;
+                    }
+                }
+            }
+
+            assert!(map.keys().windows(2).all(|w| w[0] < w[1]));
+            assert!(map.keys().iter().zip(map.values()).all(|(k, v)| *k == v.value));
+            assert_eq!(map.len(), present.iter().filter(|&&p| p).count());
+        }
+
+        for (key, &p) in present.iter().enumerate() {
+            assert_eq!(map.get(&(key as u32)).map(|d| d.value), if p { Some(key as u32) } else { None });
+        }
+
+        assert_eq!(drop_count.dropped(), inserted - map.len());
+        drop(map);
+        assert_eq!(drop_count.dropped(), inserted);
+    }
+
+    #[test]
+    fn range_queries() {
+        use core::ops::Bound::{Excluded, Included, Unbounded};
+
+        let mut map = InlineSortedMap::<u32, u32, 16>::new();
+        map.extend((0..16).map(|x| (2 * x, x)));
+
+        let keys = |iter: Iter<'_, u32, u32>| {
+            let mut result = crate::collections::InlineVec::<u32, 16>::new();
+            result.extend(iter.map(|(k, _)| *k));
+            result
+        };
+
+        assert_eq!(keys(map.range(3..9)), [4, 6, 8]);
+        assert_eq!(keys(map.range(4..=8)), [4, 6, 8]);
+        assert_eq!(keys(map.range((Excluded(4), Included(8)))), [6, 8]);
+        assert_eq!(keys(map.range((Excluded(3), Unbounded))).len(), 14);
+        assert_eq!(keys(map.range(..)).len(), 16);
+        assert_eq!(keys(map.range(31..)), []);
+        assert_eq!(keys(map.range(5..5)), []);
+
+        for (_, v) in map.range_mut(..4) {
+            *v = 100;
+        }
+
+        assert_eq!(&map.values()[..3], &[100, 100, 2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn range_panics_on_inverted_bounds() {
+        let map = InlineSortedMap::<u32, u32, 4>::new();
+        let _ = map.range(5..3);
+    }
+}
@@ -0,0 +1,463 @@
+//! A lock-free single-producer single-consumer queue.
+//!
+//! A [`SpscQueue`] is a ring buffer that can be [split](SpscQueue::split)
+//! into a [`Producer`] and a [`Consumer`], which may be used concurrently
+//! from different threads or execution contexts, such as an interrupt handler
+//! and the main loop. Synchronization relies solely on atomic loads and
+//! stores of the head and tail indices, so neither operation ever blocks.
+//!
+//! ```
+//! use coca::collections::InlineSpscQueue;
+//!
+//! let mut queue = InlineSpscQueue::<u32, 4>::new();
+//! let (mut producer, mut consumer) = queue.split();
+//!
+//! assert!(producer.try_push(1).is_ok());
+//! assert!(producer.try_push(2).is_ok());
+//! assert_eq!(consumer.try_pop(), Some(1));
+//! assert_eq!(consumer.try_pop(), Some(2));
+//! assert_eq!(consumer.try_pop(), None);
+//! ```
+
+use core::fmt::{self, Debug, Formatter};
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::storage::{ArrayLayout, InlineStorage, Storage};
+
+// Head and tail are kept in the range `0..2 * capacity`, so that a full
+// queue can be distinguished from an empty one without wasting a slot.
+
+#[inline(always)]
+fn advance(index: usize, n: usize, capacity: usize) -> usize {
+    let wrap = 2 * capacity - n;
+    if index >= wrap { index - wrap } else { index + n }
+}
+
+#[inline(always)]
+fn distance(head: usize, tail: usize, capacity: usize) -> usize {
+    if tail >= head { tail - head } else { tail + (2 * capacity - head) }
+}
+
+#[inline(always)]
+fn slot(index: usize, capacity: usize) -> usize {
+    if index >= capacity { index - capacity } else { index }
+}
+
+/// A fixed-capacity queue for passing values from one producer to one
+/// consumer without locking.
+///
+/// See the [module-level documentation](crate::collections::spsc) for more.
+pub struct SpscQueue<T, S: Storage<ArrayLayout<T>>> {
+    head: AtomicUsize,
+    tail: AtomicUsize,
+    buf: S,
+    elem: PhantomData<T>,
+}
+
+impl<T, S: Storage<ArrayLayout<T>>> From<S> for SpscQueue<T, S> {
+    /// Converts a contiguous block of memory into an empty queue.
+    ///
+    /// # Panics
+    /// Panics if `buf` has a capacity of zero or more than `usize::MAX / 2`.
+    fn from(buf: S) -> Self {
+        let capacity = buf.capacity();
+        assert!(capacity > 0 && capacity <= usize::MAX / 2, "invalid queue capacity: {}", capacity);
+
+        SpscQueue {
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            buf,
+            elem: PhantomData,
+        }
+    }
+}
+
+impl<T, S: Storage<ArrayLayout<T>>> SpscQueue<T, S> {
+    /// Returns the number of elements the queue can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    /// Returns the number of elements currently in the queue.
+    #[inline]
+    pub fn len(&self) -> usize {
+        distance(self.head.load(Ordering::Relaxed), self.tail.load(Ordering::Relaxed), self.capacity())
+    }
+
+    /// Returns `true` if the queue contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the queue contains the maximum number of elements it can hold.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+
+    /// Splits the queue into a producer and a consumer half, which can be
+    /// sent to different threads.
+    ///
+    /// Elements remaining in the queue when both halves are dropped can be
+    /// retrieved by splitting the queue again.
+    ///
+    /// # Examples
+    /// ```
+    /// let mut queue = coca::collections::InlineSpscQueue::<char, 4>::new();
+    /// {
+    ///     let (mut producer, _) = queue.split();
+    ///     producer.try_push('a').unwrap();
+    ///     producer.try_push('b').unwrap();
+    /// }
+    ///
+    /// assert_eq!(queue.len(), 2);
+    /// let (_, mut consumer) = queue.split();
+    /// assert_eq!(consumer.try_pop(), Some('a'));
+    /// ```
+    pub fn split(&mut self) -> (Producer<'_, T>, Consumer<'_, T>) {
+        let capacity = self.buf.capacity();
+        let buf = self.buf.get_mut_ptr().cast::<T>();
+        let (head, tail) = (&self.head, &self.tail);
+
+        let producer = Producer { buf, capacity, head, tail };
+        let consumer = Consumer { buf, capacity, head, tail };
+        (producer, consumer)
+    }
+}
+
+impl<T, S: Storage<ArrayLayout<T>>> Drop for SpscQueue<T, S> {
+    fn drop(&mut self) {
+        let (_, mut consumer) = self.split();
+        while consumer.try_pop().is_some() {}
+    }
+}
+
+impl<T, S: Storage<ArrayLayout<T>>> Debug for SpscQueue<T, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Relaxed);
+        f.debug_struct("SpscQueue")
+            .field("len", &distance(head, tail, self.capacity()))
+            .field("capacity", &self.capacity())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
+impl<T> crate::collections::AllocSpscQueue<T> {
+    /// Constructs a new, empty [`AllocSpscQueue`](crate::collections::AllocSpscQueue)
+    /// with the specified capacity.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero or greater than `usize::MAX / 2`.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from(crate::storage::AllocStorage::with_capacity(capacity))
+    }
+}
+
+impl<T, const C: usize> SpscQueue<T, InlineStorage<T, C>> {
+    /// Constructs a new, empty queue backed by an inline array.
+    ///
+    /// # Panics
+    /// Panics if `C` is zero.
+    ///
+    /// # Examples
+    /// ```
+    /// let queue = coca::collections::InlineSpscQueue::<u32, 7>::new();
+    /// assert_eq!(queue.capacity(), 7);
+    /// ```
+    pub fn new() -> Self {
+        Self::from(unsafe { MaybeUninit::<InlineStorage<T, C>>::uninit().assume_init() })
+    }
+}
+
+impl<T, const C: usize> Default for SpscQueue<T, InlineStorage<T, C>> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The writing half of a [`SpscQueue`].
+///
+/// This `struct` is created by the [`split`](SpscQueue::split) method on `SpscQueue`.
+pub struct Producer<'a, T> {
+    buf: *mut T,
+    capacity: usize,
+    head: &'a AtomicUsize,
+    tail: &'a AtomicUsize,
+}
+
+unsafe impl<T: Send> Send for Producer<'_, T> {}
+
+impl<T> Producer<'_, T> {
+    /// Returns the number of elements the queue can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of elements currently in the queue.
+    ///
+    /// Since the consumer may remove elements concurrently, this is an upper bound.
+    #[inline]
+    pub fn len(&self) -> usize {
+        distance(self.head.load(Ordering::Acquire), self.tail.load(Ordering::Relaxed), self.capacity)
+    }
+
+    /// Returns `true` if the queue contains no elements.
+    ///
+    /// Since the consumer may remove elements concurrently, a result of
+    /// `false` may be outdated by the time it is returned.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the queue is full.
+    ///
+    /// Since the consumer may remove elements concurrently, a result of
+    /// `true` may be outdated by the time it is returned.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity
+    }
+
+    /// Appends an element to the back of the queue.
+    ///
+    /// Returns [`Err`] containing the element if the queue is full.
+    pub fn try_push(&mut self, value: T) -> Result<(), T> {
+        let tail = self.tail.load(Ordering::Relaxed);
+        let head = self.head.load(Ordering::Acquire);
+        if distance(head, tail, self.capacity) == self.capacity {
+            return Err(value);
+        }
+
+        unsafe { self.buf.add(slot(tail, self.capacity)).write(value); }
+        self.tail.store(advance(tail, 1, self.capacity), Ordering::Release);
+        Ok(())
+    }
+
+    /// Appends as many elements from `items` to the back of the queue as it
+    /// has room for, returning the number of elements copied.
+    ///
+    /// # Examples
+    /// ```
+    /// let mut queue = coca::collections::InlineSpscQueue::<u8, 4>::new();
+    /// let (mut producer, mut consumer) = queue.split();
+    ///
+    /// assert_eq!(producer.push_slice(&[1, 2, 3]), 3);
+    /// assert_eq!(producer.push_slice(&[4, 5, 6]), 1);
+    /// assert_eq!(consumer.try_pop(), Some(1));
+    /// ```
+    pub fn push_slice(&mut self, items: &[T]) -> usize where T: Copy {
+        let tail = self.tail.load(Ordering::Relaxed);
+        let head = self.head.load(Ordering::Acquire);
+        let count = items.len().min(self.capacity - distance(head, tail, self.capacity));
+
+        let start = slot(tail, self.capacity);
+        let first = count.min(self.capacity - start);
+        unsafe {
+            core::ptr::copy_nonoverlapping(items.as_ptr(), self.buf.add(start), first);
+            core::ptr::copy_nonoverlapping(items.as_ptr().add(first), self.buf, count - first);
+        }
+
+        self.tail.store(advance(tail, count, self.capacity), Ordering::Release);
+        count
+    }
+}
+
+impl<T> Debug for Producer<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Producer")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+/// The reading half of a [`SpscQueue`].
+///
+/// This `struct` is created by the [`split`](SpscQueue::split) method on `SpscQueue`.
+pub struct Consumer<'a, T> {
+    buf: *mut T,
+    capacity: usize,
+    head: &'a AtomicUsize,
+    tail: &'a AtomicUsize,
+}
+
+unsafe impl<T: Send> Send for Consumer<'_, T> {}
+
+impl<T> Consumer<'_, T> {
+    /// Returns the number of elements the queue can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of elements currently in the queue.
+    ///
+    /// Since the producer may add elements concurrently, this is a lower bound.
+    #[inline]
+    pub fn len(&self) -> usize {
+        distance(self.head.load(Ordering::Relaxed), self.tail.load(Ordering::Acquire), self.capacity)
+    }
+
+    /// Returns `true` if the queue contains no elements.
+    ///
+    /// Since the producer may add elements concurrently, a result of
+    /// `true` may be outdated by the time it is returned.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a reference to the element at the front of the queue, or
+    /// [`None`] if the queue is empty.
+    pub fn peek(&self) -> Option<&T> {
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+
+        Some(unsafe { &*self.buf.add(slot(head, self.capacity)) })
+    }
+
+    /// Removes the element at the front of the queue and returns it, or
+    /// [`None`] if the queue is empty.
+    pub fn try_pop(&mut self) -> Option<T> {
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+
+        let value = unsafe { self.buf.add(slot(head, self.capacity)).read() };
+        self.head.store(advance(head, 1, self.capacity), Ordering::Release);
+        Some(value)
+    }
+
+    /// Removes as many elements from the front of the queue as fit into
+    /// `out`, returning the number of elements copied.
+    ///
+    /// # Examples
+    /// ```
+    /// let mut queue = coca::collections::InlineSpscQueue::<u8, 4>::new();
+    /// let (mut producer, mut consumer) = queue.split();
+    /// producer.push_slice(&[1, 2, 3]);
+    ///
+    /// let mut out = [0; 2];
+    /// assert_eq!(consumer.pop_into(&mut out), 2);
+    /// assert_eq!(out, [1, 2]);
+    /// assert_eq!(consumer.pop_into(&mut out), 1);
+    /// assert_eq!(out, [3, 2]);
+    /// ```
+    pub fn pop_into(&mut self, out: &mut [T]) -> usize where T: Copy {
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+        let count = out.len().min(distance(head, tail, self.capacity));
+
+        let start = slot(head, self.capacity);
+        let first = count.min(self.capacity - start);
+        unsafe {
+            core::ptr::copy_nonoverlapping(self.buf.add(start), out.as_mut_ptr(), first);
+            core::ptr::copy_nonoverlapping(self.buf, out.as_mut_ptr().add(first), count - first);
+        }
+
+        self.head.store(advance(head, count, self.capacity), Ordering::Release);
+        count
+    }
+}
+
+impl<T> Debug for Consumer<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Consumer")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use crate::collections::InlineSpscQueue;
+    use crate::test_utils::DropCounter;
+
+    #[test]
+    fn batches_wrap_around() {
+        let mut queue = InlineSpscQueue::<u32, 5>::new();
+        let (mut producer, mut consumer) = queue.split();
+
+        let mut next_in = 0;
+        let mut next_out = 0;
+        for round in 0..50 {
+            let batch: [u32; 3] = [next_in, next_in + 1, next_in + 2];
+            let pushed = producer.push_slice(&batch[..(round % 3) + 1]);
+            next_in += pushed as u32;
+
+            let mut out = [0; 4];
+            let popped = consumer.pop_into(&mut out[..(round % 4) + 1]);
+            for &x in &out[..popped] {
+                assert_eq!(x, next_out);
+                next_out += 1;
+            }
+
+            assert_eq!(producer.len(), (next_in - next_out) as usize);
+        }
+    }
+
+    #[test]
+    fn drops_remaining_elements() {
+        let counter = DropCounter::new();
+        {
+            let mut queue = InlineSpscQueue::<_, 4>::new();
+            let (mut producer, mut consumer) = queue.split();
+            for _ in 0..4 {
+                assert!(producer.try_push(counter.new_droppable(())).is_ok());
+            }
+            assert!(producer.try_push(counter.new_droppable(())).is_err());
+            assert_eq!(counter.dropped(), 1);
+
+            drop(consumer.try_pop());
+            assert_eq!(counter.dropped(), 2);
+        }
+        assert_eq!(counter.dropped(), 5);
+    }
+
+    #[test]
+    fn concurrent_transfer() {
+        const COUNT: u64 = 10_000;
+        let queue = std::boxed::Box::leak(std::boxed::Box::new(InlineSpscQueue::<u64, 16>::new()));
+        let (mut producer, mut consumer) = queue.split();
+
+        let handle = std::thread::spawn(move || {
+            let mut i = 0;
+            while i < COUNT {
+                if producer.try_push(i).is_ok() {
+                    i += 1;
+                } else {
+                    std::thread::yield_now();
+                }
+            }
+        });
+
+        let mut expected = 0;
+        while expected < COUNT {
+            if let Some(x) = consumer.try_pop() {
+                assert_eq!(x, expected);
+                expected += 1;
+            } else {
+                std::thread::yield_now();
+            }
+        }
+
+        handle.join().unwrap();
+    }
+}
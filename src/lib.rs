@@ -3,6 +3,7 @@
 #![cfg_attr(docs_rs, feature(doc_cfg))]
 #![cfg_attr(feature = "unstable", feature(unsize))]
 #![cfg_attr(feature = "unstable", feature(set_ptr_value))]
+#![cfg_attr(feature = "unstable", feature(allocator_api))]
 #![warn(missing_docs)]
 #![warn(clippy::pedantic)]
 #![allow(
@@ -105,12 +106,20 @@
 //! - `alloc`: Enables an optional dependency on the `alloc` crate and adds
 //!   the [`AllocStorage`](storage::AllocStorage) type, as well as other trait
 //!   implementations and convenience functions for using the global allocator.
+//! - `std`: Implies `alloc`, and adds [`arena::with_scratch`] for using
+//!   per-thread scratch arenas, which relies on thread-local storage.
 //! - `unstable`: Adds the [`object`] module providing a statically-sized
-//!   container for dynamically-sized types. This relies on the unstable
-//!   `feature(unsize)` and `feature(set_ptr_value)` and thus requires a nightly
-//!   compiler.
+//!   container for dynamically-sized types, and the [`ArenaAllocator`](arena::ArenaAllocator)
+//!   adapter implementing the `Allocator` trait. This relies on the unstable
+//!   `feature(unsize)`, `feature(set_ptr_value)` and `feature(allocator_api)`
+//!   and thus requires a nightly compiler.
 //! - `profile`: Adds memory profiling in arena allocators. See the
 //!   [module-level documentation](arena#memory-profiling) for details.
+//!   Also adds hit, miss and eviction counters to cache tables; see
+//!   [`CacheTable::statistics`](collections::cache::CacheTable::statistics).
+//! - `serde`: Enables an optional dependency on the [`serde`](https://serde.rs)
+//!   crate, and implements `Serialize` and `Deserialize` for object pools and
+//!   the handle types generated by [`handle_type!`].
 //! 
 //! None of these features are enabled by default.
 
@@ -118,6 +127,13 @@
 #[doc(hidden)]
 pub extern crate alloc;
 
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub extern crate serde;
+
 pub mod arena;
 pub mod collections;
 pub mod storage;
@@ -207,6 +223,12 @@ impl CapacityError {
     }
 }
 
+impl core::fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("insufficient capacity")
+    }
+}
+
 /// A specialized [`Result`](core::result::Result) type for operations on data structures with constant capacity.
 /// 
 /// This type is broadly used across `coca` for most operations which grow a data structure.
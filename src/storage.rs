@@ -294,6 +294,11 @@ impl<R: LayoutSpec> ArenaStorage<'_, R> {
     }
 }
 
+// Like a `Box`, an `ArenaStorage` uniquely owns its block, and the layout
+// carries the types of the values it may contain.
+unsafe impl<R: LayoutSpec + Send> Send for ArenaStorage<'_, R> {}
+unsafe impl<R: LayoutSpec + Sync> Sync for ArenaStorage<'_, R> {}
+
 unsafe impl<R: LayoutSpec> Storage<R> for ArenaStorage<'_, R> {
     fn get_ptr(&self) -> *const u8 {
         self.ptr.as_ptr() as _
@@ -375,6 +380,12 @@ impl<R: LayoutSpec> Drop for AllocStorage<R> {
     }
 }
 
+// See the equivalent impls for `ArenaStorage` above.
+#[cfg(feature = "alloc")]
+unsafe impl<R: LayoutSpec + Send> Send for AllocStorage<R> {}
+#[cfg(feature = "alloc")]
+unsafe impl<R: LayoutSpec + Sync> Sync for AllocStorage<R> {}
+
 #[cfg(feature = "alloc")]
 #[cfg_attr(docs_rs, doc(cfg(feature = "alloc")))]
 unsafe impl<R: LayoutSpec> Storage<R> for AllocStorage<R> {